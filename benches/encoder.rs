@@ -0,0 +1,56 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![cfg(feature = "alloc")]
+#![feature(test)]
+
+extern crate test;
+
+use qrcode2::{EcLevel, QrCode, Version, encoder::Encoder};
+use test::Bencher;
+
+/// Returns the payloads of a batch of tickets.
+fn tickets() -> Vec<String> {
+    (0..64)
+        .map(|i| format!("https://example.com/tickets/{i:08}?seat=A{i}"))
+        .collect()
+}
+
+#[bench]
+fn with_error_correction_level_batch(b: &mut Bencher) {
+    let payloads = tickets();
+    b.iter(|| {
+        for payload in &payloads {
+            QrCode::with_error_correction_level(payload, EcLevel::M).unwrap();
+        }
+    });
+}
+
+#[bench]
+fn encode_batch(b: &mut Bencher) {
+    let payloads = tickets();
+    let mut encoder = Encoder::new();
+    b.iter(|| {
+        for payload in &payloads {
+            encoder.encode(payload, EcLevel::M).unwrap();
+        }
+    });
+}
+
+#[bench]
+fn with_version_40(b: &mut Bencher) {
+    let data = [b'A'; 2000];
+    b.iter(|| QrCode::with_version(data, Version::Normal(40), EcLevel::L).unwrap());
+}
+
+#[bench]
+fn encode_with_version_40(b: &mut Bencher) {
+    let data = [b'A'; 2000];
+    let mut encoder = Encoder::new();
+    b.iter(|| {
+        encoder
+            .encode_with_version(data, Version::Normal(40), EcLevel::L)
+            .unwrap()
+    });
+}
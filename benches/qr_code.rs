@@ -2,11 +2,12 @@
 //
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+#![cfg(feature = "alloc")]
 #![feature(test)]
 
 extern crate test;
 
-use qrcode2::QrCode;
+use qrcode2::{EcLevel, QrCode, Version, canvas::Canvas};
 use test::Bencher;
 
 #[bench]
@@ -23,3 +24,17 @@ fn new_micro(b: &mut Bencher) {
 fn new_rect_micro(b: &mut Bencher) {
     b.iter(|| QrCode::new_rect_micro(b"01234567").unwrap());
 }
+
+#[bench]
+fn new_version_40(b: &mut Bencher) {
+    let data = [b'A'; 2000];
+    b.iter(|| QrCode::with_version(data, Version::Normal(40), EcLevel::L).unwrap());
+}
+
+#[bench]
+fn apply_best_mask_version_40(b: &mut Bencher) {
+    let mut canvas = Canvas::new(Version::Normal(40), EcLevel::L);
+    canvas.draw_all_functional_patterns();
+    canvas.draw_data(&[0x55; 1276], &[0xaa; 2430]);
+    b.iter(|| canvas.apply_best_mask());
+}
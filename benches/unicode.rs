@@ -2,6 +2,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+#![cfg(feature = "alloc")]
 #![feature(test)]
 
 extern crate test;
@@ -0,0 +1,243 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Encoding many QR codes with the same settings.
+//!
+//! [`BatchEncoder`] encodes payloads one after another with shared settings.
+//! With the cache enabled, a payload which has already been encoded with the
+//! same settings reuses the previous symbol instead of running the
+//! segmentation, the error correction and the masking again. This helps when a
+//! batch contains many duplicate payloads, e.g. the same product code on many
+//! labels. The cache keeps every distinct payload unless a capacity is set
+//! with [`BatchEncoder::cache_capacity`], in which case the oldest symbols are
+//! evicted first. The other payloads are encoded with an [`Encoder`], which
+//! reuses its memory between them.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{EcLevel, batch::BatchEncoder};
+//!
+//! let mut encoder = BatchEncoder::new();
+//! encoder.error_correction_level(EcLevel::Q).cache(true);
+//! let codes = ["SKU-1", "SKU-2", "SKU-1"]
+//!     .into_iter()
+//!     .map(|payload| encoder.encode(payload))
+//!     .collect::<Result<Vec<_>, _>>()
+//!     .unwrap();
+//! assert_eq!(codes[0].to_colors(), codes[2].to_colors());
+//! assert_eq!(encoder.cache_hits(), 1);
+//! ```
+
+use alloc::{collections::VecDeque, vec::Vec};
+use std::collections::HashMap;
+
+use crate::{
+    QrCode,
+    encoder::Encoder,
+    types::{EcLevel, QrResult, Version},
+};
+
+/// The payload and the settings which determine the encoded symbol.
+type CacheKey = (Vec<u8>, EcLevel, Option<Version>);
+
+/// An encoder for many QR codes with the same settings.
+#[derive(Clone, Debug, Default)]
+pub struct BatchEncoder {
+    ec_level: EcLevel,
+    version: Option<Version>,
+    cache: Option<HashMap<CacheKey, QrCode>>,
+    cache_order: VecDeque<CacheKey>,
+    cache_capacity: Option<usize>,
+    cache_hits: usize,
+    encoder: Encoder,
+}
+
+impl BatchEncoder {
+    /// Creates a new encoder.
+    ///
+    /// The defaults are the "medium" error correction level, the smallest QR
+    /// code which fits the data, and no cache.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the error correction level.
+    #[inline]
+    pub const fn error_correction_level(&mut self, ec_level: EcLevel) -> &mut Self {
+        self.ec_level = ec_level;
+        self
+    }
+
+    /// Sets the version to use for all payloads. If this is [`None`], the
+    /// smallest QR code which fits each payload is chosen.
+    #[inline]
+    pub const fn version(&mut self, version: Option<Version>) -> &mut Self {
+        self.version = version;
+        self
+    }
+
+    /// Sets whether to reuse the symbols of the payloads which have already
+    /// been encoded with the same settings. Disabling the cache discards the
+    /// symbols kept so far.
+    ///
+    /// The cache grows with every distinct payload unless its capacity is
+    /// limited by [`BatchEncoder::cache_capacity`].
+    #[inline]
+    pub fn cache(&mut self, is_enabled: bool) -> &mut Self {
+        if !is_enabled {
+            self.cache = None;
+            self.cache_order.clear();
+        } else if self.cache.is_none() {
+            self.cache = Some(HashMap::new());
+        }
+        self
+    }
+
+    /// Sets the maximum number of symbols kept in the cache. When the cache is
+    /// full, the symbol which was cached first is evicted. If this is
+    /// [`None`], which is the default, the cache is not limited.
+    #[inline]
+    pub fn cache_capacity(&mut self, capacity: Option<usize>) -> &mut Self {
+        self.cache_capacity = capacity;
+        self.evict();
+        self
+    }
+
+    /// Returns the number of payloads which have been served from the cache.
+    #[must_use]
+    #[inline]
+    pub const fn cache_hits(&self) -> usize {
+        self.cache_hits
+    }
+
+    /// Returns the number of symbols kept in the cache.
+    #[must_use]
+    #[inline]
+    pub fn cache_len(&self) -> usize {
+        self.cache.as_ref().map_or(0, HashMap::len)
+    }
+
+    /// Discards the symbols kept in the cache.
+    #[inline]
+    pub fn clear_cache(&mut self) {
+        if let Some(cache) = &mut self.cache {
+            cache.clear();
+        }
+        self.cache_order.clear();
+    }
+
+    /// Evicts the oldest symbols until the cache fits in its capacity.
+    fn evict(&mut self) {
+        let (Some(cache), Some(capacity)) = (&mut self.cache, self.cache_capacity) else {
+            return;
+        };
+        while cache.len() > capacity {
+            let Some(key) = self.cache_order.pop_front() else {
+                break;
+            };
+            cache.remove(&key);
+        }
+    }
+
+    /// Encodes the payload with the current settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the QR code cannot be constructed. Failures are not
+    /// cached.
+    pub fn encode(&mut self, data: impl AsRef<[u8]>) -> QrResult<QrCode> {
+        let data = data.as_ref();
+        if self.cache.is_none() {
+            return self.encode_uncached(data);
+        }
+        let key = (data.to_vec(), self.ec_level, self.version);
+        if let Some(code) = self.cache.as_ref().and_then(|cache| cache.get(&key)) {
+            self.cache_hits += 1;
+            return Ok(code.clone());
+        }
+        let code = self.encode_uncached(data)?;
+        if let Some(cache) = &mut self.cache {
+            cache.insert(key.clone(), code.clone());
+            self.cache_order.push_back(key);
+        }
+        self.evict();
+        Ok(code)
+    }
+
+    /// Encodes the payload with the current settings, reusing the memory of
+    /// the previous payloads.
+    fn encode_uncached(&mut self, data: &[u8]) -> QrResult<QrCode> {
+        match self.version {
+            Some(version) => self
+                .encoder
+                .encode_with_version(data, version, self.ec_level),
+            None => self.encoder.encode(data, self.ec_level),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache() {
+        let mut encoder = BatchEncoder::new();
+        encoder.cache(true);
+        let first = encoder.encode(b"01234567").unwrap();
+        let second = encoder.encode(b"01234567").unwrap();
+        assert_eq!(first.to_colors(), second.to_colors());
+        assert_eq!(encoder.cache_hits(), 1);
+
+        // Different settings are cached separately.
+        let code = encoder
+            .version(Some(Version::Micro(2)))
+            .encode(b"01234567")
+            .unwrap();
+        assert_eq!(code.version(), Version::Micro(2));
+        assert_eq!(encoder.cache_hits(), 1);
+        assert_eq!(encoder.cache_len(), 2);
+
+        encoder.clear_cache();
+        assert_eq!(encoder.cache_len(), 0);
+    }
+
+    #[test]
+    fn test_cache_capacity() {
+        let mut encoder = BatchEncoder::new();
+        encoder.cache(true).cache_capacity(Some(2));
+        for payload in ["1", "2", "3"] {
+            encoder.encode(payload).unwrap();
+        }
+        assert_eq!(encoder.cache_len(), 2);
+        // The oldest payload has been evicted.
+        encoder.encode("1").unwrap();
+        assert_eq!(encoder.cache_hits(), 0);
+        encoder.encode("3").unwrap();
+        assert_eq!(encoder.cache_hits(), 1);
+
+        encoder.cache_capacity(Some(0));
+        assert_eq!(encoder.cache_len(), 0);
+    }
+
+    #[test]
+    fn test_without_cache() {
+        let mut encoder = BatchEncoder::new();
+        encoder.encode(b"01234567").unwrap();
+        encoder.encode(b"01234567").unwrap();
+        assert_eq!(encoder.cache_hits(), 0);
+        assert_eq!(encoder.cache_len(), 0);
+    }
+
+    #[test]
+    fn test_errors_are_not_cached() {
+        let mut encoder = BatchEncoder::new();
+        encoder.cache(true).version(Some(Version::Micro(1)));
+        assert!(encoder.encode(b"HELLO").is_err());
+        assert_eq!(encoder.cache_len(), 0);
+    }
+}
@@ -9,23 +9,29 @@
 
 //! The `bits` module encodes binary data into raw bits used in a QR code.
 
-use alloc::vec::Vec;
+use alloc::{vec, vec::Vec};
 use core::cmp;
 
+#[cfg(feature = "kanji")]
+use {crate::sjis, core::ops::Range};
+
 use crate::{
     cast::{As, Truncate},
-    optimize::{self, Optimizer, Parser, Segment},
-    types::{EcLevel, Mode, QrError, QrResult, Version},
+    optimize::{self, CostModel, Optimizer, Parser, Segment, SegmentTables},
+    types::{Charset, EcLevel, Mode, QrError, QrResult, Version, VersionKind},
 };
 
 // Bits
 
 /// The `Bits` structure stores the encoded data for a QR code.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Bits {
     data: Vec<u8>,
     bit_offset: usize,
     version: Version,
+    terminated_len: Option<usize>,
+    headers: Vec<Header>,
+    dynamic: bool,
 }
 
 impl Bits {
@@ -45,6 +51,9 @@ impl Bits {
             data: Vec::new(),
             bit_offset: 0,
             version,
+            terminated_len: None,
+            headers: Vec::new(),
+            dynamic: false,
         }
     }
 
@@ -95,8 +104,13 @@ impl Bits {
     ///
     /// Returns [`Err`] on overflow.
     pub fn push_number_checked(&mut self, n: usize, number: usize) -> QrResult<()> {
-        if n > 16 || number >= (1 << n) {
-            Err(QrError::DataTooLong)
+        let bits_needed = n.max((usize::BITS - number.leading_zeros()).as_usize());
+        let bits_available = n.min(16);
+        if bits_needed > bits_available {
+            Err(QrError::DataTooLong {
+                bits_needed,
+                bits_available,
+            })
         } else {
             self.push_number(n, number.as_u16());
             Ok(())
@@ -136,6 +150,21 @@ impl Bits {
         self.data
     }
 
+    /// Returns the bytes pushed so far, like [`Bits::into_bytes`].
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Empties the bits and sets the version, keeping the allocated memory.
+    pub(crate) fn clear(&mut self, version: Version) {
+        self.data.clear();
+        self.bit_offset = 0;
+        self.version = version;
+        self.terminated_len = None;
+        self.headers.clear();
+        self.dynamic = false;
+    }
+
     /// Returns the total number of bits currently pushed.
     ///
     /// # Examples
@@ -200,6 +229,44 @@ impl Bits {
         self.version.fetch(ec_level, &DATA_LENGTHS)
     }
 
+    /// The number of bits which can still be pushed before
+    /// [`Bits::push_terminator`] fails, i.e. [`Bits::max_len`] minus
+    /// [`Bits::len`]. This is `0` if the bits are already too long.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if it is not valid to use the `ec_level` for the given
+    /// version (e.g. [`Version::Micro(1)`](Version::Micro) with
+    /// [`EcLevel::H`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{EcLevel, Version, bits::Bits};
+    /// #
+    /// let mut bits = Bits::new(Version::Normal(1));
+    /// assert_eq!(bits.remaining_len(EcLevel::M), Ok(128));
+    ///
+    /// bits.push_numeric_data(b"01234567");
+    /// assert_eq!(bits.remaining_len(EcLevel::M), Ok(87));
+    ///
+    /// bits.push_numeric_data(&[b'0'; 40]);
+    /// assert_eq!(bits.remaining_len(EcLevel::M), Ok(0));
+    /// ```
+    #[inline]
+    pub fn remaining_len(&self, ec_level: EcLevel) -> QrResult<usize> {
+        Ok(self.max_len(ec_level)?.saturating_sub(self.len()))
+    }
+
+    /// The number of data bits of the version at the lowest error correction
+    /// level which it supports, or `0` if the version is invalid.
+    fn max_capacity(&self) -> usize {
+        [EcLevel::L, EcLevel::M]
+            .into_iter()
+            .find_map(|ec_level| self.max_len(ec_level).ok())
+            .unwrap_or_default()
+    }
+
     /// Returns the version of the QR code.
     ///
     /// # Examples
@@ -272,7 +339,7 @@ mod push_number_tests {
 
 /// An "extended" mode indicator, includes all indicators supported by QR code
 /// beyond those bearing data.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ExtendedMode {
     /// ECI mode indicator, to introduce an ECI designator.
     Eci,
@@ -296,10 +363,19 @@ impl Bits {
     /// # Errors
     ///
     /// Returns [`Err`] if the mode is not supported in the provided version.
+    /// Data modes which are not supported by Micro QR code M1 or M2 are
+    /// reported as [`QrError::UnsupportedMode`].
     pub fn push_mode_indicator(&mut self, mode: ExtendedMode) -> QrResult<()> {
+        let start = self.len();
         #[allow(clippy::match_same_arms)]
         let number = match (self.version, mode) {
-            (Version::Micro(1), ExtendedMode::Data(Mode::Numeric)) => return Ok(()),
+            (Version::Micro(1), ExtendedMode::Data(Mode::Numeric)) => {
+                self.push_header_record(start, mode);
+                return Ok(());
+            }
+            (Version::Micro(_), ExtendedMode::Data(mode)) if !self.version.supports_mode(mode) => {
+                return Err(QrError::UnsupportedMode(mode));
+            }
             (Version::Micro(_), ExtendedMode::Data(Mode::Numeric)) => 0,
             (Version::Micro(_), ExtendedMode::Data(Mode::Alphanumeric)) => 1,
             (Version::Micro(_), ExtendedMode::Data(Mode::Byte)) => 0b10,
@@ -324,7 +400,72 @@ impl Bits {
         };
         let bits = self.version.mode_bits_count();
         self.push_number_checked(bits, number)
-            .or(Err(QrError::UnsupportedCharacterSet))
+            .or(Err(QrError::UnsupportedCharacterSet))?;
+        self.push_header_record(start, mode);
+        Ok(())
+    }
+
+    /// Records the mode indicator which was pushed at `start`.
+    fn push_header_record(&mut self, start: usize, mode: ExtendedMode) {
+        let end = self.len();
+        self.headers.push(Header {
+            start,
+            mode,
+            char_count: None,
+            end,
+        });
+    }
+
+    /// Returns the symbology identifier of ISO/IEC 15424 which a reader
+    /// reports for the bits, i.e. `]Q1` to `]Q6` depending on whether ECI and
+    /// FNC1 are used.
+    pub(crate) fn symbology_identifier(&self) -> &'static str {
+        let has_mode = |mode| self.headers.iter().any(|header| header.mode == mode);
+        let has_fnc1_first = has_mode(ExtendedMode::Fnc1First);
+        let has_fnc1_second = has_mode(ExtendedMode::Fnc1Second);
+        match (has_fnc1_first, has_fnc1_second, has_mode(ExtendedMode::Eci)) {
+            (true, _, false) => "]Q3",
+            (true, _, true) => "]Q4",
+            (false, true, false) => "]Q5",
+            (false, true, true) => "]Q6",
+            (false, false, true) => "]Q2",
+            (false, false, false) => "]Q1",
+        }
+    }
+}
+
+#[cfg(test)]
+mod symbology_identifier_tests {
+    use super::*;
+
+    #[test]
+    fn test_symbology_identifier() {
+        let identifier = |eci: bool, fnc1: Option<bool>| {
+            let mut bits = Bits::new(Version::Normal(1));
+            if eci {
+                bits.push_eci_designator(26).unwrap();
+            }
+            match fnc1 {
+                Some(true) => bits.push_fnc1_first_position().unwrap(),
+                Some(false) => bits.push_fnc1_second_position(37).unwrap(),
+                None => {}
+            }
+            bits.push_byte_data(b"a").unwrap();
+            bits.symbology_identifier()
+        };
+        assert_eq!(identifier(false, None), "]Q1");
+        assert_eq!(identifier(true, None), "]Q2");
+        assert_eq!(identifier(false, Some(true)), "]Q3");
+        assert_eq!(identifier(true, Some(true)), "]Q4");
+        assert_eq!(identifier(false, Some(false)), "]Q5");
+        assert_eq!(identifier(true, Some(false)), "]Q6");
+    }
+
+    #[test]
+    fn test_failed_push() {
+        let mut bits = Bits::new(Version::Micro(2));
+        assert!(bits.push_eci_designator(26).is_err());
+        assert_eq!(bits.symbology_identifier(), "]Q1");
     }
 }
 
@@ -431,9 +572,23 @@ mod eci_tests {
 impl Bits {
     fn push_header(&mut self, mode: Mode, raw_data_len: usize) -> QrResult<()> {
         let length_bits = mode.length_bits_count(self.version);
-        self.reserve(length_bits + 4 + mode.data_bits_count(raw_data_len));
+        let data_bits = mode.data_bits_count(raw_data_len);
+        self.reserve(length_bits + 4 + data_bits);
         self.push_mode_indicator(ExtendedMode::Data(mode))?;
-        self.push_number_checked(length_bits, raw_data_len)?;
+        // The character count indicator can hold the length of any data which
+        // fits in the version.
+        if raw_data_len >= 1 << length_bits {
+            return Err(QrError::DataTooLong {
+                bits_needed: self.len() + length_bits + data_bits,
+                bits_available: self.max_capacity(),
+            });
+        }
+        self.push_number(length_bits, raw_data_len.as_u16());
+        let end = self.len();
+        if let Some(header) = self.headers.last_mut() {
+            header.char_count = Some(raw_data_len);
+            header.end = end;
+        }
         Ok(())
     }
 
@@ -456,12 +611,72 @@ impl Bits {
         }
         Ok(())
     }
+
+    /// Encodes an integer to the bits in [`Mode::Numeric`], without
+    /// formatting it as a string first.
+    ///
+    /// Since the number is written in decimal without leading zeros, use
+    /// [`Bits::push_numeric_data`] if leading zeros are significant.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] on overflow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{Version, bits::Bits};
+    /// #
+    /// let mut bits = Bits::new(Version::Normal(1));
+    /// bits.push_numeric(1_234_567).unwrap();
+    ///
+    /// let mut expected = Bits::new(Version::Normal(1));
+    /// expected.push_numeric_data(b"1234567").unwrap();
+    /// assert_eq!(bits.into_bytes(), expected.into_bytes());
+    /// ```
+    pub fn push_numeric(&mut self, number: u128) -> QrResult<()> {
+        // `u128::MAX` has 39 digits.
+        let mut digits = [0_u8; 39];
+        let mut start = digits.len();
+        let mut rest = number;
+        loop {
+            start -= 1;
+            digits[start] = (rest % 10).to_le_bytes()[0];
+            rest /= 10;
+            if rest == 0 {
+                break;
+            }
+        }
+        let digits = &digits[start..];
+        self.push_header(Mode::Numeric, digits.len())?;
+        for chunk in digits.chunks(3) {
+            let number = chunk.iter().fold(0, |a, b| a * 10 + u16::from(*b));
+            self.push_number(chunk.len() * 3 + 1, number);
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod numeric_tests {
     use super::*;
 
+    #[test]
+    fn test_push_numeric() {
+        for (number, digits) in [
+            (0, &b"0"[..]),
+            (7, b"7"),
+            (1000, b"1000"),
+            (u128::MAX, b"340282366920938463463374607431768211455"),
+        ] {
+            let mut bits = Bits::new(Version::Normal(1));
+            assert_eq!(bits.push_numeric(number), Ok(()));
+            let mut expected = Bits::new(Version::Normal(1));
+            expected.push_numeric_data(digits).unwrap();
+            assert_eq!(bits.into_bytes(), expected.into_bytes());
+        }
+    }
+
     #[test]
     fn test_iso_18004_2006_example_1() {
         let mut bits = Bits::new(Version::Normal(1));
@@ -523,7 +738,10 @@ mod numeric_tests {
         let mut bits = Bits::new(Version::Micro(1));
         assert_eq!(
             bits.push_numeric_data(b"12345678"),
-            Err(QrError::DataTooLong)
+            Err(QrError::DataTooLong {
+                bits_needed: 30,
+                bits_available: 20
+            })
         );
     }
 }
@@ -553,6 +771,15 @@ fn alphanumeric_digit(character: u8) -> u16 {
     }
 }
 
+/// Returns `true` if the character can be encoded in [`Mode::Alphanumeric`].
+#[inline]
+const fn is_alphanumeric(character: u8) -> bool {
+    matches!(
+        character,
+        b'0'..=b'9' | b'A'..=b'Z' | b' ' | b'$' | b'%' | b'*' | b'+' | b'-' | b'.' | b'/' | b':'
+    )
+}
+
 impl Bits {
     /// Encodes an alphanumeric string to the bits.
     ///
@@ -602,7 +829,7 @@ mod alphanumeric_tests {
         let mut bits = Bits::new(Version::Micro(1));
         assert_eq!(
             bits.push_alphanumeric_data(b"A"),
-            Err(QrError::UnsupportedCharacterSet)
+            Err(QrError::UnsupportedMode(Mode::Alphanumeric))
         );
     }
 
@@ -611,11 +838,200 @@ mod alphanumeric_tests {
         let mut bits = Bits::new(Version::Micro(2));
         assert_eq!(
             bits.push_alphanumeric_data(b"ABCDEFGH"),
-            Err(QrError::DataTooLong)
+            Err(QrError::DataTooLong {
+                bits_needed: 48,
+                bits_available: 40
+            })
         );
     }
 }
 
+/// A record of the lowercase letters converted to uppercase by
+/// [`coerce_uppercase`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CaseCoercion {
+    positions: Vec<usize>,
+}
+
+impl CaseCoercion {
+    /// Returns the byte offsets of the letters which have been converted.
+    #[must_use]
+    #[inline]
+    pub fn positions(&self) -> &[usize] {
+        &self.positions
+    }
+
+    /// Returns the number of letters which have been converted.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns [`true`] if the data has not been changed.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+}
+
+/// Converts the lowercase letters `a` to `z` of the data into uppercase, so
+/// that the data can be encoded in [`Mode::Alphanumeric`].
+///
+/// Only use this if the payload is known to be case-insensitive, e.g. hex
+/// serials or bech32 strings. Other bytes are left unchanged, but the data
+/// should be ASCII, since a lowercase letter can also be the second byte of a
+/// Shift-JIS character.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::bits;
+/// #
+/// let (data, coercion) = bits::coerce_uppercase(b"deadBEEF");
+/// assert_eq!(data, b"DEADBEEF");
+/// assert_eq!(coercion.positions(), [0, 1, 2, 3]);
+/// ```
+#[must_use]
+pub fn coerce_uppercase(data: &[u8]) -> (Vec<u8>, CaseCoercion) {
+    let positions = data
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| b.is_ascii_lowercase())
+        .map(|(i, _)| i)
+        .collect();
+    (data.to_ascii_uppercase(), CaseCoercion { positions })
+}
+
+/// Converts the scheme and the host of a URL into uppercase, and returns
+/// whether the whole URL can then be encoded in [`Mode::Alphanumeric`].
+///
+/// The scheme and the host are case-insensitive (RFC 3986, Section 6.2.2.1),
+/// so this does not change where the URL points to. The user information, the
+/// port, the path, the query and the fragment are left unchanged, since they
+/// can be case-sensitive. If the data does not start with a scheme, it is
+/// returned unchanged.
+///
+/// The URL only fits [`Mode::Alphanumeric`] if the rest of it consists of
+/// uppercase letters, digits and the characters ` $%*+-./:`, but then the
+/// symbol is often one or two versions smaller.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::bits;
+/// #
+/// let (url, is_alphanumeric) = bits::normalize_url(b"https://Example.com/ABC-123");
+/// assert_eq!(url, b"HTTPS://EXAMPLE.COM/ABC-123");
+/// assert!(is_alphanumeric);
+///
+/// let (url, is_alphanumeric) = bits::normalize_url(b"https://user@example.com:8080/a?b=c");
+/// assert_eq!(url, b"HTTPS://user@EXAMPLE.COM:8080/a?b=c");
+/// assert!(!is_alphanumeric);
+/// ```
+#[must_use]
+pub fn normalize_url(url: &[u8]) -> (Vec<u8>, bool) {
+    let mut data = url.to_vec();
+    let scheme_len = url.iter().position(|&b| b == b':').filter(|&len| {
+        url[0].is_ascii_alphabetic()
+            && url[..len]
+                .iter()
+                .all(|&b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.'))
+    });
+    if let Some(scheme_len) = scheme_len {
+        data[..scheme_len].make_ascii_uppercase();
+        let rest = &url[scheme_len + 1..];
+        if let Some(authority) = rest.strip_prefix(b"//") {
+            let start = scheme_len + 3;
+            let authority_len = authority
+                .iter()
+                .position(|&b| matches!(b, b'/' | b'?' | b'#'))
+                .unwrap_or(authority.len());
+            let authority = &authority[..authority_len];
+            let host_start = authority
+                .iter()
+                .rposition(|&b| b == b'@')
+                .map_or(0, |i| i + 1);
+            let host = &authority[host_start..];
+            // The port follows the last colon, unless it is inside an IPv6
+            // address.
+            let host_len = match host.iter().rposition(|&b| b == b':') {
+                Some(i) if !host[i..].contains(&b']') => i,
+                _ => host.len(),
+            };
+            data[start + host_start..start + host_start + host_len].make_ascii_uppercase();
+        }
+    }
+    let is_alphanumeric = data.iter().all(|&b| {
+        b.is_ascii_digit()
+            || b.is_ascii_uppercase()
+            || matches!(
+                b,
+                b' ' | b'$' | b'%' | b'*' | b'+' | b'-' | b'.' | b'/' | b':'
+            )
+    });
+    (data, is_alphanumeric)
+}
+
+#[cfg(test)]
+mod normalize_url_tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_url() {
+        for (url, expected, is_alphanumeric) in [
+            (&b"http://example.com"[..], &b"HTTP://EXAMPLE.COM"[..], true),
+            (b"https://example.com/", b"HTTPS://EXAMPLE.COM/", true),
+            (
+                b"https://example.com/path",
+                b"HTTPS://EXAMPLE.COM/path",
+                false,
+            ),
+            (b"http://[::1]:80/A", b"HTTP://[::1]:80/A", false),
+            (b"http://[fe80::a]/X", b"HTTP://[FE80::A]/X", false),
+            (b"mailto:a@b.com", b"MAILTO:a@b.com", false),
+            (b"example.com/x", b"example.com/x", false),
+            (b"1http://a", b"1http://a", false),
+            (b"", b"", true),
+        ] {
+            let (data, fits) = normalize_url(url);
+            assert_eq!(data, expected);
+            assert_eq!(fits, is_alphanumeric);
+        }
+    }
+
+    #[test]
+    fn test_smaller_version() {
+        let url = b"https://example.com/ABCDEFGHIJKLMN";
+        let (data, is_alphanumeric) = normalize_url(url);
+        assert!(is_alphanumeric);
+        let before = encode_auto(url, EcLevel::M).unwrap().version();
+        let after = encode_auto(&data, EcLevel::M).unwrap().version();
+        assert!(after.width() < before.width());
+    }
+}
+
+#[cfg(test)]
+mod coerce_uppercase_tests {
+    use super::*;
+
+    #[test]
+    fn test_coerce_uppercase() {
+        let (data, coercion) = coerce_uppercase(b"ln1q-z:\xe9");
+        assert_eq!(data, b"LN1Q-Z:\xe9");
+        assert_eq!(coercion.positions(), [0, 1, 3, 5]);
+        assert_eq!(coercion.len(), 4);
+    }
+
+    #[test]
+    fn test_unchanged() {
+        let (data, coercion) = coerce_uppercase(b"HELLO 123");
+        assert_eq!(data, b"HELLO 123");
+        assert!(coercion.is_empty());
+    }
+}
+
 // `Mode::Byte` mode
 
 impl Bits {
@@ -631,12 +1047,73 @@ impl Bits {
         }
         Ok(())
     }
+
+    /// Encodes 8-bit byte data split into multiple chunks to the bits, as a
+    /// single segment.
+    ///
+    /// This is the same as [`Bits::push_byte_data`] with the concatenation of
+    /// the chunks, but does not need to copy the data into a contiguous
+    /// buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] on overflow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{Version, bits::Bits};
+    /// #
+    /// let mut bits = Bits::new(Version::Normal(1));
+    /// bits.push_byte_data_chunks([&b"Hello, "[..], b"world!"])
+    ///     .unwrap();
+    ///
+    /// let mut expected = Bits::new(Version::Normal(1));
+    /// expected.push_byte_data(b"Hello, world!").unwrap();
+    /// assert_eq!(bits.into_bytes(), expected.into_bytes());
+    /// ```
+    pub fn push_byte_data_chunks<'a, I>(&mut self, chunks: I) -> QrResult<()>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        let chunks: Vec<_> = chunks.into_iter().collect();
+        let len = chunks.iter().map(|chunk| chunk.len()).sum();
+        self.push_header(Mode::Byte, len)?;
+        for b in chunks.into_iter().flatten() {
+            self.push_number(8, u16::from(*b));
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod byte_tests {
     use super::*;
 
+    #[test]
+    fn test_chunks() {
+        let mut bits = Bits::new(Version::Normal(1));
+        let chunks: [&[u8]; 3] = [b"\x12\x34", b"", b"\x56\x78\x9a\xbc\xde\xf0"];
+        assert_eq!(bits.push_byte_data_chunks(chunks), Ok(()));
+        let mut expected = Bits::new(Version::Normal(1));
+        expected
+            .push_byte_data(b"\x12\x34\x56\x78\x9a\xbc\xde\xf0")
+            .unwrap();
+        assert_eq!(bits.into_bytes(), expected.into_bytes());
+    }
+
+    #[test]
+    fn test_chunks_too_long() {
+        let mut bits = Bits::new(Version::Micro(3));
+        assert_eq!(
+            bits.push_byte_data_chunks([&b"01234567"[..], b"01234567"]),
+            Err(QrError::DataTooLong {
+                bits_needed: 134,
+                bits_available: 84
+            })
+        );
+    }
+
     #[test]
     fn test() {
         let mut bits = Bits::new(Version::Normal(1));
@@ -666,7 +1143,7 @@ mod byte_tests {
         let mut bits = Bits::new(Version::Micro(2));
         assert_eq!(
             bits.push_byte_data(b"?"),
-            Err(QrError::UnsupportedCharacterSet)
+            Err(QrError::UnsupportedMode(Mode::Byte))
         );
     }
 
@@ -675,26 +1152,76 @@ mod byte_tests {
         let mut bits = Bits::new(Version::Micro(3));
         assert_eq!(
             bits.push_byte_data(b"0123456701234567"),
-            Err(QrError::DataTooLong)
+            Err(QrError::DataTooLong {
+                bits_needed: 134,
+                bits_available: 84
+            })
         );
     }
 }
 
 // `Mode::Kanji` mode
 
+/// Checks whether the data only consists of Shift JIS double-byte characters
+/// supported by [`Mode::Kanji`], i.e. in the ranges 0x8140–0x9FFC and
+/// 0xE040–0xEBBF, excluding the second byte 0x7F.
+///
+/// # Errors
+///
+/// Returns [`Err`] with [`QrError::InvalidKanji`] identifying the first invalid
+/// character.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{bits, types::QrError};
+/// #
+/// assert_eq!(bits::validate_kanji(b"\x93\x5f\xe4\xaa"), Ok(()));
+/// assert_eq!(
+///     bits::validate_kanji(b"\x93\x5f\x41\x42"),
+///     Err(QrError::InvalidKanji {
+///         offset: 2,
+///         pair: (0x41, Some(0x42))
+///     })
+/// );
+/// ```
+pub fn validate_kanji(data: &[u8]) -> QrResult<()> {
+    for (i, kanji) in data.chunks(2).enumerate() {
+        let offset = i * 2;
+        let [first, second] = *kanji else {
+            return Err(QrError::InvalidKanji {
+                offset,
+                pair: (kanji[0], None),
+            });
+        };
+        let is_valid = match first {
+            0x81..=0x9f | 0xe0..=0xea => matches!(second, 0x40..=0x7e | 0x80..=0xfc),
+            0xeb => matches!(second, 0x40..=0x7e | 0x80..=0xbf),
+            _ => false,
+        };
+        if !is_valid {
+            return Err(QrError::InvalidKanji {
+                offset,
+                pair: (first, Some(second)),
+            });
+        }
+    }
+    Ok(())
+}
+
 impl Bits {
     /// Encodes Shift JIS double-byte data to the bits.
     ///
     /// # Errors
     ///
-    /// Returns [`Err`] on overflow, or if the data is not Shift JIS double-byte
-    /// data (e.g. if the length of data is not an even number).
+    /// Returns [`Err`] on overflow, or with [`QrError::InvalidKanji`] if the
+    /// data is not Shift JIS double-byte data (e.g. if the length of data is
+    /// not an even number). The data is validated with [`validate_kanji`]
+    /// before anything is pushed.
     pub fn push_kanji_data(&mut self, data: &[u8]) -> QrResult<()> {
+        validate_kanji(data)?;
         self.push_header(Mode::Kanji, data.len() / 2)?;
         for kanji in data.chunks(2) {
-            if kanji.len() != 2 {
-                return Err(QrError::InvalidCharacter);
-            }
             let cp = u16::from(kanji[0]) * 256 + u16::from(kanji[1]);
             let bytes = if cp < 0xe040 {
                 cp - 0x8140
@@ -732,17 +1259,47 @@ mod kanji_tests {
     fn test_micro_qr_unsupported() {
         let mut bits = Bits::new(Version::Micro(2));
         assert_eq!(
-            bits.push_kanji_data(b"?"),
-            Err(QrError::UnsupportedCharacterSet)
+            bits.push_kanji_data(b"\x93\x5f"),
+            Err(QrError::UnsupportedMode(Mode::Kanji))
         );
     }
 
+    #[test]
+    fn test_invalid_kanji() {
+        let mut bits = Bits::new(Version::Normal(1));
+        assert_eq!(
+            bits.push_kanji_data(b"\x93\x5f\xeb\xc0"),
+            Err(QrError::InvalidKanji {
+                offset: 2,
+                pair: (0xeb, Some(0xc0))
+            })
+        );
+        assert_eq!(
+            bits.push_kanji_data(b"\x93\x7f"),
+            Err(QrError::InvalidKanji {
+                offset: 0,
+                pair: (0x93, Some(0x7f))
+            })
+        );
+        assert_eq!(
+            bits.push_kanji_data(b"\x93\x5f\x93"),
+            Err(QrError::InvalidKanji {
+                offset: 2,
+                pair: (0x93, None)
+            })
+        );
+        assert!(bits.is_empty());
+    }
+
     #[test]
     fn test_data_too_long() {
         let mut bits = Bits::new(Version::Micro(3));
         assert_eq!(
             bits.push_kanji_data(b"\x93_\x93_\x93_\x93_\x93_\x93_\x93_\x93_"),
-            Err(QrError::DataTooLong)
+            Err(QrError::DataTooLong {
+                bits_needed: 109,
+                bits_available: 84
+            })
         );
     }
 }
@@ -810,6 +1367,91 @@ impl Bits {
     }
 }
 
+// Structured append
+
+impl Bits {
+    /// Encodes a structured append header, which links this symbol to other
+    /// symbols holding the rest of the data.
+    ///
+    /// `index` is the position of this symbol starting from 0, `total` is the
+    /// number of symbols, and `parity` is the parity of the whole data, which
+    /// can be computed with [`structured_append_parity`]. This header must be
+    /// at the beginning of the bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the version is not a normal QR code, or if `total`
+    /// is not in `1..=16` or `index` is not less than `total`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{Version, bits, bits::Bits};
+    /// #
+    /// let data = b"ABCDEFGHIJ";
+    /// let parity = bits::structured_append_parity(data);
+    /// let mut bits = Bits::new(Version::Normal(1));
+    /// bits.push_structured_append(0, 2, parity).unwrap();
+    /// bits.push_alphanumeric_data(&data[..5]).unwrap();
+    /// ```
+    pub fn push_structured_append(&mut self, index: u8, total: u8, parity: u8) -> QrResult<()> {
+        if !(1..=16).contains(&total) || index >= total {
+            return Err(QrError::InvalidStructuredAppend);
+        }
+        self.push_mode_indicator(ExtendedMode::StructuredAppend)?;
+        self.push_number(4, u16::from(index));
+        self.push_number(4, u16::from(total - 1));
+        self.push_number(8, u16::from(parity));
+        Ok(())
+    }
+}
+
+/// Computes the parity of the whole data split across structured append
+/// symbols, i.e. all bytes combined with XOR.
+#[must_use]
+pub fn structured_append_parity(data: &[u8]) -> u8 {
+    data.iter().fold(0, |parity, b| parity ^ b)
+}
+
+#[cfg(test)]
+mod structured_append_tests {
+    use super::*;
+
+    #[test]
+    fn test_push_structured_append() {
+        let mut bits = Bits::new(Version::Normal(1));
+        assert_eq!(bits.push_structured_append(2, 4, 0x5a), Ok(()));
+        assert_eq!(
+            bits.into_bytes(),
+            vec![0b0011_0010, 0b0011_0101, 0b1010_0000]
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        let mut bits = Bits::new(Version::Normal(1));
+        assert_eq!(
+            bits.push_structured_append(4, 4, 0),
+            Err(QrError::InvalidStructuredAppend)
+        );
+        assert_eq!(
+            bits.push_structured_append(0, 17, 0),
+            Err(QrError::InvalidStructuredAppend)
+        );
+        let mut bits = Bits::new(Version::Micro(4));
+        assert_eq!(
+            bits.push_structured_append(0, 2, 0),
+            Err(QrError::UnsupportedCharacterSet)
+        );
+    }
+
+    #[test]
+    fn test_parity() {
+        assert_eq!(structured_append_parity(b""), 0);
+        assert_eq!(structured_append_parity(b"\x01\x02\x04"), 7);
+    }
+}
+
 // Finish
 
 // This table is copied from ISO/IEC 18004:2006 §6.4.10, Table 7, and ISO/IEC
@@ -897,6 +1539,12 @@ static DATA_LENGTHS: [[usize; 4]; 76] = [
 ];
 
 impl Bits {
+    /// Returns the length of the bits before the terminator and the padding,
+    /// or the current length if the bits are not terminated yet.
+    pub(crate) fn payload_len(&self) -> usize {
+        self.terminated_len.unwrap_or_else(|| self.len())
+    }
+
     /// Pushes the ending bits to indicate no more data.
     ///
     /// # Errors
@@ -914,9 +1562,13 @@ impl Bits {
         let cur_length = self.len();
         let data_length = self.max_len(ec_level)?;
         if cur_length > data_length {
-            return Err(QrError::DataTooLong);
+            return Err(QrError::DataTooLong {
+                bits_needed: cur_length,
+                bits_available: data_length,
+            });
         }
 
+        self.terminated_len = Some(cur_length);
         let terminator_size = cmp::min(terminator_size, data_length - cur_length);
         if terminator_size > 0 {
             self.push_number(terminator_size, 0);
@@ -942,13 +1594,52 @@ impl Bits {
 
         Ok(())
     }
-}
-
-#[cfg(test)]
-mod finish_tests {
-    use super::*;
 
-    #[test]
+    /// Raises the error correction level to the highest one at which the data
+    /// still fits in the version, and terminates the bits for that level.
+    ///
+    /// The bits may already be terminated, e.g. by [`encode_auto`], in which
+    /// case the terminator and the padding are replaced. The level is never
+    /// lowered below `ec_level`, and is returned to be passed to
+    /// [`QrCode::with_bits`](crate::QrCode::with_bits).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the data does not fit in the version at `ec_level`,
+    /// or if `ec_level` is not valid for the version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{EcLevel, QrCode, Version, bits};
+    /// #
+    /// let mut bits = bits::encode_auto(b"HELLO WORLD", EcLevel::L).unwrap();
+    /// assert_eq!(bits.version(), Version::Normal(1));
+    /// let ec_level = bits.boost_ec_level(EcLevel::L).unwrap();
+    /// assert_eq!(ec_level, EcLevel::Q);
+    /// let code = QrCode::with_bits(bits, ec_level).unwrap();
+    /// ```
+    pub fn boost_ec_level(&mut self, ec_level: EcLevel) -> QrResult<EcLevel> {
+        if let Some(len) = self.terminated_len.take() {
+            self.data.truncate(len.div_ceil(8));
+            self.bit_offset = len % 8;
+        }
+        let len = self.len();
+        let boosted = [EcLevel::H, EcLevel::Q, EcLevel::M, EcLevel::L]
+            .into_iter()
+            .take_while(|level| *level > ec_level)
+            .find(|level| self.max_len(*level).is_ok_and(|max_len| len <= max_len))
+            .unwrap_or(ec_level);
+        self.push_terminator(boosted)?;
+        Ok(boosted)
+    }
+}
+
+#[cfg(test)]
+mod finish_tests {
+    use super::*;
+
+    #[test]
     fn test_hello_world() {
         let mut bits = Bits::new(Version::Normal(1));
         assert_eq!(bits.push_alphanumeric_data(b"HELLO WORLD"), Ok(()));
@@ -977,7 +1668,41 @@ mod finish_tests {
     fn test_too_long() {
         let mut bits = Bits::new(Version::Micro(1));
         assert_eq!(bits.push_numeric_data(b"9999999"), Ok(()));
-        assert_eq!(bits.push_terminator(EcLevel::L), Err(QrError::DataTooLong));
+        assert_eq!(
+            bits.push_terminator(EcLevel::L),
+            Err(QrError::DataTooLong {
+                bits_needed: 27,
+                bits_available: 20
+            })
+        );
+    }
+
+    #[test]
+    fn test_boost_ec_level() {
+        let mut bits = Bits::new(Version::Normal(1));
+        bits.push_alphanumeric_data(b"HELLO WORLD").unwrap();
+        bits.push_terminator(EcLevel::L).unwrap();
+        assert_eq!(bits.boost_ec_level(EcLevel::L), Ok(EcLevel::Q));
+        let mut expected = Bits::new(Version::Normal(1));
+        expected.push_alphanumeric_data(b"HELLO WORLD").unwrap();
+        expected.push_terminator(EcLevel::Q).unwrap();
+        assert_eq!(bits.payload_len(), expected.payload_len());
+        assert_eq!(bits.into_bytes(), expected.into_bytes());
+
+        // The version does not support any higher level.
+        let mut bits = Bits::new(Version::Micro(1));
+        bits.push_numeric_data(b"1").unwrap();
+        assert_eq!(bits.boost_ec_level(EcLevel::L), Ok(EcLevel::L));
+
+        let mut bits = Bits::new(Version::Micro(1));
+        bits.push_numeric_data(b"9999999").unwrap();
+        assert_eq!(
+            bits.boost_ec_level(EcLevel::L),
+            Err(QrError::DataTooLong {
+                bits_needed: 27,
+                bits_available: 20
+            })
+        );
     }
 
     #[test]
@@ -1013,8 +1738,694 @@ mod finish_tests {
     }
 }
 
+// Segments
+
+/// A header pushed to the bits: the mode indicator, and the character count
+/// indicator if it is a data segment.
+#[derive(Clone, Copy, Debug)]
+struct Header {
+    /// The position of the mode indicator.
+    start: usize,
+    mode: ExtendedMode,
+    char_count: Option<usize>,
+    /// The position after the header.
+    end: usize,
+}
+
+/// A segment pushed to the bits, see [`Bits::segments`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SegmentInfo {
+    /// The mode indicator of the segment.
+    pub mode: ExtendedMode,
+
+    /// The number of characters of a data segment, or [`None`] for ECI, FNC1
+    /// and structured append headers.
+    pub char_count: Option<usize>,
+
+    /// The position of the first bit of the segment.
+    pub start: usize,
+
+    /// The number of bits of the segment, including the mode indicator and
+    /// the character count indicator.
+    pub len: usize,
+}
+
+impl Bits {
+    /// Returns the segments pushed so far, in order.
+    ///
+    /// Each segment starts with a mode indicator, and extends to the next
+    /// segment or to the end of the data, excluding the terminator and the
+    /// padding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{
+    /// #     Version,
+    /// #     bits::{Bits, ExtendedMode},
+    /// #     types::Mode,
+    /// # };
+    /// #
+    /// let mut bits = Bits::new(Version::Normal(1));
+    /// bits.push_eci_designator(26).unwrap();
+    /// bits.push_byte_data(b"abc").unwrap();
+    /// let segments = bits.segments().collect::<Vec<_>>();
+    /// assert_eq!(segments.len(), 2);
+    /// assert_eq!(segments[0].mode, ExtendedMode::Eci);
+    /// assert_eq!(segments[0].len, 12);
+    /// assert_eq!(segments[1].mode, ExtendedMode::Data(Mode::Byte));
+    /// assert_eq!(segments[1].char_count, Some(3));
+    /// assert_eq!(segments[1].len, 4 + 8 + 24);
+    /// ```
+    pub fn segments(&self) -> impl Iterator<Item = SegmentInfo> + '_ {
+        let data_end = self.terminated_len.unwrap_or_else(|| self.len());
+        let ends = self
+            .headers
+            .iter()
+            .skip(1)
+            .map(|header| header.start)
+            .chain([data_end]);
+        self.headers
+            .iter()
+            .zip(ends)
+            .map(|(header, end)| SegmentInfo {
+                mode: header.mode,
+                char_count: header.char_count,
+                start: header.start,
+                len: end - header.start,
+            })
+    }
+
+    /// Returns the number of characters in the mode which can still be pushed
+    /// as one more segment before [`Bits::push_terminator`] fails, including
+    /// the mode indicator and the character count indicator of the segment.
+    ///
+    /// This uses the same definition of a character as
+    /// [`Version::capacity`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if it is not valid to use the `ec_level` for the given
+    /// version, or if the version does not support the mode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{EcLevel, Version, bits::Bits, types::Mode};
+    /// #
+    /// let mut bits = Bits::new(Version::Normal(1));
+    /// assert_eq!(bits.remaining_capacity(Mode::Byte, EcLevel::M), Ok(14));
+    ///
+    /// bits.push_numeric_data(b"01234567").unwrap();
+    /// assert_eq!(bits.remaining_capacity(Mode::Byte, EcLevel::M), Ok(9));
+    /// ```
+    pub fn remaining_capacity(&self, mode: Mode, ec_level: EcLevel) -> QrResult<usize> {
+        let remaining_len = self.remaining_len(ec_level)?;
+        if !self.version.supports_mode(mode) {
+            return Err(QrError::UnsupportedMode(mode));
+        }
+        Ok(mode.chars_fitting(self.version, remaining_len))
+    }
+}
+
+#[cfg(test)]
+mod segments_tests {
+    use super::*;
+
+    #[test]
+    fn test_segments() {
+        let mut bits = Bits::new(Version::Micro(3));
+        bits.push_numeric_data(b"12345").unwrap();
+        bits.push_alphanumeric_data(b"AB").unwrap();
+        bits.push_terminator(EcLevel::L).unwrap();
+        assert_eq!(
+            bits.segments().collect::<Vec<_>>(),
+            [
+                SegmentInfo {
+                    mode: ExtendedMode::Data(Mode::Numeric),
+                    char_count: Some(5),
+                    start: 0,
+                    len: 2 + 5 + 17,
+                },
+                SegmentInfo {
+                    mode: ExtendedMode::Data(Mode::Alphanumeric),
+                    char_count: Some(2),
+                    start: 24,
+                    len: 2 + 4 + 11,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_micro_1_segments() {
+        // M1 has no mode indicator.
+        let mut bits = Bits::new(Version::Micro(1));
+        bits.push_numeric_data(b"1").unwrap();
+        bits.push_numeric_data(b"23").unwrap();
+        let segments = bits.segments().collect::<Vec<_>>();
+        assert_eq!(segments[0].char_count, Some(1));
+        assert_eq!(segments[0].len, 3 + 4);
+        assert_eq!(segments[1].char_count, Some(2));
+        assert_eq!(segments[1].start, 7);
+    }
+
+    #[test]
+    fn test_remaining_capacity_fits() {
+        // Pushing the reported number of characters always fits, and one more
+        // never does.
+        for mode in [Mode::Numeric, Mode::Alphanumeric, Mode::Byte, Mode::Kanji] {
+            for prefix in 0..20 {
+                let mut bits = Bits::new(Version::Normal(1));
+                bits.push_numeric_data(&b"0123456789012345678901"[..prefix])
+                    .unwrap();
+                let capacity = bits.remaining_capacity(mode, EcLevel::Q).unwrap();
+                let fits = |len: usize| {
+                    let mut bits = Bits::new(Version::Normal(1));
+                    bits.push_numeric_data(&b"0123456789012345678901"[..prefix])
+                        .unwrap();
+                    let pushed = match mode {
+                        Mode::Numeric => bits.push_numeric_data(&vec![b'1'; len]),
+                        Mode::Alphanumeric => bits.push_alphanumeric_data(&vec![b'A'; len]),
+                        Mode::Byte => bits.push_byte_data(&vec![b'a'; len]),
+                        Mode::Kanji => bits.push_kanji_data(&b"\x93\x5f".repeat(len)),
+                    };
+                    pushed.is_ok() && bits.push_terminator(EcLevel::Q).is_ok()
+                };
+                assert!(fits(capacity), "{mode:?} {prefix}");
+                assert!(!fits(capacity + 1), "{mode:?} {prefix}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_remaining_capacity_errors() {
+        let bits = Bits::new(Version::Micro(1));
+        assert_eq!(
+            bits.remaining_capacity(Mode::Byte, EcLevel::L),
+            Err(QrError::UnsupportedMode(Mode::Byte))
+        );
+        assert!(bits.remaining_capacity(Mode::Numeric, EcLevel::H).is_err());
+    }
+}
+
+// Dynamic version
+
+impl Bits {
+    /// Constructs a new, empty bits structure whose version is chosen after the
+    /// data is pushed, by
+    /// [`QrCode::with_bits_auto`](crate::QrCode::with_bits_auto).
+    ///
+    /// The data is pushed to the largest version of the `kind`, and the header
+    /// of each segment is recorded, so that the bits can be re-encoded in any
+    /// smaller version. This allows ECI designators, FNC1 indicators and
+    /// explicit segments without guessing the version up front.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{
+    /// #     Version,
+    /// #     bits::Bits,
+    /// #     types::VersionKind,
+    /// # };
+    /// #
+    /// let bits = Bits::new_dynamic(VersionKind::Normal);
+    /// assert_eq!(bits.version(), Version::Normal(40));
+    /// ```
+    #[must_use]
+    pub fn new_dynamic(kind: VersionKind) -> Self {
+        Self {
+            dynamic: true,
+            ..Self::new(kind.largest())
+        }
+    }
+
+    /// Returns the number in the `n` bits starting at `start`.
+    fn read_number(&self, start: usize, n: usize) -> u16 {
+        (start..start + n).fold(0, |number, i| {
+            (number << 1) | u16::from((self.data[i / 8] >> (7 - i % 8)) & 1)
+        })
+    }
+
+    /// Pushes the bits of `other` in the range from `start` to `end`.
+    fn push_bits_of(&mut self, other: &Self, start: usize, end: usize) {
+        self.reserve(end - start);
+        let mut position = start;
+        while position < end {
+            let n = cmp::min(end - position, 16);
+            self.push_number(n, other.read_number(position, n));
+            position += n;
+        }
+    }
+
+    /// Re-encodes the recorded headers for `version`, copying the bits between
+    /// them unchanged.
+    fn reencode(&self, version: Version) -> QrResult<Self> {
+        let mut bits = Self::new(version);
+        let mut position = 0;
+        for header in &self.headers {
+            bits.push_bits_of(self, position, header.start);
+            match (header.mode, header.char_count) {
+                (ExtendedMode::Data(mode), Some(char_count)) => {
+                    bits.push_header(mode, char_count)?;
+                }
+                (mode, _) => bits.push_mode_indicator(mode)?,
+            }
+            position = header.end;
+        }
+        let end = self.terminated_len.unwrap_or_else(|| self.len());
+        bits.push_bits_of(self, position, end);
+        Ok(bits)
+    }
+
+    /// Re-encodes the bits from [`Bits::new_dynamic`] in the smallest version
+    /// of the same kind which the data fits in, and terminates them.
+    ///
+    /// Bits from [`Bits::new`] keep their version.
+    pub(crate) fn finish_smallest(mut self, ec_level: EcLevel) -> QrResult<Self> {
+        if !self.dynamic {
+            if self.terminated_len.is_none() {
+                self.push_terminator(ec_level)?;
+            }
+            return Ok(self);
+        }
+        let mut result = Err(QrError::InvalidVersion { version: None });
+        for version in self.version.kind().versions() {
+            result = self.reencode(version).and_then(|mut bits| {
+                bits.push_terminator(ec_level)?;
+                Ok(bits)
+            });
+            if result.is_ok() {
+                break;
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod dynamic_tests {
+    use super::*;
+
+    #[test]
+    fn test_same_as_fixed_version() {
+        let push = |bits: &mut Bits| {
+            bits.push_eci_designator(26).unwrap();
+            bits.push_byte_data(b"Hello, ").unwrap();
+            bits.push_numeric(12_345_678).unwrap();
+        };
+        let mut bits = Bits::new_dynamic(VersionKind::Normal);
+        push(&mut bits);
+        let bits = bits.finish_smallest(EcLevel::M).unwrap();
+        assert_eq!(bits.version(), Version::Normal(1));
+        assert_eq!(bits.symbology_identifier(), "]Q2");
+
+        let mut expected = Bits::new(Version::Normal(1));
+        push(&mut expected);
+        expected.push_terminator(EcLevel::M).unwrap();
+        assert_eq!(bits.into_bytes(), expected.into_bytes());
+    }
+
+    #[test]
+    fn test_count_bits_shrink() {
+        // The character count indicators of version 40 are longer, so the data
+        // only fits version 1 after re-encoding.
+        let mut bits = Bits::new_dynamic(VersionKind::Normal);
+        bits.push_fnc1_first_position().unwrap();
+        bits.push_numeric_data(b"0109501101020917").unwrap();
+        bits.push_byte_data(b"abcdefg").unwrap();
+        assert_eq!(bits.len(), 4 + 4 + 14 + 54 + 4 + 16 + 56);
+        let bits = bits.finish_smallest(EcLevel::L).unwrap();
+        assert_eq!(bits.version(), Version::Normal(1));
+        assert_eq!(bits.symbology_identifier(), "]Q3");
+    }
+
+    #[test]
+    fn test_micro_modes() {
+        let mut bits = Bits::new_dynamic(VersionKind::Micro);
+        bits.push_numeric_data(b"123").unwrap();
+        let numeric = bits.finish_smallest(EcLevel::L).unwrap();
+        assert_eq!(numeric.version(), Version::Micro(1));
+
+        // M1 does not support alphanumeric mode.
+        let mut bits = Bits::new_dynamic(VersionKind::Micro);
+        bits.push_alphanumeric_data(b"A1").unwrap();
+        let alphanumeric = bits.finish_smallest(EcLevel::L).unwrap();
+        assert_eq!(alphanumeric.version(), Version::Micro(2));
+    }
+
+    #[test]
+    fn test_rect_micro_smallest_area() {
+        let mut bits = Bits::new_dynamic(VersionKind::RectMicro);
+        bits.push_byte_data(b"Hello").unwrap();
+        let bits = bits.finish_smallest(EcLevel::M).unwrap();
+        let expected = encode_auto_rect_micro(b"Hello", EcLevel::M, RectMicroStrategy::Area)
+            .unwrap()
+            .version();
+        assert_eq!(bits.version(), expected);
+    }
+
+    #[test]
+    fn test_too_long() {
+        let mut bits = Bits::new_dynamic(VersionKind::Micro);
+        bits.push_byte_data(&[b'a'; 16]).unwrap();
+        assert_eq!(
+            bits.finish_smallest(EcLevel::L).err(),
+            Some(QrError::DataTooLong {
+                bits_needed: 136,
+                bits_available: 128
+            })
+        );
+    }
+
+    #[test]
+    fn test_fixed_version() {
+        let mut bits = Bits::new(Version::Normal(3));
+        bits.push_byte_data(b"a").unwrap();
+        let bits = bits.finish_smallest(EcLevel::L).unwrap();
+        assert_eq!(bits.version(), Version::Normal(3));
+    }
+}
+
+// Capacity
+
+/// Gets the maximum number of characters which a single segment in the mode
+/// can hold in any version accepted by `version_filter`, at the error
+/// correction level.
+///
+/// This is the largest [`Version::capacity`] among the versions, and is meant
+/// for showing how many characters are left while the data is being typed,
+/// without encoding it.
+///
+/// # Errors
+///
+/// Returns [`Err`] if no version accepted by `version_filter` supports the
+/// mode at the error correction level.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{EcLevel, Version, bits, types::Mode};
+/// #
+/// let len = bits::max_data_len(Mode::Alphanumeric, EcLevel::M, Version::is_micro);
+/// assert_eq!(len, Ok(18));
+///
+/// let len = bits::max_data_len(Mode::Byte, EcLevel::L, |v| {
+///     matches!(v, Version::Normal(1..=10))
+/// });
+/// assert_eq!(len, Ok(271));
+/// ```
+pub fn max_data_len(
+    mode: Mode,
+    ec_level: EcLevel,
+    version_filter: impl Fn(Version) -> bool,
+) -> QrResult<usize> {
+    let rect_micro_versions = Version::RMQR_ALL_HEIGHT.into_iter().flat_map(|height| {
+        Version::RMQR_ALL_WIDTH
+            .into_iter()
+            .map(move |width| Version::RectMicro(height, width))
+    });
+    let mut result = Err(QrError::InvalidVersion { version: None });
+    for version in (1..=40)
+        .map(Version::Normal)
+        .chain((1..=4).map(Version::Micro))
+        .chain(rect_micro_versions.filter(|v| v.is_rect_micro()))
+        .filter(|v| version_filter(*v))
+    {
+        let capacity = version.capacity(mode, ec_level);
+        result = match (result, capacity) {
+            (Ok(max), Ok(len)) => Ok(cmp::max(max, len)),
+            (Ok(max), Err(_)) => Ok(max),
+            (Err(_), capacity) => capacity,
+        };
+    }
+    result
+}
+
+#[cfg(test)]
+mod max_data_len_tests {
+    use super::*;
+
+    #[test]
+    fn test_all_versions() {
+        assert_eq!(max_data_len(Mode::Numeric, EcLevel::L, |_| true), Ok(7089));
+        assert_eq!(max_data_len(Mode::Kanji, EcLevel::H, |_| true), Ok(784));
+        assert_eq!(
+            max_data_len(Mode::Byte, EcLevel::H, Version::is_rect_micro),
+            Ok(Version::RectMicro(17, 139)
+                .capacity(Mode::Byte, EcLevel::H)
+                .unwrap())
+        );
+    }
+
+    #[test]
+    fn test_no_version() {
+        assert_eq!(
+            max_data_len(Mode::Numeric, EcLevel::L, |_| false),
+            Err(QrError::InvalidVersion { version: None })
+        );
+        assert_eq!(
+            max_data_len(Mode::Byte, EcLevel::L, |v| {
+                matches!(v, Version::Micro(1 | 2))
+            }),
+            Err(QrError::UnsupportedMode(Mode::Byte))
+        );
+        assert_eq!(
+            max_data_len(Mode::Numeric, EcLevel::H, Version::is_micro),
+            Err(QrError::InvalidVersion {
+                version: Some(Version::Micro(4))
+            })
+        );
+    }
+}
+
 // Front end
 
+/// An entry of the data stream of a symbol.
+///
+/// Unlike [`Segment`], which only describes a range of the data to encode in a
+/// mode, a list of entries describes the complete data stream including the
+/// ECI, FNC1 and structured append headers, so that it can be stored and
+/// replayed with [`Bits::push_entries`].
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{Version, bits::{Bits, Entry}, types::Mode};
+/// #
+/// let entries = [
+///     Entry::Eci(26),
+///     Entry::Data {
+///         mode: Mode::Byte,
+///         data: "ÆØÅ".into(),
+///     },
+/// ];
+/// let mut bits = Bits::new(Version::Normal(1));
+/// bits.push_entries(&entries).unwrap();
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Entry {
+    /// An ECI designator, see [`Bits::push_eci_designator`].
+    Eci(u32),
+
+    /// An FNC1 indicator in the first position, see
+    /// [`Bits::push_fnc1_first_position`].
+    Fnc1First,
+
+    /// An FNC1 indicator in the second position with the application
+    /// indicator, see [`Bits::push_fnc1_second_position`].
+    Fnc1Second(u8),
+
+    /// A structured append header, see [`Bits::push_structured_append`].
+    StructuredAppend {
+        /// The position of the symbol, starting from 0.
+        index: u8,
+
+        /// The number of symbols.
+        total: u8,
+
+        /// The parity of the whole data.
+        parity: u8,
+    },
+
+    /// The data encoded in the mode.
+    Data {
+        /// The mode of the data.
+        mode: Mode,
+
+        /// The data.
+        data: Vec<u8>,
+    },
+}
+
+impl Bits {
+    /// Pushes an entry to the bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] on overflow, or if the entry is not supported in the
+    /// version or is invalid, e.g. with [`QrError::InvalidCharacter`] if the
+    /// data contains a character which its mode cannot encode.
+    pub fn push_entry(&mut self, entry: &Entry) -> QrResult<()> {
+        match entry {
+            Entry::Eci(designator) => self.push_eci_designator(*designator),
+            Entry::Fnc1First => self.push_fnc1_first_position(),
+            Entry::Fnc1Second(application_indicator) => {
+                self.push_fnc1_second_position(*application_indicator)
+            }
+            Entry::StructuredAppend {
+                index,
+                total,
+                parity,
+            } => self.push_structured_append(*index, *total, *parity),
+            Entry::Data { mode, data } => match mode {
+                Mode::Numeric => {
+                    check_characters(data, u8::is_ascii_digit)?;
+                    self.push_numeric_data(data)
+                }
+                Mode::Alphanumeric => {
+                    check_characters(data, |b| is_alphanumeric(*b))?;
+                    self.push_alphanumeric_data(data)
+                }
+                Mode::Byte => self.push_byte_data(data),
+                Mode::Kanji => self.push_kanji_data(data),
+            },
+        }
+    }
+
+    /// Pushes the entries to the bits in order.
+    ///
+    /// This does not terminate the bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any entry cannot be pushed.
+    pub fn push_entries<'a, I>(&mut self, entries: I) -> QrResult<()>
+    where
+        I: IntoIterator<Item = &'a Entry>,
+    {
+        entries
+            .into_iter()
+            .try_for_each(|entry| self.push_entry(entry))
+    }
+}
+
+/// Checks that every character of the data satisfies `is_valid`.
+fn check_characters(data: &[u8], is_valid: impl Fn(&u8) -> bool) -> QrResult<()> {
+    data.iter()
+        .position(|b| !is_valid(b))
+        .map_or(Ok(()), |offset| Err(QrError::InvalidCharacter { offset }))
+}
+
+/// Converts the segments of the data into entries.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{Version, bits::{self, Entry}, optimize::Parser, types::Mode};
+/// #
+/// let data = b"ABC123456789";
+/// let segments = Parser::new(data).optimize(Version::Normal(1));
+/// let entries = bits::entries_from_segments(data, segments);
+/// assert_eq!(
+///     entries[1],
+///     Entry::Data {
+///         mode: Mode::Numeric,
+///         data: b"123456789".to_vec()
+///     }
+/// );
+/// ```
+#[must_use]
+pub fn entries_from_segments<I>(data: &[u8], segments: I) -> Vec<Entry>
+where
+    I: IntoIterator<Item = Segment>,
+{
+    segments
+        .into_iter()
+        .map(|segment| Entry::Data {
+            mode: segment.mode,
+            data: data[segment.begin..segment.end].to_vec(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod entry_tests {
+    use super::*;
+
+    #[test]
+    fn test_replay() {
+        let entries = [
+            Entry::StructuredAppend {
+                index: 0,
+                total: 2,
+                parity: 0x12,
+            },
+            Entry::Fnc1First,
+            Entry::Eci(9),
+            Entry::Data {
+                mode: Mode::Numeric,
+                data: b"0123".to_vec(),
+            },
+            Entry::Data {
+                mode: Mode::Byte,
+                data: b"\xa1\xa2".to_vec(),
+            },
+        ];
+        let mut bits = Bits::new(Version::Normal(1));
+        assert_eq!(bits.push_entries(&entries), Ok(()));
+
+        let mut expected = Bits::new(Version::Normal(1));
+        expected.push_structured_append(0, 2, 0x12).unwrap();
+        expected.push_fnc1_first_position().unwrap();
+        expected.push_eci_designator(9).unwrap();
+        expected.push_numeric_data(b"0123").unwrap();
+        expected.push_byte_data(b"\xa1\xa2").unwrap();
+        assert_eq!(bits.into_bytes(), expected.into_bytes());
+    }
+
+    #[test]
+    fn test_from_segments() {
+        let data = b"ABC123456789";
+        let segments: Vec<_> = Parser::new(data).optimize(Version::Normal(1)).collect();
+        let entries = entries_from_segments(data, segments.iter().copied());
+        let mut bits = Bits::new(Version::Normal(1));
+        bits.push_entries(&entries).unwrap();
+        let mut expected = Bits::new(Version::Normal(1));
+        expected.push_segments(data, segments.into_iter()).unwrap();
+        assert_eq!(bits.into_bytes(), expected.into_bytes());
+    }
+
+    #[test]
+    fn test_unsupported() {
+        let mut bits = Bits::new(Version::Micro(2));
+        assert_eq!(
+            bits.push_entry(&Entry::Eci(3)),
+            Err(QrError::UnsupportedCharacterSet)
+        );
+    }
+
+    #[test]
+    fn test_invalid_character() {
+        let mut bits = Bits::new(Version::Normal(1));
+        let entry = |mode, data: &[u8]| Entry::Data {
+            mode,
+            data: data.to_vec(),
+        };
+        assert_eq!(
+            bits.push_entry(&entry(Mode::Numeric, b"12a")),
+            Err(QrError::InvalidCharacter { offset: 2 })
+        );
+        assert_eq!(
+            bits.push_entry(&entry(Mode::Alphanumeric, b"AbC")),
+            Err(QrError::InvalidCharacter { offset: 1 })
+        );
+        assert!(bits.is_empty());
+        assert_eq!(bits.push_entry(&entry(Mode::Alphanumeric, b"A$C:")), Ok(()));
+    }
+}
+
 impl Bits {
     /// Pushes a segmented data to the bits, and then terminate it.
     ///
@@ -1040,6 +2451,9 @@ impl Bits {
 
     /// Pushes the data the bits, using the optimal encoding.
     ///
+    /// This segments the data with the greedy [`Optimizer`], which is faster
+    /// than [`optimize::optimal_segments`] but may take a few more bits.
+    ///
     /// # Errors
     ///
     /// Returns [`Err`] on overflow.
@@ -1048,6 +2462,43 @@ impl Bits {
         let segments = Parser::new(data).optimize(self.version);
         self.push_segments(data, segments)
     }
+
+    /// Pushes the data the bits, using the encoding which is optimal for the
+    /// [`CostModel`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] on overflow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{
+    /// #     Version,
+    /// #     bits::Bits,
+    /// #     optimize::Segment,
+    /// #     types::Mode,
+    /// # };
+    /// #
+    /// // Some readers misparse Kanji mode.
+    /// let no_kanji = |segment: &Segment, version| {
+    ///     (segment.mode != Mode::Kanji).then(|| segment.encoded_len(version))
+    /// };
+    /// let mut bits = Bits::new(Version::Normal(1));
+    /// bits.push_optimal_data_with(b"\x93\x5f\x93\x5f", no_kanji)
+    ///     .unwrap();
+    /// // The mode indicator of Byte mode.
+    /// assert_eq!(bits.into_bytes()[0] >> 4, 0b0100);
+    /// ```
+    #[inline]
+    pub fn push_optimal_data_with<C: CostModel>(
+        &mut self,
+        data: &[u8],
+        cost_model: C,
+    ) -> QrResult<()> {
+        let segments = Parser::new(data).optimize_with(self.version, cost_model);
+        self.push_segments(data, segments)
+    }
 }
 
 #[cfg(test)]
@@ -1104,49 +2555,344 @@ mod encode_tests {
     #[test]
     fn test_too_long() {
         let res = encode(b">>>>>>>>", Version::Normal(1), EcLevel::H);
-        assert_eq!(res, Err(QrError::DataTooLong));
+        assert_eq!(
+            res,
+            Err(QrError::DataTooLong {
+                bits_needed: 76,
+                bits_available: 72
+            })
+        );
+    }
+}
+
+// Auto version minimization
+
+#[allow(clippy::missing_panics_doc)]
+/// Automatically determines the minimum QR code version to store the data, and
+/// encode the result.
+///
+/// This method will not consider any Micro QR code or rMQR code versions. The
+/// data is segmented by [`optimize::optimal_segments`].
+///
+/// # Errors
+///
+/// Returns [`Err`] if the data is too long to fit even the highest QR code
+/// version.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{EcLevel, Version, bits};
+/// #
+/// let bits = bits::encode_auto(b"Hello, world!", EcLevel::M).unwrap();
+/// assert_eq!(bits.version(), Version::Normal(1));
+/// ```
+pub fn encode_auto(data: &[u8], ec_level: EcLevel) -> QrResult<Bits> {
+    let mut bits = Bits::new(Version::Normal(1));
+    encode_auto_into(
+        data,
+        ec_level,
+        &mut SegmentTables::default(),
+        &mut Vec::new(),
+        &mut bits,
+    )?;
+    Ok(bits)
+}
+
+/// Replaces the contents of `bits` with the result of [`encode_auto`], using
+/// `tables` and `segments` as scratch space.
+pub(crate) fn encode_auto_into(
+    data: &[u8],
+    ec_level: EcLevel,
+    tables: &mut SegmentTables,
+    segments: &mut Vec<Segment>,
+    bits: &mut Bits,
+) -> QrResult<()> {
+    let mut too_long = (0, 0);
+    for version in &[Version::Normal(9), Version::Normal(26), Version::Normal(40)] {
+        tables.optimal_segments_into(data, *version, segments);
+        let total_len = optimize::total_encoded_len(segments, *version);
+        let data_capacity = version
+            .fetch(ec_level, &DATA_LENGTHS)
+            .expect("invalid `DATA_LENGTHS`");
+        if total_len <= data_capacity {
+            bits.clear(find_min_version(total_len, ec_level));
+            bits.reserve(total_len);
+            bits.push_segments(data, segments.iter().copied())?;
+            bits.push_terminator(ec_level)?;
+            return Ok(());
+        }
+        too_long = (total_len, data_capacity);
+    }
+    let (bits_needed, bits_available) = too_long;
+    Err(QrError::DataTooLong {
+        bits_needed,
+        bits_available,
+    })
+}
+
+/// Transcodes the text into the character set and encodes it after the ECI
+/// designator of the character set, using the smallest normal QR code version.
+///
+/// ASCII digits and letters are still encoded in [`Mode::Numeric`] or
+/// [`Mode::Alphanumeric`] where it is shorter, unless the character set is
+/// not ASCII-compatible, in which case the whole text is encoded in
+/// [`Mode::Byte`]. [`Mode::Kanji`] is never used, since its characters are
+/// always interpreted as Shift JIS.
+///
+/// # Errors
+///
+/// Returns [`Err`] with [`QrError::InvalidCharacter`] if the character set
+/// cannot represent the text, or [`QrError::DataTooLong`] if the data is too
+/// long.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{EcLevel, Version, bits, types::Charset};
+/// #
+/// let bits = bits::encode_auto_with_charset("Grüße", Charset::Iso8859_1, EcLevel::M).unwrap();
+/// assert_eq!(bits.version(), Version::Normal(1));
+/// ```
+#[allow(clippy::missing_panics_doc)]
+pub fn encode_auto_with_charset(data: &str, charset: Charset, ec_level: EcLevel) -> QrResult<Bits> {
+    let data = charset.encode(data)?;
+    let segments = if charset.is_ascii_compatible() {
+        Parser::new(&data).collect::<Vec<Segment>>()
+    } else {
+        vec![Segment {
+            mode: Mode::Byte,
+            begin: 0,
+            end: data.len(),
+        }]
+    };
+    let cost_model = |segment: &Segment, version| {
+        (segment.mode != Mode::Kanji).then(|| segment.encoded_len(version))
+    };
+    // The mode indicator and an 8-bit designator, since all designators of
+    // `Charset` are less than 128.
+    let eci_len = 12;
+    let mut too_long = (0, 0);
+    for version in &[Version::Normal(9), Version::Normal(26), Version::Normal(40)] {
+        let opt_segments =
+            Optimizer::with_cost_model(segments.iter().copied(), *version, cost_model)
+                .collect::<Vec<_>>();
+        let total_len = eci_len + optimize::total_encoded_len(&opt_segments, *version);
+        let data_capacity = version
+            .fetch(ec_level, &DATA_LENGTHS)
+            .expect("invalid `DATA_LENGTHS`");
+        if total_len <= data_capacity {
+            let min_version = find_min_version(total_len, ec_level);
+            let mut bits = Bits::new(min_version);
+            bits.reserve(total_len);
+            bits.push_eci_designator(charset.eci_designator())?;
+            bits.push_segments(&data, opt_segments.into_iter())?;
+            bits.push_terminator(ec_level)?;
+            return Ok(bits);
+        }
+        too_long = (total_len, data_capacity);
+    }
+    let (bits_needed, bits_available) = too_long;
+    Err(QrError::DataTooLong {
+        bits_needed,
+        bits_available,
+    })
+}
+
+/// The minimum number of consecutive characters which [`encode_auto_kanji`]
+/// encodes in [`Mode::Kanji`], unless they are the whole text. Shorter runs
+/// are not smaller than UTF-8 once the extra segment header is counted.
+#[cfg(feature = "kanji")]
+const MIN_KANJI_RUN: usize = 3;
+
+/// Encodes the text using the smallest normal QR code version, transcoding
+/// Japanese characters into Shift JIS to encode them in [`Mode::Kanji`].
+///
+/// Runs of at least 3 characters which [`Mode::Kanji`] can represent, or the
+/// whole text if it consists of such characters, are encoded in
+/// [`Mode::Kanji`]. The rest of the text is kept in UTF-8 and segmented like
+/// [`encode_auto`]. A character in [`Mode::Kanji`] takes 13 bits instead of 24
+/// bits in UTF-8, so Japanese text takes about half the space.
+///
+/// # Errors
+///
+/// Returns [`Err`] if the data is too long to fit in a QR code.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{EcLevel, Version, bits};
+/// #
+/// let text = "東京都千代田区千代田一丁目一番一号";
+/// let bits = bits::encode_auto_kanji(text, EcLevel::M).unwrap();
+/// assert_eq!(bits.version(), Version::Normal(3));
+/// let bits = bits::encode_auto(text.as_bytes(), EcLevel::M).unwrap();
+/// assert_eq!(bits.version(), Version::Normal(4));
+/// ```
+#[cfg(feature = "kanji")]
+#[allow(clippy::missing_panics_doc)]
+pub fn encode_auto_kanji(text: &str, ec_level: EcLevel) -> QrResult<Bits> {
+    /// The cost of a segment which must not be formed. The optimizer adds
+    /// costs with saturating arithmetic, so such a merge is never cheaper than
+    /// the segments it would replace.
+    const FORBIDDEN: usize = usize::MAX;
+
+    let mut runs = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if sjis::encode_char(c).is_none() {
+            continue;
+        }
+        let mut end = start + c.len_utf8();
+        let mut count = 1;
+        while let Some((i, c)) = chars.next_if(|(_, c)| sjis::encode_char(*c).is_some()) {
+            end = i + c.len_utf8();
+            count += 1;
+        }
+        if count >= MIN_KANJI_RUN || (start == 0 && end == text.len()) {
+            runs.push(start..end);
+        }
+    }
+
+    let mut data = Vec::with_capacity(text.len());
+    let mut segments = Vec::new();
+    let mut kanji_ranges = Vec::with_capacity(runs.len());
+    let mut push_other = |data: &mut Vec<u8>, other: &str| {
+        let offset = data.len();
+        data.extend_from_slice(other.as_bytes());
+        segments.extend(Parser::new(other.as_bytes()).map(|segment| Segment {
+            begin: segment.begin + offset,
+            end: segment.end + offset,
+            ..segment
+        }));
+    };
+    let mut last_end = 0;
+    for run in runs {
+        push_other(&mut data, &text[last_end..run.start]);
+        let begin = data.len();
+        data.extend(
+            text[run.clone()]
+                .chars()
+                .filter_map(sjis::encode_char)
+                .flatten(),
+        );
+        kanji_ranges.push(begin..data.len());
+        last_end = run.end;
+    }
+    push_other(&mut data, &text[last_end..]);
+    for range in &kanji_ranges {
+        segments.push(Segment {
+            mode: Mode::Kanji,
+            begin: range.start,
+            end: range.end,
+        });
+    }
+    segments.sort_unstable_by_key(|segment| segment.begin);
+
+    // Only the transcoded runs may be in `Mode::Kanji`, and they must never be
+    // merged with the UTF-8 text around them.
+    let cost_model = |segment: &Segment, version| {
+        let overlaps =
+            |range: &Range<usize>| range.start < segment.end && segment.begin < range.end;
+        if segment.mode == Mode::Kanji {
+            kanji_ranges
+                .iter()
+                .any(|range| range.contains(&segment.begin) && segment.end <= range.end)
+                .then(|| segment.encoded_len(version))
+        } else if kanji_ranges.iter().any(overlaps) {
+            Some(FORBIDDEN)
+        } else {
+            Some(segment.encoded_len(version))
+        }
+    };
+    let mut too_long = (0, 0);
+    for version in &[Version::Normal(9), Version::Normal(26), Version::Normal(40)] {
+        let opt_segments =
+            Optimizer::with_cost_model(segments.iter().copied(), *version, cost_model)
+                .collect::<Vec<_>>();
+        let total_len = optimize::total_encoded_len(&opt_segments, *version);
+        let data_capacity = version
+            .fetch(ec_level, &DATA_LENGTHS)
+            .expect("invalid `DATA_LENGTHS`");
+        if total_len <= data_capacity {
+            let min_version = find_min_version(total_len, ec_level);
+            let mut bits = Bits::new(min_version);
+            bits.reserve(total_len);
+            bits.push_segments(&data, opt_segments.into_iter())?;
+            bits.push_terminator(ec_level)?;
+            return Ok(bits);
+        }
+        too_long = (total_len, data_capacity);
     }
+    let (bits_needed, bits_available) = too_long;
+    Err(QrError::DataTooLong {
+        bits_needed,
+        bits_available,
+    })
 }
 
-// Auto version minimization
-
-#[allow(clippy::missing_panics_doc)]
-/// Automatically determines the minimum QR code version to store the data, and
-/// encode the result.
+/// Encodes a string of decimal digits entirely in [`Mode::Numeric`], using the
+/// smallest QR code version.
 ///
-/// This method will not consider any Micro QR code or rMQR code versions.
+/// Unlike [`encode_auto`], this never splits the data into other modes, and
+/// keeps the leading zeros. This also works for numbers which are too large
+/// for any integer type.
 ///
 /// # Errors
 ///
-/// Returns [`Err`] if the data is too long to fit even the highest QR code
-/// version.
+/// Returns [`Err`] with [`QrError::InvalidCharacter`] if the data contains a
+/// character other than `0` to `9`, or [`QrError::DataTooLong`] if the data is
+/// too long.
 ///
 /// # Examples
 ///
 /// ```
 /// # use qrcode2::{EcLevel, Version, bits};
 /// #
-/// let bits = bits::encode_auto(b"Hello, world!", EcLevel::M).unwrap();
-/// assert_eq!(bits.version(), Version::Normal(1));
+/// let bits =
+///     bits::encode_numeric(b"000123456789012345678901234567890123456789", EcLevel::M).unwrap();
+/// assert_eq!(bits.version(), Version::Normal(2));
 /// ```
-pub fn encode_auto(data: &[u8], ec_level: EcLevel) -> QrResult<Bits> {
-    let segments = Parser::new(data).collect::<Vec<Segment>>();
-    for version in &[Version::Normal(9), Version::Normal(26), Version::Normal(40)] {
-        let opt_segments = Optimizer::new(segments.iter().copied(), *version).collect::<Vec<_>>();
-        let total_len = optimize::total_encoded_len(&opt_segments, *version);
-        let data_capacity = version
-            .fetch(ec_level, &DATA_LENGTHS)
-            .expect("invalid `DATA_LENGTHS`");
+pub fn encode_numeric(digits: &[u8], ec_level: EcLevel) -> QrResult<Bits> {
+    check_characters(digits, u8::is_ascii_digit)?;
+    let mut bits = Bits::new(find_numeric_version(digits.len(), ec_level)?);
+    bits.push_numeric_data(digits)?;
+    bits.push_terminator(ec_level)?;
+    Ok(bits)
+}
+
+/// Encodes an integer in [`Mode::Numeric`], using the smallest QR code
+/// version.
+pub(crate) fn encode_number(number: u128, ec_level: EcLevel) -> QrResult<Bits> {
+    let len = number.checked_ilog10().map_or(1, |n| n.as_usize() + 1);
+    let mut bits = Bits::new(find_numeric_version(len, ec_level)?);
+    bits.push_numeric(number)?;
+    bits.push_terminator(ec_level)?;
+    Ok(bits)
+}
+
+/// Finds the smallest version (QR code only) that can store N digits in
+/// [`Mode::Numeric`] in the given error correction level.
+fn find_numeric_version(len: usize, ec_level: EcLevel) -> QrResult<Version> {
+    let segment = Segment {
+        mode: Mode::Numeric,
+        begin: 0,
+        end: len,
+    };
+    let mut too_long = (0, 0);
+    for version in [Version::Normal(9), Version::Normal(26), Version::Normal(40)] {
+        let total_len = segment.encoded_len(version);
+        let data_capacity = version.fetch(ec_level, &DATA_LENGTHS)?;
         if total_len <= data_capacity {
-            let min_version = find_min_version(total_len, ec_level);
-            let mut bits = Bits::new(min_version);
-            bits.reserve(total_len);
-            bits.push_segments(data, opt_segments.into_iter())?;
-            bits.push_terminator(ec_level)?;
-            return Ok(bits);
+            return Ok(find_min_version(total_len, ec_level));
         }
+        too_long = (total_len, data_capacity);
     }
-    Err(QrError::DataTooLong)
+    let (bits_needed, bits_available) = too_long;
+    Err(QrError::DataTooLong {
+        bits_needed,
+        bits_available,
+    })
 }
 
 /// Finds the smallest version (QR code only) that can store N bits of data in
@@ -1176,6 +2922,83 @@ fn find_min_version(length: usize, ec_level: EcLevel) -> Version {
     Version::Normal((base + 1).as_i16())
 }
 
+/// The group separator (GS), which terminates a variable-length element string
+/// of GS1 data.
+const GS1_SEPARATOR: u8 = 0x1d;
+
+/// Encodes GS1 element strings with FNC1 in the first position, using the
+/// smallest normal QR code version.
+///
+/// `data` is the concatenation of the element strings, i.e. each application
+/// identifier followed by its data field, with a group separator (GS, `0x1D`)
+/// after each variable-length data field which is not the last one. The
+/// separator is encoded as `%` in [`Mode::Alphanumeric`] and as GS in
+/// [`Mode::Byte`], as specified by ISO/IEC 18004. A literal `%` is always
+/// encoded in [`Mode::Byte`], since `%` means the separator in
+/// [`Mode::Alphanumeric`].
+///
+/// # Errors
+///
+/// Returns [`Err`] if the data is too long to fit in a QR code.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{EcLevel, Version, bits};
+/// #
+/// // (01) GTIN, (10) batch number, (17) expiration date.
+/// let bits = bits::encode_gs1(b"010950110102091710ABC123\x1d17251231", EcLevel::M).unwrap();
+/// assert_eq!(bits.version(), Version::Normal(2));
+/// ```
+#[allow(clippy::missing_panics_doc)]
+pub fn encode_gs1(data: &[u8], ec_level: EcLevel) -> QrResult<Bits> {
+    // Swap the separator and `%` for the segmentation, so that the separator
+    // may join an alphanumeric segment, and a literal `%` never does.
+    let classes = data
+        .iter()
+        .map(|&b| match b {
+            GS1_SEPARATOR => b'%',
+            b'%' => GS1_SEPARATOR,
+            b => b,
+        })
+        .collect::<Vec<_>>();
+    let segments = Parser::new(&classes).collect::<Vec<Segment>>();
+    let mut too_long = (0, 0);
+    for version in &[Version::Normal(9), Version::Normal(26), Version::Normal(40)] {
+        let opt_segments = Optimizer::new(segments.iter().copied(), *version).collect::<Vec<_>>();
+        let total_len =
+            version.mode_bits_count() + optimize::total_encoded_len(&opt_segments, *version);
+        let data_capacity = version
+            .fetch(ec_level, &DATA_LENGTHS)
+            .expect("invalid `DATA_LENGTHS`");
+        if total_len <= data_capacity {
+            let mut payload = data.to_vec();
+            for segment in &opt_segments {
+                if segment.mode == Mode::Alphanumeric {
+                    for b in &mut payload[segment.begin..segment.end] {
+                        if *b == GS1_SEPARATOR {
+                            *b = b'%';
+                        }
+                    }
+                }
+            }
+            let min_version = find_min_version(total_len, ec_level);
+            let mut bits = Bits::new(min_version);
+            bits.reserve(total_len);
+            bits.push_fnc1_first_position()?;
+            bits.push_segments(&payload, opt_segments.into_iter())?;
+            bits.push_terminator(ec_level)?;
+            return Ok(bits);
+        }
+        too_long = (total_len, data_capacity);
+    }
+    let (bits_needed, bits_available) = too_long;
+    Err(QrError::DataTooLong {
+        bits_needed,
+        bits_available,
+    })
+}
+
 #[cfg(test)]
 mod encode_auto_tests {
     use super::*;
@@ -1210,12 +3033,115 @@ mod encode_auto_tests {
     }
 }
 
+#[cfg(test)]
+mod encode_auto_with_charset_tests {
+    use super::*;
+
+    #[test]
+    fn test_utf_8() {
+        let bits = encode_auto_with_charset("1234567 日本", Charset::Utf8, EcLevel::M).unwrap();
+        let mut expected = Bits::new(Version::Normal(2));
+        expected.push_eci_designator(26).unwrap();
+        expected.push_alphanumeric_data(b"1234567 ").unwrap();
+        expected.push_byte_data("日本".as_bytes()).unwrap();
+        expected.push_terminator(EcLevel::M).unwrap();
+        assert_eq!(bits.into_bytes(), expected.into_bytes());
+    }
+
+    #[test]
+    fn test_utf_16_be_is_byte_only() {
+        let bits = encode_auto_with_charset("12", Charset::Utf16Be, EcLevel::M).unwrap();
+        let mut expected = Bits::new(Version::Normal(1));
+        expected.push_eci_designator(25).unwrap();
+        expected.push_byte_data(b"\x001\x002").unwrap();
+        expected.push_terminator(EcLevel::M).unwrap();
+        assert_eq!(bits.into_bytes(), expected.into_bytes());
+    }
+
+    #[test]
+    fn test_unrepresentable() {
+        assert_eq!(
+            encode_auto_with_charset("AЖ", Charset::Iso8859_15, EcLevel::M).unwrap_err(),
+            QrError::InvalidCharacter { offset: 1 }
+        );
+    }
+}
+
+#[cfg(all(test, feature = "kanji"))]
+mod encode_auto_kanji_tests {
+    use super::*;
+
+    #[test]
+    fn test_whole_text() {
+        let bits = encode_auto_kanji("日本", EcLevel::M).unwrap();
+        let mut expected = Bits::new(Version::Normal(1));
+        expected.push_kanji_data(b"\x93\xfa\x96\x7b").unwrap();
+        expected.push_terminator(EcLevel::M).unwrap();
+        assert_eq!(bits.into_bytes(), expected.into_bytes());
+    }
+
+    #[test]
+    fn test_mixed() {
+        let bits = encode_auto_kanji("Hi 日本語です! 12345678 は", EcLevel::M).unwrap();
+        let mut expected = Bits::new(Version::Normal(3));
+        expected.push_byte_data(b"Hi ").unwrap();
+        expected
+            .push_kanji_data(b"\x93\xfa\x96\x7b\x8c\xea\x82\xc5\x82\xb7")
+            .unwrap();
+        expected.push_byte_data(b"! ").unwrap();
+        expected.push_numeric_data(b"12345678").unwrap();
+        expected.push_byte_data(" は".as_bytes()).unwrap();
+        expected.push_terminator(EcLevel::M).unwrap();
+        assert_eq!(bits.into_bytes(), expected.into_bytes());
+    }
+
+    #[test]
+    fn test_no_kanji() {
+        let bits = encode_auto_kanji("Hello, world!", EcLevel::M).unwrap();
+        let expected = encode_auto(b"Hello, world!", EcLevel::M).unwrap();
+        assert_eq!(bits.into_bytes(), expected.into_bytes());
+    }
+}
+
+#[cfg(test)]
+mod encode_gs1_tests {
+    use super::*;
+
+    #[test]
+    fn test_separator_in_alphanumeric() {
+        let bits = encode_gs1(b"10ABC123\x1d17251231", EcLevel::M).unwrap();
+        let mut expected = Bits::new(Version::Normal(1));
+        expected.push_fnc1_first_position().unwrap();
+        expected.push_optimal_data(b"10ABC123%17251231").unwrap();
+        expected.push_terminator(EcLevel::M).unwrap();
+        assert_eq!(bits.into_bytes(), expected.into_bytes());
+    }
+
+    #[test]
+    fn test_literal_percent() {
+        let bits = encode_gs1(b"9910%", EcLevel::M).unwrap();
+        let mut expected = Bits::new(Version::Normal(1));
+        expected.push_fnc1_first_position().unwrap();
+        expected.push_numeric_data(b"9910").unwrap();
+        expected.push_byte_data(b"%").unwrap();
+        expected.push_terminator(EcLevel::M).unwrap();
+        assert_eq!(bits.into_bytes(), expected.into_bytes());
+    }
+
+    #[test]
+    fn test_symbology_identifier() {
+        let bits = encode_gs1(b"0109501101020917", EcLevel::M).unwrap();
+        assert_eq!(bits.symbology_identifier(), "]Q3");
+    }
+}
+
 // Auto Micro QR code's version minimization
 
 /// Automatically determines the minimum Micro QR code version to store the
 /// data, and encode the result.
 ///
-/// This method will not consider any QR code or rMQR code versions.
+/// This method will not consider any QR code or rMQR code versions. The data
+/// is segmented by [`optimize::optimal_segments`].
 ///
 /// # Errors
 ///
@@ -1231,32 +3157,198 @@ mod encode_auto_tests {
 /// assert_eq!(bits.version(), Version::Micro(4));
 /// ```
 pub fn encode_auto_micro(data: &[u8], ec_level: EcLevel) -> QrResult<Bits> {
-    let segments = Parser::new(data).collect::<Vec<Segment>>();
-    let mut possible_versions = Vec::new();
+    encode_auto_micro_with_strategy(data, ec_level, MicroStrategy::Exact).map(|(bits, _)| bits)
+}
+
+/// Auto Micro QR code's error correction level strategy.
+///
+/// M1 only supports error detection, which is represented by [`EcLevel::L`],
+/// M2 and M3 support up to [`EcLevel::M`], and M4 supports up to
+/// [`EcLevel::Q`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum MicroStrategy {
+    /// Only uses the requested error correction level, skipping the versions
+    /// which do not support it.
+    #[default]
+    Exact,
+
+    /// Uses the requested error correction level if possible, and otherwise
+    /// the highest lower level which the smallest version that the data fits
+    /// in supports.
+    AllowLowerEc,
+}
+
+/// Automatically determines the minimum Micro QR code version to store the
+/// data with the given error correction level strategy, and encode the
+/// result.
+///
+/// Returns the encoded data and the error correction level actually used,
+/// which is needed to construct the symbol.
+///
+/// # Errors
+///
+/// Returns [`Err`] if the data is too long to fit even the highest Micro QR
+/// code version at any allowed error correction level, or with
+/// [`QrError::InvalidVersion`] if no Micro QR code version supports any
+/// allowed error correction level.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{
+/// #     EcLevel, Version,
+/// #     bits::{self, MicroStrategy},
+/// # };
+/// #
+/// let (bits, ec_level) =
+///     bits::encode_auto_micro_with_strategy(b"12345", EcLevel::H, MicroStrategy::AllowLowerEc)
+///         .unwrap();
+/// assert_eq!(bits.version(), Version::Micro(1));
+/// assert_eq!(ec_level, EcLevel::L);
+///
+/// // No Micro QR code version supports the "high" level.
+/// assert!(
+///     bits::encode_auto_micro_with_strategy(b"12345", EcLevel::H, MicroStrategy::Exact).is_err()
+/// );
+/// ```
+pub fn encode_auto_micro_with_strategy(
+    data: &[u8],
+    ec_level: EcLevel,
+    strategy: MicroStrategy,
+) -> QrResult<(Bits, EcLevel)> {
+    // The levels to try, from the highest.
+    let ec_levels = [EcLevel::H, EcLevel::Q, EcLevel::M, EcLevel::L]
+        .into_iter()
+        .filter(|&level| match strategy {
+            MicroStrategy::Exact => level == ec_level,
+            MicroStrategy::AllowLowerEc => level <= ec_level,
+        })
+        .collect::<Vec<_>>();
+    let lowest = ec_levels.last().copied().unwrap_or(ec_level);
+    let largest = Version::Micro(4);
+    // M4 supports every level which any Micro QR code version supports, so
+    // there is nothing to try if it does not support the lowest allowed level.
+    let largest_capacity = largest.fetch(lowest, &DATA_LENGTHS)?;
     for version in 1..=4 {
         let version = Version::Micro(version);
-        let opt_segments = Optimizer::new(segments.iter().copied(), version).collect::<Vec<_>>();
+        let opt_segments = optimize::optimal_segments(data, version);
+        if !opt_segments.iter().all(|s| version.supports_mode(s.mode)) {
+            continue;
+        }
         let total_len = optimize::total_encoded_len(&opt_segments, version);
-        let data_capacity = version.fetch(ec_level, &DATA_LENGTHS);
-        if let Ok(capacity) = data_capacity {
-            if total_len <= capacity {
-                possible_versions.push(version);
-                break;
-            }
+        let fitting_level = ec_levels.iter().copied().find(|&level| {
+            version
+                .fetch(level, &DATA_LENGTHS)
+                .is_ok_and(|capacity| total_len <= capacity)
+        });
+        if let Some(level) = fitting_level {
+            let mut bits = Bits::new(version);
+            bits.reserve(total_len);
+            bits.push_segments(data, opt_segments.into_iter())?;
+            bits.push_terminator(level)?;
+            return Ok((bits, level));
         }
     }
+    let opt_segments = optimize::optimal_segments(data, largest);
+    Err(QrError::DataTooLong {
+        bits_needed: optimize::total_encoded_len(&opt_segments, largest),
+        bits_available: largest_capacity,
+    })
+}
 
-    let min_version = possible_versions.iter().min_by_key(|v| v.width());
+/// The behavior of [`encode_micro`] when the data needs a mode which is not
+/// supported by the requested Micro QR code version.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum MicroModePolicy {
+    /// Returns [`QrError::UnsupportedMode`] naming the disallowed mode.
+    #[default]
+    Reject,
+
+    /// Uses the next higher Micro QR code version which supports all modes of
+    /// the data and has enough capacity.
+    BumpVersion,
+}
 
-    if let Some(version) = min_version {
-        let mut bits = Bits::new(*version);
-        let opt_segments = Optimizer::new(segments.iter().copied(), *version).collect::<Vec<_>>();
-        bits.reserve(optimize::total_encoded_len(&opt_segments, *version));
-        bits.push_segments(data, opt_segments.into_iter())?;
-        bits.push_terminator(ec_level)?;
-        return Ok(bits);
+/// Encodes the data into the given Micro QR code version, validating that
+/// every mode needed by the data is supported by the version.
+///
+/// With [`MicroModePolicy::BumpVersion`], higher versions up to M4 are tried in
+/// order until the data can be stored.
+///
+/// # Errors
+///
+/// Returns [`Err`] if `version` is not a Micro QR code version, if the data
+/// needs a mode which is not supported by the version, or if the data is too
+/// long. With [`MicroModePolicy::BumpVersion`], the error of the last version
+/// tried is returned.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{
+/// #     EcLevel, Version,
+/// #     bits::{self, MicroModePolicy},
+/// #     types::{Mode, QrError},
+/// # };
+/// #
+/// let result = bits::encode_micro(
+///     b"A1",
+///     Version::Micro(1),
+///     EcLevel::L,
+///     MicroModePolicy::Reject,
+/// );
+/// assert_eq!(
+///     result.err(),
+///     Some(QrError::UnsupportedMode(Mode::Alphanumeric))
+/// );
+///
+/// let bits = bits::encode_micro(
+///     b"A1",
+///     Version::Micro(1),
+///     EcLevel::L,
+///     MicroModePolicy::BumpVersion,
+/// )
+/// .unwrap();
+/// assert_eq!(bits.version(), Version::Micro(2));
+/// ```
+pub fn encode_micro(
+    data: &[u8],
+    version: Version,
+    ec_level: EcLevel,
+    policy: MicroModePolicy,
+) -> QrResult<Bits> {
+    let Version::Micro(first) = version.validate()? else {
+        return Err(QrError::InvalidVersion {
+            version: Some(version),
+        });
+    };
+    let last = match policy {
+        MicroModePolicy::Reject => first,
+        MicroModePolicy::BumpVersion => 4,
+    };
+
+    let mut result = Err(QrError::InvalidVersion {
+        version: Some(version),
+    });
+    for v in first..=last {
+        result = encode_micro_exact(data, Version::Micro(v), ec_level);
+        if result.is_ok() {
+            break;
+        }
+    }
+    result
+}
+
+fn encode_micro_exact(data: &[u8], version: Version, ec_level: EcLevel) -> QrResult<Bits> {
+    let segments = Parser::new(data).optimize(version).collect::<Vec<_>>();
+    if let Some(segment) = segments.iter().find(|s| !version.supports_mode(s.mode)) {
+        return Err(QrError::UnsupportedMode(segment.mode));
     }
-    Err(QrError::DataTooLong)
+    let mut bits = Bits::new(version);
+    bits.reserve(optimize::total_encoded_len(&segments, version));
+    bits.push_segments(data, segments.into_iter())?;
+    bits.push_terminator(ec_level)?;
+    Ok(bits)
 }
 
 #[cfg(test)]
@@ -1280,12 +3372,149 @@ mod encode_auto_micro_tests {
         let bits = encode_auto_micro(b"Mixed. 1234567890", EcLevel::M).unwrap();
         assert_eq!(bits.version(), Version::Micro(4));
     }
+
+    #[test]
+    fn test_skip_unsupported_mode() {
+        let bits = encode_auto_micro(b"a", EcLevel::L).unwrap();
+        assert_eq!(bits.version(), Version::Micro(3));
+        let bits = encode_auto_micro(b"A", EcLevel::L).unwrap();
+        assert_eq!(bits.version(), Version::Micro(2));
+    }
+}
+
+#[cfg(test)]
+mod encode_auto_micro_with_strategy_tests {
+    use super::*;
+
+    #[test]
+    fn test_exact() {
+        let (bits, ec_level) =
+            encode_auto_micro_with_strategy(b"12345", EcLevel::M, MicroStrategy::Exact).unwrap();
+        assert_eq!(bits.version(), Version::Micro(2));
+        assert_eq!(ec_level, EcLevel::M);
+        assert_eq!(
+            encode_auto_micro_with_strategy(b"12345", EcLevel::H, MicroStrategy::Exact).err(),
+            Some(QrError::InvalidVersion {
+                version: Some(Version::Micro(4))
+            })
+        );
+    }
+
+    #[test]
+    fn test_allow_lower_ec() {
+        let (bits, ec_level) =
+            encode_auto_micro_with_strategy(b"12345", EcLevel::M, MicroStrategy::AllowLowerEc)
+                .unwrap();
+        assert_eq!(bits.version(), Version::Micro(1));
+        assert_eq!(ec_level, EcLevel::L);
+        // M3 supports "medium" and M4 supports "quartile", so the smaller M3
+        // wins.
+        let (bits, ec_level) = encode_auto_micro_with_strategy(
+            b"HELLO WORLD",
+            EcLevel::H,
+            MicroStrategy::AllowLowerEc,
+        )
+        .unwrap();
+        assert_eq!(bits.version(), Version::Micro(3));
+        assert_eq!(ec_level, EcLevel::M);
+        // The requested level is kept if it does not make the symbol larger.
+        let (bits, ec_level) =
+            encode_auto_micro_with_strategy(b"a", EcLevel::M, MicroStrategy::AllowLowerEc).unwrap();
+        assert_eq!(bits.version(), Version::Micro(3));
+        assert_eq!(ec_level, EcLevel::M);
+    }
+
+    #[test]
+    fn test_too_long() {
+        let data = [b'a'; 16];
+        assert_eq!(
+            encode_auto_micro_with_strategy(&data, EcLevel::Q, MicroStrategy::AllowLowerEc).err(),
+            Some(QrError::DataTooLong {
+                bits_needed: 136,
+                bits_available: 128
+            })
+        );
+    }
+}
+
+#[cfg(test)]
+mod encode_micro_tests {
+    use super::*;
+
+    #[test]
+    fn test_reject() {
+        let result = encode_micro(b"a", Version::Micro(2), EcLevel::L, MicroModePolicy::Reject);
+        assert_eq!(result.err(), Some(QrError::UnsupportedMode(Mode::Byte)));
+        let bits = encode_micro(
+            b"123",
+            Version::Micro(1),
+            EcLevel::L,
+            MicroModePolicy::Reject,
+        );
+        assert_eq!(bits.unwrap().version(), Version::Micro(1));
+    }
+
+    #[test]
+    fn test_bump_version() {
+        let bits = encode_micro(
+            b"a",
+            Version::Micro(1),
+            EcLevel::L,
+            MicroModePolicy::BumpVersion,
+        );
+        assert_eq!(bits.unwrap().version(), Version::Micro(3));
+        let bits = encode_micro(
+            b"a",
+            Version::Micro(4),
+            EcLevel::L,
+            MicroModePolicy::BumpVersion,
+        );
+        assert_eq!(bits.unwrap().version(), Version::Micro(4));
+    }
+
+    #[test]
+    fn test_bump_version_too_long() {
+        let result = encode_micro(
+            &[b'a'; 100],
+            Version::Micro(1),
+            EcLevel::L,
+            MicroModePolicy::BumpVersion,
+        );
+        assert_eq!(
+            result.err(),
+            Some(QrError::DataTooLong {
+                bits_needed: 808,
+                bits_available: 128
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_version() {
+        let result = encode_micro(
+            b"1",
+            Version::Normal(1),
+            EcLevel::L,
+            MicroModePolicy::Reject,
+        );
+        assert_eq!(
+            result.err(),
+            Some(QrError::InvalidVersion {
+                version: Some(Version::Normal(1))
+            })
+        );
+    }
 }
 
 // Auto rMQR code's version minimization
 
 /// Auto rMQR code's version minimization strategy.
+///
+/// Each strategy chooses among the shortest version of each width which the
+/// data fits in, since a taller version of the same width is only larger.
+/// Ties are broken by choosing the narrower version.
 #[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
 pub enum RectMicroStrategy {
     /// Minimize the width.
     Width,
@@ -1295,12 +3524,29 @@ pub enum RectMicroStrategy {
 
     /// Minimize the area.
     Area,
+
+    /// Minimize the width, using only the versions which are at most the
+    /// given number of modules tall, e.g. to fit the print head of a label
+    /// printer.
+    MaxHeight(u32),
+
+    /// Minimize the height, using only the versions which are at most the
+    /// given number of modules wide.
+    MaxWidth(u32),
+
+    /// Choose the version whose ratio of the width to the height is the
+    /// closest to the given ratio, and then the smallest area.
+    AspectRatio(f32),
+
+    /// Minimize the cost which the function returns for each version.
+    Custom(fn(Version) -> u32),
 }
 
 /// Automatically determines the minimum rMQR code version to store the data,
 /// and encode the result.
 ///
-/// This method will not consider any QR code or Micro QR code versions.
+/// This method will not consider any QR code or Micro QR code versions. The
+/// data is segmented by [`optimize::optimal_segments`].
 ///
 /// # Errors
 ///
@@ -1324,7 +3570,6 @@ pub fn encode_auto_rect_micro(
     ec_level: EcLevel,
     strategy: RectMicroStrategy,
 ) -> QrResult<Bits> {
-    let segments = Parser::new(data).collect::<Vec<Segment>>();
     let mut possible_versions = Vec::new();
     for width in Version::RMQR_ALL_WIDTH {
         for height in Version::RMQR_ALL_HEIGHT {
@@ -1332,8 +3577,7 @@ pub fn encode_auto_rect_micro(
             if !version.is_rect_micro() {
                 continue;
             }
-            let opt_segments =
-                Optimizer::new(segments.iter().copied(), version).collect::<Vec<_>>();
+            let opt_segments = optimize::optimal_segments(data, version);
             let total_len = optimize::total_encoded_len(&opt_segments, version);
             let data_capacity = version.fetch(ec_level, &DATA_LENGTHS)?;
             if total_len <= data_capacity {
@@ -1343,24 +3587,72 @@ pub fn encode_auto_rect_micro(
         }
     }
 
+    // `possible_versions` is already sorted by width, and `min_by_key` returns
+    // the first of the equal elements.
     let min_version = match strategy {
-        // `possible_versions` is already sorted by width
         RectMicroStrategy::Width => possible_versions.first(),
         RectMicroStrategy::Height => possible_versions.iter().min_by_key(|v| v.height()),
         RectMicroStrategy::Area => possible_versions
             .iter()
             .min_by_key(|v| v.width() * v.height()),
+        RectMicroStrategy::MaxHeight(max_height) => possible_versions
+            .iter()
+            .find(|v| v.height().as_u32() <= max_height),
+        RectMicroStrategy::MaxWidth(max_width) => possible_versions
+            .iter()
+            .filter(|v| v.width().as_u32() <= max_width)
+            .min_by_key(|v| v.height()),
+        RectMicroStrategy::AspectRatio(ratio) => {
+            let distance = |v: &Version| {
+                let distance = f64::from(v.width()) / f64::from(v.height()) - f64::from(ratio);
+                distance.abs()
+            };
+            possible_versions.iter().min_by(|a, b| {
+                distance(a)
+                    .total_cmp(&distance(b))
+                    .then_with(|| (a.width() * a.height()).cmp(&(b.width() * b.height())))
+            })
+        }
+        RectMicroStrategy::Custom(cost) => possible_versions.iter().min_by_key(|v| cost(**v)),
     };
 
     if let Some(version) = min_version {
         let mut bits = Bits::new(*version);
-        let opt_segments = Optimizer::new(segments.iter().copied(), *version).collect::<Vec<_>>();
+        let opt_segments = optimize::optimal_segments(data, *version);
         bits.reserve(optimize::total_encoded_len(&opt_segments, *version));
         bits.push_segments(data, opt_segments.into_iter())?;
         bits.push_terminator(ec_level)?;
         return Ok(bits);
     }
-    Err(QrError::DataTooLong)
+
+    // Report the capacity of the largest version which the strategy allows.
+    let capacity = |v: &Version| {
+        let is_allowed = match strategy {
+            RectMicroStrategy::MaxHeight(max_height) => v.height().as_u32() <= max_height,
+            RectMicroStrategy::MaxWidth(max_width) => v.width().as_u32() <= max_width,
+            _ => true,
+        };
+        if is_allowed {
+            v.fetch(ec_level, &DATA_LENGTHS).unwrap_or_default()
+        } else {
+            0
+        }
+    };
+    let largest = Version::RMQR_ALL_WIDTH
+        .into_iter()
+        .flat_map(|width| {
+            Version::RMQR_ALL_HEIGHT
+                .into_iter()
+                .map(move |height| Version::RectMicro(height, width))
+        })
+        .filter(|v| v.is_rect_micro())
+        .max_by_key(capacity)
+        .unwrap_or(Version::RectMicro(17, 139));
+    let opt_segments = optimize::optimal_segments(data, largest);
+    Err(QrError::DataTooLong {
+        bits_needed: optimize::total_encoded_len(&opt_segments, largest),
+        bits_available: capacity(&largest),
+    })
 }
 
 #[cfg(test)]
@@ -1441,4 +3733,46 @@ mod encode_auto_rect_micro_tests {
         .unwrap();
         assert_eq!(bits.version(), Version::RectMicro(13, 99));
     }
+
+    #[test]
+    fn test_max_height() {
+        let data = b"This is a mixed data test. 1234567890";
+        let strategy = RectMicroStrategy::MaxHeight(9);
+        let bits = encode_auto_rect_micro(data, EcLevel::M, strategy).unwrap();
+        assert_eq!(bits.version(), Version::RectMicro(9, 99));
+        let strategy = RectMicroStrategy::MaxHeight(6);
+        assert_eq!(
+            encode_auto_rect_micro(data, EcLevel::M, strategy).err(),
+            Some(QrError::DataTooLong {
+                bits_needed: 273,
+                bits_available: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_max_width() {
+        let strategy = RectMicroStrategy::MaxWidth(50);
+        let bits = encode_auto_rect_micro(b"HELLO WORLD", EcLevel::H, strategy).unwrap();
+        assert_eq!(bits.version(), Version::RectMicro(11, 43));
+    }
+
+    #[test]
+    fn test_aspect_ratio() {
+        let strategy = RectMicroStrategy::AspectRatio(2.0);
+        let bits = encode_auto_rect_micro(b"HELLO WORLD", EcLevel::M, strategy).unwrap();
+        assert_eq!(bits.version(), Version::RectMicro(13, 27));
+        // R7x59 is the shortest version of its width which the data fits in.
+        let strategy = RectMicroStrategy::AspectRatio(8.0);
+        let bits = encode_auto_rect_micro(b"HELLO WORLD", EcLevel::M, strategy).unwrap();
+        assert_eq!(bits.version(), Version::RectMicro(7, 59));
+    }
+
+    #[test]
+    fn test_custom() {
+        // Prefer the widest version.
+        let strategy = RectMicroStrategy::Custom(|v| (139 - v.width()).as_u32());
+        let bits = encode_auto_rect_micro(b"HELLO WORLD", EcLevel::M, strategy).unwrap();
+        assert_eq!(bits.version(), Version::RectMicro(7, 139));
+    }
 }
@@ -23,15 +23,55 @@
 //! c.apply_mask(MaskPattern::Checkerboard);
 //! let colors = c.into_colors();
 //! ```
+//!
+//! # Drawing extra patterns
+//!
+//! The steps of [`QrCode::with_bits`](crate::QrCode::with_bits) are public, so
+//! that extra patterns can be drawn between them: create the canvas with
+//! [`Canvas::new`], draw the functional patterns with
+//! [`Canvas::draw_all_functional_patterns`], place the codewords with
+//! [`Canvas::draw_data`], and mask with [`Canvas::apply_best_mask`] or
+//! [`Canvas::apply_mask`]. Modules set with [`Canvas::put`],
+//! [`Canvas::fill_rect`] or as [`Module::Masked`] are not changed by the mask.
+//! The result can be turned into a [`QrCode`] with [`QrCode::from_canvas`].
+//!
+//! Overwriting data modules destroys codewords, so keep the changes within
+//! the error correction capacity of the symbol.
+//!
+//! ```
+//! use qrcode2::{
+//!     Color, EcLevel, QrCode, Version,
+//!     bits::Bits,
+//!     canvas::{Canvas, Module},
+//!     ec,
+//! };
+//!
+//! let mut bits = Bits::new(Version::Normal(3));
+//! bits.push_optimal_data(b"Hello").unwrap();
+//! bits.push_terminator(EcLevel::H).unwrap();
+//! let (data, ec) =
+//!     ec::construct_codewords(&bits.into_bytes(), Version::Normal(3), EcLevel::H).unwrap();
+//!
+//! let mut canvas = Canvas::new(Version::Normal(3), EcLevel::H);
+//! canvas.draw_all_functional_patterns();
+//! canvas.draw_data(&data, &ec);
+//! // Leave a light region in the center.
+//! canvas.fill_rect(12, 12, 5, 5, Module::Masked(Color::Light));
+//! let code = QrCode::from_canvas(canvas.apply_best_mask());
+//! assert_eq!(code[(14, 14)], Color::Light);
+//! ```
 
 #[cfg(test)]
 use alloc::string::String;
-use alloc::{boxed::Box, vec, vec::Vec};
+use alloc::{vec, vec::Vec};
 use core::{cmp, iter};
 
 use crate::{
+    QrCode,
+    bits::Bits,
     cast::As,
-    types::{Color, EcLevel, Version},
+    ec,
+    types::{Color, EcLevel, QrError, QrResult, Region, Version},
 };
 
 // Modules
@@ -147,6 +187,26 @@ impl Canvas {
         }
     }
 
+    /// Empties the canvas and resizes it for the given version, keeping the
+    /// allocated memory.
+    pub(crate) fn reset(&mut self, version: Version, ec_level: EcLevel) {
+        let (width, height) = (version.width(), version.height());
+        self.width = width;
+        self.height = height;
+        self.version = version;
+        self.ec_level = ec_level;
+        self.modules.clear();
+        self.modules
+            .resize((width * height).as_usize(), Module::Empty);
+    }
+
+    /// Copies the modules of `source` into the canvas, keeping the allocated
+    /// memory unlike [`Clone::clone_from`].
+    fn copy_from(&mut self, source: &Self) {
+        self.reset(source.version, source.ec_level);
+        self.modules.copy_from_slice(&source.modules);
+    }
+
     /// Converts the canvas into a human-readable string.
     #[cfg(test)]
     fn to_debug_str(&self) -> String {
@@ -215,6 +275,69 @@ impl Canvas {
     pub fn put(&mut self, x: i16, y: i16, color: Color) {
         *self.get_mut(x, y) = Module::Masked(color);
     }
+
+    /// Sets the modules of the rectangle whose top-left corner is at the
+    /// given coordinates. For convenience, negative coordinates of the corner
+    /// will wrap around.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{Color, EcLevel, Version, canvas::{Canvas, Module}};
+    /// #
+    /// let mut canvas = Canvas::new(Version::Normal(1), EcLevel::L);
+    /// canvas.fill_rect(-3, 0, 3, 2, Module::Masked(Color::Dark));
+    /// assert_eq!(canvas.get(20, 1), Module::Masked(Color::Dark));
+    /// assert_eq!(canvas.get(17, 1), Module::Empty);
+    /// ```
+    pub fn fill_rect(&mut self, x: i16, y: i16, width: i16, height: i16, module: Module) {
+        let x = if x < 0 { x + self.width } else { x };
+        let y = if y < 0 { y + self.height } else { y };
+        for j in y..y + height {
+            for i in x..x + width {
+                *self.get_mut(i, j) = module;
+            }
+        }
+    }
+
+    /// Returns the version of the QR code.
+    #[must_use]
+    #[inline]
+    pub const fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns the error correction level of the QR code.
+    #[must_use]
+    #[inline]
+    pub const fn error_correction_level(&self) -> EcLevel {
+        self.ec_level
+    }
+
+    /// Returns the number of modules per row.
+    #[must_use]
+    #[inline]
+    pub const fn width(&self) -> i16 {
+        self.width
+    }
+
+    /// Returns the number of modules per column.
+    #[must_use]
+    #[inline]
+    pub const fn height(&self) -> i16 {
+        self.height
+    }
+
+    /// Returns all modules in row major order.
+    ///
+    /// Before masking, this tells the data and error correction modules
+    /// ([`Module::Unmasked`]) apart from the functional patterns
+    /// ([`Module::Masked`]).
+    #[must_use]
+    #[inline]
+    pub fn modules(&self) -> &[Module] {
+        &self.modules
+    }
 }
 
 #[cfg(test)]
@@ -460,16 +583,9 @@ impl Canvas {
     /// The alignment patterns are 5×5 square patterns inside the QR code symbol
     /// to help the scanner create the square grid.
     fn draw_alignment_patterns(&mut self) {
-        match self.version {
-            Version::Micro(_) | Version::Normal(1) | Version::RectMicro(..) => {}
-            Version::Normal(2..=6) => self.draw_alignment_pattern_at(-7, -7),
-            Version::Normal(a) => {
-                let positions = ALIGNMENT_PATTERN_POSITIONS[(a - 7).as_usize()];
-                for x in positions {
-                    for y in positions {
-                        self.draw_alignment_pattern_at(*x, *y);
-                    }
-                }
+        if self.version.is_normal() {
+            for (x, y) in alignment_pattern_centers(self.version) {
+                self.draw_alignment_pattern_at(x, y);
             }
         }
     }
@@ -477,16 +593,107 @@ impl Canvas {
     /// Draws the alignment patterns in rMQR code.
     fn draw_alignment_patterns_rmqr(&mut self) {
         if self.version.is_rect_micro() {
-            let index = self.version.rect_micro_width_index().unwrap() + 34;
-            let x_positons = ALIGNMENT_PATTERN_POSITIONS[index];
-            for x in x_positons {
-                self.draw_alignment_pattern_rmqr_at(*x, 1);
-                self.draw_alignment_pattern_rmqr_at(*x, self.height - 2);
+            for (x, y) in alignment_pattern_centers(self.version) {
+                self.draw_alignment_pattern_rmqr_at(x, y);
             }
         }
     }
 }
 
+/// Returns the coordinates of the rows and columns in which the alignment
+/// patterns are centered, as listed in the ISO/IEC 18004 and ISO/IEC 23941
+/// tables.
+///
+/// For QR code, the alignment patterns are centered at every combination of
+/// these coordinates, except where they would overlap the finder patterns. For
+/// rMQR code, these are the columns of the alignment patterns, which are
+/// centered in the second row from the top and the bottom edges. Returns an
+/// empty slice for QR code version 1, Micro QR code, and invalid versions.
+///
+/// See [`alignment_pattern_centers`] for the centers of the patterns.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{Version, canvas};
+/// #
+/// assert!(canvas::alignment_pattern_positions(Version::Normal(1)).is_empty());
+/// assert_eq!(
+///     canvas::alignment_pattern_positions(Version::Normal(2)),
+///     [6, 18]
+/// );
+/// assert_eq!(
+///     canvas::alignment_pattern_positions(Version::Normal(7)),
+///     [6, 22, 38]
+/// );
+/// assert_eq!(
+///     canvas::alignment_pattern_positions(Version::RectMicro(7, 59)),
+///     [19, 39]
+/// );
+/// ```
+#[must_use]
+pub fn alignment_pattern_positions(version: Version) -> &'static [i16] {
+    match version {
+        Version::Normal(a @ 1..=40) => ALIGNMENT_PATTERN_POSITIONS[(a - 1).as_usize()],
+        Version::RectMicro(..) => match version.rect_micro_width_index() {
+            Ok(index) if version.is_rect_micro() => ALIGNMENT_PATTERN_POSITIONS[index + 40],
+            _ => &[],
+        },
+        Version::Normal(_) | Version::Micro(_) => &[],
+    }
+}
+
+/// Returns the coordinates of the centers of the alignment patterns.
+///
+/// QR code from version 2 has 5×5 alignment patterns, except where they would
+/// overlap the finder patterns. rMQR code has 3×3 alignment patterns at the top
+/// and the bottom edges. Micro QR code has no alignment patterns. Returns an
+/// empty vector if the version is invalid.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{Version, canvas};
+/// #
+/// assert!(canvas::alignment_pattern_centers(Version::Normal(1)).is_empty());
+/// assert_eq!(
+///     canvas::alignment_pattern_centers(Version::Normal(2)),
+///     [(18, 18)]
+/// );
+/// assert_eq!(
+///     canvas::alignment_pattern_centers(Version::Normal(7)).len(),
+///     6
+/// );
+/// assert_eq!(
+///     canvas::alignment_pattern_centers(Version::RectMicro(7, 59)),
+///     [(19, 1), (19, 5), (39, 1), (39, 5)]
+/// );
+/// ```
+#[must_use]
+pub fn alignment_pattern_centers(version: Version) -> Vec<(i16, i16)> {
+    let positions = alignment_pattern_positions(version);
+    match version {
+        Version::Normal(2..=40) => {
+            let (first, last) = (positions[0], positions[positions.len() - 1]);
+            positions
+                .iter()
+                .flat_map(|&y| positions.iter().map(move |&x| (x, y)))
+                .filter(|&(x, y)| {
+                    !matches!(
+                        (x == first, y == first, x == last, y == last),
+                        (true, true, ..) | (true, _, _, true) | (_, true, true, _)
+                    )
+                })
+                .collect()
+        }
+        Version::RectMicro(height, _) => positions
+            .iter()
+            .flat_map(|&x| [(x, 1), (x, height - 2)])
+            .collect(),
+        Version::Normal(_) | Version::Micro(_) => Vec::new(),
+    }
+}
+
 #[cfg(test)]
 mod alignment_pattern_tests {
     use super::*;
@@ -644,13 +851,47 @@ mod alignment_pattern_tests {
             )
         );
     }
+
+    #[test]
+    fn test_alignment_pattern_positions() {
+        for a in 2..=40 {
+            let version = Version::Normal(a);
+            let positions = alignment_pattern_positions(version);
+            assert_eq!(positions.len().as_i16(), a / 7 + 2);
+            assert_eq!(positions[0], 6);
+            assert_eq!(positions[positions.len() - 1], version.width() - 7);
+            assert_eq!(
+                alignment_pattern_centers(version).len(),
+                positions.len().pow(2) - 3
+            );
+        }
+        assert_eq!(
+            alignment_pattern_positions(Version::RectMicro(17, 139)),
+            [27, 55, 83, 111]
+        );
+        for version in [
+            Version::Normal(0),
+            Version::Normal(41),
+            Version::Micro(4),
+            Version::RectMicro(7, 27),
+            Version::RectMicro(7, 28),
+        ] {
+            assert!(alignment_pattern_positions(version).is_empty());
+        }
+    }
 }
 
 /// `ALIGNMENT_PATTERN_POSITIONS` describes the x- and y-coordinates of the
 /// center of the alignment patterns. Since the QR code is symmetric, only one
 /// coordinate is needed. rMQR code is symmetrically placed at the top and
 /// bottom, so only one coordinate is needed.
-static ALIGNMENT_PATTERN_POSITIONS: [&[i16]; 40] = [
+static ALIGNMENT_PATTERN_POSITIONS: [&[i16]; 46] = [
+    &[],
+    &[6, 18],
+    &[6, 22],
+    &[6, 26],
+    &[6, 30],
+    &[6, 34],
     &[6, 22, 38],
     &[6, 24, 42],
     &[6, 26, 46],
@@ -806,8 +1047,7 @@ impl Canvas {
             );
         }
 
-        let position_index = self.version.rect_micro_width_index().unwrap() + 34;
-        for x in ALIGNMENT_PATTERN_POSITIONS[position_index] {
+        for x in alignment_pattern_positions(self.version) {
             self.draw_line(*x, 3, *x, height - 4, Color::Dark, Color::Light);
         }
     }
@@ -1580,7 +1820,7 @@ pub fn is_functional(version: Version, width: i16, x: i16, y: i16) -> bool {
                 1 => false,
                 2..=6 => (width - 7 - x).abs() <= 2 && (width - 7 - y).abs() <= 2,
                 _ => {
-                    let positions = ALIGNMENT_PATTERN_POSITIONS[(a - 7).as_usize()];
+                    let positions = alignment_pattern_positions(version);
                     let last = positions.len() - 1;
                     for (i, align_x) in positions.iter().enumerate() {
                         for (j, align_y) in positions.iter().enumerate() {
@@ -2650,7 +2890,7 @@ mod draw_codewords_tests {
 
 /// The mask patterns. Since QR code and Micro QR code do not use the same
 /// pattern number, we name them according to their shape instead of the number.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum MaskPattern {
     /// QR code pattern 000: `(x + y) % 2 == 0`.
     Checkerboard = 0b000,
@@ -2729,8 +2969,8 @@ impl Canvas {
     /// patterns.
     pub fn apply_mask(&mut self, pattern: MaskPattern) {
         let mask_fn = get_mask_function(pattern);
-        for x in 0..self.width {
-            for y in 0..self.height {
+        for y in 0..self.height {
+            for x in 0..self.width {
                 let module = self.get_mut(x, y);
                 *module = module.mask(mask_fn(x, y));
             }
@@ -2974,15 +3214,15 @@ impl Canvas {
         let mut total_score = 0;
 
         for i in 0..self.width {
-            for j in 0..self.width - 6 {
-                // TODO a ref to a closure should be enough?
-                let get: Box<dyn Fn(i16) -> Color> = if is_horizontal {
-                    Box::new(|k| self.get(k, i).into())
+            let get = |k| -> Color {
+                if is_horizontal {
+                    self.get(k, i).into()
                 } else {
-                    Box::new(|k| self.get(i, k).into())
-                };
-
-                if (j..(j + 7)).map(&*get).ne(PATTERN.iter().copied()) {
+                    self.get(i, k).into()
+                }
+            };
+            for j in 0..self.width - 6 {
+                if (j..(j + 7)).map(get).ne(PATTERN.iter().copied()) {
                     continue;
                 }
 
@@ -3041,22 +3281,90 @@ impl Canvas {
     /// Computes the total penalty scores. A QR code having higher points is
     /// less desirable.
     fn compute_total_penalty_scores(&self) -> u16 {
+        self.penalty_score().total()
+    }
+
+    /// Computes the penalty score of each rule for the masked canvas.
+    ///
+    /// QR code is scored with the four rules of the standard, and Micro QR
+    /// code with the light modules on the sides only. rMQR code has a single
+    /// mask pattern, so every score is 0.
+    #[must_use]
+    pub fn penalty_score(&self) -> PenaltyScore {
         match self.version {
-            Version::Normal(_) => {
-                let s1_a = self.compute_adjacent_penalty_score(true);
-                let s1_b = self.compute_adjacent_penalty_score(false);
-                let s2 = self.compute_block_penalty_score();
-                let s3_a = self.compute_finder_penalty_score(true);
-                let s3_b = self.compute_finder_penalty_score(false);
-                let s4 = self.compute_balance_penalty_score();
-                s1_a + s1_b + s2 + s3_a + s3_b + s4
-            }
-            Version::Micro(_) => self.compute_light_side_penalty_score(),
-            Version::RectMicro(..) => 0,
+            Version::Normal(_) => PenaltyScore {
+                adjacent: self.compute_adjacent_penalty_score(true)
+                    + self.compute_adjacent_penalty_score(false),
+                block: self.compute_block_penalty_score(),
+                finder: self.compute_finder_penalty_score(true)
+                    + self.compute_finder_penalty_score(false),
+                balance: self.compute_balance_penalty_score(),
+                light_sides: 0,
+            },
+            Version::Micro(_) => PenaltyScore {
+                light_sides: self.compute_light_side_penalty_score(),
+                ..PenaltyScore::default()
+            },
+            Version::RectMicro(..) => PenaltyScore::default(),
         }
     }
 }
 
+/// The penalty score of a masked symbol, broken down by rule.
+///
+/// A symbol having higher points is less desirable.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PenaltyScore {
+    /// The score for runs of adjacent modules of the same color in a row or a
+    /// column (rule 1).
+    pub adjacent: u16,
+
+    /// The score for 2×2 blocks of modules of the same color (rule 2).
+    pub block: u16,
+
+    /// The score for patterns which look like a finder pattern (rule 3).
+    pub finder: u16,
+
+    /// The score for the imbalance between dark and light modules (rule 4).
+    pub balance: u16,
+
+    /// The score for light modules on the right and the bottom sides of Micro
+    /// QR code.
+    pub light_sides: u16,
+}
+
+impl PenaltyScore {
+    /// Returns the sum of the scores of every rule.
+    #[must_use]
+    #[inline]
+    pub const fn total(&self) -> u16 {
+        self.adjacent + self.block + self.finder + self.balance + self.light_sides
+    }
+}
+
+/// Computes the penalty score of each rule for the QR code.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{EcLevel, QrCode, bits, canvas::{self, MaskPattern}};
+/// #
+/// let bits = bits::encode_auto(b"Hello", EcLevel::M).unwrap();
+/// let code = QrCode::with_bits_and_mask(bits, EcLevel::M, MaskPattern::Diamonds).unwrap();
+/// let score = canvas::penalty_score(&code);
+/// let scores = &code.mask_selection().unwrap().scores;
+/// assert!(scores.contains(&(MaskPattern::Diamonds, score.total())));
+/// ```
+#[must_use]
+pub fn penalty_score(code: &QrCode) -> PenaltyScore {
+    Canvas::from_colors(
+        code.version(),
+        code.error_correction_level(),
+        &code.to_colors(),
+    )
+    .penalty_score()
+}
+
 #[cfg(test)]
 mod penalty_tests {
     use super::*;
@@ -3130,6 +3438,29 @@ mod penalty_tests {
         assert_eq!(c.compute_balance_penalty_score(), 2);
     }
 
+    #[test]
+    fn test_penalty_score() {
+        let c = create_test_canvas();
+        let score = c.penalty_score();
+        assert_eq!(
+            score,
+            PenaltyScore {
+                adjacent: 180,
+                block: 90,
+                finder: 40,
+                balance: 2,
+                light_sides: 0,
+            }
+        );
+        assert_eq!(score.total(), 312);
+    }
+
+    #[test]
+    fn test_penalty_score_of_qr_code() {
+        let code = QrCode::from_canvas(create_test_canvas());
+        assert_eq!(penalty_score(&code), create_test_canvas().penalty_score());
+    }
+
     #[test]
     fn test_penalty_score_light_sides() {
         static HORIZONTAL_SIDE: [Color; 17] = [
@@ -3203,28 +3534,817 @@ static ALL_PATTERNS_MICRO_QR: [MaskPattern; 4] = [
 
 static ALL_PATTERNS_RMQR: [MaskPattern; 1] = [MaskPattern::LargeCheckerboard];
 
+/// How the mask pattern of a symbol is chosen.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum MaskPolicy {
+    /// Chooses the pattern with the lowest penalty score.
+    #[default]
+    Auto,
+
+    /// Always uses the pattern, e.g. to reproduce a reference symbol.
+    Fixed(MaskPattern),
+
+    /// Calls the function with the penalty score of every pattern which the
+    /// version supports, and uses the pattern which it returns.
+    Custom(fn(&[(MaskPattern, u16)]) -> MaskPattern),
+}
+
+/// The mask pattern chosen by a [`MaskPolicy`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct MaskSelection {
+    /// The chosen pattern.
+    pub pattern: MaskPattern,
+
+    /// The penalty score of every pattern which the version supports. A lower
+    /// score is better.
+    pub scores: Vec<(MaskPattern, u16)>,
+}
+
+/// Returns the mask patterns which can be used by the version.
+pub(crate) const fn mask_patterns(version: Version) -> &'static [MaskPattern] {
+    match version {
+        Version::Normal(_) => &ALL_PATTERNS_QR,
+        Version::Micro(_) => &ALL_PATTERNS_MICRO_QR,
+        Version::RectMicro(..) => &ALL_PATTERNS_RMQR,
+    }
+}
+
 impl Canvas {
     #[allow(clippy::missing_panics_doc)]
     /// Constructs a new canvas and apply the best masking that gives the lowest
     /// penalty score.
     #[must_use]
     pub fn apply_best_mask(&self) -> Self {
-        match self.version {
-            Version::Normal(_) => ALL_PATTERNS_QR.iter(),
-            Version::Micro(_) => ALL_PATTERNS_MICRO_QR.iter(),
-            Version::RectMicro(..) => ALL_PATTERNS_RMQR.iter(),
+        let (canvas, _) = self
+            .apply_mask_policy(MaskPolicy::Auto)
+            .expect("at least one pattern");
+        canvas
+    }
+
+    /// Computes the total penalty score of every mask pattern which the
+    /// version supports.
+    ///
+    /// Each pattern is applied to `scratch`, which is reset from `self` in
+    /// between, so that scoring does not allocate a canvas for each pattern.
+    fn mask_scores_with(&self, scratch: &mut Self) -> Vec<(MaskPattern, u16)> {
+        mask_patterns(self.version)
+            .iter()
+            .map(|ptn| {
+                scratch.copy_from(self);
+                scratch.apply_mask(*ptn);
+                (*ptn, scratch.compute_total_penalty_scores())
+            })
+            .collect()
+    }
+
+    /// Returns the pattern chosen by the policy from the penalty scores.
+    fn choose_mask(
+        &self,
+        policy: MaskPolicy,
+        scores: &[(MaskPattern, u16)],
+    ) -> QrResult<MaskPattern> {
+        let pattern = match policy {
+            MaskPolicy::Auto => {
+                scores
+                    .iter()
+                    .min_by_key(|(_, score)| *score)
+                    .ok_or(QrError::InvalidMaskPattern)?
+                    .0
+            }
+            MaskPolicy::Fixed(pattern) => pattern,
+            MaskPolicy::Custom(choose) => choose(scores),
+        };
+        if !mask_patterns(self.version).contains(&pattern) {
+            return Err(QrError::InvalidMaskPattern);
         }
-        .map(|ptn| {
-            let mut c = self.clone();
-            c.apply_mask(*ptn);
-            c
-        })
-        .min_by_key(Self::compute_total_penalty_scores)
-        .expect("at least one pattern")
+        Ok(pattern)
+    }
+
+    /// Constructs a new canvas masked with the pattern chosen by the policy,
+    /// and reports the choice together with the penalty score of every
+    /// pattern which the version supports.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] with [`QrError::InvalidMaskPattern`] if the chosen
+    /// pattern is not supported by the version, e.g.
+    /// [`MaskPattern::Checkerboard`] for Micro QR code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{
+    /// #     EcLevel, Version,
+    /// #     canvas::{Canvas, MaskPattern, MaskPolicy},
+    /// # };
+    /// #
+    /// let mut canvas = Canvas::new(Version::Normal(1), EcLevel::L);
+    /// canvas.draw_all_functional_patterns();
+    /// canvas.draw_data(&[0; 19], &[0; 7]);
+    /// let policy = MaskPolicy::Fixed(MaskPattern::Diamonds);
+    /// let (_, selection) = canvas.apply_mask_policy(policy).unwrap();
+    /// assert_eq!(selection.pattern, MaskPattern::Diamonds);
+    /// assert_eq!(selection.scores.len(), 8);
+    /// ```
+    pub fn apply_mask_policy(&self, policy: MaskPolicy) -> QrResult<(Self, MaskSelection)> {
+        // The scratch canvas which scores the patterns is reused for the
+        // result, so that the canvas is copied only once.
+        let mut canvas = self.clone();
+        let scores = self.mask_scores_with(&mut canvas);
+        let pattern = self.choose_mask(policy, &scores)?;
+        canvas.copy_from(self);
+        canvas.apply_mask(pattern);
+        Ok((canvas, MaskSelection { pattern, scores }))
+    }
+
+    /// Masks the canvas in place with the pattern chosen by the policy, like
+    /// [`Canvas::apply_mask_policy`], scoring the patterns on `scratch`.
+    pub(crate) fn apply_mask_policy_with(
+        &mut self,
+        policy: MaskPolicy,
+        scratch: &mut Self,
+    ) -> QrResult<MaskSelection> {
+        let scores = self.mask_scores_with(scratch);
+        let pattern = self.choose_mask(policy, &scores)?;
+        self.apply_mask(pattern);
+        Ok(MaskSelection { pattern, scores })
+    }
+
+    /// Returns the coordinates of the empty modules in the order in which
+    /// [`Canvas::draw_data`] fills them.
+    pub(crate) fn data_placement_order(&self) -> Vec<(i16, i16)> {
+        DataModuleIter::new(self.version)
+            .filter(|&(x, y)| self.get(x, y) == Module::Empty)
+            .collect()
     }
 
     /// Converts the modules into a vector of colors.
     pub fn into_colors(self) -> Vec<Color> {
         self.modules.into_iter().map(Color::from).collect()
     }
+
+    /// Returns the colors of the modules, like [`Canvas::into_colors`].
+    pub(crate) fn to_colors(&self) -> Vec<Color> {
+        self.modules.iter().copied().map(Color::from).collect()
+    }
+}
+
+// Reading
+
+/// Returns the item of the entry of `table` whose code is the nearest to one
+/// of the `codes` in Hamming distance, if they differ in at most 3 bits.
+fn nearest_code<T: Copy, I: Iterator<Item = (u32, T)> + Clone>(
+    codes: &[u32],
+    table: &I,
+) -> Option<T> {
+    codes
+        .iter()
+        .flat_map(|code| {
+            table
+                .clone()
+                .map(move |(entry, item)| ((entry ^ code).count_ones(), item))
+        })
+        .filter(|(distance, _)| *distance <= 3)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, item)| item)
+}
+
+impl Canvas {
+    /// Constructs a canvas holding the modules of a masked symbol.
+    pub(crate) fn from_colors(version: Version, ec_level: EcLevel, colors: &[Color]) -> Self {
+        let mut canvas = Self::new(version, ec_level);
+        for (module, color) in canvas.modules.iter_mut().zip(colors) {
+            *module = Module::Masked(*color);
+        }
+        canvas
+    }
+
+    /// Reads the modules at the coordinates as a number, most significant bit
+    /// first.
+    fn read_number(&self, coords: &[(i16, i16)]) -> u32 {
+        coords.iter().fold(0, |number, &(x, y)| {
+            number << 1 | u32::from(self.get(x, y).is_dark())
+        })
+    }
+
+    /// Reads the error correction level and the mask pattern from the format
+    /// information, correcting up to 3 wrong bits.
+    ///
+    /// For Micro QR code, the format information must match the version of
+    /// the canvas. For rMQR code, this reads the version information, which
+    /// also holds the error correction level.
+    pub(crate) fn read_format_info(&self) -> Option<(EcLevel, MaskPattern)> {
+        const EC_LEVELS: [EcLevel; 4] = [EcLevel::L, EcLevel::M, EcLevel::Q, EcLevel::H];
+        match self.version {
+            Version::Normal(_) => {
+                let codes = [
+                    self.read_number(&FORMAT_INFO_COORDS_QR_MAIN),
+                    self.read_number(&FORMAT_INFO_COORDS_QR_SIDE),
+                ];
+                let entries = FORMAT_INFOS_QR
+                    .iter()
+                    .enumerate()
+                    .map(|(i, info)| (u32::from(*info), i));
+                let i = nearest_code(&codes, &entries)?;
+                Some((EC_LEVELS[(i >> 3) ^ 1], ALL_PATTERNS_QR[i & 0b111]))
+            }
+            Version::Micro(a) => {
+                const SYMBOLS: [(u32, EcLevel); 8] = [
+                    (1, EcLevel::L),
+                    (2, EcLevel::L),
+                    (2, EcLevel::M),
+                    (3, EcLevel::L),
+                    (3, EcLevel::M),
+                    (4, EcLevel::L),
+                    (4, EcLevel::M),
+                    (4, EcLevel::Q),
+                ];
+                let codes = [self.read_number(&FORMAT_INFO_COORDS_MICRO_QR)];
+                let entries = FORMAT_INFOS_MICRO_QR
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| SYMBOLS[i >> 2].0 == u32::from(a.unsigned_abs()))
+                    .map(|(i, info)| (u32::from(*info), i));
+                let i = nearest_code(&codes, &entries)?;
+                Some((SYMBOLS[i >> 2].1, ALL_PATTERNS_MICRO_QR[i & 0b11]))
+            }
+            Version::RectMicro(..) => {
+                let index = self.version.rect_micro_index().ok()?;
+                let ec_level = |i: usize| if i == 0 { EcLevel::M } else { EcLevel::H };
+                let left = self.read_number(&RMQR_VERSION_INFO_COORDS_L);
+                let right = self.read_number(&RMQR_VERSION_INFO_COORDS_R);
+                let i = nearest_code(
+                    &[left],
+                    &RMQR_VERSION_INFOS_L[index].iter().copied().zip(0..),
+                )
+                .or_else(|| {
+                    nearest_code(
+                        &[right],
+                        &RMQR_VERSION_INFOS_R[index].iter().copied().zip(0..),
+                    )
+                })?;
+                Some((ec_level(i), MaskPattern::LargeCheckerboard))
+            }
+        }
+    }
+
+    /// Reads the version from the version information of QR code version 7 or
+    /// later, correcting up to 3 wrong bits.
+    ///
+    /// Returns [`None`] for other versions, or if the version information
+    /// cannot be read.
+    pub(crate) fn read_version_info(&self) -> Option<Version> {
+        let Version::Normal(7..) = self.version else {
+            return None;
+        };
+        let codes = [
+            self.read_number(&VERSION_INFO_COORDS_BL),
+            self.read_number(&VERSION_INFO_COORDS_TR),
+        ];
+        let entries = VERSION_INFOS.iter().zip(7..).map(|(info, v)| (*info, v));
+        nearest_code(&codes, &entries).map(Version::Normal)
+    }
+
+    /// Reads `data_len` data codewords and `ec_len` error correction codewords
+    /// in the order in which [`Canvas::draw_data`] draws them, removing the
+    /// mask pattern.
+    pub(crate) fn read_codewords(
+        &self,
+        pattern: MaskPattern,
+        data_len: usize,
+        ec_len: usize,
+    ) -> (Vec<u8>, Vec<u8>) {
+        let is_half_codeword_at_end = matches!(
+            (self.version, self.ec_level),
+            (Version::Micro(1 | 3), EcLevel::L) | (Version::Micro(3), EcLevel::M)
+        );
+        let mut reference = Self::new(self.version, self.ec_level);
+        reference.draw_all_functional_patterns();
+        let mask_fn = get_mask_function(pattern);
+        let mut bits = DataModuleIter::new(self.version)
+            .filter(|&(x, y)| reference.get(x, y) == Module::Empty)
+            .map(|(x, y)| self.get(x, y).is_dark() != mask_fn(x, y));
+        let mut read_codeword = |len: u32| {
+            let byte = (0..len).fold(0_u8, |byte, _| {
+                byte << 1 | u8::from(bits.next() == Some(true))
+            });
+            byte << (8 - len)
+        };
+        let data = (0..data_len)
+            .map(|i| {
+                if is_half_codeword_at_end && i == data_len - 1 {
+                    read_codeword(4)
+                } else {
+                    read_codeword(8)
+                }
+            })
+            .collect();
+        let ec = (0..ec_len).map(|_| read_codeword(8)).collect();
+        (data, ec)
+    }
+}
+
+// Codeword placement
+
+/// The bit of a codeword which a module holds.
+#[cfg(feature = "trace")]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CodewordBit {
+    /// The index of the error correction block.
+    pub block: usize,
+
+    /// The index of the codeword in the block. The error correction codewords
+    /// follow the data codewords.
+    pub codeword: usize,
+
+    /// The index of the bit in the codeword, where 7 is the most significant
+    /// bit.
+    pub bit: u8,
+
+    /// Whether the codeword is an error correction codeword.
+    pub is_error_correction: bool,
+}
+
+#[cfg(feature = "trace")]
+impl Canvas {
+    /// Returns the codeword bit which each module holds, in row major order.
+    ///
+    /// This follows the placement of [`Canvas::draw_data`]. The modules of the
+    /// functional patterns and the remainder bits are [`None`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the version and the error correction level are
+    /// incompatible.
+    pub fn codeword_placement(
+        version: Version,
+        ec_level: EcLevel,
+    ) -> QrResult<Vec<Option<CodewordBit>>> {
+        let mut canvas = Self::new(version, ec_level);
+        canvas.draw_all_functional_patterns();
+        let (data, ec) = ec::codeword_positions(version, ec_level)?;
+        let codeword_bits = |positions: Vec<(usize, usize)>, is_error_correction| {
+            positions.into_iter().flat_map(move |(block, codeword)| {
+                (0..8).rev().map(move |bit| CodewordBit {
+                    block,
+                    codeword,
+                    bit,
+                    is_error_correction,
+                })
+            })
+        };
+        // The last data codeword of M1 and M3 may only have 4 bits.
+        let data_len = Bits::new(version).max_len(ec_level)?;
+        let bits = codeword_bits(data, false)
+            .take(data_len)
+            .chain(codeword_bits(ec, true));
+
+        let mut placement = vec![None; canvas.modules.len()];
+        for ((x, y), bit) in canvas.data_placement_order().into_iter().zip(bits) {
+            placement[canvas.coords_to_index(x, y)] = Some(bit);
+        }
+        Ok(placement)
+    }
+}
+
+#[cfg(all(test, feature = "trace"))]
+mod codeword_placement_tests {
+    use super::*;
+
+    #[test]
+    fn test_first_and_last_modules() {
+        let placement = Canvas::codeword_placement(Version::Normal(1), EcLevel::M).unwrap();
+        let bit = |x: usize, y: usize| placement[y * 21 + x];
+        // The placement starts from the bottom right corner.
+        let first = CodewordBit {
+            block: 0,
+            codeword: 0,
+            bit: 7,
+            is_error_correction: false,
+        };
+        assert_eq!(bit(20, 20), Some(first));
+        assert_eq!(bit(19, 20), Some(CodewordBit { bit: 6, ..first }));
+        assert_eq!(bit(20, 19), Some(CodewordBit { bit: 5, ..first }));
+        assert_eq!(bit(0, 0), None);
+        let count = placement.iter().flatten().count();
+        assert_eq!(count, 26 * 8);
+        assert!(placement.iter().flatten().any(|b| *b
+            == CodewordBit {
+                block: 0,
+                codeword: 25,
+                bit: 0,
+                is_error_correction: true
+            }));
+    }
+
+    #[test]
+    fn test_half_codeword() {
+        let placement = Canvas::codeword_placement(Version::Micro(1), EcLevel::L).unwrap();
+        let bits: Vec<_> = placement.iter().flatten().collect();
+        assert_eq!(bits.len(), 20 + 2 * 8);
+        assert!(!bits.iter().any(|b| b.codeword == 2 && b.bit < 4));
+        assert!(bits.iter().any(|b| b.codeword == 2 && b.bit == 4));
+    }
+
+    #[test]
+    fn test_matches_draw_data() {
+        let mut canvas = Canvas::new(Version::Normal(5), EcLevel::Q);
+        canvas.draw_all_functional_patterns();
+        let placement = Canvas::codeword_placement(Version::Normal(5), EcLevel::Q).unwrap();
+        // Set only bit 3 of codeword 2 in block 1, which is the 11th data
+        // codeword in the interleaved order.
+        let mut data = vec![0; 62];
+        data[4 * 2 + 1] = 1 << 3;
+        canvas.draw_data(&data, &[0; 72]);
+        let dark: Vec<_> = canvas
+            .modules
+            .iter()
+            .zip(&placement)
+            .filter(|(m, _)| **m == Module::Unmasked(Color::Dark))
+            .map(|(_, b)| *b)
+            .collect();
+        assert_eq!(
+            dark,
+            [Some(CodewordBit {
+                block: 1,
+                codeword: 2,
+                bit: 3,
+                is_error_correction: false
+            })]
+        );
+    }
+}
+
+// Module kinds
+
+/// The kind of a module, i.e. the part of the symbol which the module belongs
+/// to.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ModuleKind {
+    /// The finder pattern, including the finder sub-pattern and the corner
+    /// finder patterns of rMQR code.
+    Finder,
+
+    /// The separator around the finder pattern.
+    Separator,
+
+    /// The timing pattern.
+    Timing,
+
+    /// The alignment pattern.
+    Alignment,
+
+    /// The format information. For rMQR code, this also contains the version.
+    FormatInfo,
+
+    /// The version information of QR code version 7 or later.
+    VersionInfo,
+
+    /// The data codewords.
+    Data,
+
+    /// The error correction codewords.
+    ErrorCorrection,
+
+    /// The remainder bits, which are not part of any codeword.
+    Remainder,
+}
+
+impl Canvas {
+    /// Classifies every module of the symbol, in row major order.
+    ///
+    /// The functional patterns are drawn in the same order as
+    /// [`Canvas::draw_all_functional_patterns`], and each module is classified
+    /// by the pattern which draws it first.
+    pub(crate) fn module_kinds(version: Version, ec_level: EcLevel) -> QrResult<Vec<ModuleKind>> {
+        let mut canvas = Self::new(version, ec_level);
+        let mut kinds = vec![None; canvas.modules.len()];
+        let mut classify = |canvas: &mut Self, draw: fn(&mut Self), kind| {
+            draw(canvas);
+            for (k, module) in kinds.iter_mut().zip(&canvas.modules) {
+                if k.is_none() && *module != Module::Empty {
+                    *k = Some(kind);
+                }
+            }
+        };
+        classify(&mut canvas, Self::draw_finder_patterns, ModuleKind::Finder);
+        classify(
+            &mut canvas,
+            Self::draw_alignment_patterns,
+            ModuleKind::Alignment,
+        );
+        classify(
+            &mut canvas,
+            Self::draw_reserved_format_info_patterns,
+            ModuleKind::FormatInfo,
+        );
+        classify(&mut canvas, Self::draw_timing_patterns, ModuleKind::Timing);
+        classify(
+            &mut canvas,
+            Self::draw_corner_finder_pattern,
+            ModuleKind::Finder,
+        );
+        classify(
+            &mut canvas,
+            Self::draw_alignment_patterns_rmqr,
+            ModuleKind::Alignment,
+        );
+        // rMQR code has no version information, and its format information
+        // which contains the version is drawn along with it.
+        let version_info_kind = if version.is_rect_micro() {
+            ModuleKind::FormatInfo
+        } else {
+            ModuleKind::VersionInfo
+        };
+        classify(
+            &mut canvas,
+            Self::draw_version_info_patterns,
+            version_info_kind,
+        );
+
+        // The light border of each 7×7 finder pattern is the separator.
+        let (width, height) = (canvas.width, canvas.height);
+        let centers: &[(i16, i16)] = match version {
+            Version::Normal(_) => &[(3, 3), (width - 4, 3), (3, height - 4)],
+            Version::Micro(_) | Version::RectMicro(..) => &[(3, 3)],
+        };
+        for &(cx, cy) in centers {
+            for y in cmp::max(cy - 4, 0)..cmp::min(cy + 5, height) {
+                for x in cmp::max(cx - 4, 0)..cmp::min(cx + 5, width) {
+                    if cmp::max((x - cx).abs(), (y - cy).abs()) == 4 {
+                        kinds[canvas.coords_to_index(x, y)] = Some(ModuleKind::Separator);
+                    }
+                }
+            }
+        }
+
+        let data_len = Bits::new(version).max_len(ec_level)?;
+        let ec_len = ec::ec_codewords_len(version, ec_level)? * 8;
+        for (i, (x, y)) in canvas.data_placement_order().into_iter().enumerate() {
+            let kind = match i {
+                _ if i < data_len => ModuleKind::Data,
+                _ if i < data_len + ec_len => ModuleKind::ErrorCorrection,
+                _ => ModuleKind::Remainder,
+            };
+            kinds[canvas.coords_to_index(x, y)] = Some(kind);
+        }
+        Ok(kinds
+            .into_iter()
+            .map(|kind| kind.unwrap_or(ModuleKind::Remainder))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod module_kinds_tests {
+    use super::*;
+
+    fn count(kinds: &[ModuleKind], kind: ModuleKind) -> usize {
+        kinds.iter().filter(|k| **k == kind).count()
+    }
+
+    #[test]
+    fn test_normal() {
+        let kinds = Canvas::module_kinds(Version::Normal(7), EcLevel::M).unwrap();
+        assert_eq!(kinds.len(), 45 * 45);
+        assert_eq!(count(&kinds, ModuleKind::Finder), 3 * 49);
+        assert_eq!(count(&kinds, ModuleKind::Separator), 3 * 15);
+        assert_eq!(count(&kinds, ModuleKind::Alignment), 6 * 25);
+        assert_eq!(count(&kinds, ModuleKind::FormatInfo), 31);
+        assert_eq!(count(&kinds, ModuleKind::VersionInfo), 36);
+        assert_eq!(count(&kinds, ModuleKind::Data), 124 * 8);
+        assert_eq!(count(&kinds, ModuleKind::ErrorCorrection), 72 * 8);
+        assert_eq!(count(&kinds, ModuleKind::Remainder), 0);
+        assert_eq!(kinds[6 * 45 + 8], ModuleKind::Timing);
+    }
+
+    #[test]
+    fn test_micro() {
+        let kinds = Canvas::module_kinds(Version::Micro(1), EcLevel::L).unwrap();
+        assert_eq!(count(&kinds, ModuleKind::Finder), 49);
+        assert_eq!(count(&kinds, ModuleKind::Separator), 15);
+        assert_eq!(count(&kinds, ModuleKind::FormatInfo), 15);
+        assert_eq!(count(&kinds, ModuleKind::Timing), 6);
+        assert_eq!(count(&kinds, ModuleKind::Data), 20);
+        assert_eq!(count(&kinds, ModuleKind::ErrorCorrection), 16);
+    }
+
+    #[test]
+    fn test_rect_micro() {
+        let kinds = Canvas::module_kinds(Version::RectMicro(7, 43), EcLevel::M).unwrap();
+        assert_eq!(kinds.len(), 7 * 43);
+        assert_eq!(kinds[0], ModuleKind::Finder);
+        assert_eq!(kinds[7], ModuleKind::Separator);
+        assert_eq!(kinds[43 * 7 - 1], ModuleKind::Finder);
+        assert_eq!(count(&kinds, ModuleKind::FormatInfo), 2 * 18);
+        assert_eq!(count(&kinds, ModuleKind::VersionInfo), 0);
+        assert_eq!(count(&kinds, ModuleKind::Data), 6 * 8);
+        assert_eq!(count(&kinds, ModuleKind::ErrorCorrection), 7 * 8);
+    }
+}
+
+// Geometry
+
+/// The bounding boxes of the functional patterns of a symbol.
+///
+/// The patterns which cross each other overlap, e.g. a timing pattern of QR
+/// code from version 7 passes through alignment patterns.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Geometry {
+    /// The 7×7 finder patterns.
+    pub finder_patterns: Vec<Region>,
+
+    /// The light separators around the finder patterns. Each L-shaped
+    /// separator is split into a horizontal and a vertical part.
+    pub separators: Vec<Region>,
+
+    /// The timing patterns, each of which is a single row or column.
+    pub timing_patterns: Vec<Region>,
+
+    /// The alignment patterns.
+    pub alignment_patterns: Vec<Region>,
+
+    /// The 5×5 finder sub-pattern in the bottom right corner of rMQR code.
+    pub finder_sub_patterns: Vec<Region>,
+
+    /// The corner finder patterns in the top right and bottom left corners of
+    /// rMQR code.
+    pub corner_finder_patterns: Vec<Region>,
+}
+
+/// Returns the bounding boxes of the functional patterns of the version.
+///
+/// Returns an empty geometry if the version is invalid.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{Version, canvas, types::Region};
+/// #
+/// let geometry = canvas::geometry(Version::Micro(2));
+/// assert_eq!(
+///     geometry.finder_patterns,
+///     [Region {
+///         x: 0,
+///         y: 0,
+///         width: 7,
+///         height: 7
+///     }]
+/// );
+/// assert_eq!(geometry.timing_patterns.len(), 2);
+/// ```
+#[must_use]
+pub fn geometry(version: Version) -> Geometry {
+    let rect = |x: i16, y: i16, width: i16, height: i16| Region {
+        x: x.as_usize(),
+        y: y.as_usize(),
+        width: width.as_usize(),
+        height: height.as_usize(),
+    };
+    let (width, height) = (version.width(), version.height());
+    let mut geometry = Geometry::default();
+    if version.is_normal() {
+        geometry.finder_patterns = vec![
+            rect(0, 0, 7, 7),
+            rect(width - 7, 0, 7, 7),
+            rect(0, height - 7, 7, 7),
+        ];
+        geometry.separators = vec![
+            rect(0, 7, 8, 1),
+            rect(7, 0, 1, 7),
+            rect(width - 8, 7, 8, 1),
+            rect(width - 8, 0, 1, 7),
+            rect(0, height - 8, 8, 1),
+            rect(7, height - 7, 1, 7),
+        ];
+        geometry.timing_patterns = vec![rect(8, 6, width - 16, 1), rect(6, 8, 1, height - 16)];
+    } else if version.is_micro() {
+        geometry.finder_patterns = vec![rect(0, 0, 7, 7)];
+        geometry.separators = vec![rect(0, 7, 8, 1), rect(7, 0, 1, 7)];
+        geometry.timing_patterns = vec![rect(8, 0, width - 8, 1), rect(0, 8, 1, height - 8)];
+    } else if version.is_rect_micro() {
+        geometry.finder_patterns = vec![rect(0, 0, 7, 7)];
+        geometry.separators = vec![rect(7, 0, 1, 7)];
+        if height > 7 {
+            geometry.separators.insert(0, rect(0, 7, 8, 1));
+        }
+        geometry.finder_sub_patterns = vec![rect(width - 5, height - 5, 5, 5)];
+        geometry.corner_finder_patterns = vec![
+            rect(width - 2, 0, 2, 2),
+            if height >= 11 {
+                rect(0, height - 2, 3, 2)
+            } else {
+                rect(0, height - 1, 3, 1)
+            },
+        ];
+        let bottom_start = if height == 7 { 8 } else { 3 };
+        geometry.timing_patterns = vec![
+            rect(8, 0, width - 10, 1),
+            rect(bottom_start, height - 1, width - 5 - bottom_start, 1),
+        ];
+        if height >= 11 {
+            geometry.timing_patterns.push(rect(0, 8, 1, height - 10));
+        }
+        if height >= 9 {
+            geometry
+                .timing_patterns
+                .push(rect(width - 1, 2, 1, height - 7));
+        }
+    }
+
+    let alignment_size = if version.is_rect_micro() { 3 } else { 5 };
+    let radius = alignment_size / 2;
+    let centers = alignment_pattern_centers(version);
+    geometry.alignment_patterns = centers
+        .iter()
+        .map(|&(x, y)| rect(x - radius, y - radius, alignment_size, alignment_size))
+        .collect();
+    if version.is_rect_micro() {
+        // The vertical timing patterns between the top and bottom alignment
+        // patterns.
+        let columns = centers.iter().filter(|&&(_, y)| y == 1);
+        geometry
+            .timing_patterns
+            .extend(columns.map(|&(x, _)| rect(x, 3, 1, height - 6)));
+    }
+    geometry
+}
+
+#[cfg(test)]
+mod geometry_tests {
+    use super::*;
+
+    /// Checks that the regions only contain modules of the kinds.
+    fn check(version: Version, regions: &[Region], kinds: &[ModuleKind]) {
+        let width = version.width().as_usize();
+        let module_kinds = Canvas::module_kinds(version, EcLevel::M).unwrap();
+        for region in regions {
+            for y in region.y..region.y + region.height {
+                for x in region.x..region.x + region.width {
+                    let kind = module_kinds[y * width + x];
+                    assert!(kinds.contains(&kind), "{version:?} ({x}, {y}) is {kind:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_module_kinds() {
+        for version in [
+            Version::Normal(1),
+            Version::Normal(2),
+            Version::Normal(7),
+            Version::Normal(40),
+            Version::Micro(2),
+            Version::Micro(4),
+            Version::RectMicro(7, 43),
+            Version::RectMicro(9, 59),
+            Version::RectMicro(11, 27),
+            Version::RectMicro(17, 139),
+        ] {
+            let g = geometry(version);
+            check(version, &g.finder_patterns, &[ModuleKind::Finder]);
+            check(version, &g.separators, &[ModuleKind::Separator]);
+            check(
+                version,
+                &g.timing_patterns,
+                &[ModuleKind::Timing, ModuleKind::Alignment],
+            );
+            // The alignment patterns of rMQR code lie on the timing patterns
+            // along the edges.
+            check(
+                version,
+                &g.alignment_patterns,
+                &[ModuleKind::Alignment, ModuleKind::Timing],
+            );
+            check(version, &g.finder_sub_patterns, &[ModuleKind::Finder]);
+            let separators: usize = g.separators.iter().map(Region::area).sum();
+            let module_kinds = Canvas::module_kinds(version, EcLevel::M).unwrap();
+            let count = module_kinds
+                .iter()
+                .filter(|k| **k == ModuleKind::Separator)
+                .count();
+            assert_eq!(separators, count, "{version:?}");
+        }
+    }
+
+    #[test]
+    fn test_timing_lengths() {
+        assert_eq!(geometry(Version::Normal(1)).timing_patterns[0].width, 5);
+        let rmqr = geometry(Version::RectMicro(7, 43));
+        // The top and bottom rows, and the columns of the alignment pattern.
+        assert_eq!(
+            rmqr.timing_patterns
+                .iter()
+                .map(|r| (r.x, r.y, r.width, r.height))
+                .collect::<Vec<_>>(),
+            [(8, 0, 33, 1), (8, 6, 30, 1), (21, 3, 1, 1)]
+        );
+        assert_eq!(rmqr.corner_finder_patterns[1].y, 6);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(geometry(Version::Normal(41)), Geometry::default());
+    }
 }
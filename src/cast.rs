@@ -14,6 +14,13 @@ impl Truncate for u16 {
     }
 }
 
+impl Truncate for u32 {
+    #[allow(clippy::cast_possible_truncation)]
+    fn truncate_as_u8(self) -> u8 {
+        self as u8
+    }
+}
+
 #[allow(clippy::wrong_self_convention)]
 pub trait As {
     fn as_i16(self) -> i16;
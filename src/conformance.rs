@@ -0,0 +1,309 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Strict conformance checks against ISO/IEC 18004:2024 and ISO/IEC
+//! 23941:2022.
+//!
+//! The regular constructors of [`QrCode`] accept some configurations which are
+//! readable by most scanners but are not allowed by the standards. The
+//! functions in this module check every such rule and report all violations,
+//! so that symbols which merely "happen to scan" can be rejected.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{EcLevel, QrCode, Version, conformance};
+//!
+//! let (code, report) =
+//!     conformance::encode_strict(b"01234567", Version::Normal(1), EcLevel::M).unwrap();
+//! assert!(report.is_conformant());
+//!
+//! // Rendering without the quiet zone is not conformant.
+//! let mut renderer = code.render::<char>();
+//! renderer.has_quiet_zone(false);
+//! let report = conformance::check_renderer(code.version(), &renderer);
+//! assert!(!report.is_conformant());
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{
+    QrCode,
+    bits::Bits,
+    optimize::{self, Segment},
+    render::{Pixel, Renderer},
+    types::{EcLevel, Mode, QrError, Version},
+};
+
+/// A normative requirement checked by this module.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Requirement {
+    /// The error correction level must be supported by the version, e.g. M1
+    /// only supports error detection and rMQR code only supports M and H.
+    EcLevel,
+
+    /// Micro QR code M1 only supports [`Mode::Numeric`], and M2 only supports
+    /// [`Mode::Numeric`] and [`Mode::Alphanumeric`].
+    MicroQrMode,
+
+    /// Byte mode data without an ECI designator is interpreted as ISO/IEC
+    /// 8859-1, so it must not contain C1 control characters or multibyte
+    /// UTF-8 sequences.
+    CharacterSet,
+
+    /// The data must fit in the capacity of the version.
+    Capacity,
+
+    /// The rendered symbol must be surrounded by a light quiet zone of at
+    /// least 4 modules for QR code, and 2 modules for Micro QR code and rMQR
+    /// code.
+    QuietZone,
+}
+
+impl fmt::Display for Requirement {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EcLevel => write!(f, "error correction level supported by the version"),
+            Self::MicroQrMode => write!(f, "data mode supported by the Micro QR code version"),
+            Self::CharacterSet => write!(f, "byte data compatible with ISO/IEC 8859-1"),
+            Self::Capacity => write!(f, "data within the capacity of the version"),
+            Self::QuietZone => write!(f, "minimum quiet zone width"),
+        }
+    }
+}
+
+/// The result of a conformance check.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ConformanceReport {
+    checked: Vec<Requirement>,
+    violations: Vec<Requirement>,
+}
+
+impl ConformanceReport {
+    fn record(&mut self, requirement: Requirement, is_satisfied: bool) {
+        self.checked.push(requirement);
+        if !is_satisfied {
+            self.violations.push(requirement);
+        }
+    }
+
+    /// Returns [`true`] if no requirement is violated.
+    #[must_use]
+    #[inline]
+    pub fn is_conformant(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Returns the requirements which have been checked.
+    #[must_use]
+    #[inline]
+    pub fn checked(&self) -> &[Requirement] {
+        &self.checked
+    }
+
+    /// Returns the requirements which are violated.
+    #[must_use]
+    #[inline]
+    pub fn violations(&self) -> &[Requirement] {
+        &self.violations
+    }
+}
+
+/// Checks whether `data` contains C1 control characters, or bytes which look
+/// like a multibyte UTF-8 sequence.
+fn is_latin1_compatible(data: &[u8]) -> bool {
+    let has_c1_control = data.iter().any(|b| (0x80..=0x9f).contains(b));
+    let has_utf8_sequence = data.iter().any(|b| *b >= 0x80) && core::str::from_utf8(data).is_ok();
+    !has_c1_control && !has_utf8_sequence
+}
+
+/// Checks whether encoding `data` with the given version and error correction
+/// level conforms to the standards.
+///
+/// The data is segmented by [`optimize::optimal_segments`], in the same way as
+/// [`encode_strict`] encodes it.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{EcLevel, Version, conformance::{self, Requirement}};
+/// #
+/// let report = conformance::check(b"AB", Version::Micro(1), EcLevel::L);
+/// assert_eq!(report.violations(), [Requirement::MicroQrMode]);
+///
+/// let report = conformance::check("café".as_bytes(), Version::Normal(1), EcLevel::M);
+/// assert_eq!(report.violations(), [Requirement::CharacterSet]);
+/// ```
+#[must_use]
+pub fn check(data: &[u8], version: Version, ec_level: EcLevel) -> ConformanceReport {
+    let segments = optimize::optimal_segments(data, version);
+    check_segments(data, &segments, version, ec_level)
+}
+
+/// Checks whether encoding `data` as `segments` conforms to the standards.
+fn check_segments(
+    data: &[u8],
+    segments: &[Segment],
+    version: Version,
+    ec_level: EcLevel,
+) -> ConformanceReport {
+    let mut report = ConformanceReport::default();
+
+    let capacity = Bits::new(version).max_len(ec_level);
+    report.record(Requirement::EcLevel, capacity.is_ok());
+
+    if version.is_micro() {
+        let modes_are_supported = segments.iter().all(|s| version.supports_mode(s.mode));
+        report.record(Requirement::MicroQrMode, modes_are_supported);
+    }
+
+    let byte_data_is_latin1 = segments
+        .iter()
+        .filter(|s| s.mode == Mode::Byte)
+        .all(|s| is_latin1_compatible(&data[s.begin..s.end]));
+    report.record(Requirement::CharacterSet, byte_data_is_latin1);
+
+    if let Ok(capacity) = capacity {
+        let total_len = optimize::total_encoded_len(segments, version);
+        report.record(Requirement::Capacity, total_len <= capacity);
+    }
+
+    report
+}
+
+/// Checks whether the renderer settings conform to the standards for a symbol
+/// of the given version.
+#[must_use]
+pub fn check_renderer<P: Pixel>(version: Version, renderer: &Renderer<'_, P>) -> ConformanceReport {
+    let minimum_quiet_zone = if version.is_normal() { 4 } else { 2 };
+    let mut report = ConformanceReport::default();
+    report.record(
+        Requirement::QuietZone,
+        renderer.effective_quiet_zone() >= minimum_quiet_zone,
+    );
+    report
+}
+
+/// Constructs a new QR code for the given version and error correction level,
+/// rejecting any configuration which does not conform to the standards.
+///
+/// # Errors
+///
+/// Returns [`Err`] with [`QrError::NonConformant`] if [`check`] reports any
+/// violation, or with the construction error if the QR code cannot be
+/// constructed. In both cases the report is returned alongside the error, so
+/// the violated requirements can be inspected.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{EcLevel, Version, conformance::{self, Requirement}, types::QrError};
+/// #
+/// let (err, report) =
+///     conformance::encode_strict(b"AB", Version::Micro(1), EcLevel::L).unwrap_err();
+/// assert_eq!(err, QrError::NonConformant);
+/// assert_eq!(report.violations(), [Requirement::MicroQrMode]);
+/// ```
+pub fn encode_strict(
+    data: &[u8],
+    version: Version,
+    ec_level: EcLevel,
+) -> Result<(QrCode, ConformanceReport), (QrError, ConformanceReport)> {
+    // The symbol is encoded from the same segments which have been checked,
+    // so that the verdicts match the symbol.
+    let segments = optimize::optimal_segments(data, version);
+    let report = check_segments(data, &segments, version, ec_level);
+    if !report.is_conformant() {
+        return Err((QrError::NonConformant, report));
+    }
+    let mut bits = Bits::new(version);
+    let code = bits
+        .push_segments(data, segments.into_iter())
+        .and_then(|()| bits.push_terminator(ec_level))
+        .and_then(|()| QrCode::with_bits(bits, ec_level));
+    match code {
+        Ok(code) => Ok((code, report)),
+        Err(err) => Err((err, report)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conformant() {
+        let report = check(b"AB12", Version::Micro(2), EcLevel::L);
+        assert!(report.is_conformant());
+        assert_eq!(
+            report.checked(),
+            [
+                Requirement::EcLevel,
+                Requirement::MicroQrMode,
+                Requirement::CharacterSet,
+                Requirement::Capacity
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ec_level() {
+        let report = check(b"1", Version::RectMicro(7, 43), EcLevel::L);
+        assert_eq!(report.violations(), [Requirement::EcLevel]);
+        let report = check(b"1", Version::Micro(1), EcLevel::M);
+        assert_eq!(report.violations(), [Requirement::EcLevel]);
+    }
+
+    #[test]
+    fn test_micro_qr_mode() {
+        let report = check(b"a", Version::Micro(2), EcLevel::L);
+        assert_eq!(report.violations(), [Requirement::MicroQrMode]);
+        assert!(check(b"a", Version::Micro(3), EcLevel::L).is_conformant());
+    }
+
+    #[test]
+    fn test_character_set() {
+        assert!(check(b"caf\xe9", Version::Normal(1), EcLevel::M).is_conformant());
+        let report = check(b"\x85", Version::Normal(1), EcLevel::M);
+        assert_eq!(report.violations(), [Requirement::CharacterSet]);
+    }
+
+    #[test]
+    fn test_capacity() {
+        let report = check(&[b'a'; 100], Version::Normal(1), EcLevel::M);
+        assert_eq!(report.violations(), [Requirement::Capacity]);
+    }
+
+    #[test]
+    fn test_encode_strict() {
+        assert!(encode_strict(b"12345", Version::Micro(1), EcLevel::L).is_ok());
+        let (err, report) = encode_strict(b"\x85", Version::Normal(1), EcLevel::M).unwrap_err();
+        assert_eq!(err, QrError::NonConformant);
+        assert_eq!(report.violations(), [Requirement::CharacterSet]);
+    }
+
+    #[test]
+    fn test_encode_strict_capacity() {
+        // Version 1-M holds at most 34 digits.
+        let data = [b'0'; 35];
+        assert!(encode_strict(&data[..34], Version::Normal(1), EcLevel::M).is_ok());
+        let (err, report) = encode_strict(&data, Version::Normal(1), EcLevel::M).unwrap_err();
+        assert_eq!(err, QrError::NonConformant);
+        assert_eq!(report.violations(), [Requirement::Capacity]);
+    }
+
+    #[test]
+    fn test_quiet_zone() {
+        let code = QrCode::new_micro(b"1").unwrap();
+        let mut renderer = code.render::<char>();
+        assert!(check_renderer(code.version(), &renderer).is_conformant());
+        renderer.quiet_zone(1);
+        assert_eq!(
+            check_renderer(code.version(), &renderer).violations(),
+            [Requirement::QuietZone]
+        );
+    }
+}
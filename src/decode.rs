@@ -0,0 +1,598 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Decoding of module matrices.
+//!
+//! This reads the modules of a QR code, Micro QR code or rMQR code symbol
+//! which have already been sampled into a grid, e.g. the output of
+//! [`QrCode::to_colors`]. It does not locate a symbol in a camera image.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, decode};
+//!
+//! let code = QrCode::new(b"Hello, world!").unwrap();
+//! let decoded = decode::decode_qr_code(&code).unwrap();
+//! assert_eq!(decoded.payload(), b"Hello, world!");
+//! assert_eq!(decoded.corrected_errors, 0);
+//! ```
+
+use alloc::vec::Vec;
+use core::{error::Error, fmt};
+
+use crate::{
+    QrCode,
+    bits::Bits,
+    canvas::{Canvas, MaskPattern},
+    cast::{As, Truncate},
+    ec,
+    types::{Color, EcLevel, Mode, Version},
+};
+
+/// `DecodeError` encodes the error encountered when decoding a module matrix.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DecodeError {
+    /// The number of modules, the width or the height does not match any
+    /// version.
+    InvalidDimensions,
+
+    /// The format information cannot be read.
+    InvalidFormatInfo,
+
+    /// The version information does not match the dimensions.
+    InvalidVersionInfo,
+
+    /// A block has more errors than the error correction codewords can
+    /// correct.
+    TooManyErrors,
+
+    /// The corrected data codewords do not form a valid bit stream.
+    InvalidData,
+}
+
+impl fmt::Display for DecodeError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match *self {
+            Self::InvalidDimensions => "invalid dimensions",
+            Self::InvalidFormatInfo => "invalid format information",
+            Self::InvalidVersionInfo => "invalid version information",
+            Self::TooManyErrors => "too many errors",
+            Self::InvalidData => "invalid data",
+        };
+        f.write_str(msg)
+    }
+}
+
+impl Error for DecodeError {}
+
+/// `DecodeResult` is a convenient alias for a decoding result.
+pub type DecodeResult<T> = Result<T, DecodeError>;
+
+/// A segment of the decoded bit stream.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Segment {
+    /// Data encoded in the given mode. Numeric and alphanumeric data are
+    /// returned as ASCII, and Kanji data as Shift JIS.
+    Data {
+        /// The mode of the segment.
+        mode: Mode,
+
+        /// The decoded bytes.
+        data: Vec<u8>,
+    },
+
+    /// An ECI designator, which applies to the following data.
+    Eci(u32),
+
+    /// FNC1 in the first position, for GS1 data.
+    Fnc1First,
+
+    /// FNC1 in the second position, with the application indicator.
+    Fnc1Second(u8),
+
+    /// Structured append header.
+    StructuredAppend {
+        /// The 0-based position of this symbol.
+        index: u8,
+
+        /// The total number of symbols.
+        total: u8,
+
+        /// The parity of the whole data.
+        parity: u8,
+    },
+}
+
+/// The result of decoding a symbol.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Decoded {
+    /// The version of the symbol.
+    pub version: Version,
+
+    /// The error correction level of the symbol.
+    pub ec_level: EcLevel,
+
+    /// The mask pattern of the symbol.
+    pub mask_pattern: MaskPattern,
+
+    /// The segments of the bit stream, in order.
+    pub segments: Vec<Segment>,
+
+    /// The number of codewords corrected by the error correction.
+    pub corrected_errors: usize,
+}
+
+impl Decoded {
+    /// Returns the bytes of all data segments concatenated, ignoring the other
+    /// segments.
+    #[must_use]
+    pub fn payload(&self) -> Vec<u8> {
+        self.segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Data { data, .. } => Some(&data[..]),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .concat()
+    }
+}
+
+/// Decodes the modules of a symbol without the quiet zone, in row-major
+/// order.
+///
+/// The version is determined from `width` and `height`. Up to 3 wrong bits of
+/// the format and version information are corrected.
+///
+/// # Errors
+///
+/// Returns [`Err`] if the symbol cannot be decoded.
+///
+/// # Examples
+///
+/// ```
+/// use qrcode2::{Color, QrCode, Version, decode};
+///
+/// let code = QrCode::new_micro(b"01234567").unwrap();
+/// let mut colors = code.to_colors();
+/// colors[60] = !colors[60];
+/// let decoded = decode::decode(&colors, code.width(), code.height()).unwrap();
+/// assert_eq!(decoded.version, Version::Micro(2));
+/// assert_eq!(decoded.payload(), b"01234567");
+/// ```
+pub fn decode(colors: &[Color], width: usize, height: usize) -> DecodeResult<Decoded> {
+    if colors.len() != width * height {
+        return Err(DecodeError::InvalidDimensions);
+    }
+    let version = version_from_dimensions(width, height).ok_or(DecodeError::InvalidDimensions)?;
+
+    let canvas = Canvas::from_colors(version, EcLevel::L, colors);
+    let (ec_level, mask_pattern) = canvas
+        .read_format_info()
+        .ok_or(DecodeError::InvalidFormatInfo)?;
+    if canvas
+        .read_version_info()
+        .is_some_and(|read| read != version)
+    {
+        return Err(DecodeError::InvalidVersionInfo);
+    }
+
+    let data_len =
+        ec::data_codewords_len(version, ec_level).map_err(|_| DecodeError::InvalidFormatInfo)?;
+    let ec_len =
+        ec::ec_codewords_len(version, ec_level).map_err(|_| DecodeError::InvalidFormatInfo)?;
+    let canvas = Canvas::from_colors(version, ec_level, colors);
+    let (data, ec) = canvas.read_codewords(mask_pattern, data_len, ec_len);
+    let (data, corrected_errors) = ec::correct_codewords(&data, &ec, version, ec_level)
+        .map_err(|_| DecodeError::InvalidFormatInfo)?
+        .ok_or(DecodeError::TooManyErrors)?;
+
+    let bits_len = Bits::new(version)
+        .max_len(ec_level)
+        .map_err(|_| DecodeError::InvalidFormatInfo)?;
+    let segments = parse_segments(&data, bits_len, version)?;
+    Ok(Decoded {
+        version,
+        ec_level,
+        mask_pattern,
+        segments,
+        corrected_errors,
+    })
+}
+
+/// Decodes the modules of a [`QrCode`].
+///
+/// This is useful to verify that a symbol encodes the expected data.
+///
+/// # Errors
+///
+/// Returns [`Err`] if the symbol cannot be decoded.
+#[inline]
+pub fn decode_qr_code(code: &QrCode) -> DecodeResult<Decoded> {
+    decode(&code.to_colors(), code.width(), code.height())
+}
+
+/// Returns the version of a symbol of the given dimensions.
+fn version_from_dimensions(width: usize, height: usize) -> Option<Version> {
+    let width = i16::try_from(width).ok()?;
+    let height = i16::try_from(height).ok()?;
+    let version = if width != height {
+        Version::RectMicro(height, width)
+    } else if (11..=17).contains(&width) && width % 2 == 1 {
+        Version::Micro((width - 9) / 2)
+    } else if (21..=177).contains(&width) && (width - 17) % 4 == 0 {
+        Version::Normal((width - 17) / 4)
+    } else {
+        return None;
+    };
+    version.validate().ok()
+}
+
+/// Reads numbers from the data codewords.
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    len: usize,
+}
+
+impl BitReader<'_> {
+    /// Returns the number of bits which are not read yet.
+    const fn remaining(&self) -> usize {
+        self.len - self.position
+    }
+
+    /// Reads a number of `n` bits, at most 32, without advancing.
+    fn peek(&self, n: usize) -> DecodeResult<u32> {
+        if n > self.remaining() {
+            return Err(DecodeError::InvalidData);
+        }
+        Ok((self.position..self.position + n).fold(0, |number, i| {
+            number << 1 | u32::from(self.data[i / 8] >> (7 - i % 8) & 1)
+        }))
+    }
+
+    /// Reads a number of `n` bits, at most 32.
+    fn read(&mut self, n: usize) -> DecodeResult<u32> {
+        let number = self.peek(n)?;
+        self.position += n;
+        Ok(number)
+    }
+
+    /// Reads a number of `n` bits which must be less than `limit`.
+    fn read_below(&mut self, n: usize, limit: u32) -> DecodeResult<u32> {
+        self.read(n).and_then(|number| {
+            (number < limit)
+                .then_some(number)
+                .ok_or(DecodeError::InvalidData)
+        })
+    }
+}
+
+/// The characters of the alphanumeric mode, in the order of their values.
+const ALPHANUMERIC_CHARS: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// The mode indicator read from the bit stream.
+enum Indicator {
+    Data(Mode),
+    Eci,
+    Fnc1First,
+    Fnc1Second,
+    StructuredAppend,
+}
+
+/// Parses the first `len` bits of the data codewords into segments, until the
+/// terminator.
+fn parse_segments(data: &[u8], len: usize, version: Version) -> DecodeResult<Vec<Segment>> {
+    let terminator_size = match version {
+        Version::Micro(a) => a.as_usize() * 2 + 1,
+        Version::RectMicro(..) => 3,
+        Version::Normal(_) => 4,
+    };
+    let mut reader = BitReader {
+        data,
+        position: 0,
+        len,
+    };
+    let mut segments = Vec::new();
+    while reader.remaining() > 0 {
+        if reader.peek(terminator_size.min(reader.remaining()))? == 0 {
+            break;
+        }
+        let segment = match read_indicator(&mut reader, version)? {
+            Indicator::Data(mode) => {
+                let count = reader.read(mode.length_bits_count(version))?;
+                Segment::Data {
+                    mode,
+                    data: read_data(&mut reader, mode, count)?,
+                }
+            }
+            Indicator::Eci => Segment::Eci(read_eci_designator(&mut reader)?),
+            Indicator::Fnc1First => Segment::Fnc1First,
+            Indicator::Fnc1Second => Segment::Fnc1Second(reader.read(8)?.truncate_as_u8()),
+            Indicator::StructuredAppend => Segment::StructuredAppend {
+                index: reader.read(4)?.truncate_as_u8(),
+                total: reader.read(4)?.truncate_as_u8() + 1,
+                parity: reader.read(8)?.truncate_as_u8(),
+            },
+        };
+        segments.push(segment);
+    }
+    Ok(segments)
+}
+
+/// Reads a mode indicator.
+fn read_indicator(reader: &mut BitReader<'_>, version: Version) -> DecodeResult<Indicator> {
+    let number = reader.read(version.mode_bits_count())?;
+    #[allow(clippy::match_same_arms)]
+    let indicator = match (version, number) {
+        (Version::Micro(_), 0) => Indicator::Data(Mode::Numeric),
+        (Version::Micro(_), 1) => Indicator::Data(Mode::Alphanumeric),
+        (Version::Micro(_), 0b10) => Indicator::Data(Mode::Byte),
+        (Version::Micro(_), 0b11) => Indicator::Data(Mode::Kanji),
+        (Version::RectMicro(..), 0b001) => Indicator::Data(Mode::Numeric),
+        (Version::RectMicro(..), 0b010) => Indicator::Data(Mode::Alphanumeric),
+        (Version::RectMicro(..), 0b011) => Indicator::Data(Mode::Byte),
+        (Version::RectMicro(..), 0b100) => Indicator::Data(Mode::Kanji),
+        (Version::RectMicro(..), 0b101) => Indicator::Fnc1First,
+        (Version::RectMicro(..), 0b110) => Indicator::Fnc1Second,
+        (Version::RectMicro(..), 0b111) => Indicator::Eci,
+        (Version::Normal(_), 0b0001) => Indicator::Data(Mode::Numeric),
+        (Version::Normal(_), 0b0010) => Indicator::Data(Mode::Alphanumeric),
+        (Version::Normal(_), 0b0100) => Indicator::Data(Mode::Byte),
+        (Version::Normal(_), 0b1000) => Indicator::Data(Mode::Kanji),
+        (Version::Normal(_), 0b0111) => Indicator::Eci,
+        (Version::Normal(_), 0b0101) => Indicator::Fnc1First,
+        (Version::Normal(_), 0b1001) => Indicator::Fnc1Second,
+        (Version::Normal(_), 0b0011) => Indicator::StructuredAppend,
+        _ => return Err(DecodeError::InvalidData),
+    };
+    match indicator {
+        Indicator::Data(mode) if !version.supports_mode(mode) => Err(DecodeError::InvalidData),
+        indicator => Ok(indicator),
+    }
+}
+
+/// Reads an ECI designator of 1, 2 or 3 bytes.
+fn read_eci_designator(reader: &mut BitReader<'_>) -> DecodeResult<u32> {
+    let designator = match reader.read(8)? {
+        first @ 0..=0x7f => first,
+        first @ 0x80..=0xbf => ((first & 0x3f) << 8) | reader.read(8)?,
+        first @ 0xc0..=0xdf => ((first & 0x1f) << 16) | reader.read(16)?,
+        _ => return Err(DecodeError::InvalidData),
+    };
+    if designator < 1_000_000 {
+        Ok(designator)
+    } else {
+        Err(DecodeError::InvalidData)
+    }
+}
+
+/// Reads `count` characters of data in the given mode.
+fn read_data(reader: &mut BitReader<'_>, mode: Mode, count: u32) -> DecodeResult<Vec<u8>> {
+    let count = count.as_usize();
+    let mut data = Vec::with_capacity(count);
+    match mode {
+        Mode::Numeric => {
+            for digits in (0..count).step_by(3).map(|i| (count - i).min(3)) {
+                let (bits, limit) = [(4, 10), (7, 100), (10, 1000)][digits - 1];
+                let number = reader.read_below(bits, limit)?;
+                let start = data.len();
+                data.resize(start + digits, 0);
+                (0..digits).rev().fold(number, |number, i| {
+                    data[start + i] = b'0' + (number % 10).truncate_as_u8();
+                    number / 10
+                });
+            }
+        }
+        Mode::Alphanumeric => {
+            for _ in 0..count / 2 {
+                let number = reader.read_below(11, 45 * 45)?;
+                data.push(ALPHANUMERIC_CHARS[(number / 45).as_usize()]);
+                data.push(ALPHANUMERIC_CHARS[(number % 45).as_usize()]);
+            }
+            if count % 2 == 1 {
+                data.push(ALPHANUMERIC_CHARS[reader.read_below(6, 45)?.as_usize()]);
+            }
+        }
+        Mode::Byte => {
+            for _ in 0..count {
+                data.push(reader.read(8)?.truncate_as_u8());
+            }
+        }
+        Mode::Kanji => {
+            for _ in 0..count {
+                let number = reader.read(13)?;
+                let code = ((number / 0xc0) << 8) | (number % 0xc0);
+                let code = if code < 0x1f00 {
+                    code + 0x8140
+                } else {
+                    code + 0xc140
+                };
+                data.extend([(code >> 8).truncate_as_u8(), code.truncate_as_u8()]);
+            }
+        }
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::bits::{Bits, ExtendedMode};
+
+    fn assert_round_trip(code: &QrCode, data: &[u8]) {
+        let decoded = decode_qr_code(code).unwrap();
+        assert_eq!(decoded.version, code.version());
+        assert_eq!(decoded.ec_level, code.error_correction_level());
+        assert_eq!(decoded.payload(), data);
+        assert_eq!(decoded.corrected_errors, 0);
+    }
+
+    #[test]
+    fn test_normal() {
+        for data in [
+            &b"01234567"[..],
+            b"HELLO WORLD",
+            b"Hello, world!",
+            b"\x93\x5f\xe4\xaa",
+            b"12345678901234567890ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz",
+        ] {
+            assert_round_trip(&QrCode::new(data).unwrap(), data);
+        }
+        let data = [b'a'; 1000];
+        for ec_level in [EcLevel::L, EcLevel::M, EcLevel::Q, EcLevel::H] {
+            let code = QrCode::with_error_correction_level(data, ec_level).unwrap();
+            assert_round_trip(&code, &data);
+        }
+    }
+
+    #[test]
+    fn test_micro() {
+        for data in [&b"01234"[..], b"0123456789", b"HELLO", b"Hello"] {
+            assert_round_trip(&QrCode::new_micro(data).unwrap(), data);
+        }
+        for ec_level in [EcLevel::L, EcLevel::M, EcLevel::Q] {
+            let code = QrCode::micro_with_error_correction_level(b"ABC", ec_level).unwrap();
+            assert_round_trip(&code, b"ABC");
+        }
+    }
+
+    #[test]
+    fn test_rect_micro() {
+        for data in [&b"0123456789"[..], b"HELLO WORLD", b"Hello, world!"] {
+            assert_round_trip(&QrCode::new_rect_micro(data).unwrap(), data);
+        }
+        let code = QrCode::rect_micro_with_error_correction_level(b"Hello", EcLevel::H).unwrap();
+        assert_round_trip(&code, b"Hello");
+    }
+
+    #[test]
+    fn test_segments() {
+        let mut bits = Bits::new(Version::Normal(2));
+        bits.push_structured_append(1, 3, 0x5a).unwrap();
+        bits.push_eci_designator(26).unwrap();
+        bits.push_fnc1_second_position(37).unwrap();
+        bits.push_eci_designator(20000).unwrap();
+        bits.push_byte_data(b"abc").unwrap();
+        bits.push_terminator(EcLevel::M).unwrap();
+        let code = QrCode::with_bits(bits, EcLevel::M).unwrap();
+        assert_eq!(
+            decode_qr_code(&code).unwrap().segments,
+            [
+                Segment::StructuredAppend {
+                    index: 1,
+                    total: 3,
+                    parity: 0x5a
+                },
+                Segment::Eci(26),
+                Segment::Fnc1Second(37),
+                Segment::Eci(20000),
+                Segment::Data {
+                    mode: Mode::Byte,
+                    data: b"abc".to_vec()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_kanji() {
+        let mut bits = Bits::new(Version::RectMicro(11, 27));
+        bits.push_mode_indicator(ExtendedMode::Fnc1First).unwrap();
+        bits.push_kanji_data(b"\x93\x5f\xe4\xaa").unwrap();
+        bits.push_terminator(EcLevel::M).unwrap();
+        let code = QrCode::with_bits(bits, EcLevel::M).unwrap();
+        assert_eq!(
+            decode_qr_code(&code).unwrap().segments,
+            [
+                Segment::Fnc1First,
+                Segment::Data {
+                    mode: Mode::Kanji,
+                    data: b"\x93\x5f\xe4\xaa".to_vec()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_correct_errors() {
+        let code = QrCode::with_error_correction_level(b"Hello, world!", EcLevel::H).unwrap();
+        let mut colors = code.to_colors();
+        let width = code.width();
+        // Flip every module of a few rows in the data region.
+        for color in &mut colors[width * 10 + 9..width * 10 + 13] {
+            *color = !*color;
+        }
+        let decoded = decode(&colors, width, code.height()).unwrap();
+        assert_eq!(decoded.payload(), b"Hello, world!");
+        assert!(decoded.corrected_errors > 0);
+    }
+
+    #[test]
+    fn test_corrupted_format_info() {
+        let code = QrCode::new(b"Hello, world!").unwrap();
+        let mut colors = code.to_colors();
+        let width = code.width();
+        for x in [0, 1, 2] {
+            colors[width * 8 + x] = !colors[width * 8 + x];
+        }
+        assert_eq!(
+            decode(&colors, width, code.height()).unwrap().payload(),
+            b"Hello, world!"
+        );
+    }
+
+    #[test]
+    fn test_too_many_errors() {
+        let code = QrCode::with_error_correction_level(b"Hello, world!", EcLevel::L).unwrap();
+        let mut colors = code.to_colors();
+        let width = code.width();
+        for y in 9..21 {
+            for x in 9..13 {
+                colors[width * y + x] = !colors[width * y + x];
+            }
+        }
+        assert_eq!(
+            decode(&colors, width, code.height()),
+            Err(DecodeError::TooManyErrors)
+        );
+    }
+
+    #[test]
+    fn test_invalid_dimensions() {
+        let colors = vec![Color::Light; 22 * 22];
+        assert_eq!(decode(&colors, 22, 22), Err(DecodeError::InvalidDimensions));
+        assert_eq!(decode(&colors, 21, 21), Err(DecodeError::InvalidDimensions));
+    }
+
+    #[test]
+    fn test_invalid_format_info() {
+        let colors = vec![Color::Light; 21 * 21];
+        assert_eq!(decode(&colors, 21, 21), Err(DecodeError::InvalidFormatInfo));
+    }
+
+    #[test]
+    fn test_invalid_version_info() {
+        let code = QrCode::with_version(b"Hello", Version::Normal(7), EcLevel::L).unwrap();
+        let mut colors = code.to_colors();
+        let other = QrCode::with_version(b"Hello", Version::Normal(8), EcLevel::L)
+            .unwrap()
+            .to_colors();
+        // Replace the version information with that of version 8.
+        for y in 0..6 {
+            for x in 34..37 {
+                colors[45 * y + x] = other[49 * y + x + 4];
+                colors[45 * x + y] = other[49 * (x + 4) + y];
+            }
+        }
+        assert_eq!(
+            decode(&colors, 45, 45),
+            Err(DecodeError::InvalidVersionInfo)
+        );
+    }
+}
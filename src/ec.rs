@@ -7,7 +7,11 @@
 
 //! The `ec` module applies the Reed-Solomon error correction codes.
 
-use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "trace")]
+use core::iter;
+#[cfg(any(feature = "trace", all(test, feature = "alloc")))]
 use core::ops::Deref;
 
 use crate::types::{EcLevel, QrResult, Version};
@@ -23,30 +27,51 @@ use crate::types::{EcLevel, QrResult, Version};
 /// x<sup>m+n</sup> + a\[1\] x<sup>m+n-1</sup> + … + a\[m\] x<sup>n</sup>) in
 /// GF(2<sup>8</sup>), and then computes the polynomial modulus with a generator
 /// polynomial of degree N.
+#[cfg(feature = "alloc")]
 #[must_use]
 pub fn create_error_correction_code(data: &[u8], ec_code_size: usize) -> Vec<u8> {
-    let data_len = data.len();
-    let log_den = GENERATOR_POLYNOMIALS[ec_code_size];
-
-    let mut res = data.to_vec();
-    res.resize(ec_code_size + data_len, 0);
+    let mut res = vec![0; ec_code_size];
+    write_error_correction_code(data, &mut res);
+    res
+}
 
-    for i in 0..data_len {
-        let lead_coeff = res[i] as usize;
-        if lead_coeff == 0 {
-            continue;
+/// Writes the error correction code of the data into `ec`, whose length is the
+/// number of error correction codewords, without allocating.
+///
+/// Like `create_error_correction_code`, this only supports up to 69
+/// codewords.
+pub(crate) const fn write_error_correction_code(data: &[u8], ec: &mut [u8]) {
+    // This is a `const fn` for `heapless::QrCodeBuf::encode`, hence the loops.
+    let log_den = GENERATOR_POLYNOMIALS[ec.len()];
+    let mut i = 0;
+    while i < ec.len() {
+        ec[i] = 0;
+        i += 1;
+    }
+    let mut k = 0;
+    while k < data.len() {
+        // Shift the remainder by one term, and subtract the generator
+        // polynomial multiplied by the leading coefficient.
+        let lead_coeff = (data[k] ^ ec[0]) as usize;
+        let mut i = 1;
+        while i < ec.len() {
+            ec[i - 1] = ec[i];
+            i += 1;
         }
-
-        let log_lead_coeff = usize::from(LOG_TABLE[lead_coeff]);
-        for (u, v) in res[i + 1..].iter_mut().zip(log_den.iter()) {
-            *u ^= EXP_TABLE[(usize::from(*v) + log_lead_coeff) % 255];
+        ec[ec.len() - 1] = 0;
+        if lead_coeff != 0 {
+            let log_lead_coeff = LOG_TABLE[lead_coeff] as usize;
+            let mut i = 0;
+            while i < ec.len() {
+                ec[i] ^= EXP_TABLE[(log_den[i] as usize + log_lead_coeff) % 255];
+                i += 1;
+            }
         }
+        k += 1;
     }
-
-    res.split_off(data_len)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "alloc"))]
 mod ec_tests {
     use super::*;
 
@@ -72,6 +97,332 @@ mod ec_tests {
     }
 }
 
+// Reusable encoder
+
+/// A Reed-Solomon encoder over GF(2<sup>8</sup>) with the primitive
+/// polynomial x<sup>8</sup> + x<sup>4</sup> + x<sup>3</sup> + x<sup>2</sup> +
+/// 1, as used by QR code, Micro QR code and rMQR code.
+///
+/// The generator polynomial of degree N is (x − 2<sup>0</sup>)(x −
+/// 2<sup>1</sup>)…(x − 2<sup>N-1</sup>). A block of data and error correction
+/// codewords can only be corrected if it is at most 255 codewords long.
+///
+/// # Examples
+///
+/// The example of ISO/IEC 18004:2000 Annex I, which encodes `01234567` in
+/// version 1-M:
+///
+/// ```
+/// # use qrcode2::ec::ReedSolomon;
+/// #
+/// let data = b"\x10\x20\x0c\x56\x61\x80\xec\x11\xec\x11\xec\x11\xec\x11\xec\x11";
+/// let ec = ReedSolomon.encode_block(data, 10);
+/// assert_eq!(ec, b"\xa5\x24\xd4\xc1\xed\x36\xc7\x87\x2c\x55");
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ReedSolomon;
+
+#[cfg(feature = "alloc")]
+impl ReedSolomon {
+    /// Computes the `ec_len` error correction codewords of a block of data
+    /// codewords.
+    ///
+    /// This is the remainder of the data, multiplied by x<sup>`ec_len`</sup>,
+    /// divided by the generator polynomial of degree `ec_len`.
+    #[must_use]
+    pub fn encode_block(self, data: &[u8], ec_len: usize) -> Vec<u8> {
+        if ec_len < GENERATOR_POLYNOMIALS.len() {
+            return create_error_correction_code(data, ec_len);
+        }
+
+        let generator = self.generator_poly(ec_len);
+        let mut res = data.to_vec();
+        res.resize(data.len() + ec_len, 0);
+        for i in 0..data.len() {
+            let lead_coeff = res[i];
+            if lead_coeff == 0 {
+                continue;
+            }
+            for (u, v) in res[i + 1..].iter_mut().zip(&generator[1..]) {
+                *u ^= mul(*v, lead_coeff);
+            }
+        }
+        res.split_off(data.len())
+    }
+
+    /// Returns the coefficients of the generator polynomial of the given
+    /// degree, from the highest degree term to the constant term.
+    ///
+    /// The polynomial is monic, so the first coefficient is always 1.
+    ///
+    /// # Examples
+    ///
+    /// The generator polynomial for 7 error correction codewords from ISO/IEC
+    /// 18004 Annex A, x<sup>7</sup> + 2<sup>87</sup>x<sup>6</sup> +
+    /// 2<sup>229</sup>x<sup>5</sup> + 2<sup>146</sup>x<sup>4</sup> +
+    /// 2<sup>149</sup>x<sup>3</sup> + 2<sup>238</sup>x<sup>2</sup> +
+    /// 2<sup>102</sup>x + 2<sup>21</sup>:
+    ///
+    /// ```
+    /// # use qrcode2::ec::ReedSolomon;
+    /// #
+    /// assert_eq!(
+    ///     ReedSolomon.generator_poly(7),
+    ///     [0x01, 0x7f, 0x7a, 0x9a, 0xa4, 0x0b, 0x44, 0x75]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn generator_poly(self, degree: usize) -> Vec<u8> {
+        GENERATOR_POLYNOMIALS.get(degree).map_or_else(
+            || compute_generator_poly(degree),
+            |logs| {
+                core::iter::once(1)
+                    .chain(logs.iter().map(|log| EXP_TABLE[usize::from(*log)]))
+                    .collect()
+            },
+        )
+    }
+
+    /// Corrects the errors of a block of data codewords followed by `ec_len`
+    /// error correction codewords in place.
+    ///
+    /// Returns the number of corrected codewords, which is at most `ec_len /
+    /// 2`. Returns [`None`] if the errors cannot be corrected, or if the block
+    /// is longer than 255 codewords.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::ec::ReedSolomon;
+    /// #
+    /// let data = b"\x10\x20\x0c\x56\x61\x80\xec\x11\xec\x11\xec\x11\xec\x11\xec\x11";
+    /// let mut block = [&data[..], &ReedSolomon.encode_block(data, 10)].concat();
+    /// block[0] = 0;
+    /// block[20] ^= 0x55;
+    /// assert_eq!(ReedSolomon.correct_block(&mut block, 10), Some(2));
+    /// assert_eq!(block[..16], data[..]);
+    /// ```
+    #[must_use]
+    pub fn correct_block(self, block: &mut [u8], ec_len: usize) -> Option<usize> {
+        let len = block.len();
+        if len > 255 || ec_len > len {
+            return None;
+        }
+        // The coefficient of x^i is at `block[len - 1 - i]`.
+        let eval = |block: &[u8], x: u8| block.iter().fold(0, |acc, c| mul(acc, x) ^ c);
+        let syndromes = (0..ec_len)
+            .map(|j| eval(block, EXP_TABLE[j]))
+            .collect::<Vec<_>>();
+        if syndromes.iter().all(|s| *s == 0) {
+            return Some(0);
+        }
+
+        // Find the error locator polynomial, lowest degree first, with the
+        // Berlekamp-Massey algorithm.
+        let mut locator = Vec::from([1]);
+        let mut prev_locator = Vec::from([1]);
+        let mut errors = 0;
+        let mut shift = 1;
+        let mut prev_discrepancy = 1;
+        for k in 0..ec_len {
+            let discrepancy = (1..=errors).fold(syndromes[k], |d, i| {
+                d ^ mul(locator.get(i).copied().unwrap_or(0), syndromes[k - i])
+            });
+            if discrepancy == 0 {
+                shift += 1;
+                continue;
+            }
+            let coeff = div(discrepancy, prev_discrepancy);
+            let last_locator = locator.clone();
+            locator.resize(locator.len().max(prev_locator.len() + shift), 0);
+            for (i, c) in prev_locator.iter().enumerate() {
+                locator[i + shift] ^= mul(coeff, *c);
+            }
+            if 2 * errors <= k {
+                errors = k + 1 - errors;
+                prev_locator = last_locator;
+                prev_discrepancy = discrepancy;
+                shift = 1;
+            } else {
+                shift += 1;
+            }
+        }
+        locator.truncate(errors + 1);
+        if 2 * errors > ec_len {
+            return None;
+        }
+
+        // Find the error positions as the inverses of the roots of the error
+        // locator polynomial, with a Chien search.
+        let eval_low_first =
+            |poly: &[u8], x: u8| poly.iter().rev().fold(0, |acc, c| mul(acc, x) ^ c);
+        let positions = (0..len)
+            .filter(|i| eval_low_first(&locator, EXP_TABLE[(255 - i) % 255]) == 0)
+            .collect::<Vec<_>>();
+        if positions.len() != errors {
+            return None;
+        }
+
+        // Find the error values with the Forney algorithm.
+        let mut evaluator = vec![0; ec_len];
+        for (i, s) in syndromes.iter().enumerate() {
+            for (j, c) in locator.iter().enumerate().take(ec_len - i) {
+                evaluator[i + j] ^= mul(*s, *c);
+            }
+        }
+        let derivative = locator
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, c)| if i % 2 == 1 { *c } else { 0 })
+            .collect::<Vec<_>>();
+        for i in positions {
+            let x = EXP_TABLE[i % 255];
+            let x_inv = EXP_TABLE[(255 - i) % 255];
+            let denominator = eval_low_first(&derivative, x_inv);
+            if denominator == 0 {
+                return None;
+            }
+            let value = div(mul(x, eval_low_first(&evaluator, x_inv)), denominator);
+            block[len - 1 - i] ^= value;
+        }
+
+        (0..ec_len)
+            .all(|j| eval(block, EXP_TABLE[j]) == 0)
+            .then_some(errors)
+    }
+}
+
+/// Multiplies two elements of GF(2<sup>8</sup>).
+#[cfg(feature = "alloc")]
+fn mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    let log = usize::from(LOG_TABLE[usize::from(a)]) + usize::from(LOG_TABLE[usize::from(b)]);
+    EXP_TABLE[log % 255]
+}
+
+/// Divides an element of GF(2<sup>8</sup>) by a non-zero element.
+#[cfg(feature = "alloc")]
+fn div(a: u8, b: u8) -> u8 {
+    if a == 0 {
+        return 0;
+    }
+    let log = usize::from(LOG_TABLE[usize::from(a)]) + 255 - usize::from(LOG_TABLE[usize::from(b)]);
+    EXP_TABLE[log % 255]
+}
+
+/// Computes the coefficients of the generator polynomial of the given degree by
+/// multiplying its factors.
+#[cfg(feature = "alloc")]
+fn compute_generator_poly(degree: usize) -> Vec<u8> {
+    let mut poly = Vec::with_capacity(degree + 1);
+    poly.push(1);
+    for i in 0..degree {
+        // Multiply by (x − 2^i), where subtraction is the same as addition.
+        let root = EXP_TABLE[i % 255];
+        poly.push(0);
+        for j in (1..poly.len()).rev() {
+            poly[j] ^= mul(poly[j - 1], root);
+        }
+    }
+    poly
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod reed_solomon_tests {
+    use super::*;
+
+    #[test]
+    fn test_generator_poly_matches_table() {
+        for degree in 0..GENERATOR_POLYNOMIALS.len() {
+            assert_eq!(
+                ReedSolomon.generator_poly(degree),
+                compute_generator_poly(degree),
+                "degree {degree}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_encode_block_matches_computed_generator() {
+        let data = b"CUF\x86W&U\xc2w2\x06\x12\x06g&";
+        for ec_len in [18, 69] {
+            let generator = compute_generator_poly(ec_len);
+            let mut res = data.to_vec();
+            res.resize(data.len() + ec_len, 0);
+            for i in 0..data.len() {
+                let lead_coeff = res[i];
+                for (u, v) in res[i + 1..].iter_mut().zip(&generator[1..]) {
+                    *u ^= mul(*v, lead_coeff);
+                }
+            }
+            assert_eq!(ReedSolomon.encode_block(data, ec_len), res[data.len()..]);
+        }
+    }
+
+    #[test]
+    fn test_encode_block_roots() {
+        // A codeword is divisible by the generator polynomial, so it evaluates
+        // to 0 at each root.
+        let data = b"\x10\x20\x0c\x56\x61\x80\xec\x11";
+        let ec_len = 100;
+        let codeword = [&data[..], &ReedSolomon.encode_block(data, ec_len)].concat();
+        for (i, &root) in EXP_TABLE.iter().enumerate().take(ec_len) {
+            let value = codeword.iter().fold(0, |acc, c| mul(acc, root) ^ c);
+            assert_eq!(value, 0, "root 2^{i}");
+        }
+    }
+
+    #[test]
+    fn test_correct_block() {
+        let data = b"CUF\x86W&U\xc2w2\x06\x12\x06g&";
+        let ec_len = 18;
+        let codeword = [&data[..], &ReedSolomon.encode_block(data, ec_len)].concat();
+        for errors in 0..=ec_len / 2 {
+            let mut block = codeword.clone();
+            for i in 0..errors {
+                block[i * 3] ^= u8::try_from(i + 1).unwrap();
+            }
+            assert_eq!(
+                ReedSolomon.correct_block(&mut block, ec_len),
+                Some(errors),
+                "{errors} errors"
+            );
+            assert_eq!(block, codeword);
+        }
+    }
+
+    #[test]
+    fn test_correct_block_too_many_errors() {
+        let data = b"\x10\x20\x0c\x56\x61\x80\xec\x11";
+        let ec_len = 4;
+        let mut block = [&data[..], &ReedSolomon.encode_block(data, ec_len)].concat();
+        for c in &mut block[..3] {
+            *c ^= 0xff;
+        }
+        assert_eq!(ReedSolomon.correct_block(&mut block, ec_len), None);
+    }
+
+    #[test]
+    fn test_correct_block_long() {
+        let data = (0..=200).collect::<Vec<u8>>();
+        let ec_len = 54;
+        let codeword = [&data[..], &ReedSolomon.encode_block(&data, ec_len)].concat();
+        let mut block = codeword.clone();
+        for i in (0..block.len()).step_by(10) {
+            block[i] = !block[i];
+        }
+        assert_eq!(ReedSolomon.correct_block(&mut block, ec_len), Some(26));
+        assert_eq!(block, codeword);
+
+        let mut block = [codeword, Vec::from([0])].concat();
+        assert_eq!(ReedSolomon.correct_block(&mut block, ec_len), None);
+    }
+}
+
 // Interleave support
 
 /// This method interleaves a vector of slices into a single vector.
@@ -81,6 +432,7 @@ mod ec_tests {
 ///
 /// The longest slice must be at the last of `blocks`, and `blocks` must not be
 /// empty.
+#[cfg(any(feature = "trace", all(test, feature = "alloc")))]
 fn interleave<T: Copy, V: Deref<Target = [T]>>(blocks: &[V]) -> Vec<T> {
     let last_block_len = blocks.last().expect("non-empty blocks").len();
     let mut res = Vec::with_capacity(last_block_len * blocks.len());
@@ -94,7 +446,26 @@ fn interleave<T: Copy, V: Deref<Target = [T]>>(blocks: &[V]) -> Vec<T> {
     res
 }
 
-#[cfg(test)]
+/// This method splits interleaved elements into blocks of the given sizes. It
+/// is the inverse of `interleave`.
+#[cfg(feature = "alloc")]
+fn deinterleave<T: Copy>(elements: &[T], sizes: &[usize]) -> Vec<Vec<T>> {
+    let mut blocks = sizes
+        .iter()
+        .map(|size| Vec::with_capacity(*size))
+        .collect::<Vec<_>>();
+    let mut elements = elements.iter();
+    for i in 0..sizes.iter().max().copied().unwrap_or_default() {
+        for (block, size) in blocks.iter_mut().zip(sizes) {
+            if i < *size {
+                block.extend(elements.next());
+            }
+        }
+    }
+    blocks
+}
+
+#[cfg(all(test, feature = "alloc"))]
 mod interleave_tests {
     use super::*;
 
@@ -103,6 +474,12 @@ mod interleave_tests {
         let res = interleave(&[&b"1234"[..], b"5678", b"abcdef", b"ghijkl"]);
         assert_eq!(&*res, b"15ag26bh37ci48djekfl");
     }
+
+    #[test]
+    fn test_deinterleave() {
+        let res = deinterleave(b"15ag26bh37ci48djekfl", &[4, 4, 6, 6]);
+        assert_eq!(res, [&b"1234"[..], b"5678", b"abcdef", b"ghijkl"]);
+    }
 }
 
 // QR code error correction
@@ -114,13 +491,30 @@ mod interleave_tests {
 ///
 /// Returns [`Err`] if it is not valid to use the `ec_level` for the given
 /// version (e.g. [`Version::Micro(1)`](Version::Micro) with [`EcLevel::H`]).
+#[cfg(feature = "alloc")]
 pub fn construct_codewords(
     rawbits: &[u8],
     version: Version,
     ec_level: EcLevel,
 ) -> QrResult<(Vec<u8>, Vec<u8>)> {
+    let (mut data, mut ec) = (Vec::new(), Vec::new());
+    construct_codewords_into(rawbits, version, ec_level, &mut data, &mut ec)?;
+    Ok((data, ec))
+}
+
+/// Replaces the contents of `data` and `ec` with the result of
+/// [`construct_codewords`], without allocating other memory.
+#[cfg(feature = "alloc")]
+pub(crate) fn construct_codewords_into(
+    rawbits: &[u8],
+    version: Version,
+    ec_level: EcLevel,
+    data: &mut Vec<u8>,
+    ec: &mut Vec<u8>,
+) -> QrResult<()> {
     let (block_1_size, block_1_count, block_2_size, block_2_count) =
         version.fetch(ec_level, &DATA_BYTES_PER_BLOCK)?;
+    let ec_bytes = version.fetch(ec_level, &EC_BYTES_PER_BLOCK)?;
 
     let blocks_count = block_1_count + block_2_count;
     let block_1_end = block_1_size * block_1_count;
@@ -128,27 +522,128 @@ pub fn construct_codewords(
 
     debug_assert_eq!(rawbits.len(), total_size);
 
-    // Divide the data into blocks.
-    let mut blocks = Vec::with_capacity(blocks_count);
-    blocks.extend(rawbits[..block_1_end].chunks(block_1_size));
-    if block_2_size > 0 {
-        blocks.extend(rawbits[block_1_end..].chunks(block_2_size));
+    // Divide the data into blocks, and interleave them.
+    let block = |i: usize| {
+        if i < block_1_count {
+            &rawbits[i * block_1_size..][..block_1_size]
+        } else {
+            &rawbits[block_1_end + (i - block_1_count) * block_2_size..][..block_2_size]
+        }
+    };
+    data.clear();
+    data.reserve(total_size);
+    for j in 0..block_1_size.max(block_2_size) {
+        data.extend((0..blocks_count).filter_map(|i| block(i).get(j)));
     }
 
-    // Generate EC codes.
+    // Generate EC codes, and interleave them.
+    ec.clear();
+    ec.resize(ec_bytes * blocks_count, 0);
+    let mut block_ec = [0; GENERATOR_POLYNOMIALS.len()];
+    let block_ec = &mut block_ec[..ec_bytes];
+    for i in 0..blocks_count {
+        write_error_correction_code(block(i), block_ec);
+        for (j, codeword) in block_ec.iter().enumerate() {
+            ec[j * blocks_count + i] = *codeword;
+        }
+    }
+    Ok(())
+}
+
+/// Corrects the data and error correction codewords read from the QR code
+/// matrix, which is the inverse of [`construct_codewords`].
+///
+/// Returns the data codewords and the number of corrected codewords, or
+/// [`None`] if a block has too many errors.
+#[cfg(feature = "alloc")]
+pub(crate) fn correct_codewords(
+    data: &[u8],
+    ec: &[u8],
+    version: Version,
+    ec_level: EcLevel,
+) -> QrResult<Option<(Vec<u8>, usize)>> {
+    let (block_1_size, block_1_count, block_2_size, block_2_count) =
+        version.fetch(ec_level, &DATA_BYTES_PER_BLOCK)?;
     let ec_bytes = version.fetch(ec_level, &EC_BYTES_PER_BLOCK)?;
-    let ec_codes = blocks
-        .iter()
-        .map(|block| create_error_correction_code(block, ec_bytes))
-        .collect::<Vec<Vec<u8>>>();
+    let max_errors = (ec_bytes - misdecode_protection_codewords(version, ec_level)) / 2;
 
-    let blocks_vec = interleave(&blocks);
-    let ec_vec = interleave(&ec_codes);
+    let mut sizes = Vec::with_capacity(block_1_count + block_2_count);
+    sizes.resize(block_1_count, block_1_size);
+    sizes.resize(block_1_count + block_2_count, block_2_size);
+    let data_blocks = deinterleave(data, &sizes);
+    let ec_blocks = deinterleave(ec, &vec![ec_bytes; sizes.len()]);
 
-    Ok((blocks_vec, ec_vec))
+    let mut res = Vec::with_capacity(data.len());
+    let mut corrected = 0;
+    for (data_block, ec_block) in data_blocks.iter().zip(&ec_blocks) {
+        let mut block = [&data_block[..], ec_block].concat();
+        match ReedSolomon.correct_block(&mut block, ec_bytes) {
+            Some(errors) if errors <= max_errors => corrected += errors,
+            _ => return Ok(None),
+        }
+        res.extend(&block[..data_block.len()]);
+    }
+    Ok(Some((res, corrected)))
 }
 
-#[cfg(test)]
+/// The block and the index in the block of each codeword.
+#[cfg(feature = "trace")]
+type CodewordPositions = Vec<(usize, usize)>;
+
+/// Returns the block and the index in the block of each data codeword and
+/// each error correction codeword, in the order in which they are placed in
+/// the symbol.
+///
+/// The error correction codewords of a block are indexed after its data
+/// codewords.
+#[cfg(feature = "trace")]
+pub(crate) fn codeword_positions(
+    version: Version,
+    ec_level: EcLevel,
+) -> QrResult<(CodewordPositions, CodewordPositions)> {
+    let (block_1_size, block_1_count, block_2_size, block_2_count) =
+        version.fetch(ec_level, &DATA_BYTES_PER_BLOCK)?;
+    let ec_bytes = version.fetch(ec_level, &EC_BYTES_PER_BLOCK)?;
+    let sizes = iter::repeat_n(block_1_size, block_1_count)
+        .chain(iter::repeat_n(block_2_size, block_2_count))
+        .enumerate();
+    let (data_blocks, ec_blocks): (Vec<Vec<_>>, Vec<Vec<_>>) = sizes
+        .map(|(block, size)| {
+            (
+                (0..size).map(|i| (block, i)).collect(),
+                (size..size + ec_bytes).map(|i| (block, i)).collect(),
+            )
+        })
+        .unzip();
+    Ok((interleave(&data_blocks), interleave(&ec_blocks)))
+}
+
+#[cfg(all(test, feature = "trace"))]
+mod codeword_positions_tests {
+    use super::*;
+
+    #[test]
+    fn test_single_block() {
+        let (data, ec) = codeword_positions(Version::Normal(1), EcLevel::M).unwrap();
+        assert_eq!(data, (0..16).map(|i| (0, i)).collect::<Vec<_>>());
+        assert_eq!(ec, (16..26).map(|i| (0, i)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_two_block_sizes() {
+        // 2 blocks of 15 and 2 blocks of 16 data codewords, with 18 error
+        // correction codewords each.
+        let (data, ec) = codeword_positions(Version::Normal(5), EcLevel::Q).unwrap();
+        assert_eq!(data.len(), 62);
+        assert_eq!(&data[..5], [(0, 0), (1, 0), (2, 0), (3, 0), (0, 1)]);
+        assert_eq!(&data[60..], [(2, 15), (3, 15)]);
+        assert_eq!(ec.len(), 72);
+        assert_eq!(&ec[..2], [(0, 15), (1, 15)]);
+        assert_eq!(&ec[2..4], [(2, 16), (3, 16)]);
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
 mod construct_codewords_tests {
     use super::*;
 
@@ -192,19 +687,64 @@ mod construct_codewords_tests {
 /// Returns [`Err`] if it is not valid to use the `ec_level` for the given
 /// version (e.g. [`Version::Micro(1)`](Version::Micro) with [`EcLevel::H`]).
 pub fn max_allowed_errors(version: Version, ec_level: EcLevel) -> QrResult<usize> {
-    let p = match (version, ec_level) {
+    let p = misdecode_protection_codewords(version, ec_level);
+    let ec_bytes = ec_codewords_len(version, ec_level)?;
+    Ok((ec_bytes - p) / 2)
+}
+
+/// Returns the number of error correction codewords which are reserved for
+/// detecting misdecodes instead of correcting errors.
+///
+/// Only symbols with a single block reserve such codewords.
+const fn misdecode_protection_codewords(version: Version, ec_level: EcLevel) -> usize {
+    match (version, ec_level) {
         (Version::Micro(2) | Version::Normal(1), EcLevel::L) => 3,
         (Version::Micro(_) | Version::Normal(2), EcLevel::L)
         | (Version::Micro(2) | Version::Normal(1), EcLevel::M) => 2,
         (Version::Normal(1), _) | (Version::Normal(3), EcLevel::L) => 1,
         _ => 0,
-    };
+    }
+}
+
+/// Returns the number of error correction blocks, and the number of erasures
+/// which each block can recover. Each error costs two of them.
+#[cfg(feature = "simulate")]
+pub(crate) fn block_correction_capacity(
+    version: Version,
+    ec_level: EcLevel,
+) -> QrResult<(usize, usize)> {
+    let ec_bytes_per_block = version.fetch(ec_level, &EC_BYTES_PER_BLOCK)?;
+    let (_, count1, _, count2) = version.fetch(ec_level, &DATA_BYTES_PER_BLOCK)?;
+    let p = misdecode_protection_codewords(version, ec_level);
+    Ok((count1 + count2, ec_bytes_per_block - p))
+}
 
+/// Returns the total number of data codewords.
+pub(crate) fn data_codewords_len(version: Version, ec_level: EcLevel) -> QrResult<usize> {
+    let (size1, count1, size2, count2) = version.fetch(ec_level, &DATA_BYTES_PER_BLOCK)?;
+    Ok(size1 * count1 + size2 * count2)
+}
+
+/// Returns the total number of error correction codewords.
+pub(crate) fn ec_codewords_len(version: Version, ec_level: EcLevel) -> QrResult<usize> {
     let ec_bytes_per_block = version.fetch(ec_level, &EC_BYTES_PER_BLOCK)?;
     let (_, count1, _, count2) = version.fetch(ec_level, &DATA_BYTES_PER_BLOCK)?;
-    let ec_bytes = (count1 + count2) * ec_bytes_per_block;
+    Ok((count1 + count2) * ec_bytes_per_block)
+}
 
-    Ok((ec_bytes - p) / 2)
+/// Returns the block sizes of the normal QR code version in the form of
+/// [`DATA_BYTES_PER_BLOCK`], and the number of error correction codewords per
+/// block.
+#[cfg(feature = "heapless")]
+pub(crate) const fn block_layout(
+    version: usize,
+    ec_level: EcLevel,
+) -> ((usize, usize, usize, usize), usize) {
+    let ec_level = ec_level as usize;
+    (
+        DATA_BYTES_PER_BLOCK[version - 1][ec_level],
+        EC_BYTES_PER_BLOCK[version - 1][ec_level],
+    )
 }
 
 #[cfg(test)]
@@ -0,0 +1,182 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Encoding many QR codes while reusing memory.
+//!
+//! Each call of [`QrCode::with_error_correction_level`] allocates the tables
+//! of the segmentation, the encoded bits, the codewords and two canvases, and
+//! frees them before returning. [`Encoder`] keeps them between calls instead,
+//! so that once they have grown to the size of the symbols, little more than
+//! the returned [`QrCode`] is allocated. This helps when encoding a large
+//! number of symbols.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{EcLevel, QrCode, encoder::Encoder};
+//!
+//! let mut encoder = Encoder::new();
+//! for ticket in 0..100 {
+//!     let data = format!("https://example.com/tickets/{ticket}");
+//!     let code = encoder.encode(&data, EcLevel::M).unwrap();
+//!     assert_eq!(
+//!         code.to_colors(),
+//!         QrCode::with_error_correction_level(&data, EcLevel::M)
+//!             .unwrap()
+//!             .to_colors()
+//!     );
+//! }
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::{
+    QrCode,
+    bits::{self, Bits},
+    canvas::{Canvas, MaskPolicy},
+    optimize::{Segment, SegmentTables},
+    types::{EcLevel, QrResult, Version},
+};
+
+/// An encoder which reuses its memory for each QR code.
+///
+/// The symbols are the same as those of [`QrCode::with_error_correction_level`]
+/// and [`QrCode::with_version`]. The memory grows to fit the largest symbol
+/// encoded so far, and is freed when the encoder is dropped.
+#[derive(Clone, Debug, Default)]
+pub struct Encoder {
+    segment_tables: SegmentTables,
+    segments: Vec<Segment>,
+    bits: Option<Bits>,
+    buffers: Buffers,
+}
+
+/// The memory used by [`QrCode::encode_bits`].
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Buffers {
+    /// The interleaved data codewords.
+    pub(crate) data_codewords: Vec<u8>,
+
+    /// The interleaved error correction codewords.
+    pub(crate) ec_codewords: Vec<u8>,
+
+    /// The canvas of the symbol.
+    pub(crate) canvas: Option<Canvas>,
+
+    /// The canvas on which each mask pattern is scored.
+    pub(crate) mask_canvas: Option<Canvas>,
+}
+
+impl Encoder {
+    /// Creates a new encoder. No memory is allocated until the first QR code
+    /// is encoded.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encodes the data in the smallest normal QR code at the given error
+    /// correction level, like [`QrCode::with_error_correction_level`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the QR code cannot be constructed, e.g. when the data
+    /// is too long.
+    pub fn encode(&mut self, data: impl AsRef<[u8]>, ec_level: EcLevel) -> QrResult<QrCode> {
+        let bits = self
+            .bits
+            .get_or_insert_with(|| Bits::new(Version::Normal(1)));
+        bits::encode_auto_into(
+            data.as_ref(),
+            ec_level,
+            &mut self.segment_tables,
+            &mut self.segments,
+            bits,
+        )?;
+        QrCode::encode_bits(bits, ec_level, None, MaskPolicy::Auto, &mut self.buffers)
+    }
+
+    /// Encodes the data in the given version at the given error correction
+    /// level, like [`QrCode::with_version`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the QR code cannot be constructed, e.g. when the data
+    /// is too long, or when the version and error correction level are
+    /// incompatible.
+    pub fn encode_with_version(
+        &mut self,
+        data: impl AsRef<[u8]>,
+        version: Version,
+        ec_level: EcLevel,
+    ) -> QrResult<QrCode> {
+        let bits = self.bits.get_or_insert_with(|| Bits::new(version));
+        bits.clear(version);
+        bits.push_optimal_data(data.as_ref())?;
+        bits.push_terminator(ec_level)?;
+        QrCode::encode_bits(bits, ec_level, None, MaskPolicy::Auto, &mut self.buffers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::QrError;
+
+    #[test]
+    fn test_encode() {
+        let mut encoder = Encoder::new();
+        // Grow and shrink the buffers between different versions.
+        for data in ["Hello", &"0123456789".repeat(50), "HELLO WORLD", ""] {
+            for ec_level in [EcLevel::L, EcLevel::M, EcLevel::Q, EcLevel::H] {
+                let code = encoder.encode(data, ec_level).unwrap();
+                let expected = QrCode::with_error_correction_level(data, ec_level).unwrap();
+                assert_eq!(code.version(), expected.version());
+                assert_eq!(code.to_colors(), expected.to_colors());
+                assert_eq!(code.mask_selection(), expected.mask_selection());
+                assert_eq!(code.spare_capacity_bits(), expected.spare_capacity_bits());
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_with_version() {
+        let mut encoder = Encoder::new();
+        for version in [
+            Version::Normal(40),
+            Version::Micro(2),
+            Version::RectMicro(7, 43),
+            Version::Normal(1),
+        ] {
+            let code = encoder
+                .encode_with_version(b"01234567", version, EcLevel::M)
+                .unwrap();
+            let expected = QrCode::with_version(b"01234567", version, EcLevel::M).unwrap();
+            assert_eq!(code.to_colors(), expected.to_colors());
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut encoder = Encoder::new();
+        assert!(matches!(
+            encoder.encode([0; 3000], EcLevel::L),
+            Err(QrError::DataTooLong { .. })
+        ));
+        assert_eq!(
+            encoder
+                .encode_with_version(b"1", Version::Micro(1), EcLevel::H)
+                .unwrap_err(),
+            QrError::InvalidVersion {
+                version: Some(Version::Micro(1))
+            }
+        );
+
+        // The encoder can still be used after an error.
+        let code = encoder.encode(b"Hello", EcLevel::M).unwrap();
+        let expected = QrCode::with_error_correction_level(b"Hello", EcLevel::M).unwrap();
+        assert_eq!(code.to_colors(), expected.to_colors());
+    }
+}
@@ -0,0 +1,1137 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The `heapless` module encodes normal QR codes into a caller-provided
+//! buffer, without allocating.
+//!
+//! This works without the `alloc` feature, e.g. on microcontrollers without a
+//! heap. The data is encoded as a single segment in the most compact of the
+//! numeric, alphanumeric and byte modes, and the mask pattern is chosen in the
+//! same way as `QrCode` does. Micro QR code and rMQR code are not supported.
+//!
+//! The encoder consists of `const fn`s, so a QR code can also be encoded at
+//! compile time into a [`QrCodeBuf`], e.g. with the [`qr!`](crate::qr) macro.
+//!
+//! # Examples
+//!
+//! ```
+//! # use qrcode2::{
+//! #     Color, EcLevel, Version,
+//! #     heapless::{self, QrCodeRef},
+//! # };
+//! #
+//! let mut buffer = [0; heapless::buffer_len(Version::Normal(2))];
+//! let code = QrCodeRef::encode_into(b"HELLO WORLD", Version::Normal(2), EcLevel::M, &mut buffer)
+//!     .unwrap();
+//! assert_eq!(code.width(), 25);
+//! assert_eq!(code[(0, 0)], Color::Dark);
+//! ```
+
+use core::ops::Index;
+
+use crate::{
+    cast::As,
+    ec,
+    types::{Color, EcLevel, Mode, QrError, QrResult, Version},
+};
+
+/// Returns the number of bytes of the buffer needed to encode a QR code of
+/// the version, or 0 if the version is not a valid normal QR code version.
+///
+/// The buffer holds the data and error correction codewords, followed by the
+/// modules of the symbol, one bit each.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{Version, heapless};
+/// #
+/// assert_eq!(heapless::buffer_len(Version::Normal(1)), 82);
+/// assert_eq!(heapless::buffer_len(Version::Micro(1)), 0);
+/// ```
+#[must_use]
+pub const fn buffer_len(version: Version) -> usize {
+    let Version::Normal(v @ 1..=40) = version else {
+        return 0;
+    };
+    let v = v.unsigned_abs() as usize;
+    let width = v * 4 + 17;
+    raw_data_modules(v) / 8 + (width * width).div_ceil(8)
+}
+
+/// The number of bytes of the buffer needed to encode a QR code of any
+/// version.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{
+/// #     EcLevel, Version,
+/// #     heapless::{self, QrCodeRef},
+/// # };
+/// #
+/// let mut buffer = [0; heapless::MAX_BUFFER_LEN];
+/// let code =
+///     QrCodeRef::encode_into(b"12345", Version::Normal(40), EcLevel::H, &mut buffer).unwrap();
+/// assert_eq!(code.width(), 177);
+/// ```
+pub const MAX_BUFFER_LEN: usize = buffer_len(Version::Normal(40));
+
+/// Returns the number of modules which hold the data and error correction
+/// codewords, including the remainder bits.
+const fn raw_data_modules(v: usize) -> usize {
+    let mut modules = (16 * v + 128) * v + 64;
+    if v >= 2 {
+        let alignment_count = v / 7 + 2;
+        modules -= (25 * alignment_count - 10) * alignment_count - 55;
+        if v >= 7 {
+            modules -= 36;
+        }
+    }
+    modules
+}
+
+/// Returns the smallest version which the data fits in at the error
+/// correction level, when encoded by [`QrCodeRef::encode_into`].
+///
+/// # Errors
+///
+/// Returns [`Err`] if the data is too long even for version 40.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{EcLevel, Version, heapless};
+/// #
+/// assert_eq!(
+///     heapless::min_version(b"HELLO WORLD", EcLevel::Q),
+///     Ok(Version::Normal(1))
+/// );
+/// assert_eq!(
+///     heapless::min_version(b"HELLO WORLD", EcLevel::H),
+///     Ok(Version::Normal(2))
+/// );
+/// ```
+pub const fn min_version(data: &[u8], ec_level: EcLevel) -> QrResult<Version> {
+    let mut version: i16 = 1;
+    loop {
+        let v = version.unsigned_abs() as usize;
+        let bits_available = Layout::new(v, ec_level).data_len() * 8;
+        let (mode, bits_needed) = segment_bits(data, v);
+        if bits_needed <= bits_available && data.len() < 1 << length_bits_count(mode, v) {
+            return Ok(Version::Normal(version));
+        }
+        if version == 40 {
+            return Err(QrError::DataTooLong {
+                bits_needed,
+                bits_available,
+            });
+        }
+        version += 1;
+    }
+}
+
+/// The encoded QR code symbol, borrowing the buffer which it is encoded into.
+#[derive(Clone, Copy, Debug)]
+pub struct QrCodeRef<'a> {
+    modules: &'a [u8],
+    version: Version,
+    ec_level: EcLevel,
+    width: usize,
+}
+
+impl<'a> QrCodeRef<'a> {
+    /// Encodes the data into a QR code of the version, using `buffer` instead
+    /// of allocating.
+    ///
+    /// The buffer should be at least [`buffer_len`] bytes long, e.g. an array
+    /// of [`MAX_BUFFER_LEN`] bytes fits every version. Its initial content
+    /// does not matter.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the version is not a normal QR code version, if the
+    /// buffer is too small, or if the data is too long.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{EcLevel, Version, heapless::QrCodeRef, types::QrError};
+    /// #
+    /// let mut buffer = [0; 64];
+    /// assert_eq!(
+    ///     QrCodeRef::encode_into(b"12345", Version::Normal(1), EcLevel::M, &mut buffer).unwrap_err(),
+    ///     QrError::BufferTooSmall {
+    ///         bytes_needed: 82,
+    ///         bytes_available: 64
+    ///     }
+    /// );
+    /// ```
+    pub fn encode_into(
+        data: &[u8],
+        version: Version,
+        ec_level: EcLevel,
+        buffer: &'a mut [u8],
+    ) -> QrResult<Self> {
+        let modules_start = encode(data, version, ec_level, buffer)?;
+        let buffer: &'a [u8] = buffer;
+        Ok(Self {
+            modules: &buffer[modules_start..],
+            version,
+            ec_level,
+            width: version.width().as_usize(),
+        })
+    }
+
+    /// Gets the version of this QR code.
+    #[must_use]
+    #[inline]
+    pub const fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Gets the error correction level of this QR code.
+    #[must_use]
+    #[inline]
+    pub const fn error_correction_level(&self) -> EcLevel {
+        self.ec_level
+    }
+
+    /// Gets the number of modules per side, i.e. the width of this QR code.
+    ///
+    /// The width here does not contain the quiet zone paddings.
+    #[must_use]
+    #[inline]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+}
+
+impl Index<(usize, usize)> for QrCodeRef<'_> {
+    type Output = Color;
+
+    #[inline]
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        assert!(x < self.width && y < self.width, "module out of bounds");
+        if get_bit(self.modules, y * self.width + x) {
+            &Color::Dark
+        } else {
+            &Color::Light
+        }
+    }
+}
+
+/// The encoded QR code symbol, owning a buffer of `N` bytes.
+///
+/// Unlike [`QrCodeRef::encode_into`], [`QrCodeBuf::encode`] is a `const fn`,
+/// so a QR code can be encoded at compile time, e.g. with the
+/// [`qr!`](crate::qr) macro.
+#[derive(Clone, Copy, Debug)]
+pub struct QrCodeBuf<const N: usize> {
+    buffer: [u8; N],
+    modules_start: usize,
+    version: Version,
+    ec_level: EcLevel,
+}
+
+impl<const N: usize> QrCodeBuf<N> {
+    /// Encodes the data into a QR code of the version.
+    ///
+    /// `N` should be at least [`buffer_len`] of the version.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the version is not a normal QR code version, if `N`
+    /// is too small, or if the data is too long.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{
+    /// #     EcLevel, Version,
+    /// #     heapless::{self, QrCodeBuf},
+    /// # };
+    /// #
+    /// const LEN: usize = heapless::buffer_len(Version::Normal(1));
+    /// static CODE: QrCodeBuf<LEN> =
+    ///     match QrCodeBuf::encode(b"01234567", Version::Normal(1), EcLevel::M) {
+    ///         Ok(code) => code,
+    ///         Err(_) => panic!("the data does not fit"),
+    ///     };
+    /// assert_eq!(CODE.as_code_ref().width(), 21);
+    /// ```
+    pub const fn encode(data: &[u8], version: Version, ec_level: EcLevel) -> QrResult<Self> {
+        let mut buffer = [0; N];
+        match encode(data, version, ec_level, &mut buffer) {
+            Ok(modules_start) => Ok(Self {
+                buffer,
+                modules_start,
+                version,
+                ec_level,
+            }),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Borrows the QR code as a [`QrCodeRef`].
+    #[must_use]
+    #[inline]
+    pub const fn as_code_ref(&self) -> QrCodeRef<'_> {
+        let (_, modules) = self.buffer.split_at(self.modules_start);
+        QrCodeRef {
+            modules,
+            version: self.version,
+            ec_level: self.ec_level,
+            width: self.version.width().unsigned_abs() as usize,
+        }
+    }
+}
+
+/// Encodes a QR code at compile time, and evaluates to a
+/// [`QrCodeRef<'static>`](crate::heapless::QrCodeRef).
+///
+/// The data is a byte string, and the version is chosen with
+/// [`heapless::min_version`] unless it is given. Both should be constant
+/// expressions. If the data cannot be encoded, the compilation fails.
+///
+/// Large symbols take long to evaluate, up to a minute for version 40.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{Color, EcLevel, Version, heapless::QrCodeRef, qr};
+/// #
+/// static CODE: QrCodeRef<'static> = qr!(b"https://example.com", EcLevel::M);
+/// assert_eq!(CODE.version(), Version::Normal(2));
+/// assert_eq!(CODE[(0, 0)], Color::Dark);
+///
+/// let code = qr!(b"HELLO WORLD", Version::Normal(3), EcLevel::H);
+/// assert_eq!(code.width(), 29);
+/// ```
+///
+/// [`heapless::min_version`]: crate::heapless::min_version
+#[macro_export]
+macro_rules! qr {
+    ($data:expr, $version:expr, $ec_level:expr $(,)?) => {{
+        const VERSION: $crate::Version = $version;
+        #[allow(long_running_const_eval)]
+        static CODE: $crate::heapless::QrCodeBuf<{ $crate::heapless::buffer_len(VERSION) }> =
+            match $crate::heapless::QrCodeBuf::encode($data, VERSION, $ec_level) {
+                Ok(code) => code,
+                Err(_) => ::core::panic!("cannot encode the data into a QR code"),
+            };
+        CODE.as_code_ref()
+    }};
+    ($data:expr, $ec_level:expr $(,)?) => {
+        $crate::qr!(
+            $data,
+            match $crate::heapless::min_version($data, $ec_level) {
+                Ok(version) => version,
+                Err(_) => ::core::panic!("the data is too long for a QR code"),
+            },
+            $ec_level
+        )
+    };
+}
+
+/// Encodes the QR code into the buffer, and returns the offset of the
+/// modules in the buffer.
+const fn encode(
+    data: &[u8],
+    version: Version,
+    ec_level: EcLevel,
+    buffer: &mut [u8],
+) -> QrResult<usize> {
+    let Version::Normal(1..=40) = version else {
+        return Err(QrError::InvalidVersion {
+            version: Some(version),
+        });
+    };
+    let bytes_needed = buffer_len(version);
+    if buffer.len() < bytes_needed {
+        return Err(QrError::BufferTooSmall {
+            bytes_needed,
+            bytes_available: buffer.len(),
+        });
+    }
+
+    let v = (version.width().unsigned_abs() as usize - 17) / 4;
+    let layout = Layout::new(v, ec_level);
+    let (buffer, _) = buffer.split_at_mut(bytes_needed);
+    let (codewords, modules) = buffer.split_at_mut(layout.codewords_len());
+    let (data_codewords, ec_codewords) = codewords.split_at_mut(layout.data_len());
+    if let Err(error) = encode_data(data, v, data_codewords) {
+        return Err(error);
+    }
+    let mut block = 0;
+    while block < layout.blocks_count() {
+        let (start, len) = layout.block_range(block);
+        let (_, block_data) = data_codewords.split_at(start);
+        let (block_data, _) = block_data.split_at(len);
+        let (_, ec) = ec_codewords.split_at_mut(block * layout.ec_per_block);
+        let (ec, _) = ec.split_at_mut(layout.ec_per_block);
+        ec::write_error_correction_code(block_data, ec);
+        block += 1;
+    }
+
+    let mut matrix = Matrix::new(v, modules);
+    matrix.draw_functional_patterns();
+    matrix.draw_codewords(codewords, &layout);
+    matrix.apply_best_mask(ec_level);
+    Ok(layout.codewords_len())
+}
+
+// Data encoding
+
+/// Returns the mode of the segment, and the number of bits of the segment
+/// including its header.
+const fn segment_bits(data: &[u8], version: usize) -> (Mode, usize) {
+    let mut is_numeric = true;
+    let mut is_alphanumeric = true;
+    let mut i = 0;
+    while i < data.len() {
+        is_numeric &= data[i].is_ascii_digit();
+        is_alphanumeric &= alphanumeric_value(data[i]).is_some();
+        i += 1;
+    }
+    let mode = if is_numeric {
+        Mode::Numeric
+    } else if is_alphanumeric {
+        Mode::Alphanumeric
+    } else {
+        Mode::Byte
+    };
+    let bits = 4 + length_bits_count(mode, version) + mode.data_bits_count(data.len());
+    (mode, bits)
+}
+
+/// Returns the number of bits of the character count indicator, like
+/// [`Mode::length_bits_count`] for normal QR code.
+const fn length_bits_count(mode: Mode, version: usize) -> usize {
+    match version {
+        1..=9 => match mode {
+            Mode::Numeric => 10,
+            Mode::Alphanumeric => 9,
+            Mode::Byte | Mode::Kanji => 8,
+        },
+        10..=26 => match mode {
+            Mode::Numeric => 12,
+            Mode::Alphanumeric => 11,
+            Mode::Byte => 16,
+            Mode::Kanji => 10,
+        },
+        _ => match mode {
+            Mode::Numeric => 14,
+            Mode::Alphanumeric => 13,
+            Mode::Byte => 16,
+            Mode::Kanji => 12,
+        },
+    }
+}
+
+/// Writes the data as a single segment, followed by the terminator and the
+/// padding, into the data codewords.
+const fn encode_data(data: &[u8], version: usize, codewords: &mut [u8]) -> QrResult<()> {
+    const PADDING_BYTES: [u8; 2] = [0b1110_1100, 0b0001_0001];
+
+    let (mode, bits_needed) = segment_bits(data, version);
+    let length_bits = length_bits_count(mode, version);
+    let bits_available = codewords.len() * 8;
+    if bits_needed > bits_available || data.len() >= 1 << length_bits {
+        return Err(QrError::DataTooLong {
+            bits_needed,
+            bits_available,
+        });
+    }
+
+    let mut writer = BitWriter {
+        data: codewords,
+        len: 0,
+    };
+    writer.clear();
+    let mode_indicator = match mode {
+        Mode::Numeric => 0b0001,
+        Mode::Alphanumeric => 0b0010,
+        Mode::Byte | Mode::Kanji => 0b0100,
+    };
+    writer.push(4, mode_indicator);
+    writer.push(length_bits, data.len());
+    let chunk_len = match mode {
+        Mode::Numeric => 3,
+        Mode::Alphanumeric => 2,
+        Mode::Byte | Mode::Kanji => 1,
+    };
+    let mut i = 0;
+    while i < data.len() {
+        let end = if i + chunk_len < data.len() {
+            i + chunk_len
+        } else {
+            data.len()
+        };
+        let mut number = 0;
+        let mut j = i;
+        while j < end {
+            number = match mode {
+                Mode::Numeric => number * 10 + (data[j] - b'0') as usize,
+                Mode::Alphanumeric => match alphanumeric_value(data[j]) {
+                    Some(value) => number * 45 + value as usize,
+                    None => number,
+                },
+                Mode::Byte | Mode::Kanji => data[j] as usize,
+            };
+            j += 1;
+        }
+        let bits = match mode {
+            Mode::Numeric => (end - i) * 3 + 1,
+            Mode::Alphanumeric => (end - i) * 5 + 1,
+            Mode::Byte | Mode::Kanji => 8,
+        };
+        writer.push(bits, number);
+        i = end;
+    }
+
+    // The terminator is up to 4 zero bits, and the rest of the last byte is
+    // zero too, so that the padding starts at the next byte.
+    let terminated_len = if writer.len + 4 < bits_available {
+        writer.len + 4
+    } else {
+        bits_available
+    };
+    let mut i = terminated_len.div_ceil(8);
+    while i < codewords.len() {
+        codewords[i] = PADDING_BYTES[(i - terminated_len.div_ceil(8)) % 2];
+        i += 1;
+    }
+    Ok(())
+}
+
+/// Returns the value of the character in alphanumeric mode.
+const fn alphanumeric_value(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'A'..=b'Z' => Some(b - b'A' + 10),
+        b' ' => Some(36),
+        b'$' => Some(37),
+        b'%' => Some(38),
+        b'*' => Some(39),
+        b'+' => Some(40),
+        b'-' => Some(41),
+        b'.' => Some(42),
+        b'/' => Some(43),
+        b':' => Some(44),
+        _ => None,
+    }
+}
+
+/// Appends bits to bytes, from the most significant bit.
+struct BitWriter<'b> {
+    data: &'b mut [u8],
+    len: usize,
+}
+
+impl BitWriter<'_> {
+    const fn clear(&mut self) {
+        let mut i = 0;
+        while i < self.data.len() {
+            self.data[i] = 0;
+            i += 1;
+        }
+        self.len = 0;
+    }
+
+    const fn push(&mut self, bits: usize, number: usize) {
+        let mut i = bits;
+        while i > 0 {
+            i -= 1;
+            if number >> i & 1 == 1 {
+                self.data[self.len / 8] |= 0x80 >> (self.len % 8);
+            }
+            self.len += 1;
+        }
+    }
+}
+
+// Codewords
+
+/// The blocks of the data and error correction codewords.
+///
+/// The error correction codewords of each block are stored after all data
+/// codewords, in the order of the blocks, and are interleaved only when they
+/// are placed in the symbol.
+struct Layout {
+    block_1_size: usize,
+    block_1_count: usize,
+    block_2_size: usize,
+    block_2_count: usize,
+    ec_per_block: usize,
+}
+
+impl Layout {
+    const fn new(version: usize, ec_level: EcLevel) -> Self {
+        let ((block_1_size, block_1_count, block_2_size, block_2_count), ec_per_block) =
+            ec::block_layout(version, ec_level);
+        Self {
+            block_1_size,
+            block_1_count,
+            block_2_size,
+            block_2_count,
+            ec_per_block,
+        }
+    }
+
+    const fn blocks_count(&self) -> usize {
+        self.block_1_count + self.block_2_count
+    }
+
+    const fn data_len(&self) -> usize {
+        self.block_1_size * self.block_1_count + self.block_2_size * self.block_2_count
+    }
+
+    const fn codewords_len(&self) -> usize {
+        self.data_len() + self.ec_per_block * self.blocks_count()
+    }
+
+    /// Returns the start and the length of the data codewords of the block.
+    const fn block_range(&self, block: usize) -> (usize, usize) {
+        if block < self.block_1_count {
+            (block * self.block_1_size, self.block_1_size)
+        } else {
+            let block_1_end = self.block_1_size * self.block_1_count;
+            let start = block_1_end + (block - self.block_1_count) * self.block_2_size;
+            (start, self.block_2_size)
+        }
+    }
+
+    /// Returns the index of the `n`-th codeword in the interleaved order.
+    const fn interleaved_index(&self, n: usize) -> usize {
+        let blocks_count = self.blocks_count();
+        let data_len = self.data_len();
+        if n >= data_len {
+            let n = n - data_len;
+            return data_len + (n % blocks_count) * self.ec_per_block + n / blocks_count;
+        }
+
+        // Every block has the first `block_1_size` codewords, and only the
+        // longer blocks have the rest.
+        let short_len = self.block_1_size * blocks_count;
+        if n < short_len {
+            self.block_range(n % blocks_count).0 + n / blocks_count
+        } else {
+            let block = self.block_1_count + (n - short_len) % self.block_2_count;
+            let offset = self.block_1_size + (n - short_len) / self.block_2_count;
+            self.block_range(block).0 + offset
+        }
+    }
+}
+
+// Module matrix
+
+static FORMAT_INFO_COORDS_MAIN: [(usize, usize); 15] = [
+    (0, 8),
+    (1, 8),
+    (2, 8),
+    (3, 8),
+    (4, 8),
+    (5, 8),
+    (7, 8),
+    (8, 8),
+    (8, 7),
+    (8, 5),
+    (8, 4),
+    (8, 3),
+    (8, 2),
+    (8, 1),
+    (8, 0),
+];
+
+/// The modules of the symbol, one bit each in row-major order.
+struct Matrix<'b> {
+    modules: &'b mut [u8],
+    version: usize,
+    width: usize,
+    alignment_count: usize,
+    alignment_step: usize,
+}
+
+impl<'b> Matrix<'b> {
+    const fn new(version: usize, modules: &'b mut [u8]) -> Self {
+        let mut i = 0;
+        while i < modules.len() {
+            modules[i] = 0;
+            i += 1;
+        }
+        // The number of the alignment patterns along one side, and the
+        // distance between them except for the first one.
+        let (alignment_count, alignment_step) = match version {
+            1 => (0, 0),
+            32 => (6, 26),
+            _ => {
+                let count = version / 7 + 2;
+                (count, (version * 4 + count * 2 + 1) / (count * 2 - 2) * 2)
+            }
+        };
+        Self {
+            modules,
+            version,
+            width: version * 4 + 17,
+            alignment_count,
+            alignment_step,
+        }
+    }
+
+    const fn get(&self, x: usize, y: usize) -> bool {
+        get_bit(self.modules, y * self.width + x)
+    }
+
+    const fn put(&mut self, x: usize, y: usize, dark: bool) {
+        let i = y * self.width + x;
+        if dark {
+            self.modules[i / 8] |= 0x80 >> (i % 8);
+        } else {
+            self.modules[i / 8] &= !(0x80 >> (i % 8));
+        }
+    }
+
+    /// Returns the coordinates of the centers of the alignment patterns along
+    /// one side, the first of which is always 6.
+    const fn alignment_position(&self, index: usize) -> usize {
+        if index == 0 {
+            6
+        } else {
+            self.width - 7 - (self.alignment_count - 1 - index) * self.alignment_step
+        }
+    }
+
+    /// Returns the index of the alignment pattern center within 2 modules of
+    /// the coordinate, if any.
+    const fn alignment_index(&self, coord: usize) -> Option<usize> {
+        if self.alignment_count == 0 {
+            return None;
+        }
+        if coord.abs_diff(6) <= 2 {
+            return Some(0);
+        }
+        let first = self.alignment_position(1);
+        if coord + 2 < first {
+            return None;
+        }
+        let offset = coord + 2 - first;
+        let index = 1 + offset / self.alignment_step;
+        if index < self.alignment_count && offset % self.alignment_step <= 4 {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    /// Checks whether the alignment pattern would overlap a finder pattern.
+    const fn is_finder_corner(&self, i: usize, j: usize) -> bool {
+        let last = self.alignment_count - 1;
+        (i == 0 && (j == 0 || j == last)) || (i == last && j == 0)
+    }
+
+    /// Checks whether the module holds a functional pattern, the format
+    /// information or the version information.
+    const fn is_functional(&self, x: usize, y: usize) -> bool {
+        let width = self.width;
+        let timing_patterns = x == 6 || y == 6;
+        let finder_patterns = (x < 9 && (y < 9 || y >= width - 8)) || (x >= width - 8 && y < 9);
+        let version_info =
+            self.version >= 7 && ((x < 6 && y >= width - 11) || (x >= width - 11 && y < 6));
+        if timing_patterns || finder_patterns || version_info {
+            return true;
+        }
+        match (self.alignment_index(x), self.alignment_index(y)) {
+            (Some(i), Some(j)) => !self.is_finder_corner(i, j),
+            _ => false,
+        }
+    }
+
+    /// Draws a square pattern, whose modules are dark unless their distance
+    /// from the center is `light_distance`.
+    const fn draw_square(&mut self, left: usize, top: usize, size: usize, light_distance: usize) {
+        let center = size / 2;
+        let mut dy = 0;
+        while dy < size {
+            let mut dx = 0;
+            while dx < size {
+                let distance = if dx.abs_diff(center) > dy.abs_diff(center) {
+                    dx.abs_diff(center)
+                } else {
+                    dy.abs_diff(center)
+                };
+                self.put(left + dx, top + dy, distance != light_distance);
+                dx += 1;
+            }
+            dy += 1;
+        }
+    }
+
+    const fn draw_functional_patterns(&mut self) {
+        let width = self.width;
+
+        // Finder patterns, surrounded by the light separators.
+        self.draw_square(0, 0, 7, 2);
+        self.draw_square(width - 7, 0, 7, 2);
+        self.draw_square(0, width - 7, 7, 2);
+
+        // Timing patterns.
+        let mut i = 8;
+        while i < width - 8 {
+            self.put(i, 6, i % 2 == 0);
+            self.put(6, i, i % 2 == 0);
+            i += 1;
+        }
+
+        // Alignment patterns.
+        if self.alignment_count > 0 {
+            let count = self.alignment_count;
+            let mut j = 0;
+            while j < count {
+                let mut i = 0;
+                while i < count {
+                    if !self.is_finder_corner(i, j) {
+                        let (x, y) = (self.alignment_position(i), self.alignment_position(j));
+                        self.draw_square(x - 2, y - 2, 5, 1);
+                    }
+                    i += 1;
+                }
+                j += 1;
+            }
+        }
+
+        // Version information.
+        if self.version >= 7 {
+            let info = version_info(self.version);
+            let mut i = 0;
+            while i < 18 {
+                let dark = info >> (17 - i) & 1 == 1;
+                self.put(5 - i / 3, width - 9 - i % 3, dark);
+                self.put(width - 9 - i % 3, 5 - i / 3, dark);
+                i += 1;
+            }
+        }
+
+        // Dark module.
+        self.put(8, width - 8, true);
+    }
+
+    const fn draw_format_info(&mut self, ec_level: EcLevel, pattern: usize) {
+        let w = self.width;
+        let info = format_info(ec_level, pattern);
+        let mut i = 0;
+        while i < 15 {
+            let dark = info >> (14 - i) & 1 == 1;
+            let (x, y) = FORMAT_INFO_COORDS_MAIN[i];
+            self.put(x, y, dark);
+            if i < 7 {
+                self.put(8, w - 1 - i, dark);
+            } else {
+                self.put(w - 15 + i, 8, dark);
+            }
+            i += 1;
+        }
+    }
+
+    /// Places the codewords in the interleaved order, in two-module wide
+    /// columns zigzagging from the bottom right corner.
+    const fn draw_codewords(&mut self, codewords: &[u8], layout: &Layout) {
+        let w = self.width;
+        let bits_count = codewords.len() * 8;
+        let mut n = 0;
+        let mut right = w - 1;
+        loop {
+            let upward = (right + 1) & 2 == 0;
+            let mut vertical = 0;
+            while vertical < w {
+                let y = if upward { w - 1 - vertical } else { vertical };
+                let mut x = right + 1;
+                while x > right - 1 {
+                    x -= 1;
+                    if n < bits_count && !self.is_functional(x, y) {
+                        let codeword = codewords[layout.interleaved_index(n / 8)];
+                        self.put(x, y, codeword & (0x80 >> (n % 8)) != 0);
+                        n += 1;
+                    }
+                }
+                vertical += 1;
+            }
+            match right {
+                1 => break,
+                // Skip the vertical timing pattern.
+                8 => right = 5,
+                _ => right -= 2,
+            }
+        }
+    }
+
+    const fn apply_mask(&mut self, pattern: usize) {
+        let mut y = 0;
+        while y < self.width {
+            let mut x = 0;
+            while x < self.width {
+                if mask(pattern, x, y) && !self.is_functional(x, y) {
+                    self.put(x, y, !self.get(x, y));
+                }
+                x += 1;
+            }
+            y += 1;
+        }
+    }
+
+    /// Applies the mask pattern with the lowest penalty score. On a tie, the
+    /// pattern with the smallest number wins.
+    const fn apply_best_mask(&mut self, ec_level: EcLevel) {
+        let (mut best_pattern, mut best_score) = (0, usize::MAX);
+        let mut pattern = 0;
+        while pattern < 8 {
+            self.apply_mask(pattern);
+            self.draw_format_info(ec_level, pattern);
+            let score = self.penalty_score();
+            if score < best_score {
+                (best_pattern, best_score) = (pattern, score);
+            }
+            self.apply_mask(pattern);
+            pattern += 1;
+        }
+        self.apply_mask(best_pattern);
+        self.draw_format_info(ec_level, best_pattern);
+    }
+}
+
+const fn get_bit(modules: &[u8], i: usize) -> bool {
+    modules[i / 8] & (0x80 >> (i % 8)) != 0
+}
+
+const fn mask(pattern: usize, x: usize, y: usize) -> bool {
+    match pattern {
+        0 => (x + y) % 2 == 0,
+        1 => y % 2 == 0,
+        2 => x % 3 == 0,
+        3 => (x + y) % 3 == 0,
+        4 => (y / 2 + x / 3) % 2 == 0,
+        5 => (x * y) % 2 + (x * y) % 3 == 0,
+        6 => ((x * y) % 2 + (x * y) % 3) % 2 == 0,
+        _ => ((x + y) % 2 + (x * y) % 3) % 2 == 0,
+    }
+}
+
+/// Computes the format information with its BCH code, masked.
+const fn format_info(ec_level: EcLevel, pattern: usize) -> usize {
+    let data = ((ec_level as usize) ^ 1) << 3 | pattern;
+    let mut remainder = data;
+    let mut i = 0;
+    while i < 10 {
+        remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+        i += 1;
+    }
+    (data << 10 | remainder) ^ 0x5412
+}
+
+/// Computes the version information with its BCH code.
+const fn version_info(version: usize) -> usize {
+    let mut remainder = version;
+    let mut i = 0;
+    while i < 12 {
+        remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1f25);
+        i += 1;
+    }
+    version << 12 | remainder
+}
+
+// Penalty score
+
+impl Matrix<'_> {
+    /// Gets the `k`-th module of the `i`-th row or column.
+    const fn get_in_line(&self, is_horizontal: bool, i: usize, k: usize) -> bool {
+        if is_horizontal {
+            self.get(k, i)
+        } else {
+            self.get(i, k)
+        }
+    }
+
+    /// Computes the penalty score of the adjacent modules and the finder-like
+    /// patterns in a row or column.
+    const fn line_penalty_score(&self, is_horizontal: bool, i: usize) -> usize {
+        let w = self.width;
+        let mut score = 0;
+        let (mut run, mut last) = (0, false);
+        // The last 15 modules, where the modules outside the symbol are light.
+        // A finder-like pattern is the middle 7 of them, and needs 4 light
+        // modules on either side.
+        let mut window = 0;
+        let mut k = 0;
+        while k < w + 4 {
+            let dark = k < w && self.get_in_line(is_horizontal, i, k);
+            if k < w && k > 0 && dark == last {
+                run += 1;
+            } else {
+                if run >= 5 {
+                    score += run - 2;
+                }
+                (run, last) = (1, dark);
+            }
+
+            window = (window << 1 | dark as usize) & 0x7fff;
+            if k >= 10
+                && (window >> 4) & 0x7f == 0b101_1101
+                && (window >> 11 == 0 || window.trailing_zeros() >= 4)
+            {
+                score += 40;
+            }
+            k += 1;
+        }
+        score
+    }
+
+    /// Computes the total penalty score in the same way as
+    /// `Canvas::penalty_score`.
+    const fn penalty_score(&self) -> usize {
+        let w = self.width;
+        let mut score = 0;
+        let mut dark_modules = 0;
+        let mut i = 0;
+        while i < w {
+            score += self.line_penalty_score(true, i) + self.line_penalty_score(false, i);
+            i += 1;
+        }
+        let mut y = 0;
+        while y < w {
+            let mut x = 0;
+            while x < w {
+                let this = self.get(x, y);
+                dark_modules += this as usize;
+                if x + 1 < w
+                    && y + 1 < w
+                    && this == self.get(x + 1, y)
+                    && this == self.get(x, y + 1)
+                    && this == self.get(x + 1, y + 1)
+                {
+                    score += 3;
+                }
+                x += 1;
+            }
+            y += 1;
+        }
+
+        let balance = (dark_modules * 200 / (w * w)).abs_diff(100);
+        score - 720 + balance
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::{QrCode, bits::Bits};
+
+    fn assert_same_as_qr_code(data: &[u8], version: Version, ec_level: EcLevel) {
+        let mut bits = Bits::new(version);
+        if data.iter().all(u8::is_ascii_digit) {
+            bits.push_numeric_data(data).unwrap();
+        } else if data.iter().all(|b| alphanumeric_value(*b).is_some()) {
+            bits.push_alphanumeric_data(data).unwrap();
+        } else {
+            bits.push_byte_data(data).unwrap();
+        }
+        bits.push_terminator(ec_level).unwrap();
+        let expected = QrCode::with_bits(bits, ec_level).unwrap();
+
+        let mut buffer = [0; MAX_BUFFER_LEN];
+        let code = QrCodeRef::encode_into(data, version, ec_level, &mut buffer).unwrap();
+        assert_eq!(code.width(), expected.width());
+        for y in 0..code.width() {
+            for x in 0..code.width() {
+                assert_eq!(
+                    code[(x, y)],
+                    expected[(x, y)],
+                    "{version:?} {ec_level:?} ({x}, {y})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_same_as_qr_code() {
+        // Versions with one or two block sizes, with or without the version
+        // information, and with each number of the alignment patterns.
+        for v in [1, 2, 3, 6, 7, 9, 10, 14, 21, 27, 32] {
+            for ec_level in [EcLevel::L, EcLevel::M, EcLevel::Q, EcLevel::H] {
+                let version = Version::Normal(v);
+                // Fill the symbol, so that every block is used.
+                let len = ec::data_codewords_len(version, ec_level).unwrap() - 3;
+                let data = (0..len)
+                    .map(|i| (i * 7).to_le_bytes()[0])
+                    .collect::<Vec<_>>();
+                assert_same_as_qr_code(&data, version, ec_level);
+                assert_same_as_qr_code(b"QR", version, ec_level);
+            }
+        }
+    }
+
+    #[test]
+    fn test_same_as_qr_code_modes() {
+        assert_same_as_qr_code(b"01234567", Version::Normal(1), EcLevel::M);
+        assert_same_as_qr_code(b"HELLO WORLD", Version::Normal(1), EcLevel::Q);
+        assert_same_as_qr_code(&[b'7'; 1000], Version::Normal(20), EcLevel::L);
+        assert_same_as_qr_code(&[0xff; 2000], Version::Normal(40), EcLevel::M);
+    }
+
+    #[test]
+    fn test_encode_at_compile_time() {
+        static CODE: QrCodeRef<'static> = qr!(b"https://example.com/", EcLevel::Q);
+        assert_eq!(CODE.version(), Version::Normal(2));
+        let code = qr!(b"https://example.com/", Version::Normal(10), EcLevel::L);
+
+        for (code, version) in [(CODE, Version::Normal(2)), (code, Version::Normal(10))] {
+            let mut buffer = [0; MAX_BUFFER_LEN];
+            let expected = QrCodeRef::encode_into(
+                b"https://example.com/",
+                version,
+                code.error_correction_level(),
+                &mut buffer,
+            )
+            .unwrap();
+            assert_eq!(code.width(), expected.width());
+            for y in 0..code.width() {
+                for x in 0..code.width() {
+                    assert_eq!(code[(x, y)], expected[(x, y)]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_buffer_len() {
+        for v in 1..=40 {
+            let version = Version::Normal(v);
+            let codewords = ec::data_codewords_len(version, EcLevel::M).unwrap()
+                + ec::ec_codewords_len(version, EcLevel::M).unwrap();
+            let modules = version.width().as_usize().pow(2).div_ceil(8);
+            assert_eq!(buffer_len(version), codewords + modules);
+        }
+        assert_eq!(MAX_BUFFER_LEN, 7623);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut buffer = [0; MAX_BUFFER_LEN];
+        assert_eq!(
+            QrCodeRef::encode_into(b"1", Version::Micro(2), EcLevel::L, &mut buffer).unwrap_err(),
+            QrError::InvalidVersion {
+                version: Some(Version::Micro(2))
+            }
+        );
+        assert_eq!(
+            QrCodeRef::encode_into(&[0; 18], Version::Normal(1), EcLevel::L, &mut buffer)
+                .unwrap_err(),
+            QrError::DataTooLong {
+                bits_needed: 156,
+                bits_available: 152
+            }
+        );
+    }
+}
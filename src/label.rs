@@ -0,0 +1,524 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Printing QR codes on sheets of labels as [PDF].
+//!
+//! [`LabelSheet`] places one QR code per label, with an optional caption
+//! below it, on the cells of a [`LabelTemplate`], and starts a new page when
+//! the sheet is full. Templates for common Avery and Herma sheets are built
+//! in, and a custom template can be described by its cell size, margins and
+//! pitch.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::label::{LabelSheet, LabelTemplate};
+//!
+//! let skus = ["SKU-1", "SKU-2", "SKU-3"];
+//! let pdf = LabelSheet::new(LabelTemplate::AVERY_L7160)
+//!     .build(skus.iter().map(|sku| (sku, sku)))
+//!     .unwrap();
+//! assert!(pdf.starts_with(b"%PDF-1.4"));
+//! ```
+//!
+//! [PDF]: https://en.wikipedia.org/wiki/PDF
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::{
+    QrCode,
+    cast::As,
+    types::{Color, EcLevel, QrResult},
+};
+
+/// Converts millimeters to points.
+const fn mm(length: f64) -> f64 {
+    length * 72.0 / 25.4
+}
+
+/// Converts inches to points.
+const fn inch(length: f64) -> f64 {
+    length * 72.0
+}
+
+/// The width of a glyph of the Courier font relative to the font size.
+const COURIER_ADVANCE: f64 = 0.6;
+
+/// The layout of a sheet of labels.
+///
+/// All lengths are in points, i.e. 1/72 inch.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LabelTemplate {
+    /// The width of the page.
+    pub page_width: f64,
+
+    /// The height of the page.
+    pub page_height: f64,
+
+    /// The number of labels in each row.
+    pub columns: u32,
+
+    /// The number of labels in each column.
+    pub rows: u32,
+
+    /// The width of a label.
+    pub label_width: f64,
+
+    /// The height of a label.
+    pub label_height: f64,
+
+    /// The distance from the left edge of the page to the left edge of the
+    /// first column.
+    pub left_margin: f64,
+
+    /// The distance from the top edge of the page to the top edge of the first
+    /// row.
+    pub top_margin: f64,
+
+    /// The distance between the left edges of adjacent columns.
+    pub horizontal_pitch: f64,
+
+    /// The distance between the top edges of adjacent rows.
+    pub vertical_pitch: f64,
+}
+
+impl LabelTemplate {
+    /// Avery L7160: 21 labels of 63.5×38.1 mm on A4.
+    pub const AVERY_L7160: Self = Self::a4(3, 7, 63.5, 38.1, 7.21, 15.15, 66.04, 38.1);
+
+    /// Avery L7163: 14 labels of 99.1×38.1 mm on A4.
+    pub const AVERY_L7163: Self = Self::a4(2, 7, 99.1, 38.1, 4.65, 15.15, 101.6, 38.1);
+
+    /// Avery L7165: 8 labels of 99.1×67.7 mm on A4.
+    pub const AVERY_L7165: Self = Self::a4(2, 4, 99.1, 67.7, 4.65, 13.1, 101.6, 67.7);
+
+    /// Avery 5160: 30 labels of 2.625×1 inch on US Letter.
+    pub const AVERY_5160: Self = Self::letter(3, 10, 2.625, 1.0, 0.1875, 0.5, 2.75, 1.0);
+
+    /// Avery 5163: 10 labels of 4×2 inch on US Letter.
+    pub const AVERY_5163: Self = Self::letter(2, 5, 4.0, 2.0, 0.156_25, 0.5, 4.1875, 2.0);
+
+    /// Herma 4360: 24 labels of 70×36 mm on A4.
+    pub const HERMA_4360: Self = Self::a4(3, 8, 70.0, 36.0, 0.0, 4.5, 70.0, 36.0);
+
+    /// Creates a template for A4 paper from lengths in millimeters.
+    #[allow(clippy::too_many_arguments)]
+    const fn a4(
+        columns: u32,
+        rows: u32,
+        label_width: f64,
+        label_height: f64,
+        left_margin: f64,
+        top_margin: f64,
+        horizontal_pitch: f64,
+        vertical_pitch: f64,
+    ) -> Self {
+        Self {
+            page_width: mm(210.0),
+            page_height: mm(297.0),
+            columns,
+            rows,
+            label_width: mm(label_width),
+            label_height: mm(label_height),
+            left_margin: mm(left_margin),
+            top_margin: mm(top_margin),
+            horizontal_pitch: mm(horizontal_pitch),
+            vertical_pitch: mm(vertical_pitch),
+        }
+    }
+
+    /// Creates a template for US Letter paper from lengths in inches.
+    #[allow(clippy::too_many_arguments)]
+    const fn letter(
+        columns: u32,
+        rows: u32,
+        label_width: f64,
+        label_height: f64,
+        left_margin: f64,
+        top_margin: f64,
+        horizontal_pitch: f64,
+        vertical_pitch: f64,
+    ) -> Self {
+        Self {
+            page_width: inch(8.5),
+            page_height: inch(11.0),
+            columns,
+            rows,
+            label_width: inch(label_width),
+            label_height: inch(label_height),
+            left_margin: inch(left_margin),
+            top_margin: inch(top_margin),
+            horizontal_pitch: inch(horizontal_pitch),
+            vertical_pitch: inch(vertical_pitch),
+        }
+    }
+
+    /// Returns the number of labels on a page.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::label::LabelTemplate;
+    /// #
+    /// assert_eq!(LabelTemplate::AVERY_5160.labels_per_page(), 30);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn labels_per_page(&self) -> u32 {
+        self.columns * self.rows
+    }
+}
+
+/// A builder for a PDF document of label sheets.
+#[derive(Clone, Copy, Debug)]
+pub struct LabelSheet {
+    template: LabelTemplate,
+    ec_level: EcLevel,
+    quiet_zone: Option<u32>,
+    padding: f64,
+    caption_size: f64,
+    first_label: u32,
+}
+
+impl LabelSheet {
+    /// Creates a new builder for the template.
+    ///
+    /// The defaults are the "medium" error correction level, the standard
+    /// quiet zone, a padding of 2 mm inside each label, a caption of 8 points
+    /// and starting from the first label.
+    #[must_use]
+    pub const fn new(template: LabelTemplate) -> Self {
+        Self {
+            template,
+            ec_level: EcLevel::M,
+            quiet_zone: None,
+            padding: mm(2.0),
+            caption_size: 8.0,
+            first_label: 0,
+        }
+    }
+
+    /// Sets the error correction level of the QR codes.
+    #[inline]
+    pub const fn error_correction_level(&mut self, ec_level: EcLevel) -> &mut Self {
+        self.ec_level = ec_level;
+        self
+    }
+
+    /// Sets the width of the quiet zone in modules. By default, this is 4
+    /// modules for QR code and 2 modules for Micro QR code and rMQR code.
+    #[inline]
+    pub const fn quiet_zone(&mut self, quiet_zone: u32) -> &mut Self {
+        self.quiet_zone = Some(quiet_zone);
+        self
+    }
+
+    /// Sets the blank space between the edges of a label and its content in
+    /// points.
+    #[inline]
+    pub const fn padding(&mut self, padding: f64) -> &mut Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets the font size of the captions in points. A caption which is too
+    /// wide for the label is drawn smaller.
+    #[inline]
+    pub const fn caption_size(&mut self, caption_size: f64) -> &mut Self {
+        self.caption_size = caption_size;
+        self
+    }
+
+    /// Sets the index of the first label to print, counting from left to right
+    /// and then from top to bottom. This allows printing on a partially used
+    /// sheet. It must be less than [`LabelTemplate::labels_per_page`].
+    #[inline]
+    pub const fn first_label(&mut self, first_label: u32) -> &mut Self {
+        self.first_label = first_label;
+        self
+    }
+
+    /// Builds a PDF document with a label for each payload and caption. An
+    /// empty caption is not drawn, and leaves the space to the QR code. If
+    /// there are no labels, the document consists of a single blank page.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if a payload cannot be encoded into a QR code.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the template has no columns or no rows, or if the first label
+    /// is not on the first page.
+    pub fn build<I, D, C>(&self, labels: I) -> QrResult<Vec<u8>>
+    where
+        I: IntoIterator<Item = (D, C)>,
+        D: AsRef<[u8]>,
+        C: AsRef<str>,
+    {
+        let per_page = self.template.labels_per_page();
+        assert!(per_page > 0, "the template has no labels");
+        assert!(
+            self.first_label < per_page,
+            "the first label should be on the first page"
+        );
+        let mut pages = Vec::<String>::new();
+        for (cell, (data, caption)) in (self.first_label..).zip(labels) {
+            let code = QrCode::with_error_correction_level(data, self.ec_level)?;
+            let page = (cell / per_page).as_usize();
+            pages.resize_with(pages.len().max(page + 1), String::new);
+            self.draw_label(&mut pages[page], cell % per_page, &code, caption.as_ref());
+        }
+        if pages.is_empty() {
+            pages.push(String::new());
+        }
+        Ok(self.write_document(&pages).into_bytes())
+    }
+
+    /// Draws a label into the content stream of a page.
+    #[allow(clippy::suboptimal_flops)]
+    fn draw_label(&self, content: &mut String, cell: u32, code: &QrCode, caption: &str) {
+        let template = &self.template;
+        let column = f64::from(cell % template.columns);
+        let row = f64::from(cell / template.columns);
+        let left = template.left_margin + column * template.horizontal_pitch;
+        let top = template.page_height - template.top_margin - row * template.vertical_pitch;
+        let inner_width = template.label_width - 2.0 * self.padding;
+        let caption_height = if caption.is_empty() {
+            0.0
+        } else {
+            self.caption_size * 1.25
+        };
+        let inner_height = template.label_height - 2.0 * self.padding - caption_height;
+
+        let quiet_zone = self
+            .quiet_zone
+            .unwrap_or_else(|| if code.version().is_normal() { 4 } else { 2 });
+        let (width, height) = code.version().size();
+        let module_size = (inner_width / f64::from(width + 2 * quiet_zone))
+            .min(inner_height / f64::from(height + 2 * quiet_zone));
+        if module_size > 0.0 {
+            let symbol_left = left + (template.label_width - module_size * f64::from(width)) / 2.0;
+            let symbol_top =
+                top - self.padding - (inner_height - module_size * f64::from(height)) / 2.0;
+            let colors = code.to_colors();
+            for (y, line) in colors.chunks(width.as_usize()).enumerate() {
+                let bottom = symbol_top - f64::from(y.as_u32() + 1) * module_size;
+                let mut x = 0;
+                while x < line.len() {
+                    let run = line[x..].iter().take_while(|c| **c == Color::Dark).count();
+                    if run > 0 {
+                        writeln!(
+                            content,
+                            "{:.3} {bottom:.3} {:.3} {module_size:.3} re",
+                            symbol_left + f64::from(x.as_u32()) * module_size,
+                            f64::from(run.as_u32()) * module_size
+                        )
+                        .unwrap();
+                    }
+                    x += run + 1;
+                }
+            }
+            content.push_str("f\n");
+        }
+
+        if !caption.is_empty() {
+            let len = f64::from(caption.chars().count().as_u32());
+            let size = self.caption_size.min(inner_width / (COURIER_ADVANCE * len));
+            let text_left = left + (template.label_width - COURIER_ADVANCE * size * len) / 2.0;
+            let baseline = top - template.label_height + self.padding + 0.25 * self.caption_size;
+            writeln!(
+                content,
+                "BT /F1 {size:.3} Tf {text_left:.3} {baseline:.3} Td ({}) Tj ET",
+                escape_text(caption)
+            )
+            .unwrap();
+        }
+    }
+
+    /// Writes the pages into a PDF document.
+    fn write_document(&self, pages: &[String]) -> String {
+        let mut objects = Vec::with_capacity(3 + 2 * pages.len());
+        objects.push(String::from("<< /Type /Catalog /Pages 2 0 R >>"));
+        let kids = (0..pages.len())
+            .map(|i| format!("{} 0 R", 4 + 2 * i))
+            .collect::<Vec<_>>()
+            .join(" ");
+        objects.push(format!(
+            "<< /Type /Pages /Kids [{kids}] /Count {} >>",
+            pages.len()
+        ));
+        objects.push(String::from(
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Courier >>",
+        ));
+        let (width, height) = (self.template.page_width, self.template.page_height);
+        for (i, content) in pages.iter().enumerate() {
+            objects.push(format!(
+                concat!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.3} {:.3}] ",
+                    "/Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>"
+                ),
+                width,
+                height,
+                5 + 2 * i
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{content}endstream",
+                content.len()
+            ));
+        }
+
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            writeln!(pdf, "{} 0 obj\n{object}\nendobj", i + 1).unwrap();
+        }
+        let xref = pdf.len();
+        write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).unwrap();
+        for offset in offsets {
+            writeln!(pdf, "{offset:010} 00000 n ").unwrap();
+        }
+        write!(
+            pdf,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        )
+        .unwrap();
+        pdf
+    }
+}
+
+/// Escapes a caption as the content of a PDF literal string. Characters
+/// outside printable ASCII are replaced with `?` since the standard font only
+/// covers them.
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            ' '..='~' => escaped.push(c),
+            _ => escaped.push('?'),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the byte offsets of the objects listed in the cross-reference
+    /// table.
+    fn xref_offsets(pdf: &str) -> Vec<usize> {
+        let xref = pdf[pdf.rfind("startxref\n").unwrap() + 10..]
+            .lines()
+            .next()
+            .unwrap()
+            .parse::<usize>()
+            .unwrap();
+        pdf[xref..]
+            .lines()
+            .skip(3)
+            .take_while(|line| line.ends_with(" n "))
+            .map(|line| line[..10].parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_templates_fit_on_page() {
+        for template in [
+            LabelTemplate::AVERY_L7160,
+            LabelTemplate::AVERY_L7163,
+            LabelTemplate::AVERY_L7165,
+            LabelTemplate::AVERY_5160,
+            LabelTemplate::AVERY_5163,
+            LabelTemplate::HERMA_4360,
+        ] {
+            let right = template.left_margin
+                + f64::from(template.columns - 1) * template.horizontal_pitch
+                + template.label_width;
+            let bottom = template.top_margin
+                + f64::from(template.rows - 1) * template.vertical_pitch
+                + template.label_height;
+            assert!(right <= template.page_width + 0.01, "{template:?}");
+            assert!(bottom <= template.page_height + 0.01, "{template:?}");
+        }
+    }
+
+    #[test]
+    fn test_pages() {
+        let labels = (0..25).map(|i| (format!("ITEM-{i}"), format!("Item {i}")));
+        let pdf = LabelSheet::new(LabelTemplate::AVERY_L7160)
+            .build(labels)
+            .unwrap();
+        let pdf = String::from_utf8(pdf).unwrap();
+        assert!(pdf.contains("/Count 2 >>"));
+        assert_eq!(pdf.matches(") Tj ET").count(), 25);
+        assert!(pdf.ends_with("%%EOF\n"));
+
+        let offsets = xref_offsets(&pdf);
+        assert_eq!(offsets.len(), 7);
+        for (i, offset) in offsets.into_iter().enumerate() {
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj\n", i + 1)));
+        }
+    }
+
+    #[test]
+    fn test_first_label() {
+        let mut sheet = LabelSheet::new(LabelTemplate::AVERY_5163);
+        let pdf = sheet.build([("A", "")]).unwrap();
+        assert!(String::from_utf8(pdf).unwrap().contains("/Count 1 >>"));
+
+        // The 10th label is the last one on the first page.
+        let pdf = sheet.first_label(9).build([("A", ""), ("B", "")]).unwrap();
+        let pdf = String::from_utf8(pdf).unwrap();
+        assert!(pdf.contains("/Count 2 >>"));
+        assert!(!pdf.contains("BT "));
+    }
+
+    #[test]
+    #[should_panic(expected = "the first label should be on the first page")]
+    fn test_first_label_beyond_page() {
+        let _ = LabelSheet::new(LabelTemplate::AVERY_5163)
+            .first_label(10)
+            .build([("A", "")]);
+    }
+
+    #[test]
+    fn test_no_labels() {
+        let pdf = LabelSheet::new(LabelTemplate::AVERY_5160)
+            .build(Vec::<(&str, &str)>::new())
+            .unwrap();
+        let pdf = String::from_utf8(pdf).unwrap();
+        assert!(pdf.contains("/Count 1 >>"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        for (i, offset) in xref_offsets(&pdf).into_iter().enumerate() {
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj\n", i + 1)));
+        }
+    }
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(escape_text(r"(a\b)"), r"\(a\\b\)");
+        assert_eq!(escape_text("Größe"), "Gr??e");
+    }
+
+    #[test]
+    fn test_invalid_payload() {
+        let data = [b'0'; 8000];
+        let result = LabelSheet::new(LabelTemplate::AVERY_5160).build([(data, "")]);
+        assert_eq!(
+            result,
+            Err(crate::types::QrError::DataTooLong {
+                bits_needed: 26685,
+                bits_available: 18672
+            })
+        );
+    }
+}
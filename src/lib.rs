@@ -45,34 +45,81 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 // Lint levels of rustc.
 #![deny(missing_docs)]
+// Without `alloc`, only a part of the tables and helpers is used.
+#![cfg_attr(not(feature = "alloc"), allow(dead_code))]
 
+#[cfg(feature = "alloc")]
 extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "alloc")]
 pub mod bits;
+#[cfg(feature = "alloc")]
 pub mod canvas;
 mod cast;
+#[cfg(feature = "alloc")]
+pub mod conformance;
+#[cfg(feature = "alloc")]
+pub mod decode;
 pub mod ec;
+#[cfg(feature = "alloc")]
+pub mod encoder;
+#[cfg(feature = "heapless")]
+pub mod heapless;
+#[cfg(feature = "label")]
+pub mod label;
+#[cfg(feature = "alloc")]
 pub mod optimize;
+#[cfg(feature = "alloc")]
+pub mod payload;
+#[cfg(feature = "alloc")]
+pub mod profile;
+#[cfg(feature = "alloc")]
 pub mod render;
+#[cfg(any(feature = "simulate", all(feature = "std", feature = "svg")))]
+mod rng;
+#[cfg(feature = "simulate")]
+pub mod simulate;
+#[cfg(feature = "kanji")]
+mod sjis;
 pub mod types;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "alloc")]
+pub mod wire;
 
-use alloc::{string::String, vec::Vec};
-use core::ops::Index;
+#[cfg(feature = "alloc")]
+use alloc::{
+    string::String,
+    vec::{self, Vec},
+};
+#[cfg(feature = "alloc")]
+use core::{
+    iter::{self, FusedIterator},
+    ops::Index,
+    slice,
+};
 
 #[cfg(feature = "image")]
 pub use image;
 
 pub use crate::types::{Color, EcLevel, QrResult, Version};
+#[cfg(feature = "alloc")]
 use crate::{
-    bits::{Bits, RectMicroStrategy},
-    canvas::Canvas,
+    bits::{Bits, MicroStrategy, RectMicroStrategy},
+    canvas::{Canvas, MaskPattern, MaskPolicy, MaskSelection, ModuleKind},
     cast::As,
+    encoder::Buffers,
+    profile::Profile,
     render::{Pixel, Renderer},
+    types::{QrError, Region},
 };
 
 /// The encoded QR code symbol.
+#[cfg(feature = "alloc")]
 #[derive(Clone, Debug)]
 pub struct QrCode {
     content: Vec<Color>,
@@ -80,8 +127,13 @@ pub struct QrCode {
     ec_level: EcLevel,
     width: usize,
     height: usize,
+    spare_capacity_bits: Option<usize>,
+    symbology_identifier: Option<&'static str>,
+    reserved_region: Option<Region>,
+    mask_selection: Option<MaskSelection>,
 }
 
+#[cfg(feature = "alloc")]
 impl QrCode {
     /// Constructs a new QR code which automatically encodes the given data.
     ///
@@ -150,6 +202,32 @@ impl QrCode {
         Self::rect_micro_with_error_correction_level(data, EcLevel::M)
     }
 
+    /// Constructs a new GS1 QR code which encodes the given GS1 element
+    /// strings at a specific error correction level.
+    ///
+    /// Variable-length element strings are terminated by the group separator
+    /// (GS, `0x1D`), see [`bits::encode_gs1`]. This method automatically
+    /// chooses the smallest QR code.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the QR code cannot be constructed, e.g. when the data
+    /// is too long.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{EcLevel, QrCode};
+    /// #
+    /// let code = QrCode::new_gs1(b"10ABC123\x1d0109501101020917", EcLevel::M).unwrap();
+    /// assert_eq!(code.symbology_identifier(), Some("]Q3"));
+    /// ```
+    #[inline]
+    pub fn new_gs1(data: impl AsRef<[u8]>, ec_level: EcLevel) -> QrResult<Self> {
+        let bits = bits::encode_gs1(data.as_ref(), ec_level)?;
+        Self::with_bits(bits, ec_level)
+    }
+
     /// Constructs a new QR code which automatically encodes the given data at a
     /// specific error correction level.
     ///
@@ -176,6 +254,36 @@ impl QrCode {
         Self::with_bits(bits, ec_level)
     }
 
+    /// Constructs a new QR code which automatically encodes the given data at
+    /// the highest error correction level which fits in the smallest QR code
+    /// for `min_ec_level`.
+    ///
+    /// The version is chosen like [`QrCode::with_error_correction_level`], and
+    /// then the error correction level is raised as long as the data still
+    /// fits, so the symbol does not get larger. Use
+    /// [`QrCode::error_correction_level`] to get the chosen level. See
+    /// [`Bits::boost_ec_level`] for other kinds of symbols.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the QR code cannot be constructed, e.g. when the data
+    /// is too long.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{EcLevel, QrCode, Version};
+    /// #
+    /// let code = QrCode::with_boosted_ec_level(b"HELLO WORLD", EcLevel::L).unwrap();
+    /// assert_eq!(code.version(), Version::Normal(1));
+    /// assert_eq!(code.error_correction_level(), EcLevel::Q);
+    /// ```
+    pub fn with_boosted_ec_level(data: impl AsRef<[u8]>, min_ec_level: EcLevel) -> QrResult<Self> {
+        let mut bits = bits::encode_auto(data.as_ref(), min_ec_level)?;
+        let ec_level = bits.boost_ec_level(min_ec_level)?;
+        Self::with_bits(bits, ec_level)
+    }
+
     /// Constructs a new Micro QR code which automatically encodes the given
     /// data at a specific error correction level.
     ///
@@ -202,6 +310,39 @@ impl QrCode {
         Self::with_bits(bits, ec_level)
     }
 
+    /// Constructs a new Micro QR code which automatically encodes the given
+    /// data, choosing the error correction level with the given strategy.
+    ///
+    /// With [`MicroStrategy::AllowLowerEc`], the level may be lower than
+    /// `ec_level`; [`QrCode::error_correction_level`] reports the level
+    /// actually used.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the Micro QR code cannot be constructed, e.g. when
+    /// the data is too long.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{EcLevel, QrCode, Version, bits::MicroStrategy};
+    /// #
+    /// let code = QrCode::micro_with_strategy(b"HELLO WORLD", EcLevel::Q, MicroStrategy::AllowLowerEc)
+    ///     .unwrap();
+    /// assert_eq!(code.version(), Version::Micro(3));
+    /// assert_eq!(code.error_correction_level(), EcLevel::M);
+    /// ```
+    #[inline]
+    pub fn micro_with_strategy(
+        data: impl AsRef<[u8]>,
+        ec_level: EcLevel,
+        strategy: MicroStrategy,
+    ) -> QrResult<Self> {
+        let (bits, ec_level) =
+            bits::encode_auto_micro_with_strategy(data.as_ref(), ec_level, strategy)?;
+        Self::with_bits(bits, ec_level)
+    }
+
     /// Constructs a new rMQR code which automatically encodes the given data at
     /// a specific error correction level.
     ///
@@ -229,6 +370,92 @@ impl QrCode {
         Self::with_bits(bits, ec_level)
     }
 
+    /// Constructs a new rMQR code which automatically encodes the given data at
+    /// a specific error correction level, choosing the version with the given
+    /// strategy.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the rMQR code cannot be constructed, e.g. when the
+    /// data is too long or no version satisfies the strategy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{EcLevel, QrCode, Version, bits::RectMicroStrategy};
+    /// #
+    /// let code =
+    ///     QrCode::rect_micro_with_strategy(b"Some data", EcLevel::M, RectMicroStrategy::MaxWidth(43))
+    ///         .unwrap();
+    /// assert!(code.version().width() <= 43);
+    /// ```
+    #[inline]
+    pub fn rect_micro_with_strategy(
+        data: impl AsRef<[u8]>,
+        ec_level: EcLevel,
+        strategy: RectMicroStrategy,
+    ) -> QrResult<Self> {
+        let bits = bits::encode_auto_rect_micro(data.as_ref(), ec_level, strategy)?;
+        Self::with_bits(bits, ec_level)
+    }
+
+    /// Constructs a new QR code which encodes the integer in numeric mode.
+    ///
+    /// This method uses the "medium" error correction level and automatically
+    /// chooses the smallest QR code. The number is encoded without formatting
+    /// it as a string first. See [`bits::encode_numeric`] for numbers with
+    /// leading zeros or numbers which are too large for [`u128`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the QR code cannot be constructed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, Version};
+    /// #
+    /// let code = QrCode::new_numeric(4_294_967_296_u64).unwrap();
+    /// assert_eq!(code.version(), Version::Normal(1));
+    /// ```
+    #[inline]
+    pub fn new_numeric(number: impl Into<u128>) -> QrResult<Self> {
+        let bits = bits::encode_number(number.into(), EcLevel::M)?;
+        Self::with_bits(bits, EcLevel::M)
+    }
+
+    /// Constructs a new QR code which automatically encodes the given data at a
+    /// specific error correction level, after converting the lowercase letters
+    /// `a` to `z` into uppercase.
+    ///
+    /// This lets case-insensitive payloads such as hex serials or bech32
+    /// strings be encoded in the more compact alphanumeric mode. The returned
+    /// [`CaseCoercion`](bits::CaseCoercion) records every converted letter. See
+    /// [`bits::coerce_uppercase`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the QR code cannot be constructed, e.g. when the data
+    /// is too long.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{EcLevel, QrCode};
+    /// #
+    /// let (code, coercion) =
+    ///     QrCode::with_uppercase_coercion(b"lnurl1dp68gurn8ghj7um9", EcLevel::M).unwrap();
+    /// assert_eq!(coercion.len(), 16);
+    /// ```
+    pub fn with_uppercase_coercion(
+        data: impl AsRef<[u8]>,
+        ec_level: EcLevel,
+    ) -> QrResult<(Self, bits::CaseCoercion)> {
+        let (data, coercion) = bits::coerce_uppercase(data.as_ref());
+        let code = Self::with_error_correction_level(data, ec_level)?;
+        Ok((code, coercion))
+    }
+
     /// Constructs a new QR code for the given version and error correction
     /// level.
     ///
@@ -265,6 +492,151 @@ impl QrCode {
         Self::with_bits(bits, ec_level)
     }
 
+    /// Constructs a new QR code from the given entries for the given version
+    /// and error correction level.
+    ///
+    /// Unlike [`QrCode::with_version`], the data is not segmented
+    /// automatically. Each [`Entry::Data`](bits::Entry::Data) becomes exactly
+    /// one segment in its mode, e.g. to reproduce the output of another
+    /// encoder.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the QR code cannot be constructed, e.g. when the data
+    /// is too long, when the data of an entry cannot be encoded in its mode,
+    /// or when the version and error correction level are incompatible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{EcLevel, QrCode, Version, bits::Entry, types::Mode};
+    /// #
+    /// let entries = [
+    ///     Entry::Data {
+    ///         mode: Mode::Numeric,
+    ///         data: b"123".to_vec(),
+    ///     },
+    ///     Entry::Data {
+    ///         mode: Mode::Byte,
+    ///         data: b"abc".to_vec(),
+    ///     },
+    /// ];
+    /// let code = QrCode::with_entries(&entries, Version::Normal(1), EcLevel::M).unwrap();
+    /// ```
+    pub fn with_entries(
+        entries: &[bits::Entry],
+        version: Version,
+        ec_level: EcLevel,
+    ) -> QrResult<Self> {
+        let mut bits = Bits::new(version);
+        bits.push_entries(entries)?;
+        bits.push_terminator(ec_level)?;
+        Self::with_bits(bits, ec_level)
+    }
+
+    /// Constructs a new QR code for the given data, using the given version as
+    /// the minimum size.
+    ///
+    /// If the data does not fit, the next larger version of the same kind is
+    /// tried until the data fits, i.e. a QR code grows to the next version
+    /// number, a Micro QR code grows up to M4, and a rMQR code grows to the
+    /// next width with the same height. Check [`QrCode::version`] to see which
+    /// version was actually used.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the version is invalid, or if the QR code cannot be
+    /// constructed even in the largest version. If the data does not fit,
+    /// the error is [`QrError::DataTooLong`] with the numbers of the largest
+    /// version which supports the error correction level. Otherwise the error
+    /// is the one of the requested version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{EcLevel, QrCode, Version};
+    /// #
+    /// let code = QrCode::with_min_version(b"Some data", Version::Normal(3), EcLevel::M).unwrap();
+    /// assert_eq!(code.version(), Version::Normal(3));
+    ///
+    /// let code = QrCode::with_min_version(&[b'x'; 100], Version::Normal(3), EcLevel::M).unwrap();
+    /// assert_eq!(code.version(), Version::Normal(6));
+    ///
+    /// let code =
+    ///     QrCode::with_min_version(&[b'x'; 40], Version::RectMicro(11, 27), EcLevel::M).unwrap();
+    /// assert_eq!(code.version(), Version::RectMicro(11, 77));
+    /// ```
+    pub fn with_min_version(
+        data: impl AsRef<[u8]>,
+        version: Version,
+        ec_level: EcLevel,
+    ) -> QrResult<Self> {
+        let data = data.as_ref();
+        let versions: Vec<_> = match version {
+            Version::Normal(v) if version.is_normal() => (v..=40).map(Version::Normal).collect(),
+            Version::Micro(v) if version.is_micro() => (v..=4).map(Version::Micro).collect(),
+            Version::RectMicro(h, w) if version.is_rect_micro() => Version::RMQR_ALL_WIDTH
+                .into_iter()
+                .filter(|&width| width >= w)
+                .map(|width| Version::RectMicro(h, width))
+                .filter(|v| v.is_rect_micro())
+                .collect(),
+            _ => {
+                return Err(QrError::InvalidVersion {
+                    version: Some(version),
+                });
+            }
+        };
+        // Report that the data is too long for the largest version which
+        // supports the error correction level, or else the error of the
+        // requested version, rather than whatever the last attempt returned.
+        let mut too_long = None;
+        let mut first_error = None;
+        for candidate in versions {
+            match Self::with_version(data, candidate, ec_level) {
+                Ok(code) => return Ok(code),
+                Err(err @ QrError::DataTooLong { .. }) => too_long = Some(err),
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+        Err(too_long.or(first_error).unwrap_or(QrError::InvalidVersion {
+            version: Some(version),
+        }))
+    }
+
+    /// Constructs a new QR code which automatically encodes the given data with
+    /// the settings of the profile.
+    ///
+    /// The smallest QR code is chosen, up to the largest version of the
+    /// profile.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the QR code cannot be constructed, e.g. when the data
+    /// does not fit in the largest version of the profile.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{EcLevel, QrCode, profile::Profile};
+    /// #
+    /// let code = QrCode::with_profile(b"Some data", Profile::IndustrialDamageProne).unwrap();
+    /// assert_eq!(code.error_correction_level(), EcLevel::H);
+    /// ```
+    pub fn with_profile(data: impl AsRef<[u8]>, profile: Profile) -> QrResult<Self> {
+        let ec_level = profile.error_correction_level();
+        let bits = bits::encode_auto(data.as_ref(), ec_level)?;
+        if bits.version().width() > profile.max_version().width() {
+            return Err(QrError::DataTooLong {
+                bits_needed: bits.payload_len(),
+                bits_available: Bits::new(profile.max_version()).max_len(ec_level)?,
+            });
+        }
+        Self::with_bits(bits, ec_level)
+    }
+
     /// Constructs a new QR code with encoded bits.
     ///
     /// Use this method only if there are very special need to manipulate the
@@ -293,116 +665,525 @@ impl QrCode {
     /// bits.push_terminator(EcLevel::L);
     /// let qrcode = QrCode::with_bits(bits, EcLevel::L);
     /// ```
+    #[allow(clippy::needless_pass_by_value)]
     pub fn with_bits(bits: Bits, ec_level: EcLevel) -> QrResult<Self> {
-        let version = bits.version();
-        let data = bits.into_bytes();
-        let (encoded_data, ec_data) = ec::construct_codewords(&data, version, ec_level)?;
-        let mut canvas = Canvas::new(version, ec_level);
-        canvas.draw_all_functional_patterns();
-        canvas.draw_data(&encoded_data, &ec_data);
-        let content = canvas.apply_best_mask().into_colors();
-        let (width, height) = (version.width().as_usize(), version.height().as_usize());
-        Ok(Self {
-            content,
-            version,
+        Self::encode_bits(
+            &bits,
             ec_level,
-            width,
-            height,
-        })
+            None,
+            MaskPolicy::Auto,
+            &mut Buffers::default(),
+        )
     }
 
-    /// Gets the version of this QR code.
+    /// Constructs a new QR code with bits from [`Bits::new_dynamic`], choosing
+    /// the smallest version of their kind which the data fits in.
+    ///
+    /// The bits must not be terminated, since the terminator depends on the
+    /// version. For rMQR codes, the version with the smallest area is chosen.
+    /// Bits from [`Bits::new`] keep their version, and are terminated if
+    /// needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the QR code cannot be constructed, e.g. when the bits
+    /// are too long even for the largest version.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use qrcode2::{QrCode, Version};
+    /// # use qrcode2::{EcLevel, QrCode, Version, bits::Bits, types::VersionKind};
     /// #
-    /// let code = QrCode::new(b"Some data").unwrap();
+    /// let mut bits = Bits::new_dynamic(VersionKind::Normal);
+    /// bits.push_eci_designator(9).unwrap();
+    /// bits.push_byte_data(b"\xca\xfe\xe4\xe9\xea\xe1\xf2 QR")
+    ///     .unwrap();
+    /// let code = QrCode::with_bits_auto(bits, EcLevel::L).unwrap();
     /// assert_eq!(code.version(), Version::Normal(1));
     /// ```
-    #[must_use]
-    #[inline]
-    pub const fn version(&self) -> Version {
-        self.version
+    pub fn with_bits_auto(bits: Bits, ec_level: EcLevel) -> QrResult<Self> {
+        Self::with_bits(bits.finish_smallest(ec_level)?, ec_level)
     }
 
-    /// Gets the error correction level of this QR code.
+    /// Constructs a new QR code for the given data, choosing the mask pattern
+    /// with the policy.
+    ///
+    /// The choice and the penalty score of every pattern are available from
+    /// [`QrCode::mask_selection`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] with [`QrError::InvalidMaskPattern`] if the chosen
+    /// pattern is not supported by the version, or another error if the QR
+    /// code cannot be constructed.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use qrcode2::{EcLevel, QrCode};
+    /// # use qrcode2::{
+    /// #     EcLevel, QrCode,
+    /// #     canvas::{MaskPattern, MaskPolicy},
+    /// # };
     /// #
-    /// let code = QrCode::new(b"Some data").unwrap();
-    /// assert_eq!(code.error_correction_level(), EcLevel::M);
+    /// // Choose the pattern with the highest penalty score.
+    /// let policy =
+    ///     MaskPolicy::Custom(|scores| scores.iter().max_by_key(|(_, score)| *score).unwrap().0);
+    /// let code = QrCode::with_mask_policy(b"Hello", EcLevel::M, policy).unwrap();
+    /// let selection = code.mask_selection().unwrap();
+    /// let worst = selection.scores.iter().map(|(_, score)| *score).max();
+    /// assert!(
+    ///     selection
+    ///         .scores
+    ///         .contains(&(selection.pattern, worst.unwrap()))
+    /// );
     /// ```
-    #[must_use]
-    #[inline]
-    pub const fn error_correction_level(&self) -> EcLevel {
-        self.ec_level
+    pub fn with_mask_policy(
+        data: impl AsRef<[u8]>,
+        ec_level: EcLevel,
+        policy: MaskPolicy,
+    ) -> QrResult<Self> {
+        let bits = bits::encode_auto(data.as_ref(), ec_level)?;
+        Self::with_bits_and_mask_policy(bits, ec_level, policy)
     }
 
-    /// Gets the number of modules per side, i.e. the width of this QR code.
+    /// Constructs a new QR code with encoded bits, choosing the mask pattern
+    /// with the policy.
     ///
-    /// The width here does not contain the quiet zone paddings.
-    ///
-    /// # Examples
+    /// # Errors
     ///
-    /// ```
-    /// # use qrcode2::QrCode;
-    /// #
-    /// let code = QrCode::new_rect_micro(b"Some data").unwrap();
-    /// assert_eq!(code.width(), 27);
-    /// ```
-    #[must_use]
-    #[inline]
-    pub const fn width(&self) -> usize {
-        self.width
+    /// Returns [`Err`] with [`QrError::InvalidMaskPattern`] if the chosen
+    /// pattern is not supported by the version, or another error if the QR
+    /// code cannot be constructed.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn with_bits_and_mask_policy(
+        bits: Bits,
+        ec_level: EcLevel,
+        policy: MaskPolicy,
+    ) -> QrResult<Self> {
+        Self::encode_bits(&bits, ec_level, None, policy, &mut Buffers::default())
     }
 
-    /// Gets the number of modules per side, i.e. the height of this QR code.
+    /// Constructs a new QR code with encoded bits, masked with the given
+    /// pattern instead of the one with the lowest penalty score.
     ///
-    /// The height here does not contain the quiet zone paddings.
+    /// This is a shorthand for [`QrCode::with_bits_and_mask_policy`] with
+    /// [`MaskPolicy::Fixed`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] with [`QrError::InvalidMaskPattern`] if the pattern is
+    /// not supported by the version, or another error if the QR code cannot
+    /// be constructed.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use qrcode2::QrCode;
+    /// # use qrcode2::{EcLevel, QrCode, bits, canvas::MaskPattern};
     /// #
-    /// let code = QrCode::new_rect_micro(b"Some data").unwrap();
-    /// assert_eq!(code.height(), 13);
+    /// let bits = bits::encode_auto(b"Hello", EcLevel::M).unwrap();
+    /// let code = QrCode::with_bits_and_mask(bits, EcLevel::M, MaskPattern::Meadow).unwrap();
+    /// assert_eq!(code.mask_selection().unwrap().pattern, MaskPattern::Meadow);
     /// ```
-    #[must_use]
+    #[allow(clippy::needless_pass_by_value)]
     #[inline]
-    pub const fn height(&self) -> usize {
-        self.height
+    pub fn with_bits_and_mask(
+        bits: Bits,
+        ec_level: EcLevel,
+        pattern: MaskPattern,
+    ) -> QrResult<Self> {
+        Self::encode_bits(
+            &bits,
+            ec_level,
+            None,
+            MaskPolicy::Fixed(pattern),
+            &mut Buffers::default(),
+        )
     }
 
-    #[allow(clippy::missing_panics_doc)]
-    /// Gets the maximum number of allowed erratic modules can be introduced
-    /// before the data becomes corrupted. Note that errors should not be
-    /// introduced to functional modules.
+    /// Constructs a new QR code for the given data, leaving a region of
+    /// modules blank for a logo.
+    ///
+    /// The region may only cover the modules of the data and error correction
+    /// codewords, and the codewords damaged by blanking it must be within the
+    /// error correction capacity. The modules of the region are light in the
+    /// symbol, so every renderer leaves the region blank. The region is taken
+    /// into account when choosing the mask pattern, and is declared as the
+    /// logo to [`Renderer::audit`](render::Renderer::audit).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] with [`QrError::InvalidReservedRegion`] if the region is
+    /// not allowed, or another error if the QR code cannot be constructed.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use qrcode2::QrCode;
+    /// # use qrcode2::{Color, EcLevel, QrCode, Version, types::Region};
     /// #
-    /// let code = QrCode::new(b"Some data").unwrap();
-    /// assert_eq!(code.max_allowed_errors(), 4);
+    /// let version = Version::Normal(5);
+    /// let region = Region::centered(version, 9, 5);
+    /// let code = QrCode::with_reserved_region(b"Hello", version, EcLevel::H, region).unwrap();
+    /// assert_eq!(code.reserved_region(), Some(region));
+    /// assert_eq!(code[(18, 18)], Color::Light);
     /// ```
-    #[must_use]
-    #[inline]
-    pub fn max_allowed_errors(&self) -> usize {
-        ec::max_allowed_errors(self.version, self.ec_level).expect("invalid version or ec_level")
-    }
-
-    /// Checks whether a module at coordinate (x, y) is a functional module or
-    /// not.
-    ///
-    /// # Panics
-    ///
+    pub fn with_reserved_region(
+        data: impl AsRef<[u8]>,
+        version: Version,
+        ec_level: EcLevel,
+        region: Region,
+    ) -> QrResult<Self> {
+        let mut bits = Bits::new(version);
+        bits.push_optimal_data(data.as_ref())?;
+        bits.push_terminator(ec_level)?;
+        Self::encode_bits(
+            &bits,
+            ec_level,
+            Some(region),
+            MaskPolicy::Auto,
+            &mut Buffers::default(),
+        )
+    }
+
+    /// Encodes the bits into a QR code, leaving the reserved region blank and
+    /// reusing the memory of `buffers`.
+    pub(crate) fn encode_bits(
+        bits: &Bits,
+        ec_level: EcLevel,
+        reserved_region: Option<Region>,
+        mask_policy: MaskPolicy,
+        buffers: &mut Buffers,
+    ) -> QrResult<Self> {
+        let version = bits.version();
+        if let Some(region) = reserved_region {
+            check_reserved_region(version, ec_level, region)?;
+        }
+        let spare_capacity_bits = bits.max_len(ec_level)?.saturating_sub(bits.payload_len());
+        let symbology_identifier = bits.symbology_identifier();
+        let Buffers {
+            data_codewords,
+            ec_codewords,
+            canvas,
+            mask_canvas,
+        } = buffers;
+        ec::construct_codewords_into(
+            bits.as_bytes(),
+            version,
+            ec_level,
+            data_codewords,
+            ec_codewords,
+        )?;
+        let canvas = canvas.get_or_insert_with(|| Canvas::new(version, ec_level));
+        canvas.reset(version, ec_level);
+        canvas.draw_all_functional_patterns();
+        canvas.draw_data(data_codewords, ec_codewords);
+        if let Some(region) = reserved_region {
+            canvas.fill_rect(
+                region.x.as_i16(),
+                region.y.as_i16(),
+                region.width.as_i16(),
+                region.height.as_i16(),
+                canvas::Module::Masked(Color::Light),
+            );
+        }
+        // The scratch canvas is overwritten for each mask pattern, so it does
+        // not need to start as a copy of the canvas.
+        let mask_canvas = mask_canvas.get_or_insert_with(|| Canvas::new(version, ec_level));
+        let mask_selection = canvas.apply_mask_policy_with(mask_policy, mask_canvas)?;
+        let content = canvas.to_colors();
+        let (width, height) = (version.width().as_usize(), version.height().as_usize());
+        Ok(Self {
+            content,
+            version,
+            ec_level,
+            width,
+            height,
+            spare_capacity_bits: Some(spare_capacity_bits),
+            symbology_identifier: Some(symbology_identifier),
+            reserved_region,
+            mask_selection: Some(mask_selection),
+        })
+    }
+
+    /// Constructs a QR code from a canvas.
+    ///
+    /// The canvas should have been masked with [`Canvas::apply_best_mask`] or
+    /// [`Canvas::apply_mask`]. Empty modules are light. See the
+    /// [`canvas`] module for how to draw extra patterns on the canvas.
+    #[must_use]
+    #[inline]
+    pub fn from_canvas(canvas: Canvas) -> Self {
+        let (version, ec_level) = (canvas.version(), canvas.error_correction_level());
+        Self::from_colors(canvas.into_colors(), version, ec_level)
+    }
+
+    /// Constructs a QR code from an already encoded module matrix.
+    pub(crate) fn from_colors(content: Vec<Color>, version: Version, ec_level: EcLevel) -> Self {
+        let (width, height) = (version.width().as_usize(), version.height().as_usize());
+        debug_assert_eq!(content.len(), width * height);
+        Self {
+            content,
+            version,
+            ec_level,
+            width,
+            height,
+            spare_capacity_bits: None,
+            symbology_identifier: None,
+            reserved_region: None,
+            mask_selection: None,
+        }
+    }
+
+    /// Gets the version of this QR code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, Version};
+    /// #
+    /// let code = QrCode::new(b"Some data").unwrap();
+    /// assert_eq!(code.version(), Version::Normal(1));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Gets the error correction level of this QR code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{EcLevel, QrCode};
+    /// #
+    /// let code = QrCode::new(b"Some data").unwrap();
+    /// assert_eq!(code.error_correction_level(), EcLevel::M);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn error_correction_level(&self) -> EcLevel {
+        self.ec_level
+    }
+
+    /// Gets the number of data bits which are unused, i.e. filled with the
+    /// terminator and the padding.
+    ///
+    /// This can be used to tell how many more characters would fit in this
+    /// version, e.g. 3 digits in [`Mode::Numeric`](types::Mode::Numeric) take
+    /// 10 bits. Appending data in a new segment also takes the bits of its
+    /// header.
+    ///
+    /// Returns [`None`] if the QR code has not been encoded from data, e.g.
+    /// when it is restored from the [`wire`] format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, Version};
+    /// #
+    /// let code = QrCode::new(b"01234567").unwrap();
+    /// assert_eq!(code.version(), Version::Normal(1));
+    /// // 128 bits of version 1-M, minus 4 bits of the mode indicator, 10 bits of
+    /// // the character count, and 27 bits of the digits.
+    /// assert_eq!(code.spare_capacity_bits(), Some(87));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn spare_capacity_bits(&self) -> Option<usize> {
+        self.spare_capacity_bits
+    }
+
+    /// Gets the symbology identifier of ISO/IEC 15424 which a conformant
+    /// reader reports for this QR code.
+    ///
+    /// The identifier is `]Q1` for QR code, Micro QR code and rMQR code
+    /// without ECI and FNC1, `]Q2` with ECI, `]Q3` and `]Q4` with FNC1 in the
+    /// first position without and with ECI, and `]Q5` and `]Q6` with FNC1 in
+    /// the second position without and with ECI. `]Q0` of QR code Model 1 is
+    /// never used.
+    ///
+    /// Returns [`None`] if the QR code has not been encoded from data, e.g.
+    /// when it is restored from the [`wire`] format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{EcLevel, QrCode, Version, bits::Bits};
+    /// #
+    /// let code = QrCode::new(b"Some data").unwrap();
+    /// assert_eq!(code.symbology_identifier(), Some("]Q1"));
+    ///
+    /// let mut bits = Bits::new(Version::Normal(1));
+    /// bits.push_fnc1_first_position().unwrap();
+    /// bits.push_numeric_data(b"01049123451234591597033130128")
+    ///     .unwrap();
+    /// bits.push_terminator(EcLevel::L).unwrap();
+    /// let code = QrCode::with_bits(bits, EcLevel::L).unwrap();
+    /// assert_eq!(code.symbology_identifier(), Some("]Q3"));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn symbology_identifier(&self) -> Option<&'static str> {
+        self.symbology_identifier
+    }
+
+    /// Gets the bounding boxes of the functional patterns, e.g. to highlight
+    /// them in an overlay.
+    ///
+    /// See [`canvas::geometry`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::QrCode;
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let geometry = code.geometry();
+    /// assert_eq!(geometry.finder_patterns.len(), 3);
+    /// assert!(geometry.alignment_patterns.is_empty());
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn geometry(&self) -> canvas::Geometry {
+        canvas::geometry(self.version)
+    }
+
+    /// Gets the coordinates of the centers of the 7×7 finder patterns, in the
+    /// order of the top left, the top right and the bottom left corners.
+    ///
+    /// Micro QR code and rMQR code have only the top left one. The finder
+    /// sub-pattern of rMQR code is in
+    /// [`canvas::Geometry::finder_sub_patterns`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::QrCode;
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// assert_eq!(code.finder_centers(), [(3, 3), (17, 3), (3, 17)]);
+    ///
+    /// let code = QrCode::new_micro(b"Hello").unwrap();
+    /// assert_eq!(code.finder_centers(), [(3, 3)]);
+    /// ```
+    #[must_use]
+    pub fn finder_centers(&self) -> Vec<(usize, usize)> {
+        self.geometry()
+            .finder_patterns
+            .iter()
+            .map(|region| (region.x + 3, region.y + 3))
+            .collect()
+    }
+
+    /// Gets the region which is left blank for a logo, if any.
+    ///
+    /// See [`QrCode::with_reserved_region`].
+    #[must_use]
+    #[inline]
+    pub const fn reserved_region(&self) -> Option<Region> {
+        self.reserved_region
+    }
+
+    /// Gets the chosen mask pattern and the penalty score of every pattern
+    /// which the version supports.
+    ///
+    /// This is [`None`] if the QR code is not constructed by encoding data,
+    /// e.g. by [`QrCode::from_canvas`].
+    #[must_use]
+    #[inline]
+    pub const fn mask_selection(&self) -> Option<&MaskSelection> {
+        self.mask_selection.as_ref()
+    }
+
+    /// Gets the number of modules per side, i.e. the width of this QR code.
+    ///
+    /// The width here does not contain the quiet zone paddings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::QrCode;
+    /// #
+    /// let code = QrCode::new_rect_micro(b"Some data").unwrap();
+    /// assert_eq!(code.width(), 27);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Gets the number of modules per side, i.e. the height of this QR code.
+    ///
+    /// The height here does not contain the quiet zone paddings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::QrCode;
+    /// #
+    /// let code = QrCode::new_rect_micro(b"Some data").unwrap();
+    /// assert_eq!(code.height(), 13);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    #[allow(clippy::missing_panics_doc)]
+    /// Gets the maximum number of allowed erratic modules can be introduced
+    /// before the data becomes corrupted. Note that errors should not be
+    /// introduced to functional modules.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::QrCode;
+    /// #
+    /// let code = QrCode::new(b"Some data").unwrap();
+    /// assert_eq!(code.max_allowed_errors(), 4);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn max_allowed_errors(&self) -> usize {
+        ec::max_allowed_errors(self.version, self.ec_level).expect("invalid version or ec_level")
+    }
+
+    /// Decodes this QR code back and returns the decoded data, so that it can
+    /// be compared with the original data before the symbol is shipped.
+    ///
+    /// Besides decoding, this checks that the version and the error correction
+    /// level match, and that no codeword needs to be corrected unless a
+    /// reserved region was left blank.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] with [`QrError::VerificationFailed`] if the symbol
+    /// cannot be decoded or does not pass the checks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::QrCode;
+    /// #
+    /// let code = QrCode::new(b"INVOICE-2024-0042").unwrap();
+    /// assert_eq!(code.verify().unwrap(), b"INVOICE-2024-0042");
+    /// ```
+    pub fn verify(&self) -> QrResult<Vec<u8>> {
+        let decoded = decode::decode_qr_code(self).map_err(|_| QrError::VerificationFailed)?;
+        let is_exact = decoded.corrected_errors == 0 || self.reserved_region.is_some();
+        if decoded.version != self.version || decoded.ec_level != self.ec_level || !is_exact {
+            return Err(QrError::VerificationFailed);
+        }
+        Ok(decoded.payload())
+    }
+
+    /// Checks whether a module at coordinate (x, y) is a functional module or
+    /// not.
+    ///
+    /// # Panics
+    ///
     /// Panics if `x` or `y` is beyond the size of the QR code.
     #[must_use]
     pub fn is_functional(&self, x: usize, y: usize) -> bool {
@@ -411,6 +1192,100 @@ impl QrCode {
         canvas::is_functional(self.version, self.version.width(), x, y)
     }
 
+    /// Returns whether each module, in row major order, belongs to a
+    /// functional pattern or the format and version information.
+    #[cfg(all(feature = "std", feature = "svg"))]
+    pub(crate) fn functional_modules(&self) -> Vec<bool> {
+        let mut canvas = Canvas::new(self.version, self.ec_level);
+        canvas.draw_all_functional_patterns();
+        let (width, height) = (self.width.as_i16(), self.height.as_i16());
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| canvas.get(x, y) != canvas::Module::Empty)
+            .collect()
+    }
+
+    /// Returns the kind of each module, in row major order.
+    ///
+    /// This classifies every module at once, so it is much faster than calling
+    /// [`QrCode::module_kind`] for every module.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the version and the error correction level are incompatible,
+    /// which is only possible for a QR code made by [`QrCode::from_canvas`].
+    #[must_use]
+    pub fn module_kinds(&self) -> Vec<ModuleKind> {
+        Canvas::module_kinds(self.version, self.ec_level)
+            .expect("the version and the error correction level should be valid")
+    }
+
+    /// Returns the kind of the module at the given coordinates, e.g. to style
+    /// the finder patterns differently or to keep a logo away from them.
+    ///
+    /// Telling the data modules from the error correction modules requires
+    /// tracing the codeword placement, so this classifies the whole symbol on
+    /// every call and takes time proportional to the number of modules. Use
+    /// [`QrCode::module_kinds`] to classify more than a few modules.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the coordinates are outside of the symbol, or if the version
+    /// and the error correction level are incompatible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, canvas::ModuleKind};
+    /// #
+    /// let code = QrCode::new(b"Some data").unwrap();
+    /// assert_eq!(code.module_kind(0, 0), ModuleKind::Finder);
+    /// assert_eq!(code.module_kind(7, 0), ModuleKind::Separator);
+    /// assert_eq!(code.module_kind(10, 6), ModuleKind::Timing);
+    /// assert_eq!(code.module_kind(20, 20), ModuleKind::Data);
+    /// ```
+    #[must_use]
+    pub fn module_kind(&self, x: usize, y: usize) -> ModuleKind {
+        assert!(
+            x < self.width && y < self.height,
+            "the coordinates should be inside of the symbol"
+        );
+        self.module_kinds()[y * self.width + x]
+    }
+
+    /// Returns the codeword bit which each module holds, in row major order.
+    ///
+    /// The modules of the functional patterns and the remainder bits are
+    /// [`None`]. This is useful for visualizing the placement of the codewords
+    /// and studying how damage affects the error correction blocks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, canvas::CodewordBit};
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let placement = code.codeword_placement();
+    /// let width = code.width();
+    /// assert_eq!(placement[0], None);
+    /// assert_eq!(
+    ///     placement[width * width - 1],
+    ///     Some(CodewordBit {
+    ///         block: 0,
+    ///         codeword: 0,
+    ///         bit: 7,
+    ///         is_error_correction: false
+    ///     })
+    /// );
+    /// ```
+    #[cfg(feature = "trace")]
+    #[allow(clippy::missing_panics_doc)]
+    #[must_use]
+    pub fn codeword_placement(&self) -> Vec<Option<canvas::CodewordBit>> {
+        Canvas::codeword_placement(self.version, self.ec_level)
+            .expect("the version and the error correction level should be valid")
+    }
+
     /// Converts the QR code into a human-readable string. This is mainly for
     /// debugging only.
     #[must_use]
@@ -436,6 +1311,62 @@ impl QrCode {
         self.content
     }
 
+    /// Returns an iterator over the modules as `(x, y, color)`, in row major
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{Color, QrCode};
+    /// #
+    /// let code = QrCode::new_micro(b"01234567").unwrap();
+    /// let dark = code.iter().filter(|&(.., color)| color == Color::Dark);
+    /// assert_eq!(dark.count(), 93);
+    /// assert_eq!(code.iter().last(), Some((12, 12, Color::Dark)));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.content.iter().enumerate(),
+            width: self.width,
+        }
+    }
+
+    /// Converts the QR code into delimited text, such as CSV, without the quiet
+    /// zone.
+    ///
+    /// Each module is written as `dark` or `light`, modules in a row are
+    /// separated by `sep`, and rows are separated by `line_sep`. There is no
+    /// separator after the last row.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::QrCode;
+    /// #
+    /// let code = QrCode::new_micro(b"01234567").unwrap();
+    /// let csv = code.to_delimited("1", "0", ",", "\n");
+    /// assert_eq!(csv.lines().count(), 13);
+    /// assert!(csv.starts_with("1,1,1,1,1,1,1,0,1,0,1,0,1\n"));
+    /// ```
+    #[must_use]
+    pub fn to_delimited(&self, dark: &str, light: &str, sep: &str, line_sep: &str) -> String {
+        let mut s = String::new();
+        for (i, row) in self.content.chunks(self.width).enumerate() {
+            if i > 0 {
+                s.push_str(line_sep);
+            }
+            for (j, color) in row.iter().enumerate() {
+                if j > 0 {
+                    s.push_str(sep);
+                }
+                s.push_str(color.select(dark, light));
+            }
+        }
+        s
+    }
+
     /// Renders the QR code into an image. The result is an image builder, which
     /// you may do some additional configuration before copying it into a
     /// concrete image.
@@ -469,10 +1400,49 @@ impl QrCode {
     #[inline]
     pub fn render<P: Pixel>(&self) -> Renderer<'_, P> {
         let quiet_zone = if self.version.is_normal() { 4 } else { 2 };
-        Renderer::new(&self.content, self.width, self.height, quiet_zone)
+        let mut renderer = Renderer::new(&self.content, self.width, self.height, quiet_zone);
+        renderer.symbol(self.version, self.ec_level);
+        // A canvas may have been created with an invalid pair of the version and
+        // the error correction level, e.g. Micro QR code M1 at level H.
+        if let Ok(max_allowed_errors) = ec::max_allowed_errors(self.version, self.ec_level) {
+            renderer.max_allowed_errors(max_allowed_errors);
+        }
+        if let Some(region) = self.reserved_region {
+            renderer.logo_dimensions(region.width.as_u32(), region.height.as_u32());
+        }
+        renderer
     }
 }
 
+/// Checks that blanking the region leaves the symbol decodable.
+#[cfg(feature = "alloc")]
+pub(crate) fn check_reserved_region(
+    version: Version,
+    ec_level: EcLevel,
+    region: Region,
+) -> QrResult<()> {
+    let (width, height) = (version.width().as_usize(), version.height().as_usize());
+    if region.area() == 0 || region.x + region.width > width || region.y + region.height > height {
+        return Err(QrError::InvalidReservedRegion);
+    }
+    let kinds = Canvas::module_kinds(version, ec_level)?;
+    let covers_codewords_only = kinds.iter().enumerate().all(|(i, kind)| {
+        !region.contains(i % width, i / width)
+            || matches!(
+                kind,
+                canvas::ModuleKind::Data
+                    | canvas::ModuleKind::ErrorCorrection
+                    | canvas::ModuleKind::Remainder
+            )
+    });
+    let damaged = render::estimate_damaged_codewords(region.area());
+    if !covers_codewords_only || damaged > ec::max_allowed_errors(version, ec_level)? {
+        return Err(QrError::InvalidReservedRegion);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
 impl Index<(usize, usize)> for QrCode {
     type Output = Color;
 
@@ -483,10 +1453,149 @@ impl Index<(usize, usize)> for QrCode {
     }
 }
 
-#[cfg(test)]
+#[cfg(feature = "alloc")]
+impl<'a> IntoIterator for &'a QrCode {
+    type Item = (usize, usize, Color);
+    type IntoIter = Iter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl IntoIterator for QrCode {
+    type Item = (usize, usize, Color);
+    type IntoIter = IntoIter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.content.into_iter().enumerate(),
+            width: self.width,
+        }
+    }
+}
+
+/// An iterator over the modules of a [`QrCode`] as `(x, y, color)`, in row
+/// major order.
+///
+/// This is created by [`QrCode::iter`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct Iter<'a> {
+    inner: iter::Enumerate<slice::Iter<'a, Color>>,
+    width: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl Iterator for Iter<'_> {
+    type Item = (usize, usize, Color);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (i, color) = self.inner.next()?;
+        Some((i % self.width, i / self.width, *color))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl DoubleEndedIterator for Iter<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (i, color) = self.inner.next_back()?;
+        Some((i % self.width, i / self.width, *color))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ExactSizeIterator for Iter<'_> {}
+
+#[cfg(feature = "alloc")]
+impl FusedIterator for Iter<'_> {}
+
+/// An iterator which moves the modules out of a [`QrCode`] as
+/// `(x, y, color)`, in row major order.
+///
+/// This is created by the [`IntoIterator`] implementation of [`QrCode`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct IntoIter {
+    inner: iter::Enumerate<vec::IntoIter<Color>>,
+    width: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl Iterator for IntoIter {
+    type Item = (usize, usize, Color);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (i, color) = self.inner.next()?;
+        Some((i % self.width, i / self.width, color))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl DoubleEndedIterator for IntoIter {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (i, color) = self.inner.next_back()?;
+        Some((i % self.width, i / self.width, color))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ExactSizeIterator for IntoIter {}
+
+#[cfg(feature = "alloc")]
+impl FusedIterator for IntoIter {}
+
+#[cfg(all(test, feature = "alloc"))]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_iter() {
+        let code = QrCode::new_rect_micro(b"01234567").unwrap();
+        let mut count = 0;
+        for (x, y, color) in &code {
+            assert_eq!(color, code[(x, y)]);
+            count += 1;
+        }
+        assert_eq!(count, code.width() * code.height());
+        assert_eq!(code.iter().len(), count);
+        assert_eq!(code.iter().nth(code.width()), Some((0, 1, code[(0, 1)])));
+        assert_eq!(
+            code.iter().rev().collect::<Vec<_>>(),
+            code.clone().into_iter().rev().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            code.iter().map(|(.., color)| color).collect::<Vec<_>>(),
+            code.into_colors()
+        );
+    }
+
+    #[test]
+    fn test_render_invalid_ec_level() {
+        let code = QrCode::from_canvas(Canvas::new(Version::Micro(1), EcLevel::H));
+        assert_eq!(
+            code.render::<char>().has_quiet_zone(false).build().len(),
+            11 * 12 - 1
+        );
+    }
+
     #[test]
     fn test_annex_i_qr() {
         // This uses the ISO Annex I as test vector.
@@ -562,4 +1671,247 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_to_delimited() {
+        let code = QrCode::new_rect_micro(b"01234567").unwrap();
+        assert_eq!(
+            code.to_delimited("#", ".", "", "\n"),
+            code.to_debug_str('#', '.')
+        );
+
+        let code = QrCode::new_micro(b"01234567").unwrap();
+        let csv = code.to_delimited("1", "0", ",", "\r\n");
+        let rows = csv.split("\r\n").collect::<Vec<_>>();
+        assert_eq!(rows.len(), 13);
+        assert_eq!(rows[0], "1,1,1,1,1,1,1,0,1,0,1,0,1");
+        assert_eq!(rows[12], "1,0,0,0,1,1,1,0,0,0,1,1,1");
+    }
+
+    #[test]
+    fn test_spare_capacity_bits() {
+        // M1 holds 20 data bits, and has no mode indicator.
+        let code = QrCode::with_version(b"12345", Version::Micro(1), EcLevel::L).unwrap();
+        assert_eq!(code.spare_capacity_bits(), Some(0));
+        let code = QrCode::with_version(b"123", Version::Micro(1), EcLevel::L).unwrap();
+        assert_eq!(code.spare_capacity_bits(), Some(7));
+
+        let code = QrCode::new(b"01234567").unwrap();
+        let restored = wire::decode(&wire::encode(&code)).unwrap();
+        assert_eq!(restored.spare_capacity_bits(), None);
+    }
+
+    #[test]
+    fn test_with_min_version() {
+        let code = QrCode::with_min_version(b"123456", Version::Micro(1), EcLevel::M).unwrap();
+        assert_eq!(code.version(), Version::Micro(2));
+        let code = QrCode::with_min_version(b"HELLO", Version::Micro(1), EcLevel::L).unwrap();
+        assert_eq!(code.version(), Version::Micro(2));
+        assert_eq!(
+            QrCode::with_min_version([b'x'; 100], Version::Micro(1), EcLevel::L).err(),
+            Some(QrError::DataTooLong {
+                bits_needed: 808,
+                bits_available: 128
+            })
+        );
+        assert_eq!(
+            QrCode::with_min_version(b"1", Version::Micro(1), EcLevel::H).err(),
+            Some(QrError::InvalidVersion {
+                version: Some(Version::Micro(1))
+            })
+        );
+        assert!(matches!(
+            QrCode::with_min_version([b'1'; 100], Version::Micro(1), EcLevel::Q).err(),
+            Some(QrError::DataTooLong { .. })
+        ));
+        assert_eq!(
+            QrCode::with_min_version(b"1", Version::Normal(41), EcLevel::L).err(),
+            Some(QrError::InvalidVersion {
+                version: Some(Version::Normal(41))
+            })
+        );
+        assert_eq!(
+            QrCode::with_min_version(b"1", Version::RectMicro(7, 27), EcLevel::M).err(),
+            Some(QrError::InvalidVersion {
+                version: Some(Version::RectMicro(7, 27))
+            })
+        );
+    }
+
+    #[test]
+    fn test_with_profile() {
+        let data = [b'x'; 250];
+        let code = QrCode::with_profile(data, Profile::Screen).unwrap();
+        assert_eq!(code.version(), Version::Normal(10));
+        assert_eq!(code.error_correction_level(), EcLevel::L);
+        assert_eq!(
+            QrCode::with_profile(data, Profile::PrintSmall).err(),
+            Some(QrError::DataTooLong {
+                bits_needed: 2020,
+                bits_available: 1728
+            })
+        );
+    }
+
+    #[test]
+    fn test_reserved_region() {
+        let version = Version::Normal(5);
+        let region = Region::centered(version, 7, 7);
+        let code = QrCode::with_reserved_region(b"01234567", version, EcLevel::H, region).unwrap();
+        for y in 15..22 {
+            for x in 15..22 {
+                assert_eq!(code[(x, y)], Color::Light);
+            }
+        }
+        assert_eq!(
+            code.render::<char>().logo_dimensions(7, 7).audit(),
+            code.render::<char>().audit()
+        );
+
+        // The finder pattern.
+        let region = Region {
+            x: 0,
+            y: 0,
+            width: 2,
+            height: 2,
+        };
+        assert_eq!(
+            QrCode::with_reserved_region(b"01234567", version, EcLevel::H, region).err(),
+            Some(QrError::InvalidReservedRegion)
+        );
+        // 21 codewords, but only 13 can be recovered in level L.
+        let region = Region::centered(version, 9, 9);
+        assert_eq!(
+            QrCode::with_reserved_region(b"01234567", version, EcLevel::L, region).err(),
+            Some(QrError::InvalidReservedRegion)
+        );
+        // Outside the symbol.
+        let region = Region::centered(version, 40, 2);
+        assert_eq!(
+            QrCode::with_reserved_region(b"01234567", version, EcLevel::H, region).err(),
+            Some(QrError::InvalidReservedRegion)
+        );
+    }
+
+    #[test]
+    fn test_mask_policy() {
+        let auto = QrCode::new(b"01234567").unwrap();
+        let selection = auto.mask_selection().unwrap();
+        let best = selection.scores.iter().map(|(_, score)| *score).min();
+        assert!(
+            selection
+                .scores
+                .contains(&(selection.pattern, best.unwrap()))
+        );
+
+        // Every pattern of the reference symbol can be reproduced.
+        for (pattern, _) in &selection.scores {
+            let policy = MaskPolicy::Fixed(*pattern);
+            let code = QrCode::with_mask_policy(b"01234567", EcLevel::M, policy).unwrap();
+            assert_eq!(code.mask_selection().unwrap().pattern, *pattern);
+            assert_eq!(code.mask_selection().unwrap().scores, selection.scores);
+            if *pattern == selection.pattern {
+                assert_eq!(code.to_colors(), auto.to_colors());
+            }
+        }
+
+        // Micro QR code does not support the checkerboard pattern.
+        let bits = bits::encode_auto_micro(b"01234567", EcLevel::L).unwrap();
+        let policy = MaskPolicy::Fixed(MaskPattern::Checkerboard);
+        assert_eq!(
+            QrCode::with_bits_and_mask_policy(bits, EcLevel::L, policy).err(),
+            Some(QrError::InvalidMaskPattern)
+        );
+    }
+
+    #[test]
+    fn test_with_bits_and_mask() {
+        for version in [Version::Normal(2), Version::Micro(3)] {
+            for pattern in canvas::mask_patterns(version) {
+                let mut bits = Bits::new(version);
+                bits.push_optimal_data(b"01234567").unwrap();
+                bits.push_terminator(EcLevel::L).unwrap();
+                let code = QrCode::with_bits_and_mask(bits, EcLevel::L, *pattern).unwrap();
+                let selection = code.mask_selection().unwrap();
+                assert_eq!(selection.pattern, *pattern);
+                let score = canvas::penalty_score(&code).total();
+                assert!(selection.scores.contains(&(*pattern, score)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        for code in [
+            QrCode::new(b"Hello, world!").unwrap(),
+            QrCode::new_micro(b"12345").unwrap(),
+            QrCode::new_rect_micro(b"Hello").unwrap(),
+            QrCode::with_reserved_region(
+                b"Hello, world!",
+                Version::Normal(5),
+                EcLevel::H,
+                Region::centered(Version::Normal(5), 5, 5),
+            )
+            .unwrap(),
+        ] {
+            assert!(code.verify().is_ok());
+        }
+        assert_eq!(
+            QrCode::new(b"Hello, world!").unwrap().verify().unwrap(),
+            b"Hello, world!"
+        );
+
+        let code = QrCode::new(b"Hello, world!").unwrap();
+        let mut colors = code.to_colors();
+        colors[code.width() * 12 + 12] = !colors[code.width() * 12 + 12];
+        let damaged = QrCode::from_colors(colors, code.version(), code.error_correction_level());
+        assert_eq!(damaged.verify(), Err(QrError::VerificationFailed));
+    }
+
+    #[test]
+    fn test_module_kind() {
+        let code = QrCode::new_rect_micro(b"Some data").unwrap();
+        let kinds = code.module_kinds();
+        assert_eq!(kinds.len(), code.width() * code.height());
+        assert_eq!(code.module_kind(3, 3), ModuleKind::Finder);
+        assert_eq!(
+            code.module_kind(code.width() - 1, code.height() - 1),
+            ModuleKind::Finder
+        );
+        for (i, kind) in kinds.iter().enumerate() {
+            assert_eq!(code.module_kind(i % code.width(), i / code.width()), *kind);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the coordinates should be inside of the symbol")]
+    fn test_module_kind_out_of_range() {
+        let code = QrCode::new(b"Some data").unwrap();
+        let _ = code.module_kind(21, 0);
+    }
+
+    #[test]
+    fn test_with_entries() {
+        use crate::{bits::Entry, types::Mode};
+
+        let data = |mode, data: &[u8]| Entry::Data {
+            mode,
+            data: data.to_vec(),
+        };
+        // The automatic segmentation would encode all of it in byte mode.
+        let entries = [data(Mode::Numeric, b"1234"), data(Mode::Byte, b"ab")];
+        let code = QrCode::with_entries(&entries, Version::Normal(1), EcLevel::M).unwrap();
+        let mut bits = Bits::new(Version::Normal(1));
+        bits.push_numeric_data(b"1234").unwrap();
+        bits.push_byte_data(b"ab").unwrap();
+        bits.push_terminator(EcLevel::M).unwrap();
+        let expected = QrCode::with_bits(bits, EcLevel::M).unwrap();
+        assert_eq!(code.to_colors(), expected.to_colors());
+
+        let entries = [data(Mode::Byte, b"ab")];
+        assert_eq!(
+            QrCode::with_entries(&entries, Version::Micro(1), EcLevel::L).err(),
+            Some(QrError::UnsupportedMode(Mode::Byte))
+        );
+    }
 }
@@ -0,0 +1,336 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! A command-line QR code, Micro QR code and rMQR code encoder.
+
+use std::{
+    fs,
+    io::{self, Cursor, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, bail};
+use clap::{Parser, ValueEnum};
+use csscolorparser::Color;
+use qrcode2::{
+    EcLevel, QrCode, Version,
+    image::{ImageFormat, Rgba},
+    render::{Pixel, Renderer, ansi, eps, pic, svg, unicode::Dense1x2},
+};
+
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Opt {
+    /// Output the result to a file.
+    ///
+    /// If this is not specified or is "-", the result is written to stdout.
+    #[arg(short, long, value_name("FILE"))]
+    output: Option<PathBuf>,
+
+    /// Read the input data from a file.
+    #[arg(short, long, value_name("FILE"), conflicts_with("input"))]
+    read_from: Option<PathBuf>,
+
+    /// Output format.
+    ///
+    /// If this is not specified, the format is guessed from the extension of
+    /// the output file, or is UTF-8 when writing to stdout.
+    #[arg(
+        short('t'),
+        long("type"),
+        value_enum,
+        value_name("FORMAT"),
+        ignore_case(true)
+    )]
+    format: Option<Format>,
+
+    /// Error correction level.
+    #[arg(
+        short('l'),
+        long,
+        value_enum,
+        default_value_t,
+        value_name("LEVEL"),
+        ignore_case(true)
+    )]
+    error_correction_level: Ecc,
+
+    /// The version of the symbol.
+    ///
+    /// rMQR code takes the height and the width.
+    #[arg(short('v'), long, num_args(1..=2), value_name("NUMBER"))]
+    symbol_version: Option<Vec<i16>>,
+
+    /// The type of QR code.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t,
+        value_name("TYPE"),
+        ignore_case(true)
+    )]
+    variant: Variant,
+
+    /// The size of each module in pixels, or in characters for text output.
+    #[arg(short('s'), long, value_name("NUMBER"))]
+    module_size: Option<u32>,
+
+    /// The width of the quiet zone in modules.
+    ///
+    /// The default is 4 for normal QR code, and 2 for Micro QR code and rMQR
+    /// code.
+    #[arg(short('m'), long, value_name("NUMBER"))]
+    margin: Option<u32>,
+
+    /// Foreground color.
+    ///
+    /// This is ignored for PIC and UTF-8 output.
+    #[arg(long, default_value("black"), value_name("COLOR"))]
+    foreground: Color,
+
+    /// Background color.
+    ///
+    /// This is ignored for PIC and UTF-8 output.
+    #[arg(long, default_value("white"), value_name("COLOR"))]
+    background: Color,
+
+    /// Input data.
+    ///
+    /// If this and the input file are not specified, the data is read from
+    /// stdin.
+    #[arg(value_name("STRING"))]
+    input: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+enum Format {
+    /// PNG image.
+    Png,
+
+    /// SVG image.
+    Svg,
+
+    /// Encapsulated PostScript.
+    Eps,
+
+    /// PIC markup language.
+    Pic,
+
+    /// Colored text with ANSI escape sequences.
+    Ansi,
+
+    /// Text with Unicode block characters.
+    Utf8,
+}
+
+impl Format {
+    /// Guesses the format from the extension of the path.
+    fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        Self::from_str(extension, true).ok()
+    }
+
+    /// Returns `true` if the output is text which is printed to a terminal.
+    const fn is_terminal(self) -> bool {
+        matches!(self, Self::Ansi | Self::Utf8)
+    }
+}
+
+#[derive(Clone, Debug, Default, ValueEnum)]
+enum Ecc {
+    /// Level L.
+    L,
+
+    /// Level M.
+    #[default]
+    M,
+
+    /// Level Q.
+    Q,
+
+    /// Level H.
+    H,
+}
+
+impl From<Ecc> for EcLevel {
+    fn from(level: Ecc) -> Self {
+        match level {
+            Ecc::L => Self::L,
+            Ecc::M => Self::M,
+            Ecc::Q => Self::Q,
+            Ecc::H => Self::H,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, ValueEnum)]
+enum Variant {
+    /// Normal QR code.
+    #[default]
+    Normal,
+
+    /// Micro QR code.
+    Micro,
+
+    /// rMQR code.
+    Rmqr,
+}
+
+impl Opt {
+    /// Reads the input data from the argument, the file or stdin.
+    fn read_input(&self) -> anyhow::Result<Vec<u8>> {
+        if let Some(input) = &self.input {
+            Ok(input.as_bytes().to_vec())
+        } else if let Some(path) = &self.read_from {
+            fs::read(path).with_context(|| format!("could not read data from {}", path.display()))
+        } else {
+            let mut buf = Vec::new();
+            io::stdin()
+                .read_to_end(&mut buf)
+                .context("could not read data from stdin")?;
+            Ok(buf)
+        }
+    }
+
+    /// Returns the output file, or [`None`] for stdout.
+    fn output_path(&self) -> Option<&Path> {
+        self.output
+            .as_deref()
+            .filter(|path| *path != Path::new("-"))
+    }
+
+    /// Configures the options which are common to all formats.
+    fn configure<P: Pixel>(&self, renderer: &mut Renderer<'_, P>) {
+        if let Some(size) = self.module_size {
+            renderer.module_dimensions(size, size);
+        }
+        if let Some(margin) = self.margin {
+            renderer.quiet_zone(margin);
+        }
+    }
+}
+
+fn encode(opt: &Opt, data: Vec<u8>) -> anyhow::Result<QrCode> {
+    let ec_level = opt.error_correction_level.clone().into();
+    let code = if let Some(sv) = &opt.symbol_version {
+        let version = match opt.variant {
+            Variant::Normal => Version::Normal(sv[0]),
+            Variant::Micro => Version::Micro(sv[0]),
+            Variant::Rmqr => {
+                let Some(&width) = sv.get(1) else {
+                    bail!("rMQR code requires both the height and the width");
+                };
+                Version::RectMicro(sv[0], width)
+            }
+        };
+        QrCode::with_version(data, version, ec_level)
+    } else {
+        match opt.variant {
+            Variant::Normal => QrCode::with_error_correction_level(data, ec_level),
+            Variant::Micro => QrCode::micro_with_error_correction_level(data, ec_level),
+            Variant::Rmqr => QrCode::rect_micro_with_error_correction_level(data, ec_level),
+        }
+    };
+    code.context("could not construct a QR code")
+}
+
+fn render(opt: &Opt, code: &QrCode, format: Format) -> anyhow::Result<Vec<u8>> {
+    let (foreground, background) = (opt.foreground.to_rgba8(), opt.background.to_rgba8());
+    let output = match format {
+        Format::Png => {
+            let mut renderer = code.render::<Rgba<u8>>();
+            renderer
+                .dark_color(Rgba(foreground))
+                .light_color(Rgba(background));
+            opt.configure(&mut renderer);
+            let mut buf = Cursor::new(Vec::new());
+            renderer
+                .build()
+                .write_to(&mut buf, ImageFormat::Png)
+                .context("could not encode the image as PNG")?;
+            buf.into_inner()
+        }
+        Format::Svg => {
+            let (foreground, background) =
+                (opt.foreground.to_css_hex(), opt.background.to_css_hex());
+            let mut renderer = code.render();
+            renderer
+                .dark_color(svg::Color(&foreground))
+                .light_color(svg::Color(&background));
+            opt.configure(&mut renderer);
+            renderer.build().into_bytes()
+        }
+        Format::Eps => {
+            let mut renderer = code.render();
+            renderer
+                .dark_color(eps::Color(
+                    opt.foreground.to_array().map(f64::from)[..3].try_into()?,
+                ))
+                .light_color(eps::Color(
+                    opt.background.to_array().map(f64::from)[..3].try_into()?,
+                ));
+            opt.configure(&mut renderer);
+            renderer.build().into_bytes()
+        }
+        Format::Pic => {
+            let mut renderer = code.render::<pic::Color>();
+            opt.configure(&mut renderer);
+            renderer.build().into_bytes()
+        }
+        Format::Ansi => {
+            let mut renderer = code.render();
+            renderer
+                .dark_color(ansi::Color::Rgb(
+                    foreground[0],
+                    foreground[1],
+                    foreground[2],
+                ))
+                .light_color(ansi::Color::Rgb(
+                    background[0],
+                    background[1],
+                    background[2],
+                ));
+            opt.configure(&mut renderer);
+            renderer.build().into_bytes()
+        }
+        Format::Utf8 => {
+            let mut renderer = code.render::<Dense1x2>();
+            opt.configure(&mut renderer);
+            renderer.build().into_bytes()
+        }
+    };
+    Ok(output)
+}
+
+fn main() -> anyhow::Result<()> {
+    let opt = Opt::parse();
+
+    let output_path = opt.output_path();
+    let format = match (opt.format, output_path) {
+        (Some(format), _) => format,
+        (None, Some(path)) => Format::from_path(path).with_context(|| {
+            format!(
+                "could not determine the output format from {}",
+                path.display()
+            )
+        })?,
+        (None, None) => Format::Utf8,
+    };
+
+    let data = opt.read_input()?;
+    let code = encode(&opt, data)?;
+    let mut output = render(&opt, &code, format)?;
+
+    if let Some(path) = output_path {
+        fs::write(path, output)
+            .with_context(|| format!("could not write the result to {}", path.display()))
+    } else {
+        if format.is_terminal() {
+            output.push(b'\n');
+        }
+        io::stdout()
+            .write_all(&output)
+            .context("could not write the result to stdout")
+    }
+}
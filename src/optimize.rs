@@ -8,8 +8,58 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 //! Find the optimal data mode sequence to encode a piece of data.
-
-use core::slice::Iter;
+//!
+//! The segmentation is done in two steps, both of which are iterators over
+//! [`Segment`]s, so that alternative encoders and analyzers can reuse them:
+//!
+//! 1. [`Parser`] classifies each byte of the input and splits the input into
+//!    the longest segments which can each be encoded exclusively in a single
+//!    mode.
+//! 2. [`Optimizer`] merges adjacent segments when the merged segment is not
+//!    longer than the separate ones once encoded, for the given [`Version`].
+//!
+//! The segments only refer to the input by indices, and
+//! [`total_encoded_len`] gives the number of bits they take in a symbol.
+//!
+//! [`optimal_segments`] finds the shortest segmentation exactly instead, at the
+//! cost of speed.
+//!
+//! These types and functions are part of the public API and follow semantic
+//! versioning. The exact segments produced by [`Optimizer`] may become shorter
+//! in a minor release, but never longer.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{
+//!     Version,
+//!     optimize::{self, Parser, Segment},
+//!     types::Mode,
+//! };
+//!
+//! let data = b"Order 1234567890";
+//! let version = Version::Normal(1);
+//! let segments: Vec<_> = Parser::new(data).optimize(version).collect();
+//! assert_eq!(
+//!     segments,
+//!     [
+//!         Segment {
+//!             mode: Mode::Byte,
+//!             begin: 0,
+//!             end: 6
+//!         },
+//!         Segment {
+//!             mode: Mode::Numeric,
+//!             begin: 6,
+//!             end: 16
+//!         }
+//!     ]
+//! );
+//! assert_eq!(optimize::total_encoded_len(&segments, version), 108);
+//! ```
+
+use alloc::vec::Vec;
+use core::{iter::FusedIterator, slice::Iter};
 
 use crate::types::{Mode, Version};
 
@@ -29,11 +79,38 @@ pub struct Segment {
 }
 
 impl Segment {
+    /// Returns the number of bytes of the input which the segment covers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{optimize::Segment, types::Mode};
+    /// #
+    /// let segment = Segment {
+    ///     mode: Mode::Numeric,
+    ///     begin: 3,
+    ///     end: 8,
+    /// };
+    /// assert_eq!(segment.len(), 5);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.end - self.begin
+    }
+
+    /// Returns [`true`] if the segment covers no bytes of the input.
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.begin == self.end
+    }
+
     /// Computes the number of bits (including the size of the mode indicator
     /// and length bits) when this segment is encoded.
     #[must_use]
     pub fn encoded_len(&self, version: Version) -> usize {
-        let byte_size = self.end - self.begin;
+        let byte_size = self.len();
         let chars_count = if self.mode == Mode::Kanji {
             byte_size / 2
         } else {
@@ -199,6 +276,8 @@ impl Iterator for Parser<'_> {
     }
 }
 
+impl FusedIterator for Parser<'_> {}
+
 #[cfg(test)]
 mod parse_tests {
     use alloc::vec::Vec;
@@ -399,15 +478,81 @@ mod parse_tests {
     }
 }
 
+// Cost model
+
+/// A cost model which steers the segmentation done by [`Optimizer`].
+///
+/// The optimizer merges adjacent segments when the cost of the merged segment
+/// is not greater than the sum of the costs of the separate ones. The default
+/// cost model is [`EncodedLen`]. A custom cost model can, for example,
+/// penalize mode switches by adding a constant to the cost of every segment,
+/// or forbid a mode which some readers misparse.
+///
+/// Closures of the type `Fn(&Segment, Version) -> Option<usize>` also
+/// implement this trait.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{
+/// #     Version,
+/// #     optimize::{Parser, Segment},
+/// #     types::Mode,
+/// # };
+/// #
+/// // Never use Kanji mode, and add 20 bits to every segment.
+/// let cost_model = |segment: &Segment, version| {
+///     (segment.mode != Mode::Kanji).then(|| segment.encoded_len(version) + 20)
+/// };
+/// let segments: Vec<_> = Parser::new(b"\x93\x5f\x93\x5fABC12345")
+///     .optimize_with(Version::Normal(1), cost_model)
+///     .collect();
+/// assert_eq!(segments.len(), 1);
+/// assert_eq!(segments[0].mode, Mode::Byte);
+/// ```
+pub trait CostModel {
+    /// Returns the cost of encoding the segment, or [`None`] if the mode of the
+    /// segment must not be used.
+    ///
+    /// A segment whose mode is not allowed is encoded in a wider mode instead,
+    /// i.e. [`Mode::Alphanumeric`] for [`Mode::Numeric`], and [`Mode::Byte`]
+    /// for the others. [`Mode::Byte`] is always allowed, and its cost falls
+    /// back to [`Segment::encoded_len`] if this returns [`None`].
+    ///
+    /// Costs are added with saturating arithmetic, so any value up to
+    /// [`usize::MAX`] is allowed.
+    fn cost(&self, segment: &Segment, version: Version) -> Option<usize>;
+}
+
+/// The default cost model, which is the number of bits of the encoded segment.
+/// See [`Segment::encoded_len`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct EncodedLen;
+
+impl CostModel for EncodedLen {
+    #[inline]
+    fn cost(&self, segment: &Segment, version: Version) -> Option<usize> {
+        Some(segment.encoded_len(version))
+    }
+}
+
+impl<F: Fn(&Segment, Version) -> Option<usize>> CostModel for F {
+    #[inline]
+    fn cost(&self, segment: &Segment, version: Version) -> Option<usize> {
+        self(segment, version)
+    }
+}
+
 // Optimizer
 
 /// QR code data optimizer.
 #[derive(Debug)]
-pub struct Optimizer<I> {
+pub struct Optimizer<I, C = EncodedLen> {
     parser: I,
     last_segment: Segment,
     last_segment_size: usize,
     version: Version,
+    cost_model: C,
     ended: bool,
 }
 
@@ -418,26 +563,48 @@ impl<I: Iterator<Item = Segment>> Optimizer<I> {
     /// left to right until the new segment is longer than before. This method
     /// does _not_ use Annex J from the ISO standard.
     #[inline]
-    pub fn new(mut segments: I, version: Version) -> Self {
-        match segments.next() {
-            None => Self {
-                parser: segments,
-                last_segment: Segment {
-                    mode: Mode::Numeric,
-                    begin: 0,
-                    end: 0,
-                },
-                last_segment_size: 0,
-                version,
-                ended: true,
-            },
-            Some(segment) => Self {
-                parser: segments,
-                last_segment: segment,
-                last_segment_size: segment.encoded_len(version),
-                version,
-                ended: false,
+    pub fn new(segments: I, version: Version) -> Self {
+        Self::with_cost_model(segments, version, EncodedLen)
+    }
+}
+
+impl<I: Iterator<Item = Segment>, C: CostModel> Optimizer<I, C> {
+    /// Optimizes the segments like [`Optimizer::new`], but compares the
+    /// segments by the given [`CostModel`] instead of their encoded length.
+    pub fn with_cost_model(segments: I, version: Version, cost_model: C) -> Self {
+        let mut optimizer = Self {
+            parser: segments,
+            last_segment: Segment {
+                mode: Mode::Numeric,
+                begin: 0,
+                end: 0,
             },
+            last_segment_size: 0,
+            version,
+            cost_model,
+            ended: true,
+        };
+        if let Some(segment) = optimizer.parser.next() {
+            let (segment, size) = optimizer.allowed(segment);
+            optimizer.last_segment = segment;
+            optimizer.last_segment_size = size;
+            optimizer.ended = false;
+        }
+        optimizer
+    }
+
+    /// Widens the mode of the segment until the cost model allows it, and
+    /// returns the segment with its cost.
+    fn allowed(&self, mut segment: Segment) -> (Segment, usize) {
+        loop {
+            if let Some(cost) = self.cost_model.cost(&segment, self.version) {
+                return (segment, cost);
+            }
+            segment.mode = match segment.mode {
+                Mode::Numeric => Mode::Alphanumeric,
+                Mode::Alphanumeric | Mode::Kanji => Mode::Byte,
+                Mode::Byte => return (segment, segment.encoded_len(self.version)),
+            };
         }
     }
 }
@@ -449,9 +616,20 @@ impl Parser<'_> {
     pub fn optimize(self, version: Version) -> Optimizer<Self> {
         Optimizer::new(self, version)
     }
+
+    /// Creates a new `Optimizer` with the [`CostModel`] based on this parser.
+    #[must_use]
+    #[inline]
+    pub fn optimize_with<C: CostModel>(
+        self,
+        version: Version,
+        cost_model: C,
+    ) -> Optimizer<Self, C> {
+        Optimizer::with_cost_model(self, version, cost_model)
+    }
 }
 
-impl<I: Iterator<Item = Segment>> Iterator for Optimizer<I> {
+impl<I: Iterator<Item = Segment>, C: CostModel> Iterator for Optimizer<I, C> {
     type Item = Segment;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -466,16 +644,16 @@ impl<I: Iterator<Item = Segment>> Iterator for Optimizer<I> {
                     return Some(self.last_segment);
                 }
                 Some(segment) => {
-                    let seg_size = segment.encoded_len(self.version);
+                    let (segment, seg_size) = self.allowed(segment);
 
                     let new_segment = Segment {
                         mode: self.last_segment.mode.max(segment.mode),
                         begin: self.last_segment.begin,
                         end: segment.end,
                     };
-                    let new_size = new_segment.encoded_len(self.version);
+                    let (new_segment, new_size) = self.allowed(new_segment);
 
-                    if self.last_segment_size + seg_size >= new_size {
+                    if self.last_segment_size.saturating_add(seg_size) >= new_size {
                         self.last_segment = new_segment;
                         self.last_segment_size = new_size;
                     } else {
@@ -490,6 +668,8 @@ impl<I: Iterator<Item = Segment>> Iterator for Optimizer<I> {
     }
 }
 
+impl<I: Iterator<Item = Segment>, C: CostModel> FusedIterator for Optimizer<I, C> {}
+
 /// Computes the total encoded length of all segments.
 #[must_use]
 #[inline]
@@ -773,6 +953,344 @@ mod optimize_tests {
             Version::Micro(3),
         );
     }
+
+    #[test]
+    fn test_cost_model_forbid_numeric() {
+        let no_numeric = |segment: &Segment, version| {
+            (segment.mode != Mode::Numeric).then(|| segment.encoded_len(version))
+        };
+        let segs = Parser::new(b"1234567890")
+            .optimize_with(Version::Normal(1), no_numeric)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            segs,
+            [Segment {
+                mode: Mode::Alphanumeric,
+                begin: 0,
+                end: 10
+            }]
+        );
+    }
+
+    #[test]
+    fn test_cost_model_mode_switch_penalty() {
+        let data = b"ABCDEFGH12345678901234";
+        let version = Version::Normal(1);
+        assert_eq!(Parser::new(data).optimize(version).count(), 2);
+        let penalty = |segment: &Segment, version| Some(segment.encoded_len(version) + 100);
+        let segs = Parser::new(data)
+            .optimize_with(version, penalty)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            segs,
+            [Segment {
+                mode: Mode::Alphanumeric,
+                begin: 0,
+                end: 22
+            }]
+        );
+    }
+
+    #[test]
+    fn test_cost_model_byte_always_allowed() {
+        let nothing = |_: &Segment, _| None;
+        let segs = Parser::new(b"AB12")
+            .optimize_with(Version::Normal(1), nothing)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            segs,
+            [Segment {
+                mode: Mode::Byte,
+                begin: 0,
+                end: 4
+            }]
+        );
+    }
+
+    #[test]
+    fn test_cost_model_max_cost() {
+        let max = |_: &Segment, _| Some(usize::MAX);
+        let segs = Parser::new(b"AB12ab")
+            .optimize_with(Version::Normal(1), max)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            segs,
+            [Segment {
+                mode: Mode::Byte,
+                begin: 0,
+                end: 6
+            }]
+        );
+    }
+}
+
+// Exact optimizer
+
+/// The modes in the order of the columns of the cost table of
+/// [`optimal_segments`].
+const MODES: [Mode; 4] = [Mode::Numeric, Mode::Alphanumeric, Mode::Byte, Mode::Kanji];
+
+/// The cost of a character in each mode of [`MODES`], in 1/6 bits so that all
+/// costs are integers.
+const CHAR_COSTS: [usize; 4] = [20, 33, 48, 78];
+
+/// Finds the segmentation of the data which takes the fewest bits in the
+/// given version.
+///
+/// Unlike [`Optimizer`], which greedily merges the segments of [`Parser`],
+/// this tries every mode for every character by dynamic programming, and
+/// accounts for the mode indicator and the length bits of every segment, so
+/// the result is never longer than that of [`Optimizer`]. It takes time and
+/// memory linear in the length of the data, but is several times slower than
+/// [`Optimizer`].
+///
+/// Modes which the version does not support are not used, except
+/// [`Mode::Byte`] if the data has no other choice.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{
+/// #     Version,
+/// #     optimize::{self, Parser},
+/// # };
+/// #
+/// let data = b"Tel. +81 3 1234 5678";
+/// let version = Version::Normal(1);
+/// let greedy: Vec<_> = Parser::new(data).optimize(version).collect();
+/// let exact = optimize::optimal_segments(data, version);
+/// assert_eq!(optimize::total_encoded_len(&greedy, version), 163);
+/// assert_eq!(optimize::total_encoded_len(&exact, version), 143);
+/// ```
+#[must_use]
+pub fn optimal_segments(data: &[u8], version: Version) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    SegmentTables::default().optimal_segments_into(data, version, &mut segments);
+    segments
+}
+
+/// The tables of [`optimal_segments`], kept to be reused by
+/// [`Encoder`](crate::encoder::Encoder).
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SegmentTables {
+    /// `costs[i][m]` is the least cost of `data[..i]` whose last segment is in
+    /// `MODES[m]`.
+    costs: Vec<[usize; 4]>,
+
+    /// `from[i][m]` is the mode of the segment before the one of `costs[i][m]`
+    /// and whether the last character starts a new segment.
+    from: Vec<[(usize, bool); 4]>,
+}
+
+impl SegmentTables {
+    /// Replaces the contents of `segments` with the result of
+    /// [`optimal_segments`].
+    pub(crate) fn optimal_segments_into(
+        &mut self,
+        data: &[u8],
+        version: Version,
+        segments: &mut Vec<Segment>,
+    ) {
+        const INFINITY: usize = usize::MAX / 2;
+
+        let headers =
+            MODES.map(|mode| 6 * (version.mode_bits_count() + mode.length_bits_count(version)));
+        let Self { costs, from } = self;
+        costs.clear();
+        costs.resize(data.len() + 1, [INFINITY; 4]);
+        from.clear();
+        from.resize(data.len() + 1, [(0, false); 4]);
+        for i in 0..data.len() {
+            // The previous segment ends here, so round up its cost to whole
+            // bits.
+            let (prev, end_cost) = if i == 0 {
+                (0, 0)
+            } else {
+                (0..MODES.len())
+                    .map(|m| (m, costs[i][m].div_ceil(6) * 6))
+                    .min_by_key(|(_, cost)| *cost)
+                    .unwrap_or((0, INFINITY))
+            };
+            for (m, mode) in MODES.iter().enumerate() {
+                let char_len = match mode {
+                    Mode::Numeric if data[i].is_ascii_digit() => 1,
+                    Mode::Alphanumeric if is_alphanumeric(data[i]) => 1,
+                    Mode::Byte => 1,
+                    Mode::Kanji if data.get(i + 1).is_some_and(|b| is_kanji(data[i], *b)) => 2,
+                    _ => continue,
+                };
+                if *mode != Mode::Byte && !version.supports_mode(*mode) {
+                    continue;
+                }
+                let new_cost = end_cost + headers[m];
+                let (cost, step) = if costs[i][m] <= new_cost {
+                    (costs[i][m], (m, false))
+                } else {
+                    (new_cost, (prev, true))
+                };
+                let cost = cost + CHAR_COSTS[m];
+                if cost < costs[i + char_len][m] {
+                    costs[i + char_len][m] = cost;
+                    from[i + char_len][m] = step;
+                }
+            }
+        }
+
+        segments.clear();
+        let Some(mut m) = (0..MODES.len()).min_by_key(|m| costs[data.len()][*m].div_ceil(6)) else {
+            return;
+        };
+        let (mut i, mut end) = (data.len(), data.len());
+        while i > 0 {
+            let (prev, is_new) = from[i][m];
+            i -= if MODES[m] == Mode::Kanji { 2 } else { 1 };
+            if is_new {
+                segments.push(Segment {
+                    mode: MODES[m],
+                    begin: i,
+                    end,
+                });
+                end = i;
+                m = prev;
+            }
+        }
+        segments.reverse();
+    }
+}
+
+/// Returns `true` if the character can be encoded in [`Mode::Alphanumeric`].
+const fn is_alphanumeric(c: u8) -> bool {
+    matches!(
+        ExclCharSet::from_u8(c),
+        ExclCharSet::Symbol | ExclCharSet::Numeric | ExclCharSet::Alpha
+    )
+}
+
+/// Returns `true` if the pair of bytes is a Shift JIS double-byte character
+/// supported by [`Mode::Kanji`].
+const fn is_kanji(first: u8, second: u8) -> bool {
+    match first {
+        0x81..=0x9f | 0xe0..=0xea => matches!(second, 0x40..=0x7e | 0x80..=0xfc),
+        0xeb => matches!(second, 0x40..=0x7e | 0x80..=0xbf),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod optimal_segments_tests {
+    use super::*;
+
+    /// Checks that the segments cover the data, and are not longer than the
+    /// segments of `Optimizer` if the version supports them.
+    fn check(data: &[u8], version: Version) -> Vec<Segment> {
+        let segments = optimal_segments(data, version);
+        let mut end = 0;
+        for segment in &segments {
+            assert_eq!(segment.begin, end);
+            assert!(!segment.is_empty());
+            end = segment.end;
+        }
+        assert_eq!(end, data.len());
+        let greedy = Parser::new(data).optimize(version).collect::<Vec<_>>();
+        if greedy
+            .iter()
+            .all(|segment| version.supports_mode(segment.mode))
+        {
+            assert!(total_encoded_len(&segments, version) <= total_encoded_len(&greedy, version));
+        }
+        segments
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(optimal_segments(b"", Version::Normal(1)).is_empty());
+    }
+
+    #[test]
+    fn test_single_mode() {
+        for (data, mode) in [
+            (&b"0123456789"[..], Mode::Numeric),
+            (b"HELLO WORLD", Mode::Alphanumeric),
+            (b"hello", Mode::Byte),
+            (b"\x93\x5f\xe4\xaa", Mode::Kanji),
+        ] {
+            assert_eq!(
+                check(data, Version::Normal(1)),
+                [Segment {
+                    mode,
+                    begin: 0,
+                    end: data.len()
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn test_mixed() {
+        let data = b"A1B2C3D4E5F6G7H8I9J0123456789";
+        assert_eq!(
+            check(data, Version::Normal(1)),
+            [
+                Segment {
+                    mode: Mode::Alphanumeric,
+                    begin: 0,
+                    end: 19
+                },
+                Segment {
+                    mode: Mode::Numeric,
+                    begin: 19,
+                    end: 29
+                },
+            ]
+        );
+        assert_eq!(
+            check(b"Tel. +81 3 1234 5678", Version::Normal(1)),
+            [
+                Segment {
+                    mode: Mode::Byte,
+                    begin: 0,
+                    end: 3
+                },
+                Segment {
+                    mode: Mode::Alphanumeric,
+                    begin: 3,
+                    end: 20
+                },
+            ]
+        );
+        check(
+            b"Order #1234567890 for ABC-123 / \x93\x5f\x93\x5f\x93\x5f",
+            Version::Normal(10),
+        );
+        check(b"\x81\x40\x81abc0123456789012", Version::RectMicro(17, 139));
+    }
+
+    #[test]
+    fn test_unsupported_modes() {
+        assert_eq!(
+            check(b"12AB", Version::Micro(1)),
+            [
+                Segment {
+                    mode: Mode::Numeric,
+                    begin: 0,
+                    end: 2
+                },
+                Segment {
+                    mode: Mode::Byte,
+                    begin: 2,
+                    end: 4
+                },
+            ]
+        );
+        assert_eq!(
+            check(b"\x93\x5f", Version::Micro(2)),
+            [Segment {
+                mode: Mode::Byte,
+                begin: 0,
+                end: 2
+            }]
+        );
+    }
 }
 
 // Internal types and data for parsing
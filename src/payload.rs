@@ -0,0 +1,85 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Build the content of a QR code for well-known payload schemes.
+//!
+//! The builders in this module produce strings which can be passed directly to
+//! [`QrCode::new`](crate::QrCode::new) and friends.
+
+pub mod email;
+pub mod epc;
+pub mod geo;
+pub mod lightning;
+pub mod matter;
+pub mod mecard;
+pub mod shc;
+pub mod sms;
+pub mod url;
+pub mod vcard;
+pub mod wifi;
+
+use alloc::string::String;
+use core::{error::Error, fmt, fmt::Write};
+
+/// `PayloadError` encodes the error encountered when building a payload.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PayloadError {
+    /// A required field is missing or empty.
+    Missing(&'static str),
+
+    /// A field has a value outside of the range allowed by the payload scheme.
+    OutOfRange(&'static str),
+
+    /// A field has a value which is explicitly forbidden by the payload scheme.
+    Forbidden(&'static str),
+
+    /// A field contains a character which is not allowed by the payload
+    /// scheme.
+    InvalidCharacter(&'static str),
+}
+
+impl fmt::Display for PayloadError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing(field) => write!(f, "{field} is missing"),
+            Self::OutOfRange(field) => write!(f, "{field} is out of range"),
+            Self::Forbidden(field) => write!(f, "{field} is forbidden"),
+            Self::InvalidCharacter(field) => write!(f, "{field} contains an invalid character"),
+        }
+    }
+}
+
+impl Error for PayloadError {}
+
+/// `PayloadResult` is a convenient alias for a payload building result.
+pub type PayloadResult<T> = Result<T, PayloadError>;
+
+/// Appends `value` to `output`, escaping each of the `special` characters and
+/// the backslash with a backslash.
+fn escape_with_backslash(value: &str, special: &[char], output: &mut String) {
+    for c in value.chars() {
+        if c == '\\' || special.contains(&c) {
+            output.push('\\');
+        }
+        output.push(c);
+    }
+}
+
+/// Appends `value` to `output`, percent-encoding each byte for which `keep`
+/// returns `false`.
+fn percent_encode(value: &str, keep: impl Fn(u8) -> bool, output: &mut String) {
+    for b in value.bytes() {
+        if keep(b) {
+            output.push(char::from(b));
+        } else {
+            write!(output, "%{b:02X}").expect("writing into a string should not fail");
+        }
+    }
+}
+
+/// Checks whether `b` is an unreserved character of RFC 3986.
+const fn is_unreserved(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~')
+}
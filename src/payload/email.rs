@@ -0,0 +1,159 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Email payload support, as a [`mailto:`] URI.
+//!
+//! The subject and the body are percent-encoded as UTF-8, so they may contain
+//! any character.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, payload::email::Email};
+//!
+//! let payload = Email::new("support@example.com")
+//!     .subject("Order #42")
+//!     .body("Hello & thanks!")
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(
+//!     payload,
+//!     "mailto:support@example.com?subject=Order%20%2342&body=Hello%20%26%20thanks%21"
+//! );
+//!
+//! let code = QrCode::new(payload).unwrap();
+//! ```
+//!
+//! [`mailto:`]: https://datatracker.ietf.org/doc/html/rfc6068
+
+use alloc::string::String;
+
+use crate::payload::{PayloadError, PayloadResult, is_unreserved, percent_encode};
+
+/// A builder of the email payload.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Email<'a> {
+    address: &'a str,
+    cc: Option<&'a str>,
+    subject: Option<&'a str>,
+    body: Option<&'a str>,
+}
+
+impl<'a> Email<'a> {
+    /// Creates a new email to the given address.
+    #[must_use]
+    #[inline]
+    pub const fn new(address: &'a str) -> Self {
+        Self {
+            address,
+            cc: None,
+            subject: None,
+            body: None,
+        }
+    }
+
+    /// Sets the address to send a carbon copy to.
+    #[must_use]
+    #[inline]
+    pub const fn cc(mut self, cc: &'a str) -> Self {
+        self.cc = Some(cc);
+        self
+    }
+
+    /// Sets the subject.
+    #[must_use]
+    #[inline]
+    pub const fn subject(mut self, subject: &'a str) -> Self {
+        self.subject = Some(subject);
+        self
+    }
+
+    /// Sets the body.
+    #[must_use]
+    #[inline]
+    pub const fn body(mut self, body: &'a str) -> Self {
+        self.body = Some(body);
+        self
+    }
+
+    /// Builds the `mailto:` payload string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if an address is empty or does not contain `@`.
+    pub fn build(&self) -> PayloadResult<String> {
+        if self.address.is_empty() {
+            return Err(PayloadError::Missing("address"));
+        }
+        if !self.address.contains('@') {
+            return Err(PayloadError::InvalidCharacter("address"));
+        }
+        if self.cc.is_some_and(|cc| !cc.contains('@')) {
+            return Err(PayloadError::InvalidCharacter("cc"));
+        }
+
+        let is_address_char = |b| is_unreserved(b) || b == b'@';
+        let mut payload = String::from("mailto:");
+        percent_encode(self.address, is_address_char, &mut payload);
+        let fields = [
+            ("cc", self.cc),
+            ("subject", self.subject),
+            ("body", self.body),
+        ];
+        let mut separator = '?';
+        for (name, value) in fields {
+            if let Some(value) = value {
+                payload.push(separator);
+                payload.push_str(name);
+                payload.push('=');
+                percent_encode(value, is_unreserved, &mut payload);
+                separator = '&';
+            }
+        }
+        Ok(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_only() {
+        let payload = Email::new("a+b@example.com").build();
+        assert_eq!(payload.as_deref(), Ok("mailto:a%2Bb@example.com"));
+    }
+
+    #[test]
+    fn test_all_fields() {
+        let payload = Email::new("info@example.jp")
+            .cc("boss@example.jp")
+            .subject("件名")
+            .body("line 1\r\nline 2")
+            .build();
+        assert_eq!(
+            payload.as_deref(),
+            Ok(concat!(
+                "mailto:info@example.jp?cc=boss%40example.jp",
+                "&subject=%E4%BB%B6%E5%90%8D&body=line%201%0D%0Aline%202"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_invalid_fields() {
+        assert_eq!(
+            Email::new("").build(),
+            Err(PayloadError::Missing("address"))
+        );
+        assert_eq!(
+            Email::new("example.com").build(),
+            Err(PayloadError::InvalidCharacter("address"))
+        );
+        assert_eq!(
+            Email::new("a@example.com").cc("b").build(),
+            Err(PayloadError::InvalidCharacter("cc"))
+        );
+    }
+}
@@ -0,0 +1,288 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! [EPC QR code] (SEPA credit transfer) payload support.
+//!
+//! The payload follows version 002 of the European Payments Council guideline
+//! EPC069-12, in which the BIC is optional. It is encoded in UTF-8, and the
+//! guideline requires the [`EcLevel::M`](crate::EcLevel::M) error correction
+//! level and at most version 13.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{EcLevel, QrCode, payload::epc::EpcQr};
+//!
+//! let payload = EpcQr::new("Red Cross", "BE72000000001616")
+//!     .bic("BPOTBEB1")
+//!     .amount(100_00)
+//!     .text("Urgency fund")
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(
+//!     payload,
+//!     "BCD\n002\n1\nSCT\nBPOTBEB1\nRed Cross\nBE72000000001616\nEUR100.00\n\n\nUrgency fund"
+//! );
+//!
+//! let code = QrCode::with_error_correction_level(payload, EcLevel::M).unwrap();
+//! ```
+//!
+//! [EPC QR code]: https://www.europeanpaymentscouncil.eu/document-library/guidance-documents/quick-response-code-guidelines-enable-data-capture-initiation
+
+use alloc::{format, string::String};
+
+use crate::payload::{PayloadError, PayloadResult};
+
+/// The maximum number of bytes of the payload.
+const MAX_PAYLOAD_LEN: usize = 331;
+
+/// The maximum amount in euro cents, 999,999,999.99 EUR.
+const MAX_AMOUNT: u64 = 99_999_999_999;
+
+/// A builder of the EPC QR code payload.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EpcQr<'a> {
+    name: &'a str,
+    iban: &'a str,
+    bic: Option<&'a str>,
+    amount: Option<u64>,
+    purpose: Option<&'a str>,
+    reference: Option<&'a str>,
+    text: Option<&'a str>,
+    information: Option<&'a str>,
+}
+
+impl<'a> EpcQr<'a> {
+    /// Creates a new credit transfer to the beneficiary with the given name
+    /// and IBAN.
+    #[must_use]
+    #[inline]
+    pub const fn new(name: &'a str, iban: &'a str) -> Self {
+        Self {
+            name,
+            iban,
+            bic: None,
+            amount: None,
+            purpose: None,
+            reference: None,
+            text: None,
+            information: None,
+        }
+    }
+
+    /// Sets the BIC of the beneficiary bank.
+    #[must_use]
+    #[inline]
+    pub const fn bic(mut self, bic: &'a str) -> Self {
+        self.bic = Some(bic);
+        self
+    }
+
+    /// Sets the amount in euro cents.
+    #[must_use]
+    #[inline]
+    pub const fn amount(mut self, amount: u64) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    /// Sets the 4-letter purpose code, e.g. `CHAR` for a charity payment.
+    #[must_use]
+    #[inline]
+    pub const fn purpose(mut self, purpose: &'a str) -> Self {
+        self.purpose = Some(purpose);
+        self
+    }
+
+    /// Sets the structured creditor reference, e.g. an ISO 11649 `RF`
+    /// reference. This cannot be combined with [`EpcQr::text`].
+    #[must_use]
+    #[inline]
+    pub const fn reference(mut self, reference: &'a str) -> Self {
+        self.reference = Some(reference);
+        self
+    }
+
+    /// Sets the unstructured remittance information. This cannot be combined
+    /// with [`EpcQr::reference`].
+    #[must_use]
+    #[inline]
+    pub const fn text(mut self, text: &'a str) -> Self {
+        self.text = Some(text);
+        self
+    }
+
+    /// Sets the information from the beneficiary to the originator.
+    #[must_use]
+    #[inline]
+    pub const fn information(mut self, information: &'a str) -> Self {
+        self.information = Some(information);
+        self
+    }
+
+    /// Builds the EPC QR code payload string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if a field is empty or too long, if the IBAN or BIC
+    /// contains a character other than ASCII letters and digits, if the
+    /// amount is outside of 0.01 to 999,999,999.99 EUR, if both the reference
+    /// and the text are set, if a field contains a line break, or if the
+    /// payload is longer than 331 bytes.
+    pub fn build(&self) -> PayloadResult<String> {
+        if self.name.is_empty() {
+            return Err(PayloadError::Missing("name"));
+        }
+        check_text("name", self.name, 70)?;
+        if self.iban.is_empty() {
+            return Err(PayloadError::Missing("IBAN"));
+        }
+        if self.iban.len() > 34 {
+            return Err(PayloadError::OutOfRange("IBAN"));
+        }
+        if !self.iban.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Err(PayloadError::InvalidCharacter("IBAN"));
+        }
+        if let Some(bic) = self.bic {
+            if !bic.bytes().all(|b| b.is_ascii_alphanumeric()) {
+                return Err(PayloadError::InvalidCharacter("BIC"));
+            }
+            if !matches!(bic.len(), 8 | 11) {
+                return Err(PayloadError::OutOfRange("BIC"));
+            }
+        }
+        if self
+            .amount
+            .is_some_and(|amount| !(1..=MAX_AMOUNT).contains(&amount))
+        {
+            return Err(PayloadError::OutOfRange("amount"));
+        }
+        if let Some(purpose) = self.purpose {
+            if purpose.len() != 4 || !purpose.bytes().all(|b| b.is_ascii_alphanumeric()) {
+                return Err(PayloadError::InvalidCharacter("purpose"));
+            }
+        }
+        if self.reference.is_some() && self.text.is_some() {
+            return Err(PayloadError::Forbidden("text"));
+        }
+        let optional_texts = [
+            ("reference", self.reference, 35),
+            ("text", self.text, 140),
+            ("information", self.information, 70),
+        ];
+        for (field, value, max_len) in optional_texts {
+            if let Some(value) = value {
+                check_text(field, value, max_len)?;
+            }
+        }
+
+        let amount = self
+            .amount
+            .map(|amount| format!("EUR{}.{:02}", amount / 100, amount % 100));
+        let lines = [
+            "BCD",
+            "002",
+            "1",
+            "SCT",
+            self.bic.unwrap_or_default(),
+            self.name,
+            self.iban,
+            amount.as_deref().unwrap_or_default(),
+            self.purpose.unwrap_or_default(),
+            self.reference.unwrap_or_default(),
+            self.text.unwrap_or_default(),
+            self.information.unwrap_or_default(),
+        ];
+        let mut payload = lines.join("\n");
+        payload.truncate(payload.trim_end_matches('\n').len());
+        if payload.len() > MAX_PAYLOAD_LEN {
+            return Err(PayloadError::OutOfRange("payload"));
+        }
+        Ok(payload)
+    }
+}
+
+/// Checks that a text field has at most `max_len` characters and no line
+/// break.
+fn check_text(field: &'static str, value: &str, max_len: usize) -> PayloadResult<()> {
+    if value.chars().count() > max_len {
+        return Err(PayloadError::OutOfRange(field));
+    }
+    if value.contains(['\r', '\n']) {
+        return Err(PayloadError::InvalidCharacter(field));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimal() {
+        let payload = EpcQr::new("Red Cross", "BE72000000001616").build();
+        assert_eq!(
+            payload.as_deref(),
+            Ok("BCD\n002\n1\nSCT\n\nRed Cross\nBE72000000001616")
+        );
+    }
+
+    #[test]
+    fn test_all_fields() {
+        let payload = EpcQr::new("Café Müller", "DE89370400440532013000")
+            .bic("COBADEFFXXX")
+            .amount(1)
+            .purpose("GDSV")
+            .reference("RF18539007547034")
+            .information("Thank you")
+            .build();
+        assert_eq!(
+            payload.as_deref(),
+            Ok(concat!(
+                "BCD\n002\n1\nSCT\nCOBADEFFXXX\nCafé Müller\nDE89370400440532013000\n",
+                "EUR0.01\nGDSV\nRF18539007547034\n\nThank you"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_invalid_fields() {
+        let transfer = EpcQr::new("Red Cross", "BE72000000001616");
+        assert_eq!(
+            EpcQr::new("", "BE72000000001616").build(),
+            Err(PayloadError::Missing("name"))
+        );
+        assert_eq!(
+            EpcQr::new(&"a".repeat(71), "BE72000000001616").build(),
+            Err(PayloadError::OutOfRange("name"))
+        );
+        assert_eq!(
+            EpcQr::new("Red Cross", "BE72 0000 0000 1616").build(),
+            Err(PayloadError::InvalidCharacter("IBAN"))
+        );
+        assert_eq!(
+            transfer.bic("BPOTBE").build(),
+            Err(PayloadError::OutOfRange("BIC"))
+        );
+        assert_eq!(
+            transfer.amount(0).build(),
+            Err(PayloadError::OutOfRange("amount"))
+        );
+        assert_eq!(
+            transfer
+                .amount(MAX_AMOUNT)
+                .build()
+                .map(|p| p.ends_with("EUR999999999.99")),
+            Ok(true)
+        );
+        assert_eq!(
+            transfer.reference("RF18").text("Invoice 1").build(),
+            Err(PayloadError::Forbidden("text"))
+        );
+        assert_eq!(
+            transfer.text("a\nb").build(),
+            Err(PayloadError::InvalidCharacter("text"))
+        );
+    }
+}
@@ -0,0 +1,110 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Geographic location payload support, as a [`geo:`] URI.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, payload::geo::Geo};
+//!
+//! let payload = Geo::new(35.6586, 139.7454).build().unwrap();
+//! assert_eq!(payload, "geo:35.6586,139.7454");
+//!
+//! let code = QrCode::new(payload).unwrap();
+//! ```
+//!
+//! [`geo:`]: https://datatracker.ietf.org/doc/html/rfc5870
+
+use alloc::{format, string::String};
+
+use crate::payload::{PayloadError, PayloadResult};
+
+/// A builder of the geographic location payload, in the WGS 84 coordinate
+/// reference system.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Geo {
+    latitude: f64,
+    longitude: f64,
+    altitude: Option<f64>,
+}
+
+impl Geo {
+    /// Creates a new location with the latitude and the longitude in decimal
+    /// degrees.
+    #[must_use]
+    #[inline]
+    pub const fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+            altitude: None,
+        }
+    }
+
+    /// Sets the altitude in meters.
+    #[must_use]
+    #[inline]
+    pub const fn altitude(mut self, altitude: f64) -> Self {
+        self.altitude = Some(altitude);
+        self
+    }
+
+    /// Builds the `geo:` payload string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the latitude is outside of −90 to 90, the longitude
+    /// is outside of −180 to 180, or the altitude is not finite.
+    pub fn build(&self) -> PayloadResult<String> {
+        if !(-90.0..=90.0).contains(&self.latitude) {
+            return Err(PayloadError::OutOfRange("latitude"));
+        }
+        if !(-180.0..=180.0).contains(&self.longitude) {
+            return Err(PayloadError::OutOfRange("longitude"));
+        }
+        // `-0` is not valid in RFC 5870, so the signed zeros are normalized.
+        let (latitude, longitude) = (self.latitude + 0.0, self.longitude + 0.0);
+        match self.altitude {
+            None => Ok(format!("geo:{latitude},{longitude}")),
+            Some(altitude) if altitude.is_finite() => {
+                Ok(format!("geo:{latitude},{longitude},{}", altitude + 0.0))
+            }
+            Some(_) => Err(PayloadError::OutOfRange("altitude")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_altitude() {
+        let payload = Geo::new(-33.8568, 151.2153).altitude(42.5).build();
+        assert_eq!(payload.as_deref(), Ok("geo:-33.8568,151.2153,42.5"));
+    }
+
+    #[test]
+    fn test_negative_zero() {
+        let payload = Geo::new(-0.0, -0.0).altitude(-0.0).build();
+        assert_eq!(payload.as_deref(), Ok("geo:0,0,0"));
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        assert_eq!(
+            Geo::new(90.5, 0.0).build(),
+            Err(PayloadError::OutOfRange("latitude"))
+        );
+        assert_eq!(
+            Geo::new(0.0, f64::NAN).build(),
+            Err(PayloadError::OutOfRange("longitude"))
+        );
+        assert_eq!(
+            Geo::new(0.0, 0.0).altitude(f64::INFINITY).build(),
+            Err(PayloadError::OutOfRange("altitude"))
+        );
+    }
+}
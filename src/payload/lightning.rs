@@ -0,0 +1,199 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Lightning invoice and LNURL payload support.
+//!
+//! [BOLT 11] invoices and [LNURL]s are [bech32] strings, which are
+//! case-insensitive as long as they are not mixed-case. Written in lowercase
+//! they must be encoded in [`Mode::Byte`], but their uppercase form only
+//! contains characters of [`Mode::Alphanumeric`], which uses 5.5 bits per
+//! character instead of 8. The `lightning:` URI scheme is case-insensitive too.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{EcLevel, QrCode, payload::lightning};
+//!
+//! let invoice = "lightning:lnbc1pvjluezpp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypq";
+//! let payload = lightning::uppercase(invoice).unwrap();
+//! assert!(payload.starts_with("LIGHTNING:LNBC1"));
+//!
+//! let report = lightning::report(invoice, EcLevel::M).unwrap();
+//! assert!(report.saved_bits() > 0);
+//!
+//! let code = QrCode::new(payload).unwrap();
+//! ```
+//!
+//! [BOLT 11]: https://github.com/lightning/bolts/blob/master/11-payment-encoding.md
+//! [LNURL]: https://github.com/lnurl/luds
+//! [bech32]: https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki
+//! [`Mode::Byte`]: crate::types::Mode::Byte
+//! [`Mode::Alphanumeric`]: crate::types::Mode::Alphanumeric
+
+use alloc::string::String;
+
+use crate::{
+    bits, optimize,
+    payload::{PayloadError, PayloadResult},
+    types::{EcLevel, Version},
+};
+
+/// The characters allowed in the data part of a bech32 string.
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Splits the optional `lightning:` scheme from the bech32 string.
+fn split_scheme(input: &str) -> (&str, &str) {
+    const SCHEME: &str = "lightning:";
+
+    match input.get(..SCHEME.len()) {
+        Some(scheme) if scheme.eq_ignore_ascii_case(SCHEME) => input.split_at(SCHEME.len()),
+        _ => ("", input),
+    }
+}
+
+/// Checks whether `s` is a single-case bech32 string with a human-readable
+/// part starting with `ln`.
+fn is_lightning_bech32(s: &str) -> bool {
+    let is_lowercase = s.bytes().all(|b| !b.is_ascii_uppercase());
+    let is_uppercase = s.bytes().all(|b| !b.is_ascii_lowercase());
+    if !(is_lowercase || is_uppercase) {
+        return false;
+    }
+    let Some((hrp, data)) = s.rsplit_once('1') else {
+        return false;
+    };
+    let hrp_is_valid = hrp.len() >= 2
+        && hrp[..2].eq_ignore_ascii_case("ln")
+        && hrp.bytes().all(|b| b.is_ascii_alphanumeric());
+    let data_is_valid = data.len() >= 6
+        && data
+            .bytes()
+            .all(|b| BECH32_CHARSET.contains(&b.to_ascii_lowercase()));
+    hrp_is_valid && data_is_valid
+}
+
+/// Converts a Lightning invoice or LNURL, optionally prefixed with the
+/// `lightning:` scheme, into its uppercase form.
+///
+/// # Errors
+///
+/// Returns [`Err`] if the input is not a single-case bech32 string whose
+/// human-readable part starts with `ln`. Uppercasing such input may change its
+/// meaning, so it is rejected instead.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::payload::lightning;
+/// #
+/// assert_eq!(
+///     lightning::uppercase("lnurl1dp68gurn8ghj7um9").as_deref(),
+///     Ok("LNURL1DP68GURN8GHJ7UM9")
+/// );
+/// assert!(lightning::uppercase("https://example.com/").is_err());
+/// ```
+pub fn uppercase(input: &str) -> PayloadResult<String> {
+    let (_, bech32) = split_scheme(input);
+    if !is_lightning_bech32(bech32) {
+        return Err(PayloadError::InvalidCharacter("bech32 string"));
+    }
+    Ok(input.to_ascii_uppercase())
+}
+
+/// The size saving of encoding a Lightning payload in uppercase.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SizeReport {
+    /// The number of data bits needed to encode the original input.
+    pub original_bits: usize,
+
+    /// The number of data bits needed to encode the uppercase input.
+    pub uppercase_bits: usize,
+
+    /// The smallest QR code version which can store the original input, or
+    /// [`None`] if it does not fit any version.
+    pub original_version: Option<Version>,
+
+    /// The smallest QR code version which can store the uppercase input, or
+    /// [`None`] if it does not fit any version.
+    pub uppercase_version: Option<Version>,
+}
+
+impl SizeReport {
+    /// Returns the number of data bits saved by the uppercase form.
+    #[must_use]
+    #[inline]
+    pub const fn saved_bits(&self) -> usize {
+        self.original_bits.saturating_sub(self.uppercase_bits)
+    }
+}
+
+/// Measures the data bits and the smallest QR code version of the data, as
+/// [`bits::encode_auto`] encodes it.
+fn measure(data: &[u8], ec_level: EcLevel) -> (usize, Option<Version>) {
+    if let Ok(bits) = bits::encode_auto(data, ec_level) {
+        return (bits.payload_len(), Some(bits.version()));
+    }
+    let largest = Version::Normal(40);
+    let segments = optimize::optimal_segments(data, largest);
+    (optimize::total_encoded_len(&segments, largest), None)
+}
+
+/// Reports how much smaller a Lightning payload becomes when it is encoded in
+/// uppercase with the given error correction level.
+///
+/// # Errors
+///
+/// Returns [`Err`] under the same conditions as [`uppercase`].
+pub fn report(input: &str, ec_level: EcLevel) -> PayloadResult<SizeReport> {
+    let uppercase = uppercase(input)?;
+    let (original_bits, original_version) = measure(input.as_bytes(), ec_level);
+    let (uppercase_bits, uppercase_version) = measure(uppercase.as_bytes(), ec_level);
+    Ok(SizeReport {
+        original_bits,
+        uppercase_bits,
+        original_version,
+        uppercase_version,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INVOICE: &str = "lnbc1pvjluezpp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqdpl2pkx2ctnv5sxxmmwwd5kgetjypeh2ursdae8g6twvus8g6rfwvs8qun0dfjkxaq8rkx3yf5tcsyz3d73gafnh3cax9rn449d9p5uxz9ezhhypd0elx87sjle52x86fux2ypatgddc6k63n7erqz25le42c4u4ecky03ylcqca784w";
+
+    #[test]
+    fn test_uppercase() {
+        assert_eq!(uppercase(INVOICE), Ok(INVOICE.to_ascii_uppercase()));
+        assert_eq!(
+            uppercase("Lightning:lnurl1dp68gurn8ghj7um9").as_deref(),
+            Ok("LIGHTNING:LNURL1DP68GURN8GHJ7UM9")
+        );
+    }
+
+    #[test]
+    fn test_reject_non_lightning() {
+        assert!(uppercase("lnbc1pvjLUEZ").is_err());
+        assert!(uppercase("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq").is_err());
+        assert!(uppercase("lnbc1pvjlue?amount=1").is_err());
+        assert!(uppercase("lnbcpvjluez").is_err());
+    }
+
+    #[test]
+    fn test_report() {
+        let report = report(INVOICE, EcLevel::M).unwrap();
+        assert!(report.uppercase_bits < report.original_bits);
+        assert!(report.saved_bits() > 400);
+        assert_eq!(report.original_version, Some(Version::Normal(11)));
+        assert_eq!(report.uppercase_version, Some(Version::Normal(9)));
+
+        // The sizes are those of the symbols which are actually encoded.
+        let code = crate::QrCode::with_error_correction_level(INVOICE, EcLevel::M).unwrap();
+        let capacity = bits::Bits::new(code.version()).max_len(EcLevel::M).unwrap();
+        assert_eq!(
+            code.spare_capacity_bits(),
+            Some(capacity - report.original_bits)
+        );
+    }
+}
@@ -0,0 +1,276 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! [Matter] device onboarding payload support.
+//!
+//! The onboarding payload is the `MT:` string printed on Matter (and Thread)
+//! devices. It packs the vendor ID, product ID, discriminator and setup
+//! passcode into 88 bits, which are then encoded with the Base38 alphabet.
+//! Every character of the result belongs to the
+//! [`Mode::Alphanumeric`](crate::types::Mode::Alphanumeric) character set.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{
+//!     QrCode,
+//!     payload::matter::{DiscoveryCapabilities, MatterPayload},
+//! };
+//!
+//! let payload = MatterPayload::new(3840, 20_202_021)
+//!     .vendor_id(0xfff1)
+//!     .product_id(0x8000)
+//!     .discovery_capabilities(DiscoveryCapabilities::BLE)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(payload, "MT:Y.K9042C00KA0648G00");
+//!
+//! let code = QrCode::new(payload).unwrap();
+//! ```
+//!
+//! [Matter]: https://csa-iot.org/all-solutions/matter/
+
+use alloc::string::String;
+use core::ops::BitOr;
+
+use crate::{
+    cast::As,
+    payload::{PayloadError, PayloadResult},
+};
+
+/// The commissioning flow of a Matter device.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum CommissioningFlow {
+    /// The device is available for commissioning when powered on.
+    #[default]
+    Standard = 0,
+
+    /// The device requires a user action (e.g. pressing a button) before it
+    /// becomes available for commissioning.
+    UserIntent = 1,
+
+    /// The device requires a vendor specific commissioning flow.
+    Custom = 2,
+}
+
+/// The discovery capabilities bitmask of a Matter device.
+///
+/// Capabilities can be combined with the `|` operator.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DiscoveryCapabilities(pub u8);
+
+impl DiscoveryCapabilities {
+    /// The device can be discovered through a Wi-Fi soft access point.
+    pub const SOFT_AP: Self = Self(1 << 0);
+
+    /// The device can be discovered through Bluetooth Low Energy.
+    pub const BLE: Self = Self(1 << 1);
+
+    /// The device is already on the IP network.
+    pub const ON_NETWORK: Self = Self(1 << 2);
+}
+
+impl BitOr for DiscoveryCapabilities {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// A builder of the Matter onboarding payload.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MatterPayload {
+    vendor_id: u16,
+    product_id: u16,
+    commissioning_flow: CommissioningFlow,
+    discovery_capabilities: DiscoveryCapabilities,
+    discriminator: u16,
+    passcode: u32,
+}
+
+impl MatterPayload {
+    /// Creates a new onboarding payload with the 12-bit `discriminator` and
+    /// the 27-bit setup `passcode`.
+    ///
+    /// The vendor ID and product ID default to 0, the commissioning flow to
+    /// [`CommissioningFlow::Standard`] and the discovery capabilities to
+    /// [`DiscoveryCapabilities::ON_NETWORK`].
+    #[must_use]
+    #[inline]
+    pub const fn new(discriminator: u16, passcode: u32) -> Self {
+        Self {
+            vendor_id: 0,
+            product_id: 0,
+            commissioning_flow: CommissioningFlow::Standard,
+            discovery_capabilities: DiscoveryCapabilities::ON_NETWORK,
+            discriminator,
+            passcode,
+        }
+    }
+
+    /// Sets the vendor ID.
+    #[must_use]
+    #[inline]
+    pub const fn vendor_id(mut self, vendor_id: u16) -> Self {
+        self.vendor_id = vendor_id;
+        self
+    }
+
+    /// Sets the product ID.
+    #[must_use]
+    #[inline]
+    pub const fn product_id(mut self, product_id: u16) -> Self {
+        self.product_id = product_id;
+        self
+    }
+
+    /// Sets the commissioning flow.
+    #[must_use]
+    #[inline]
+    pub const fn commissioning_flow(mut self, commissioning_flow: CommissioningFlow) -> Self {
+        self.commissioning_flow = commissioning_flow;
+        self
+    }
+
+    /// Sets the discovery capabilities.
+    #[must_use]
+    #[inline]
+    pub const fn discovery_capabilities(
+        mut self,
+        discovery_capabilities: DiscoveryCapabilities,
+    ) -> Self {
+        self.discovery_capabilities = discovery_capabilities;
+        self
+    }
+
+    /// Packs the payload into 88 bits, least significant bit first.
+    fn pack(&self) -> [u8; 11] {
+        let fields = [
+            // Version.
+            (3, 0),
+            (16, u32::from(self.vendor_id)),
+            (16, u32::from(self.product_id)),
+            (2, self.commissioning_flow as u32),
+            (8, u32::from(self.discovery_capabilities.0)),
+            (12, u32::from(self.discriminator)),
+            (27, self.passcode),
+            // Padding.
+            (4, 0),
+        ];
+        let mut packed = [0; 11];
+        let mut offset = 0;
+        for (n, value) in fields {
+            for i in 0..n {
+                if value & (1 << i) != 0 {
+                    packed[offset / 8] |= 1 << (offset % 8);
+                }
+                offset += 1;
+            }
+        }
+        packed
+    }
+
+    /// Builds the `MT:` onboarding payload string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the discriminator does not fit in 12 bits, or the
+    /// passcode is outside of 1 to 99,999,998 or is one of the trivial
+    /// passcodes forbidden by the Matter specification (e.g. `12345678`).
+    pub fn build(&self) -> PayloadResult<String> {
+        const FORBIDDEN_PASSCODES: [u32; 12] = [
+            0, 11_111_111, 22_222_222, 33_333_333, 44_444_444, 55_555_555, 66_666_666, 77_777_777,
+            88_888_888, 99_999_999, 12_345_678, 87_654_321,
+        ];
+
+        if self.discriminator > 0xfff {
+            return Err(PayloadError::OutOfRange("discriminator"));
+        }
+        if self.passcode > 99_999_999 {
+            return Err(PayloadError::OutOfRange("passcode"));
+        }
+        if FORBIDDEN_PASSCODES.contains(&self.passcode) {
+            return Err(PayloadError::Forbidden("passcode"));
+        }
+
+        let mut payload = String::with_capacity(22);
+        payload.push_str("MT:");
+        base38_encode(&self.pack(), &mut payload);
+        Ok(payload)
+    }
+}
+
+/// Encodes `data` with the Base38 alphabet used by Matter.
+///
+/// Every 3 bytes are encoded as 5 characters, with a trailing chunk of 2 or 1
+/// bytes encoded as 4 or 2 characters respectively.
+fn base38_encode(data: &[u8], output: &mut String) {
+    const ALPHABET: &[u8; 38] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ-.";
+
+    for chunk in data.chunks(3) {
+        let mut value = chunk
+            .iter()
+            .rev()
+            .fold(0_u32, |acc, b| (acc << 8) | u32::from(*b));
+        let chars_count = match chunk.len() {
+            3 => 5,
+            2 => 4,
+            _ => 2,
+        };
+        for _ in 0..chars_count {
+            output.push(char::from(ALPHABET[(value % 38).as_usize()]));
+            value /= 38;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base38_encode() {
+        let mut output = String::new();
+        base38_encode(b"\x00\x01\x02\xff", &mut output);
+        assert_eq!(output, "0-E20R6");
+    }
+
+    #[test]
+    fn test_default_payload() {
+        let payload = MatterPayload::new(128, 2048)
+            .vendor_id(12)
+            .product_id(1)
+            .discovery_capabilities(DiscoveryCapabilities::SOFT_AP)
+            .build();
+        assert_eq!(payload.as_deref(), Ok("MT:M5L90MP500K64J00000"));
+    }
+
+    #[test]
+    fn test_test_device_payload() {
+        let payload = MatterPayload::new(3840, 20_202_021)
+            .vendor_id(0xfff1)
+            .product_id(0x8000)
+            .discovery_capabilities(DiscoveryCapabilities::BLE)
+            .build();
+        assert_eq!(payload.as_deref(), Ok("MT:Y.K9042C00KA0648G00"));
+    }
+
+    #[test]
+    fn test_invalid_fields() {
+        assert_eq!(
+            MatterPayload::new(0x1000, 20_202_021).build(),
+            Err(PayloadError::OutOfRange("discriminator"))
+        );
+        assert_eq!(
+            MatterPayload::new(3840, 100_000_000).build(),
+            Err(PayloadError::OutOfRange("passcode"))
+        );
+        assert_eq!(
+            MatterPayload::new(3840, 12_345_678).build(),
+            Err(PayloadError::Forbidden("passcode"))
+        );
+    }
+}
@@ -0,0 +1,211 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! [MECARD] contact payload support.
+//!
+//! MECARD is a compact alternative to vCard which was introduced by NTT
+//! DOCOMO. Special characters in the fields are escaped with a backslash.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, payload::mecard::MeCard};
+//!
+//! let payload = MeCard::new("Doe", "John")
+//!     .phone("+1-555-0100")
+//!     .email("john@example.com")
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(
+//!     payload,
+//!     r"MECARD:N:Doe,John;TEL:+1-555-0100;EMAIL:john@example.com;;"
+//! );
+//!
+//! let code = QrCode::new(payload).unwrap();
+//! ```
+//!
+//! [MECARD]: https://github.com/zxing/zxing/wiki/Barcode-Contents#contact-information
+
+use alloc::string::String;
+
+use crate::payload::{PayloadError, PayloadResult, escape_with_backslash};
+
+/// The characters which are escaped with a backslash.
+const SPECIAL_CHARS: [char; 4] = [';', ',', ':', '"'];
+
+/// A builder of the MECARD contact payload.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MeCard<'a> {
+    family_name: &'a str,
+    given_name: &'a str,
+    reading: Option<&'a str>,
+    phone: Option<&'a str>,
+    email: Option<&'a str>,
+    url: Option<&'a str>,
+    address: Option<&'a str>,
+    birthday: Option<&'a str>,
+    note: Option<&'a str>,
+}
+
+impl<'a> MeCard<'a> {
+    /// Creates a new contact with the given name. The given name may be
+    /// empty.
+    #[must_use]
+    #[inline]
+    pub const fn new(family_name: &'a str, given_name: &'a str) -> Self {
+        Self {
+            family_name,
+            given_name,
+            reading: None,
+            phone: None,
+            email: None,
+            url: None,
+            address: None,
+            birthday: None,
+            note: None,
+        }
+    }
+
+    /// Sets the phonetic reading of the name.
+    #[must_use]
+    #[inline]
+    pub const fn reading(mut self, reading: &'a str) -> Self {
+        self.reading = Some(reading);
+        self
+    }
+
+    /// Sets the phone number.
+    #[must_use]
+    #[inline]
+    pub const fn phone(mut self, phone: &'a str) -> Self {
+        self.phone = Some(phone);
+        self
+    }
+
+    /// Sets the email address.
+    #[must_use]
+    #[inline]
+    pub const fn email(mut self, email: &'a str) -> Self {
+        self.email = Some(email);
+        self
+    }
+
+    /// Sets the URL.
+    #[must_use]
+    #[inline]
+    pub const fn url(mut self, url: &'a str) -> Self {
+        self.url = Some(url);
+        self
+    }
+
+    /// Sets the postal address.
+    #[must_use]
+    #[inline]
+    pub const fn address(mut self, address: &'a str) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    /// Sets the birthday, in the `YYYYMMDD` format.
+    #[must_use]
+    #[inline]
+    pub const fn birthday(mut self, birthday: &'a str) -> Self {
+        self.birthday = Some(birthday);
+        self
+    }
+
+    /// Sets the note.
+    #[must_use]
+    #[inline]
+    pub const fn note(mut self, note: &'a str) -> Self {
+        self.note = Some(note);
+        self
+    }
+
+    /// Builds the `MECARD:` payload string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the family name is empty, or if the birthday is not
+    /// 8 digits.
+    pub fn build(&self) -> PayloadResult<String> {
+        if self.family_name.is_empty() {
+            return Err(PayloadError::Missing("family name"));
+        }
+        let is_date = |s: &str| s.len() == 8 && s.bytes().all(|b| b.is_ascii_digit());
+        if self.birthday.is_some_and(|birthday| !is_date(birthday)) {
+            return Err(PayloadError::InvalidCharacter("birthday"));
+        }
+
+        let mut payload = String::from("MECARD:N:");
+        escape_with_backslash(self.family_name, &SPECIAL_CHARS, &mut payload);
+        if !self.given_name.is_empty() {
+            payload.push(',');
+            escape_with_backslash(self.given_name, &SPECIAL_CHARS, &mut payload);
+        }
+        let fields = [
+            ("SOUND", self.reading),
+            ("TEL", self.phone),
+            ("EMAIL", self.email),
+            ("URL", self.url),
+            ("ADR", self.address),
+            ("BDAY", self.birthday),
+            ("NOTE", self.note),
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value {
+                payload.push(';');
+                payload.push_str(name);
+                payload.push(':');
+                escape_with_backslash(value, &SPECIAL_CHARS, &mut payload);
+            }
+        }
+        payload.push_str(";;");
+        Ok(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_fields() {
+        let payload = MeCard::new("Yamada", "Taro")
+            .reading("yamada,taro")
+            .phone("03-1234-5678")
+            .email("taro@example.jp")
+            .url("https://example.jp/")
+            .address("1-2-3 Chiyoda; Tokyo")
+            .birthday("19800123")
+            .note(r"C:\notes")
+            .build();
+        assert_eq!(
+            payload.as_deref(),
+            Ok(concat!(
+                r"MECARD:N:Yamada,Taro;SOUND:yamada\,taro;TEL:03-1234-5678;",
+                r"EMAIL:taro@example.jp;URL:https\://example.jp/;",
+                r"ADR:1-2-3 Chiyoda\; Tokyo;BDAY:19800123;NOTE:C\:\\notes;;"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_family_name_only() {
+        let payload = MeCard::new("ACME", "").build();
+        assert_eq!(payload.as_deref(), Ok("MECARD:N:ACME;;"));
+    }
+
+    #[test]
+    fn test_invalid_fields() {
+        assert_eq!(
+            MeCard::new("", "John").build(),
+            Err(PayloadError::Missing("family name"))
+        );
+        assert_eq!(
+            MeCard::new("Doe", "John").birthday("1980-01-23").build(),
+            Err(PayloadError::InvalidCharacter("birthday"))
+        );
+    }
+}
@@ -0,0 +1,214 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! [SMART Health Card] (`shc:/`) payload support.
+//!
+//! A SMART Health Card stores a compact JWS. Each character of the JWS is
+//! converted to a pair of digits so that the bulk of the payload is encoded in
+//! [`Mode::Numeric`](crate::types::Mode::Numeric). Long JWS strings are split
+//! into several chunks, each of which is stored in its own QR code. The chunks
+//! carry their own `shc:/<index>/<total>/` header instead of a structured
+//! append header, as the specification requires.
+//!
+//! The specification requires the [`EcLevel::L`](crate::EcLevel::L) error
+//! correction level.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{EcLevel, QrCode, payload::shc};
+//!
+//! let chunks = shc::encode("eyJ6aXAiOiJERUYifQ.e30.c2ln").unwrap();
+//! assert_eq!(chunks.len(), 1);
+//! assert!(chunks[0].starts_with("shc:/5676"));
+//!
+//! let codes = chunks
+//!     .iter()
+//!     .map(|chunk| QrCode::with_error_correction_level(chunk, EcLevel::L))
+//!     .collect::<Result<Vec<_>, _>>()
+//!     .unwrap();
+//! ```
+//!
+//! [SMART Health Card]: https://spec.smarthealth.cards/
+
+use alloc::{format, string::String, vec, vec::Vec};
+use core::cmp;
+
+use crate::payload::{PayloadError, PayloadResult};
+#[cfg(feature = "std")]
+use crate::{QrCode, batch::BatchEncoder};
+
+/// The maximum length of a JWS which can be stored in a single QR code.
+pub const MAX_SINGLE_JWS_LEN: usize = 1195;
+
+/// The maximum length of each chunk of a JWS split across several QR codes.
+pub const MAX_CHUNK_JWS_LEN: usize = 1191;
+
+/// Converts a JWS into the numeric form used by SMART Health Cards.
+///
+/// Each character is replaced by its code point minus 45, written as two
+/// decimal digits.
+///
+/// # Errors
+///
+/// Returns [`Err`] if the JWS contains a character outside of `-` to `z`,
+/// which cannot appear in a compact JWS.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::payload::shc;
+/// #
+/// assert_eq!(shc::numeric_encode("ey.J"), Ok("56760129".into()));
+/// ```
+pub fn numeric_encode(jws: &str) -> PayloadResult<String> {
+    let mut digits = String::with_capacity(jws.len() * 2);
+    for b in jws.bytes() {
+        if !(b'-'..=b'z').contains(&b) {
+            return Err(PayloadError::InvalidCharacter("JWS"));
+        }
+        let value = b - b'-';
+        digits.push(char::from(b'0' + value / 10));
+        digits.push(char::from(b'0' + value % 10));
+    }
+    Ok(digits)
+}
+
+/// Builds the `shc:/` payloads for a JWS, splitting it into chunks of equal
+/// size when it is longer than [`MAX_SINGLE_JWS_LEN`].
+///
+/// A single payload has the form `shc:/<digits>`, while chunked payloads have
+/// the form `shc:/<index>/<total>/<digits>` where `index` starts at 1.
+///
+/// # Errors
+///
+/// Returns [`Err`] if the JWS contains a character which cannot appear in a
+/// compact JWS.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::payload::shc;
+/// #
+/// let jws = "a".repeat(2000);
+/// let chunks = shc::encode(&jws).unwrap();
+/// assert_eq!(chunks.len(), 2);
+/// assert!(chunks[0].starts_with("shc:/1/2/"));
+/// assert!(chunks[1].starts_with("shc:/2/2/"));
+/// ```
+pub fn encode(jws: &str) -> PayloadResult<Vec<String>> {
+    let digits = numeric_encode(jws)?;
+    if jws.len() <= MAX_SINGLE_JWS_LEN {
+        return Ok(vec![format!("shc:/{digits}")]);
+    }
+
+    let chunks_count = jws.len().div_ceil(MAX_CHUNK_JWS_LEN);
+    let chunk_digits = jws.len().div_ceil(chunks_count) * 2;
+    let chunks = (0..chunks_count)
+        .map(|i| {
+            let begin = i * chunk_digits;
+            let end = cmp::min(begin + chunk_digits, digits.len());
+            format!("shc:/{}/{chunks_count}/{}", i + 1, &digits[begin..end])
+        })
+        .collect();
+    Ok(chunks)
+}
+
+/// Builds the `shc:/` payloads for a JWS like [`encode`], and encodes each of
+/// them into a QR code with the [`EcLevel::L`](crate::EcLevel::L) error
+/// correction level.
+///
+/// The QR codes are encoded with a [`BatchEncoder`], which reuses its memory
+/// between the chunks.
+///
+/// # Errors
+///
+/// Returns [`Err`] if the JWS contains a character which cannot appear in a
+/// compact JWS.
+///
+/// # Panics
+///
+/// Panics if a chunk does not fit in a QR code, which cannot happen since the
+/// chunks are at most [`MAX_SINGLE_JWS_LEN`] characters long.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{EcLevel, payload::shc};
+/// #
+/// let jws = "a".repeat(2000);
+/// let codes = shc::encode_qr_codes(&jws).unwrap();
+/// assert_eq!(codes.len(), 2);
+/// assert!(
+///     codes
+///         .iter()
+///         .all(|code| code.error_correction_level() == EcLevel::L)
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_qr_codes(jws: &str) -> PayloadResult<Vec<QrCode>> {
+    let chunks = encode(jws)?;
+    let mut encoder = BatchEncoder::new();
+    encoder.error_correction_level(crate::EcLevel::L);
+    let codes = chunks
+        .into_iter()
+        .map(|chunk| {
+            encoder
+                .encode(chunk)
+                .expect("a chunk should fit in a QR code")
+        })
+        .collect();
+    Ok(codes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numeric_encode() {
+        assert_eq!(numeric_encode("-z"), Ok("0077".into()));
+        assert_eq!(
+            numeric_encode("a b"),
+            Err(PayloadError::InvalidCharacter("JWS"))
+        );
+    }
+
+    #[test]
+    fn test_single_chunk() {
+        let jws = "a".repeat(MAX_SINGLE_JWS_LEN);
+        let chunks = encode(&jws).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].len(), 5 + MAX_SINGLE_JWS_LEN * 2);
+    }
+
+    #[test]
+    fn test_equal_chunks() {
+        let jws = "a".repeat(MAX_CHUNK_JWS_LEN * 2 + 1);
+        let chunks = encode(&jws).unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[2].starts_with("shc:/3/3/"));
+        let sizes = chunks
+            .iter()
+            .map(|c| c.rsplit('/').next().unwrap().len() / 2)
+            .collect::<Vec<_>>();
+        assert_eq!(sizes, [795, 795, 793]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_encode_qr_codes() {
+        use crate::types::Version;
+
+        let jws = "a".repeat(MAX_SINGLE_JWS_LEN);
+        let codes = encode_qr_codes(&jws).unwrap();
+        assert_eq!(codes.len(), 1);
+        assert_eq!(codes[0].version(), Version::Normal(22));
+
+        let jws = "a".repeat(MAX_CHUNK_JWS_LEN * 2 + 1);
+        let codes = encode_qr_codes(&jws).unwrap();
+        assert_eq!(codes.len(), 3);
+        assert!(encode_qr_codes("a b").is_err());
+    }
+}
@@ -0,0 +1,103 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! SMS payload support.
+//!
+//! The `SMSTO:` payload was introduced by [ZXing] and is understood by most
+//! camera apps. The message is not escaped, since everything after the second
+//! colon belongs to it.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, payload::sms::Sms};
+//!
+//! let payload = Sms::new("+15550100").message("STOP").build().unwrap();
+//! assert_eq!(payload, "SMSTO:+15550100:STOP");
+//!
+//! let code = QrCode::new(payload).unwrap();
+//! ```
+//!
+//! [ZXing]: https://github.com/zxing/zxing/wiki/Barcode-Contents#sms
+
+use alloc::string::String;
+
+use crate::payload::{PayloadError, PayloadResult};
+
+/// A builder of the SMS payload.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Sms<'a> {
+    number: &'a str,
+    message: Option<&'a str>,
+}
+
+impl<'a> Sms<'a> {
+    /// Creates a new SMS to the given phone number.
+    #[must_use]
+    #[inline]
+    pub const fn new(number: &'a str) -> Self {
+        Self {
+            number,
+            message: None,
+        }
+    }
+
+    /// Sets the message.
+    #[must_use]
+    #[inline]
+    pub const fn message(mut self, message: &'a str) -> Self {
+        self.message = Some(message);
+        self
+    }
+
+    /// Builds the `SMSTO:` payload string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the phone number is empty, or contains a character
+    /// other than digits, `+`, `-`, `.`, `(`, `)` and spaces.
+    pub fn build(&self) -> PayloadResult<String> {
+        if self.number.is_empty() {
+            return Err(PayloadError::Missing("number"));
+        }
+        let is_number_char = |b: u8| b.is_ascii_digit() || b" +-.()".contains(&b);
+        if !self.number.bytes().all(is_number_char) {
+            return Err(PayloadError::InvalidCharacter("number"));
+        }
+
+        let mut payload = String::from("SMSTO:");
+        payload.push_str(self.number);
+        if let Some(message) = self.message {
+            payload.push(':');
+            payload.push_str(message);
+        }
+        Ok(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_only() {
+        let payload = Sms::new("(555) 010-0000").build();
+        assert_eq!(payload.as_deref(), Ok("SMSTO:(555) 010-0000"));
+    }
+
+    #[test]
+    fn test_message_with_colon() {
+        let payload = Sms::new("12345").message("CODE: 1234").build();
+        assert_eq!(payload.as_deref(), Ok("SMSTO:12345:CODE: 1234"));
+    }
+
+    #[test]
+    fn test_invalid_number() {
+        assert_eq!(Sms::new("").build(), Err(PayloadError::Missing("number")));
+        assert_eq!(
+            Sms::new("555:0100").build(),
+            Err(PayloadError::InvalidCharacter("number"))
+        );
+    }
+}
@@ -0,0 +1,114 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! URL payload support.
+//!
+//! Scanners only open a URL which is a valid URI, so spaces, non-ASCII
+//! characters and other characters which are not allowed in a [URI] are
+//! percent-encoded as UTF-8. Characters with a meaning in a URI, such as `/`,
+//! `?` and `#`, and existing percent-encoded octets are kept.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, payload::url};
+//!
+//! let payload = url::encode("https://example.com/café menu?q=a b").unwrap();
+//! assert_eq!(payload, "https://example.com/caf%C3%A9%20menu?q=a%20b");
+//!
+//! let code = QrCode::new(payload).unwrap();
+//! ```
+//!
+//! [URI]: https://datatracker.ietf.org/doc/html/rfc3986
+
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::payload::{PayloadError, PayloadResult, is_unreserved};
+
+/// Percent-encodes the characters of `url` which are not allowed in a URI.
+///
+/// # Errors
+///
+/// Returns [`Err`] if `url` does not start with a scheme such as `https:`.
+pub fn encode(url: &str) -> PayloadResult<String> {
+    let scheme = url.split_once(':').map_or("", |(scheme, _)| scheme);
+    if scheme.is_empty() {
+        return Err(PayloadError::Missing("scheme"));
+    }
+    let is_scheme_char = |b: u8| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.');
+    if !scheme.as_bytes()[0].is_ascii_alphabetic() || !scheme.bytes().all(is_scheme_char) {
+        return Err(PayloadError::InvalidCharacter("scheme"));
+    }
+
+    let bytes = url.as_bytes();
+    let mut payload = String::with_capacity(url.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let is_escape = bytes[i] == b'%'
+            && bytes.get(i + 1).is_some_and(u8::is_ascii_hexdigit)
+            && bytes.get(i + 2).is_some_and(u8::is_ascii_hexdigit);
+        let len = if is_escape { 3 } else { 1 };
+        if is_escape || is_uri_char(bytes[i]) {
+            payload.push_str(&url[i..i + len]);
+        } else {
+            write!(payload, "%{:02X}", bytes[i]).expect("writing into a string should not fail");
+        }
+        i += len;
+    }
+    Ok(payload)
+}
+
+/// Checks whether `b` is an unreserved or reserved character of RFC 3986.
+const fn is_uri_char(b: u8) -> bool {
+    is_unreserved(b)
+        || matches!(
+            b,
+            b':' | b'/'
+                | b'?'
+                | b'#'
+                | b'['
+                | b']'
+                | b'@'
+                | b'!'
+                | b'$'
+                | b'&'
+                | b'\''
+                | b'('
+                | b')'
+                | b'*'
+                | b'+'
+                | b','
+                | b';'
+                | b'='
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unchanged() {
+        let url = "https://user@example.com:8080/a/b;c?d=e&f=g+h#frag";
+        assert_eq!(encode(url).as_deref(), Ok(url));
+    }
+
+    #[test]
+    fn test_percent() {
+        assert_eq!(
+            encode("https://example.com/100%25/50%").as_deref(),
+            Ok("https://example.com/100%25/50%25")
+        );
+    }
+
+    #[test]
+    fn test_invalid_scheme() {
+        assert_eq!(encode("example.com"), Err(PayloadError::Missing("scheme")));
+        assert_eq!(
+            encode("1http://example.com"),
+            Err(PayloadError::InvalidCharacter("scheme"))
+        );
+    }
+}
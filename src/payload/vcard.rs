@@ -0,0 +1,240 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! [vCard] 3.0 contact payload support.
+//!
+//! Text values are escaped as required by RFC 2426: backslashes, commas and
+//! semicolons are preceded by a backslash, and line breaks are written as
+//! `\n`. Lines are separated by CRLF.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, payload::vcard::VCard};
+//!
+//! let payload = VCard::new("Doe", "John")
+//!     .organization("Example, Inc.")
+//!     .phone("+1-555-0100")
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(
+//!     payload,
+//!     concat!(
+//!         "BEGIN:VCARD\r\n",
+//!         "VERSION:3.0\r\n",
+//!         "N:Doe;John;;;\r\n",
+//!         "FN:John Doe\r\n",
+//!         "ORG:Example\\, Inc.\r\n",
+//!         "TEL:+1-555-0100\r\n",
+//!         "END:VCARD"
+//!     )
+//! );
+//!
+//! let code = QrCode::new(payload).unwrap();
+//! ```
+//!
+//! [vCard]: https://datatracker.ietf.org/doc/html/rfc2426
+
+use alloc::string::String;
+
+use crate::payload::{PayloadError, PayloadResult};
+
+/// A builder of the vCard contact payload.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct VCard<'a> {
+    family_name: &'a str,
+    given_name: &'a str,
+    organization: Option<&'a str>,
+    title: Option<&'a str>,
+    phone: Option<&'a str>,
+    email: Option<&'a str>,
+    url: Option<&'a str>,
+    address: Option<&'a str>,
+    note: Option<&'a str>,
+}
+
+impl<'a> VCard<'a> {
+    /// Creates a new contact with the given name. Either name may be empty,
+    /// but not both.
+    #[must_use]
+    #[inline]
+    pub const fn new(family_name: &'a str, given_name: &'a str) -> Self {
+        Self {
+            family_name,
+            given_name,
+            organization: None,
+            title: None,
+            phone: None,
+            email: None,
+            url: None,
+            address: None,
+            note: None,
+        }
+    }
+
+    /// Sets the organization.
+    #[must_use]
+    #[inline]
+    pub const fn organization(mut self, organization: &'a str) -> Self {
+        self.organization = Some(organization);
+        self
+    }
+
+    /// Sets the job title.
+    #[must_use]
+    #[inline]
+    pub const fn title(mut self, title: &'a str) -> Self {
+        self.title = Some(title);
+        self
+    }
+
+    /// Sets the phone number.
+    #[must_use]
+    #[inline]
+    pub const fn phone(mut self, phone: &'a str) -> Self {
+        self.phone = Some(phone);
+        self
+    }
+
+    /// Sets the email address.
+    #[must_use]
+    #[inline]
+    pub const fn email(mut self, email: &'a str) -> Self {
+        self.email = Some(email);
+        self
+    }
+
+    /// Sets the URL.
+    #[must_use]
+    #[inline]
+    pub const fn url(mut self, url: &'a str) -> Self {
+        self.url = Some(url);
+        self
+    }
+
+    /// Sets the postal address, which is written as the street address.
+    #[must_use]
+    #[inline]
+    pub const fn address(mut self, address: &'a str) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    /// Sets the note.
+    #[must_use]
+    #[inline]
+    pub const fn note(mut self, note: &'a str) -> Self {
+        self.note = Some(note);
+        self
+    }
+
+    /// Builds the vCard payload string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if both names are empty.
+    pub fn build(&self) -> PayloadResult<String> {
+        if self.family_name.is_empty() && self.given_name.is_empty() {
+            return Err(PayloadError::Missing("name"));
+        }
+
+        let mut payload = String::from("BEGIN:VCARD\r\nVERSION:3.0\r\nN:");
+        escape(self.family_name, &mut payload);
+        payload.push(';');
+        escape(self.given_name, &mut payload);
+        payload.push_str(";;;\r\nFN:");
+        escape(self.given_name, &mut payload);
+        if !self.family_name.is_empty() && !self.given_name.is_empty() {
+            payload.push(' ');
+        }
+        escape(self.family_name, &mut payload);
+        // The postal address is written as the third of its 7 components.
+        let fields = [
+            ("ORG:", self.organization, ""),
+            ("TITLE:", self.title, ""),
+            ("TEL:", self.phone, ""),
+            ("EMAIL:", self.email, ""),
+            ("URL:", self.url, ""),
+            ("ADR:;;", self.address, ";;;;"),
+            ("NOTE:", self.note, ""),
+        ];
+        for (prefix, value, suffix) in fields {
+            if let Some(value) = value {
+                payload.push_str("\r\n");
+                payload.push_str(prefix);
+                escape(value, &mut payload);
+                payload.push_str(suffix);
+            }
+        }
+        payload.push_str("\r\nEND:VCARD");
+        Ok(payload)
+    }
+}
+
+/// Appends a text value, escaped as required by RFC 2426.
+fn escape(value: &str, output: &mut String) {
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' | ',' | ';' => {
+                output.push('\\');
+                output.push(c);
+            }
+            '\r' => {
+                chars.next_if_eq(&'\n');
+                output.push_str("\\n");
+            }
+            '\n' => output.push_str("\\n"),
+            c => output.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_fields() {
+        let payload = VCard::new("Yamada", "Taro")
+            .organization("Example; Ltd.")
+            .title("Engineer")
+            .phone("+81-3-1234-5678")
+            .email("taro@example.jp")
+            .url("https://example.jp/")
+            .address("1-2-3 Chiyoda")
+            .note("Line 1\r\nLine 2\nC:\\")
+            .build();
+        assert_eq!(
+            payload.as_deref(),
+            Ok(concat!(
+                "BEGIN:VCARD\r\n",
+                "VERSION:3.0\r\n",
+                "N:Yamada;Taro;;;\r\n",
+                "FN:Taro Yamada\r\n",
+                "ORG:Example\\; Ltd.\r\n",
+                "TITLE:Engineer\r\n",
+                "TEL:+81-3-1234-5678\r\n",
+                "EMAIL:taro@example.jp\r\n",
+                "URL:https://example.jp/\r\n",
+                "ADR:;;1-2-3 Chiyoda;;;;\r\n",
+                "NOTE:Line 1\\nLine 2\\nC:\\\\\r\n",
+                "END:VCARD"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_single_name() {
+        let payload = VCard::new("", "Madonna").build();
+        assert_eq!(
+            payload.as_deref(),
+            Ok("BEGIN:VCARD\r\nVERSION:3.0\r\nN:;Madonna;;;\r\nFN:Madonna\r\nEND:VCARD")
+        );
+        assert_eq!(
+            VCard::new("", "").build(),
+            Err(PayloadError::Missing("name"))
+        );
+    }
+}
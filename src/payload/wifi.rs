@@ -0,0 +1,213 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Wi-Fi network configuration payload support.
+//!
+//! The `WIFI:` payload was introduced by [ZXing] and is understood by the
+//! camera apps of Android and iOS. Special characters in the SSID and the
+//! password are escaped with a backslash, and values which could be mistaken
+//! for hexadecimal are quoted.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{
+//!     QrCode,
+//!     payload::wifi::{Security, Wifi},
+//! };
+//!
+//! let payload = Wifi::new("My Network")
+//!     .password("p@ss;word")
+//!     .security(Security::Wpa)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(payload, r"WIFI:T:WPA;S:My Network;P:p@ss\;word;;");
+//!
+//! let code = QrCode::new(payload).unwrap();
+//! ```
+//!
+//! [ZXing]: https://github.com/zxing/zxing/wiki/Barcode-Contents#wi-fi-network-config-android-ios-11
+
+use alloc::string::String;
+
+use crate::payload::{PayloadError, PayloadResult, escape_with_backslash};
+
+/// The authentication type of a Wi-Fi network.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Security {
+    /// WPA, WPA2 or WPA3 personal.
+    #[default]
+    Wpa,
+
+    /// WEP.
+    Wep,
+
+    /// An open network without a password.
+    Open,
+}
+
+impl Security {
+    /// Returns the value of the `T` field.
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Wpa => "WPA",
+            Self::Wep => "WEP",
+            Self::Open => "nopass",
+        }
+    }
+}
+
+/// A builder of the Wi-Fi network configuration payload.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Wifi<'a> {
+    ssid: &'a str,
+    password: Option<&'a str>,
+    security: Security,
+    hidden: bool,
+}
+
+impl<'a> Wifi<'a> {
+    /// Creates a new payload for the network with the given SSID.
+    ///
+    /// The security defaults to [`Security::Wpa`], and the network is not
+    /// hidden.
+    #[must_use]
+    #[inline]
+    pub const fn new(ssid: &'a str) -> Self {
+        Self {
+            ssid,
+            password: None,
+            security: Security::Wpa,
+            hidden: false,
+        }
+    }
+
+    /// Sets the password.
+    #[must_use]
+    #[inline]
+    pub const fn password(mut self, password: &'a str) -> Self {
+        self.password = Some(password);
+        self
+    }
+
+    /// Sets the authentication type.
+    #[must_use]
+    #[inline]
+    pub const fn security(mut self, security: Security) -> Self {
+        self.security = security;
+        self
+    }
+
+    /// Sets whether the network does not broadcast its SSID.
+    #[must_use]
+    #[inline]
+    pub const fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Builds the `WIFI:` payload string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the SSID is empty or longer than 32 bytes, if a
+    /// WPA or WEP network has no password, or if an open network has one.
+    pub fn build(&self) -> PayloadResult<String> {
+        if self.ssid.is_empty() {
+            return Err(PayloadError::Missing("SSID"));
+        }
+        if self.ssid.len() > 32 {
+            return Err(PayloadError::OutOfRange("SSID"));
+        }
+        match (self.security, self.password) {
+            (Security::Open, Some(_)) => return Err(PayloadError::Forbidden("password")),
+            (Security::Wpa | Security::Wep, None | Some("")) => {
+                return Err(PayloadError::Missing("password"));
+            }
+            _ => {}
+        }
+
+        let mut payload = String::from("WIFI:T:");
+        payload.push_str(self.security.as_str());
+        payload.push_str(";S:");
+        push_value(self.ssid, &mut payload);
+        if let Some(password) = self.password {
+            payload.push_str(";P:");
+            push_value(password, &mut payload);
+        }
+        if self.hidden {
+            payload.push_str(";H:true");
+        }
+        payload.push_str(";;");
+        Ok(payload)
+    }
+}
+
+/// Appends an escaped value, quoted if it could be read as hexadecimal.
+fn push_value(value: &str, output: &mut String) {
+    let is_hex = value.len() % 2 == 0 && value.bytes().all(|b| b.is_ascii_hexdigit());
+    if is_hex {
+        output.push('"');
+    }
+    escape_with_backslash(value, &[';', ',', ':', '"'], output);
+    if is_hex {
+        output.push('"');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        let payload = Wifi::new(r#"a"b\c,d:e"#).password("x;y").build();
+        assert_eq!(
+            payload.as_deref(),
+            Ok(r#"WIFI:T:WPA;S:a\"b\\c\,d\:e;P:x\;y;;"#)
+        );
+    }
+
+    #[test]
+    fn test_hex_is_quoted() {
+        let payload = Wifi::new("cafe")
+            .password("0123456789")
+            .security(Security::Wep)
+            .hidden(true)
+            .build();
+        assert_eq!(
+            payload.as_deref(),
+            Ok(r#"WIFI:T:WEP;S:"cafe";P:"0123456789";H:true;;"#)
+        );
+    }
+
+    #[test]
+    fn test_open() {
+        let payload = Wifi::new("Guest").security(Security::Open).build();
+        assert_eq!(payload.as_deref(), Ok("WIFI:T:nopass;S:Guest;;"));
+    }
+
+    #[test]
+    fn test_invalid_fields() {
+        assert_eq!(
+            Wifi::new("").password("password").build(),
+            Err(PayloadError::Missing("SSID"))
+        );
+        assert_eq!(
+            Wifi::new(&"a".repeat(33)).password("password").build(),
+            Err(PayloadError::OutOfRange("SSID"))
+        );
+        assert_eq!(
+            Wifi::new("Home").build(),
+            Err(PayloadError::Missing("password"))
+        );
+        assert_eq!(
+            Wifi::new("Guest")
+                .security(Security::Open)
+                .password("password")
+                .build(),
+            Err(PayloadError::Forbidden("password"))
+        );
+    }
+}
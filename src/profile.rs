@@ -0,0 +1,104 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Named encoding profiles.
+//!
+//! A [`Profile`] bundles the encoding settings which suit a typical use case,
+//! so that the symbol can be generated without choosing each setting
+//! individually. Use [`QrCode::with_profile`] to encode the data, and
+//! [`Renderer::profile`] to apply the quiet zone of the profile.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, profile::Profile};
+//!
+//! let profile = Profile::IndustrialDamageProne;
+//! let code = QrCode::with_profile(b"LOT 42", profile).unwrap();
+//! let image = code.render::<char>().profile(profile).build();
+//! ```
+//!
+//! [`QrCode::with_profile`]: crate::QrCode::with_profile
+//! [`Renderer::profile`]: crate::render::Renderer::profile
+
+use crate::types::{EcLevel, Version};
+
+/// A named set of encoding settings.
+///
+/// The mask pattern is always chosen by the lowest penalty score.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Profile {
+    /// For small printed labels, e.g. on packaging: medium error correction
+    /// against printing defects, up to version 10 so that the modules do not
+    /// get too small, and the standard quiet zone of 4 modules.
+    PrintSmall,
+
+    /// For displaying on a screen: low error correction since a screen is not
+    /// damaged, any version, and the standard quiet zone of 4 modules.
+    Screen,
+
+    /// For marking parts in factories and warehouses where the symbol gets
+    /// scratched or dirty: high error correction, up to version 25 so that
+    /// the symbol can be read from a distance, and a wider quiet zone of 6
+    /// modules.
+    IndustrialDamageProne,
+}
+
+impl Profile {
+    /// Returns the error correction level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{EcLevel, profile::Profile};
+    /// #
+    /// assert_eq!(Profile::Screen.error_correction_level(), EcLevel::L);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn error_correction_level(self) -> EcLevel {
+        match self {
+            Self::PrintSmall => EcLevel::M,
+            Self::Screen => EcLevel::L,
+            Self::IndustrialDamageProne => EcLevel::H,
+        }
+    }
+
+    /// Returns the largest version which can be used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{Version, profile::Profile};
+    /// #
+    /// assert_eq!(Profile::PrintSmall.max_version(), Version::Normal(10));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn max_version(self) -> Version {
+        match self {
+            Self::PrintSmall => Version::Normal(10),
+            Self::Screen => Version::Normal(40),
+            Self::IndustrialDamageProne => Version::Normal(25),
+        }
+    }
+
+    /// Returns the width of the quiet zone in modules.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::profile::Profile;
+    /// #
+    /// assert_eq!(Profile::IndustrialDamageProne.quiet_zone(), 6);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn quiet_zone(self) -> u32 {
+        match self {
+            Self::PrintSmall | Self::Screen => 4,
+            Self::IndustrialDamageProne => 6,
+        }
+    }
+}
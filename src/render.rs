@@ -9,20 +9,103 @@
 
 //! Render a QR code into image.
 
+#[cfg(feature = "animation")]
+pub mod animation;
+#[cfg(feature = "svg")]
+pub mod annotated;
+pub mod ansi;
+#[cfg(feature = "asy")]
+pub mod asy;
+pub mod bitmap;
+#[cfg(any(feature = "image", feature = "svg"))]
+pub mod debug;
+#[cfg(all(feature = "std", feature = "svg"))]
+pub mod dotted;
 #[cfg(feature = "eps")]
 pub mod eps;
+#[cfg(feature = "html")]
+pub mod html;
 #[cfg(feature = "image")]
 pub mod image;
+#[cfg(feature = "metapost")]
+pub mod metapost;
+pub mod pbm;
 #[cfg(feature = "pic")]
 pub mod pic;
+pub mod scanline;
+#[cfg(feature = "std")]
+pub mod scannability;
+#[cfg(any(feature = "image", feature = "svg"))]
+pub mod sheet;
 pub mod string;
 #[cfg(feature = "svg")]
+pub mod strip;
+pub mod style;
+#[cfg(feature = "svg")]
 pub mod svg;
+#[cfg(feature = "std")]
+pub mod terminal;
 pub mod unicode;
+pub mod yuv;
+
+use alloc::{string::String, vec::Vec};
+use core::{cmp, fmt};
+
+use crate::{
+    canvas::{Canvas as SymbolCanvas, ModuleKind},
+    cast::As,
+    profile::Profile,
+    types::{Color, EcLevel, Version},
+};
+
+/// The minimum size of a module in pixels recommended for scanning.
+pub const MIN_MODULE_SIZE: u32 = 4;
+
+/// Estimates the number of codewords damaged by covering the given number of
+/// modules.
+///
+/// Each codeword occupies a 2×4 block of modules, and a covered area is
+/// assumed to touch twice as many codewords as it fully contains.
+pub(crate) const fn estimate_damaged_codewords(modules: usize) -> usize {
+    modules.div_ceil(4)
+}
+
+/// Computes the luma of a color from its gamma-encoded RGB components, each
+/// ranging from 0.0 to 1.0.
+#[allow(clippy::suboptimal_flops)]
+pub(crate) fn luma(r: f64, g: f64, b: f64) -> f64 {
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+/// Computes the luma of a CSS color if it is in the `#rgb` or `#rrggbb` form.
+#[cfg(any(feature = "html", feature = "svg"))]
+pub(crate) fn css_luminance(color: &str) -> Option<f64> {
+    let digits = color
+        .strip_prefix('#')?
+        .chars()
+        .map(|c| c.to_digit(16))
+        .collect::<Option<Vec<_>>>()?;
+    let [r, g, b] = match *digits {
+        [r, g, b] => [r * 17, g * 17, b * 17],
+        [r1, r0, g1, g0, b1, b0] => [r1 * 16 + r0, g1 * 16 + g0, b1 * 16 + b0],
+        _ => return None,
+    }
+    .map(|c| f64::from(c) / 255.0);
+    Some(luma(r, g, b))
+}
 
-use core::cmp;
+/// A length formatted with at most 4 decimal places, without trailing zeros.
+#[cfg(any(feature = "pic", feature = "svg"))]
+pub(crate) struct Length(pub(crate) f64);
 
-use crate::{cast::As, types::Color};
+#[cfg(any(feature = "pic", feature = "svg"))]
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = alloc::format!("{:.4}", self.0);
+        let s = s.trim_end_matches('0').trim_end_matches('.');
+        f.write_str(s)
+    }
+}
 
 // Pixel trait
 
@@ -43,6 +126,15 @@ pub trait Pixel: Copy + Sized {
 
     /// Obtains the default pixel color when a module is dark or light.
     fn default_color(color: Color) -> Self;
+
+    /// Obtains the approximate luminance of the pixel, from 0.0 (black) to 1.0
+    /// (white), or [`None`] if it is unknown.
+    ///
+    /// This is used by [`Renderer::audit`] to detect inverted colors.
+    #[must_use]
+    fn luminance(self) -> Option<f64> {
+        None
+    }
 }
 
 /// Rendering canvas of a QR code image.
@@ -69,10 +161,456 @@ pub trait Canvas: Sized {
         }
     }
 
+    /// Draws a single pixel at the (x, y) coordinate in the given color
+    /// instead of the dark color, e.g. for [`Renderer::finder_dark_color`].
+    ///
+    /// The default implementation draws a dark pixel, so canvases which only
+    /// support two colors ignore `pixel`.
+    #[inline]
+    fn draw_pixel(&mut self, x: u32, y: u32, pixel: Self::Pixel) {
+        let _ = pixel;
+        self.draw_dark_pixel(x, y);
+    }
+
+    /// Draws a rectangle with dimensions `width`×`height` at the (`left`,
+    /// `top`) coordinate in the given color instead of the dark color.
+    ///
+    /// The default implementation draws a dark rectangle, so canvases which
+    /// only support two colors ignore `pixel`.
+    #[inline]
+    fn draw_rect(&mut self, left: u32, top: u32, width: u32, height: u32, pixel: Self::Pixel) {
+        let _ = pixel;
+        self.draw_dark_rect(left, top, width, height);
+    }
+
+    /// Applies the options set on the [`Renderer`] before anything is drawn.
+    ///
+    /// The default implementation ignores all options.
+    #[inline]
+    fn configure(&mut self, options: &CanvasOptions) {
+        let _ = options;
+    }
+
     /// Finalizes the canvas to a real image.
     fn into_image(self) -> Self::Image;
 }
 
+/// A canvas of a text image which can be written out without building the
+/// whole image in memory first.
+pub trait WriteCanvas: Canvas {
+    /// Finalizes the canvas and writes the image into `writer`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing into `writer` fails.
+    fn write_image<W: fmt::Write + ?Sized>(self, writer: &mut W) -> fmt::Result;
+}
+
+/// Finalizes a canvas which implements [`WriteCanvas`] to a [`String`].
+#[cfg(any(feature = "eps", feature = "pic", feature = "svg"))]
+pub(crate) fn write_image_to_string<C: WriteCanvas>(canvas: C) -> String {
+    let mut image = String::new();
+    canvas
+        .write_image(&mut image)
+        .expect("writing into a string should not fail");
+    image
+}
+
+// Canvas options
+
+/// The line ending used by text outputs.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum LineEnding {
+    /// Line feed (`\n`).
+    #[default]
+    Lf,
+
+    /// Carriage return followed by line feed (`\r\n`).
+    CrLf,
+}
+
+impl LineEnding {
+    /// Returns the characters of the line ending.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::render::LineEnding;
+    /// #
+    /// assert_eq!(LineEnding::Lf.as_str(), "\n");
+    /// assert_eq!(LineEnding::CrLf.as_str(), "\r\n");
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        }
+    }
+}
+
+/// How the background of vector outputs is painted.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Background {
+    /// The whole image is filled with the light color.
+    #[default]
+    Light,
+
+    /// The background is omitted, so only the dark modules are drawn. This is
+    /// useful when the code is layered over an existing design or printed on
+    /// pre-printed stock.
+    None,
+
+    /// The whole image is filled with opaque white, regardless of the light
+    /// color. This knocks out whatever is underneath the code.
+    White,
+}
+
+/// A unit of physical length, for the physical size of each module of SVG,
+/// Asymptote and MetaPost output.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Unit {
+    /// Millimeters.
+    Mm,
+
+    /// Centimeters.
+    Cm,
+
+    /// Inches.
+    In,
+
+    /// PostScript points, or 1/72 inches. This is `pt` in CSS and `bp` in
+    /// Asymptote and MetaPost.
+    Pt,
+
+    /// CSS pixels, or 1/96 inches.
+    Px,
+}
+
+impl Unit {
+    /// Returns the name of the unit in CSS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::render::Unit;
+    /// #
+    /// assert_eq!(Unit::Mm.as_css(), "mm");
+    /// assert_eq!(Unit::Pt.as_css(), "pt");
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn as_css(self) -> &'static str {
+        match self {
+            Self::Mm => "mm",
+            Self::Cm => "cm",
+            Self::In => "in",
+            Self::Pt => "pt",
+            Self::Px => "px",
+        }
+    }
+
+    /// Converts the length in this unit into a length in a unit of Asymptote
+    /// or MetaPost, which have no pixels. The inch is `inch` in Asymptote and
+    /// `in` in MetaPost.
+    #[cfg(any(feature = "asy", feature = "metapost"))]
+    pub(crate) fn to_tex_length(self, length: f64, inch: &'static str) -> (f64, &'static str) {
+        match self {
+            Self::Mm => (length, "mm"),
+            Self::Cm => (length, "cm"),
+            Self::In => (length, inch),
+            Self::Pt => (length, "bp"),
+            Self::Px => (length * 0.75, "bp"),
+        }
+    }
+}
+
+/// Named sets of render settings for common targets. See
+/// [`Renderer::preset`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Preset {
+    /// For printing: the standard quiet zone, modules twice as large as the
+    /// default, and an opaque white background in vector outputs so that the
+    /// code is knocked out of whatever is printed underneath.
+    Print,
+
+    /// For displaying on a screen: the standard quiet zone, the default module
+    /// size, and black on white.
+    Screen,
+
+    /// For printing on a terminal with a dark background: the standard quiet
+    /// zone, the default module size, and inverted colors, so that the bright
+    /// foreground of the terminal is used for the light modules.
+    TerminalDark,
+}
+
+/// The shape of each dark module in raster images.
+#[cfg_attr(feature = "image", doc = "See [`Renderer::module_shape`].")]
+#[derive(Clone, Copy, Debug, Default)]
+pub enum ModuleShape {
+    /// A rectangle filling the whole module.
+    #[default]
+    Square,
+
+    /// A circle, or an ellipse if the module is not square, touching the
+    /// sides of the module.
+    Circle,
+
+    /// A rectangle with rounded corners. The radius is a fraction of the
+    /// shorter side of the module, from 0.0 (square) to 0.5 (circle).
+    RoundedRect {
+        /// The radius of the corners.
+        radius: f64,
+    },
+
+    /// Calls the function with the coordinates of each pixel in the module
+    /// and the size of the module, and paints the pixel if it returns
+    /// [`true`].
+    Custom(fn(x: u32, y: u32, width: u32, height: u32) -> bool),
+}
+
+impl ModuleShape {
+    /// Returns whether the shape covers the pixel at (`x`, `y`) of a module
+    /// with dimensions `width`×`height`. A pixel is covered if its center is
+    /// inside the shape.
+    #[allow(clippy::suboptimal_flops)]
+    fn covers(self, x: u32, y: u32, width: u32, height: u32) -> bool {
+        let (cx, cy) = (f64::from(x) + 0.5, f64::from(y) + 0.5);
+        let (w, h) = (f64::from(width), f64::from(height));
+        match self {
+            Self::Square => true,
+            Self::Circle => {
+                let (dx, dy) = (2.0 * cx / w - 1.0, 2.0 * cy / h - 1.0);
+                dx * dx + dy * dy <= 1.0
+            }
+            Self::RoundedRect { radius } => {
+                let radius = radius.clamp(0.0, 0.5) * w.min(h);
+                let dx = (radius - cx).max(cx - (w - radius)).max(0.0);
+                let dy = (radius - cy).max(cy - (h - radius)).max(0.0);
+                dx * dx + dy * dy <= radius * radius
+            }
+            Self::Custom(covers) => covers(x, y, width, height),
+        }
+    }
+}
+
+/// The clockwise rotation of the symbol. See [`Renderer::rotate`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Rotation {
+    /// No rotation.
+    #[default]
+    Deg0,
+
+    /// A quarter turn clockwise.
+    Deg90,
+
+    /// A half turn.
+    Deg180,
+
+    /// Three quarter turns clockwise, i.e. a quarter turn counterclockwise.
+    Deg270,
+}
+
+/// Options passed from the [`Renderer`] to the [`Canvas`]. Each canvas only
+/// reads the options which apply to its output format.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct CanvasOptions {
+    /// The size of each module in pixels. This is always set by the renderer.
+    pub module_size: (u32, u32),
+
+    /// The line ending of text outputs.
+    pub line_ending: LineEnding,
+
+    /// Whether text outputs end with a line ending.
+    pub trailing_newline: bool,
+
+    /// Whether light pixels at the end of each line of text outputs are
+    /// omitted.
+    pub trim_trailing_light: bool,
+
+    /// The physical size of each module and its unit, for SVG, Asymptote and
+    /// MetaPost output.
+    pub physical_module_size: Option<(f64, Unit)>,
+
+    /// How the background of vector outputs is painted.
+    pub background: Background,
+
+    /// The physical size of each module in inches, for PIC output.
+    pub inches_per_module: Option<f64>,
+
+    /// The offset of the origin in inches, for PIC output.
+    pub origin_offset: (f64, f64),
+
+    /// Whether modules are drawn as outlined boxes instead of filled boxes,
+    /// for PIC output.
+    pub outline_modules: bool,
+
+    /// The line thickness in points, for PIC output.
+    pub line_thickness: Option<f64>,
+
+    /// The CSS class of the root element, for SVG output.
+    pub css_class: Option<String>,
+
+    /// The title of the image, for SVG output.
+    pub title: Option<String>,
+
+    /// The description of the image, for SVG output.
+    pub description: Option<String>,
+
+    /// The gradient of the dark modules, for SVG output.
+    #[cfg(feature = "svg")]
+    pub svg_dark_fill: Option<svg::Fill>,
+
+    /// How each pixel is drawn, for ANSI output.
+    pub ansi_block_style: ansi::BlockStyle,
+
+    /// The number of bytes of each row, for bitmap output.
+    pub bitmap_stride: bitmap::Stride,
+
+    /// The image format, for Netpbm and XBM output.
+    pub pbm_format: pbm::Format,
+
+    /// How the modules are laid out, for HTML output.
+    #[cfg(feature = "html")]
+    pub html_layout: html::Layout,
+}
+
+// Audit
+
+/// A problem found by [`Renderer::audit`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AuditWarning {
+    /// The quiet zone is disabled or has zero width.
+    QuietZoneDisabled,
+
+    /// The module is smaller than the recommended size.
+    SmallModules {
+        /// The width of the module in pixels.
+        width: u32,
+
+        /// The height of the module in pixels.
+        height: u32,
+
+        /// The recommended minimum size in pixels.
+        minimum: u32,
+    },
+
+    /// The dark modules are lighter than the light modules, and there is no
+    /// quiet zone to separate the symbol from its surroundings.
+    InvertedColorsWithoutQuietZone,
+
+    /// The logo is estimated to damage more codewords than the error
+    /// correction can recover.
+    LogoExceedsEcBudget {
+        /// The estimated number of damaged codewords.
+        codewords: usize,
+
+        /// The number of codewords which can be recovered.
+        budget: usize,
+    },
+}
+
+impl fmt::Display for AuditWarning {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::QuietZoneDisabled => write!(f, "quiet zone is disabled"),
+            Self::SmallModules {
+                width,
+                height,
+                minimum,
+            } => write!(
+                f,
+                "modules of {width}x{height} pixels are smaller than {minimum} pixels"
+            ),
+            Self::InvertedColorsWithoutQuietZone => {
+                write!(f, "inverted colors without quiet zone")
+            }
+            Self::LogoExceedsEcBudget { codewords, budget } => write!(
+                f,
+                "logo damages about {codewords} codewords, but only {budget} can be recovered"
+            ),
+        }
+    }
+}
+
+// Quiet zone
+
+/// The size of the quiet zone on each side of the symbol, in modules.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::render::QuietZone;
+/// #
+/// // No margin on the left, where the label is cut.
+/// let quiet_zone = QuietZone {
+///     left: 0,
+///     ..QuietZone::uniform(4)
+/// };
+/// assert_eq!(quiet_zone.horizontal(), 4);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct QuietZone {
+    /// The number of modules above the symbol.
+    pub top: u32,
+
+    /// The number of modules to the right of the symbol.
+    pub right: u32,
+
+    /// The number of modules below the symbol.
+    pub bottom: u32,
+
+    /// The number of modules to the left of the symbol.
+    pub left: u32,
+}
+
+impl QuietZone {
+    /// Creates a quiet zone of the same size on all sides.
+    #[must_use]
+    #[inline]
+    pub const fn uniform(size: u32) -> Self {
+        Self {
+            top: size,
+            right: size,
+            bottom: size,
+            left: size,
+        }
+    }
+
+    /// Returns the total number of modules on the left and right sides.
+    #[must_use]
+    #[inline]
+    pub const fn horizontal(&self) -> u32 {
+        self.left + self.right
+    }
+
+    /// Returns the total number of modules on the top and bottom sides.
+    #[must_use]
+    #[inline]
+    pub const fn vertical(&self) -> u32 {
+        self.top + self.bottom
+    }
+
+    /// Returns the size of the narrowest side.
+    #[must_use]
+    #[inline]
+    pub fn min(&self) -> u32 {
+        cmp::min(
+            cmp::min(self.top, self.right),
+            cmp::min(self.bottom, self.left),
+        )
+    }
+}
+
 // Renderer
 
 /// A QR code renderer. This is a builder type which converts a bool-vector into
@@ -84,11 +622,22 @@ pub struct Renderer<'a, P: Pixel> {
     horizontal_modules_count: u32,
     // we call it `vertical_modules_count` here to avoid ambiguity of `height`.
     vertical_modules_count: u32,
-    quiet_zone: u32,
+    quiet_zone: QuietZone,
     module_size: (u32, u32),
     dark_color: P,
     light_color: P,
     has_quiet_zone: bool,
+    logo_size: (u32, u32),
+    max_allowed_errors: Option<usize>,
+    canvas_options: CanvasOptions,
+    module_shape: ModuleShape,
+    symbol: Option<(Version, EcLevel)>,
+    rotation: Rotation,
+    flip_horizontal: bool,
+    invert_colors: bool,
+    finder_dark_color: Option<P>,
+    alignment_dark_color: Option<P>,
+    exact_size: Option<(u32, u32)>,
 }
 
 impl<'a, P: Pixel> Renderer<'a, P> {
@@ -118,11 +667,22 @@ impl<'a, P: Pixel> Renderer<'a, P> {
             content,
             horizontal_modules_count,
             vertical_modules_count,
-            quiet_zone,
+            quiet_zone: QuietZone::uniform(quiet_zone),
             module_size,
             dark_color,
             light_color,
             has_quiet_zone: true,
+            logo_size: (0, 0),
+            max_allowed_errors: None,
+            canvas_options: CanvasOptions::default(),
+            module_shape: ModuleShape::Square,
+            symbol: None,
+            rotation: Rotation::Deg0,
+            flip_horizontal: false,
+            invert_colors: false,
+            finder_dark_color: None,
+            alignment_dark_color: None,
+            exact_size: None,
         }
     }
 
@@ -140,6 +700,164 @@ impl<'a, P: Pixel> Renderer<'a, P> {
         self
     }
 
+    /// Rotates the symbol clockwise. Default is [`Rotation::Deg0`].
+    ///
+    /// The rotation is applied after [`Renderer::flip_horizontal`]. The quiet
+    /// zone is not rotated, so [`Renderer::quiet_zone_sides`] still refers to
+    /// the sides of the output. Set the rotation before
+    /// [`Renderer::min_dimensions`] or [`Renderer::max_dimensions`], since the
+    /// width and the height of an rMQR code are swapped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, render::Rotation};
+    /// #
+    /// let code = QrCode::new_rect_micro(b"Hello").unwrap();
+    /// let s = code
+    ///     .render::<char>()
+    ///     .has_quiet_zone(false)
+    ///     .rotate(Rotation::Deg90)
+    ///     .build();
+    /// assert_eq!(s.lines().count(), code.width());
+    /// assert!(s.lines().all(|line| line.chars().count() == code.height()));
+    /// ```
+    #[inline]
+    pub const fn rotate(&mut self, rotation: Rotation) -> &mut Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Mirrors the symbol horizontally, e.g. for printing on the back of a
+    /// transparent label.
+    ///
+    /// A mirrored symbol is still valid, but not every scanner reads it.
+    #[inline]
+    pub const fn flip_horizontal(&mut self) -> &mut Self {
+        self.flip_horizontal = true;
+        self
+    }
+
+    /// Sets whether to draw dark modules with the light color and light
+    /// modules, including the quiet zone, with the dark color. Default is
+    /// `false`.
+    ///
+    /// This produces a light-on-dark symbol for dark backgrounds without
+    /// changing [`Renderer::dark_color`] and [`Renderer::light_color`]. Not
+    /// every scanner reads inverted symbols.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::QrCode;
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let s = code
+    ///     .render()
+    ///     .dark_color('#')
+    ///     .light_color('.')
+    ///     .invert_colors(true)
+    ///     .build();
+    /// assert!(s.lines().next().unwrap().chars().all(|c| c == '#'));
+    /// ```
+    #[inline]
+    pub const fn invert_colors(&mut self, invert_colors: bool) -> &mut Self {
+        self.invert_colors = invert_colors;
+        self
+    }
+
+    /// Returns the colors which dark and light modules are drawn with.
+    const fn drawn_colors(&self) -> (P, P) {
+        if self.invert_colors {
+            (self.light_color, self.dark_color)
+        } else {
+            (self.dark_color, self.light_color)
+        }
+    }
+
+    /// Returns the number of modules of the symbol per row and per column of
+    /// the output, after the rotation.
+    pub(crate) const fn drawn_modules_count(&self) -> (u32, u32) {
+        let (w, h) = (self.horizontal_modules_count, self.vertical_modules_count);
+        match self.rotation {
+            Rotation::Deg0 | Rotation::Deg180 => (w, h),
+            Rotation::Deg90 | Rotation::Deg270 => (h, w),
+        }
+    }
+
+    /// Returns the coordinates in the symbol of the module drawn at (`x`, `y`)
+    /// of the output, both excluding the quiet zone.
+    pub(crate) const fn source_module(&self, x: u32, y: u32) -> (u32, u32) {
+        let (w, h) = (self.horizontal_modules_count, self.vertical_modules_count);
+        let (x, y) = match self.rotation {
+            Rotation::Deg0 => (x, y),
+            Rotation::Deg90 => (y, h - 1 - x),
+            Rotation::Deg180 => (w - 1 - x, h - 1 - y),
+            Rotation::Deg270 => (w - 1 - y, x),
+        };
+        if self.flip_horizontal {
+            (w - 1 - x, y)
+        } else {
+            (x, y)
+        }
+    }
+
+    /// Sets the color of the dark modules of the finder patterns, so that the
+    /// three "eyes" stand out from the data modules. Default is the dark
+    /// color.
+    ///
+    /// This is supported by image and SVG output, and only applies if the
+    /// renderer is created by [`QrCode::render`](crate::QrCode::render), since
+    /// the symbol is needed to find the patterns. Other outputs draw the
+    /// patterns with the dark color. Keep the contrast with the light color
+    /// high, or the symbol may not scan.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "image")]
+    /// # {
+    /// # use qrcode2::{QrCode, image::Rgb};
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let image = code
+    ///     .render()
+    ///     .module_dimensions(1, 1)
+    ///     .finder_dark_color(Rgb([0, 0, 128]))
+    ///     .build();
+    /// // The top left module of the finder pattern, and a module of the timing
+    /// // pattern.
+    /// assert_eq!(image.get_pixel(4, 4).0, [0, 0, 128]);
+    /// assert_eq!(image.get_pixel(12, 10).0, [0, 0, 0]);
+    /// # }
+    /// ```
+    #[inline]
+    pub const fn finder_dark_color(&mut self, color: P) -> &mut Self {
+        self.finder_dark_color = Some(color);
+        self
+    }
+
+    /// Sets the color of the dark modules of the alignment patterns. Default
+    /// is the dark color.
+    ///
+    /// Like [`Renderer::finder_dark_color`], this is supported by image and
+    /// SVG output.
+    #[inline]
+    pub const fn alignment_dark_color(&mut self, color: P) -> &mut Self {
+        self.alignment_dark_color = Some(color);
+        self
+    }
+
+    /// Returns the kind of each module of the symbol if any pattern is drawn in
+    /// its own color.
+    fn module_kinds(&self) -> Option<Vec<ModuleKind>> {
+        if self.finder_dark_color.is_none() && self.alignment_dark_color.is_none() {
+            return None;
+        }
+        let (version, ec_level) = self.symbol?;
+        SymbolCanvas::module_kinds(version, ec_level).ok()
+    }
+
     /// Sets the size of the quiet zone in the generated image.
     ///
     /// If `Renderer` is constructed using
@@ -147,6 +865,33 @@ impl<'a, P: Pixel> Renderer<'a, P> {
     /// 4 for normal QR code, and 2 for Micro QR code and rMQR code.
     #[inline]
     pub const fn quiet_zone(&mut self, quiet_zone: u32) -> &mut Self {
+        self.quiet_zone = QuietZone::uniform(quiet_zone);
+        self
+    }
+
+    /// Sets the size of the quiet zone on each side separately, e.g. to omit
+    /// the margin on a side which is butted against the edge of a label.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, render::QuietZone};
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let s = code
+    ///     .render::<char>()
+    ///     .quiet_zone_sides(QuietZone {
+    ///         top: 0,
+    ///         right: 1,
+    ///         bottom: 2,
+    ///         left: 3,
+    ///     })
+    ///     .build();
+    /// assert_eq!(s.lines().count(), 21 + 2);
+    /// assert!(s.lines().all(|line| line.chars().count() == 21 + 4));
+    /// ```
+    #[inline]
+    pub const fn quiet_zone_sides(&mut self, quiet_zone: QuietZone) -> &mut Self {
         self.quiet_zone = quiet_zone;
         self
     }
@@ -158,6 +903,26 @@ impl<'a, P: Pixel> Renderer<'a, P> {
         self
     }
 
+    /// Returns the size of the quiet zone which will be drawn on each side, or
+    /// 0 on all sides if the quiet zone is disabled.
+    #[must_use]
+    #[inline]
+    pub(crate) const fn quiet_zone_sides_drawn(&self) -> QuietZone {
+        if self.has_quiet_zone {
+            self.quiet_zone
+        } else {
+            QuietZone::uniform(0)
+        }
+    }
+
+    /// Returns the size of the narrowest side of the quiet zone which will be
+    /// drawn, or 0 if the quiet zone is disabled.
+    #[must_use]
+    #[inline]
+    pub(crate) fn effective_quiet_zone(&self) -> u32 {
+        self.quiet_zone_sides_drawn().min()
+    }
+
     /// Sets the size of each module in pixels. Default is 8×8.
     #[inline]
     pub fn module_dimensions(&mut self, width: u32, height: u32) -> &mut Self {
@@ -174,9 +939,10 @@ impl<'a, P: Pixel> Renderer<'a, P> {
     /// quiet zone. If we request an image of size ≥200×200, we get that each
     /// module's size should be 11×11, so the actual image size will be 209×209.
     pub fn min_dimensions(&mut self, width: u32, height: u32) -> &mut Self {
-        let quiet_zone = if self.has_quiet_zone { 2 } else { 0 } * self.quiet_zone;
-        let width_in_modules = self.horizontal_modules_count + quiet_zone;
-        let height_in_modules = self.vertical_modules_count + quiet_zone;
+        let quiet_zone = self.quiet_zone_sides_drawn();
+        let (w, h) = self.drawn_modules_count();
+        let width_in_modules = w + quiet_zone.horizontal();
+        let height_in_modules = h + quiet_zone.vertical();
         let unit_width = width.div_ceil(width_in_modules);
         let unit_height = height.div_ceil(height_in_modules);
         self.module_dimensions(unit_width, unit_height)
@@ -194,43 +960,634 @@ impl<'a, P: Pixel> Renderer<'a, P> {
     /// The module size is at least 1×1, so if the restriction is too small, the
     /// final image *can* be larger than the input.
     pub fn max_dimensions(&mut self, width: u32, height: u32) -> &mut Self {
-        let quiet_zone = if self.has_quiet_zone { 2 } else { 0 } * self.quiet_zone;
-        let width_in_modules = self.horizontal_modules_count + quiet_zone;
-        let height_in_modules = self.vertical_modules_count + quiet_zone;
+        let quiet_zone = self.quiet_zone_sides_drawn();
+        let (w, h) = self.drawn_modules_count();
+        let width_in_modules = w + quiet_zone.horizontal();
+        let height_in_modules = h + quiet_zone.vertical();
         let unit_width = width / width_in_modules;
         let unit_height = height / height_in_modules;
         self.module_dimensions(unit_width, unit_height)
     }
 
+    /// Sets the exact total image size in pixels. The modules are square and as
+    /// large as possible such that the symbol and the quiet zone fit, and the
+    /// remaining pixels are filled with the light color, centering the symbol.
+    ///
+    /// For instance, a version 1 QR code has 29 modules across including the
+    /// quiet zone. If we request an image of size 200×150, we get that each
+    /// module's size should be 5×5, so the symbol takes 145×145 pixels, with a
+    /// margin of 27 pixels on the left, 28 on the right, 2 on the top and 3 on
+    /// the bottom.
+    ///
+    /// Like [`Renderer::max_dimensions`], the module size is at least 1×1, so
+    /// if the restriction is too small, the final image *can* be larger than
+    /// the input. Setting the module size afterwards keeps the image size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::QrCode;
+    /// #
+    /// let code = QrCode::new(b"01234567").unwrap();
+    /// let image = code.render::<char>().exact_dimensions(40, 30).build();
+    /// let lines = image.lines().collect::<Vec<_>>();
+    /// assert_eq!(lines.len(), 30);
+    /// assert!(lines.iter().all(|line| line.chars().count() == 40));
+    /// ```
+    pub fn exact_dimensions(&mut self, width: u32, height: u32) -> &mut Self {
+        let quiet_zone = self.quiet_zone_sides_drawn();
+        let (w, h) = self.drawn_modules_count();
+        let width_in_modules = w + quiet_zone.horizontal();
+        let height_in_modules = h + quiet_zone.vertical();
+        let unit = cmp::min(width / width_in_modules, height / height_in_modules);
+        self.exact_size = Some((width, height));
+        self.module_dimensions(unit, unit)
+    }
+
+    /// Returns the size of the image in pixels, and the offset of the symbol
+    /// including the quiet zone, which is only nonzero if the image is padded
+    /// to [exact dimensions](Renderer::exact_dimensions).
+    pub(crate) fn image_layout(&self) -> ((u32, u32), (u32, u32)) {
+        let (w, h) = self.drawn_modules_count();
+        let qz = self.quiet_zone_sides_drawn();
+        let (mw, mh) = self.module_size;
+        let width = (w + qz.horizontal()) * mw;
+        let height = (h + qz.vertical()) * mh;
+        match self.exact_size {
+            Some((exact_width, exact_height)) => {
+                let image_width = cmp::max(width, exact_width);
+                let image_height = cmp::max(height, exact_height);
+                (
+                    (image_width, image_height),
+                    ((image_width - width) / 2, (image_height - height) / 2),
+                )
+            }
+            None => ((width, height), (0, 0)),
+        }
+    }
+
+    /// Declares the size in modules of a logo which will be placed over the
+    /// symbol after rendering.
+    ///
+    /// The renderer does not draw the logo. The size is only used by
+    /// [`Renderer::audit`].
+    #[inline]
+    pub const fn logo_dimensions(&mut self, width: u32, height: u32) -> &mut Self {
+        self.logo_size = (width, height);
+        self
+    }
+
+    /// Sets the number of codewords which can be recovered by the error
+    /// correction.
+    pub(crate) const fn max_allowed_errors(&mut self, max_allowed_errors: usize) -> &mut Self {
+        self.max_allowed_errors = Some(max_allowed_errors);
+        self
+    }
+
+    /// Sets the version and the error correction level of the symbol.
+    pub(crate) const fn symbol(&mut self, version: Version, ec_level: EcLevel) -> &mut Self {
+        self.symbol = Some((version, ec_level));
+        self
+    }
+
+    /// Checks the settings for problems which make the rendered image hard to
+    /// scan, without rendering it.
+    ///
+    /// The module size is checked against [`MIN_MODULE_SIZE`], or against the
+    /// default module size of the pixel type if it is smaller (e.g. 1×1 for
+    /// text output). The logo is only checked if the renderer is created by
+    /// [`QrCode::render`](crate::QrCode::render).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, render::AuditWarning};
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let mut renderer = code.render::<char>();
+    /// assert!(renderer.audit().is_empty());
+    ///
+    /// renderer.has_quiet_zone(false);
+    /// assert_eq!(renderer.audit(), [AuditWarning::QuietZoneDisabled]);
+    /// ```
+    #[must_use]
+    pub fn audit(&self) -> Vec<AuditWarning> {
+        let mut warnings = Vec::new();
+
+        let has_quiet_zone = self.effective_quiet_zone() > 0;
+        if !has_quiet_zone {
+            warnings.push(AuditWarning::QuietZoneDisabled);
+        }
+
+        let (default_width, default_height) = P::default_unit_size();
+        let minimum = cmp::min(MIN_MODULE_SIZE, cmp::min(default_width, default_height));
+        let (width, height) = self.module_size;
+        if cmp::min(width, height) < minimum {
+            warnings.push(AuditWarning::SmallModules {
+                width,
+                height,
+                minimum,
+            });
+        }
+
+        let (dark_color, light_color) = self.drawn_colors();
+        let dark = dark_color.luminance();
+        let light = light_color.luminance();
+        if let (Some(dark), Some(light)) = (dark, light) {
+            if dark > light && !has_quiet_zone {
+                warnings.push(AuditWarning::InvertedColorsWithoutQuietZone);
+            }
+        }
+
+        let logo_modules = self.logo_size.0.as_usize() * self.logo_size.1.as_usize();
+        if let Some(budget) = self.max_allowed_errors {
+            let codewords = estimate_damaged_codewords(logo_modules);
+            if codewords > budget {
+                warnings.push(AuditWarning::LogoExceedsEcBudget { codewords, budget });
+            }
+        }
+
+        warnings
+    }
+
+    /// Applies the settings of a [`Preset`] at once. Settings can still be
+    /// changed afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, render::Preset};
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let s = code.render::<char>().preset(Preset::TerminalDark).build();
+    /// // The quiet zone is drawn with the foreground color of the terminal.
+    /// assert!(s.lines().next().unwrap().chars().all(|c| c == '█'));
+    /// ```
+    pub fn preset(&mut self, preset: Preset) -> &mut Self {
+        let (width, height) = P::default_unit_size();
+        let dark_color = P::default_color(Color::Dark);
+        let light_color = P::default_color(Color::Light);
+        self.has_quiet_zone = true;
+        self.canvas_options.trim_trailing_light = false;
+        match preset {
+            Preset::Print => {
+                self.module_dimensions(width * 2, height * 2);
+                self.dark_color = dark_color;
+                self.light_color = light_color;
+                self.canvas_options.background = Background::White;
+            }
+            Preset::Screen => {
+                self.module_dimensions(width, height);
+                self.dark_color = dark_color;
+                self.light_color = light_color;
+                self.canvas_options.background = Background::Light;
+            }
+            Preset::TerminalDark => {
+                self.module_dimensions(width, height);
+                self.dark_color = light_color;
+                self.light_color = dark_color;
+                self.canvas_options.background = Background::Light;
+            }
+        }
+        self
+    }
+
+    /// Applies the quiet zone of a [`Profile`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, profile::Profile};
+    /// #
+    /// let code = QrCode::with_profile(b"Hello", Profile::IndustrialDamageProne).unwrap();
+    /// let s = code
+    ///     .render::<char>()
+    ///     .profile(Profile::IndustrialDamageProne)
+    ///     .build();
+    /// assert_eq!(s.lines().count(), 21 + 2 * 6);
+    /// ```
+    #[inline]
+    pub const fn profile(&mut self, profile: Profile) -> &mut Self {
+        self.has_quiet_zone = true;
+        self.quiet_zone = QuietZone::uniform(profile.quiet_zone());
+        self
+    }
+
     /// Renders the QR code into an image.
+    #[inline]
     pub fn build(&self) -> P::Image {
-        let w = self.horizontal_modules_count;
-        let h = self.vertical_modules_count;
-        let qz = if self.has_quiet_zone {
-            self.quiet_zone
-        } else {
-            0
-        };
-        let width = w + 2 * qz;
-        let height = h + 2 * qz;
+        self.draw().into_image()
+    }
 
+    /// Draws the QR code onto a new canvas.
+    pub(crate) fn draw(&self) -> P::Canvas {
+        let (w, h) = self.drawn_modules_count();
+        let qz = self.quiet_zone_sides_drawn();
         let (mw, mh) = self.module_size;
-        let real_width = width * mw;
-        let real_height = height * mh;
-
-        let mut canvas = P::Canvas::new(real_width, real_height, self.dark_color, self.light_color);
-        let mut i = 0;
-        for y in 0..height {
-            for x in 0..width {
-                if qz <= x && x < w + qz && qz <= y && y < h + qz {
-                    if self.content[i] != Color::Light {
-                        canvas.draw_dark_rect(x * mw, y * mh, mw, mh);
+        let ((real_width, real_height), (offset_x, offset_y)) = self.image_layout();
+
+        let (dark_color, light_color) = self.drawn_colors();
+        let mut canvas = P::Canvas::new(real_width, real_height, dark_color, light_color);
+        canvas.configure(&CanvasOptions {
+            module_size: self.module_size,
+            ..self.canvas_options.clone()
+        });
+        let kinds = self.module_kinds();
+        for y in 0..h {
+            for x in 0..w {
+                let (sx, sy) = self.source_module(x, y);
+                let i = sy.as_usize() * self.horizontal_modules_count.as_usize() + sx.as_usize();
+                if self.content[i] != Color::Light {
+                    let pixel = kinds.as_ref().and_then(|kinds| match kinds[i] {
+                        ModuleKind::Finder => self.finder_dark_color,
+                        ModuleKind::Alignment => self.alignment_dark_color,
+                        _ => None,
+                    });
+                    let left = (x + qz.left) * mw + offset_x;
+                    let top = (y + qz.top) * mh + offset_y;
+                    self.draw_dark_module(&mut canvas, left, top, pixel);
+                }
+            }
+        }
+        canvas
+    }
+
+    /// Draws a dark module with the top left corner at the (`left`, `top`)
+    /// coordinate, in `pixel` instead of the dark color if it is given.
+    fn draw_dark_module(&self, canvas: &mut P::Canvas, left: u32, top: u32, pixel: Option<P>) {
+        let (mw, mh) = self.module_size;
+        if matches!(self.module_shape, ModuleShape::Square) {
+            match pixel {
+                Some(pixel) => canvas.draw_rect(left, top, mw, mh, pixel),
+                None => canvas.draw_dark_rect(left, top, mw, mh),
+            }
+            return;
+        }
+        for y in 0..mh {
+            for x in 0..mw {
+                if self.module_shape.covers(x, y, mw, mh) {
+                    match pixel {
+                        Some(pixel) => canvas.draw_pixel(left + x, top + y, pixel),
+                        None => canvas.draw_dark_pixel(left + x, top + y),
                     }
-                    i += 1;
                 }
             }
         }
+    }
+}
+
+impl<P: Pixel<Image = String>> Renderer<'_, P> {
+    /// Sets the line ending of the text output. Default is [`LineEnding::Lf`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, render::LineEnding};
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let s = code.render::<char>().line_ending(LineEnding::CrLf).build();
+    /// assert!(s.lines().all(|line| !line.contains('\r')));
+    /// assert_eq!(s.matches("\r\n").count(), s.lines().count() - 1);
+    /// ```
+    #[inline]
+    pub const fn line_ending(&mut self, line_ending: LineEnding) -> &mut Self {
+        self.canvas_options.line_ending = line_ending;
+        self
+    }
+
+    /// Sets whether to append a line ending at the end of the text output.
+    /// Default is `false`.
+    #[inline]
+    pub const fn trailing_newline(&mut self, trailing_newline: bool) -> &mut Self {
+        self.canvas_options.trailing_newline = trailing_newline;
+        self
+    }
+
+    /// Sets whether to omit the light pixels at the end of each line. Default
+    /// is `false`.
+    ///
+    /// This only applies to string and UTF-8 rendering. The right quiet zone
+    /// and any light modules at the end of a row are removed, so lines may have
+    /// different lengths. Every line still starts at the left quiet zone, so
+    /// the symbol stays aligned as long as the output is displayed on a light
+    /// background.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::QrCode;
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let s = code.render::<char>().trim_trailing_light(true).build();
+    /// assert!(s.lines().all(|line| !line.ends_with(' ')));
+    /// ```
+    #[inline]
+    pub const fn trim_trailing_light(&mut self, trim_trailing_light: bool) -> &mut Self {
+        self.canvas_options.trim_trailing_light = trim_trailing_light;
+        self
+    }
+}
+
+impl<P: Pixel> Renderer<'_, P>
+where
+    P::Canvas: WriteCanvas,
+{
+    /// Renders the QR code and writes it into `writer`, without building the
+    /// whole image as a [`String`] first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing into `writer` fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::QrCode;
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let renderer = code.render::<char>();
+    /// let mut image = String::new();
+    /// renderer.write_to(&mut image).unwrap();
+    /// assert_eq!(image, renderer.build());
+    /// ```
+    #[inline]
+    pub fn write_to<W: fmt::Write + ?Sized>(&self, writer: &mut W) -> fmt::Result {
+        self.draw().write_image(writer)
+    }
+
+    /// Renders the QR code and writes it into `writer` as UTF-8, without
+    /// building the whole image as a [`String`] first.
+    ///
+    /// The image is written in many small pieces, so `writer` should be
+    /// buffered.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing into `writer` fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::QrCode;
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let mut image = Vec::new();
+    /// code.render::<char>().write_to_io(&mut image).unwrap();
+    /// assert_eq!(image.len(), code.render::<char>().build().len());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn write_to_io<W: std::io::Write>(&self, writer: W) -> std::io::Result<()> {
+        /// Forwards the text to an [`std::io::Write`], keeping the first I/O
+        /// error.
+        struct Adapter<W> {
+            inner: W,
+            error: std::io::Result<()>,
+        }
+
+        impl<W: std::io::Write> fmt::Write for Adapter<W> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.inner.write_all(s.as_bytes()).map_err(|err| {
+                    self.error = Err(err);
+                    fmt::Error
+                })
+            }
+        }
+
+        let mut adapter = Adapter {
+            inner: writer,
+            error: Ok(()),
+        };
+        match self.write_to(&mut adapter) {
+            Ok(()) => Ok(()),
+            Err(fmt::Error) => Err(adapter
+                .error
+                .err()
+                .unwrap_or_else(|| std::io::Error::other("formatter error"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod audit_tests {
+    use super::*;
+    use crate::QrCode;
+
+    #[test]
+    fn test_text_module_size() {
+        let content = [Color::Dark; 4];
+        let mut renderer = Renderer::<char>::new(&content, 2, 2, 1);
+        assert!(renderer.audit().is_empty());
+        renderer.module_dimensions(1, 1);
+        assert!(renderer.audit().is_empty());
+    }
+
+    #[test]
+    fn test_logo() {
+        let code = QrCode::with_version(b"Hello", Version::Normal(5), EcLevel::H).unwrap();
+        let mut renderer = code.render::<char>();
+        assert!(renderer.logo_dimensions(10, 10).audit().is_empty());
+        assert_eq!(
+            renderer.logo_dimensions(20, 20).audit(),
+            [AuditWarning::LogoExceedsEcBudget {
+                codewords: 100,
+                budget: 44
+            }]
+        );
+    }
+}
+
+#[cfg(test)]
+mod preset_tests {
+    use super::*;
+
+    #[test]
+    fn test_preset() {
+        let content = [Color::Dark, Color::Light, Color::Light, Color::Dark];
+        let mut renderer = Renderer::<char>::new(&content, 2, 2, 1);
+        renderer.has_quiet_zone(false).module_dimensions(3, 3);
+
+        assert_eq!(
+            renderer.preset(Preset::Screen).build(),
+            "    \n █  \n  █ \n    "
+        );
+        assert_eq!(
+            renderer.preset(Preset::TerminalDark).build(),
+            "████\n█ ██\n██ █\n████"
+        );
+        assert_eq!(renderer.preset(Preset::Print).module_size, (2, 2));
+        assert_eq!(renderer.canvas_options.background, Background::White);
+    }
+}
+
+#[cfg(test)]
+mod quiet_zone_tests {
+    use super::*;
+
+    #[test]
+    fn test_sides() {
+        let content = [Color::Dark];
+        let mut renderer = Renderer::<char>::new(&content, 1, 1, 0);
+        renderer
+            .module_dimensions(1, 1)
+            .quiet_zone_sides(QuietZone {
+                top: 1,
+                right: 0,
+                bottom: 2,
+                left: 3,
+            });
+        assert_eq!(renderer.build(), "    \n   \u{2588}\n    \n    ");
+        assert_eq!(renderer.effective_quiet_zone(), 0);
+    }
+
+    #[test]
+    fn test_dimensions() {
+        let content = [Color::Dark; 4];
+        let mut renderer = Renderer::<char>::new(&content, 2, 2, 0);
+        renderer.quiet_zone_sides(QuietZone {
+            left: 2,
+            ..QuietZone::uniform(0)
+        });
+        renderer.min_dimensions(8, 8);
+        assert_eq!(renderer.module_size, (2, 4));
+    }
+}
+
+#[cfg(test)]
+mod transform_tests {
+    use super::*;
+
+    const CONTENT: [Color; 6] = [
+        Color::Dark,
+        Color::Light,
+        Color::Light,
+        //
+        Color::Dark,
+        Color::Dark,
+        Color::Light,
+    ];
+
+    fn renderer() -> Renderer<'static, char> {
+        let mut renderer = Renderer::new(&CONTENT, 3, 2, 0);
+        renderer.dark_color('#').light_color('.');
+        renderer
+    }
+
+    #[test]
+    fn test_rotate() {
+        assert_eq!(renderer().build(), "#..\n##.");
+        assert_eq!(renderer().rotate(Rotation::Deg90).build(), "##\n#.\n..");
+        assert_eq!(renderer().rotate(Rotation::Deg180).build(), ".##\n..#");
+        assert_eq!(renderer().rotate(Rotation::Deg270).build(), "..\n.#\n##");
+    }
+
+    #[test]
+    fn test_flip_horizontal() {
+        assert_eq!(renderer().flip_horizontal().build(), "..#\n.##");
+        assert_eq!(
+            renderer().flip_horizontal().rotate(Rotation::Deg90).build(),
+            "..\n#.\n##"
+        );
+    }
+
+    #[test]
+    fn test_dimensions() {
+        let mut renderer = renderer();
+        renderer.rotate(Rotation::Deg270).min_dimensions(4, 6);
+        assert_eq!(renderer.module_size, (2, 2));
+    }
+
+    #[test]
+    fn test_invert_colors() {
+        let mut renderer = renderer();
+        renderer.quiet_zone(1).invert_colors(true);
+        assert_eq!(renderer.build(), "#####\n#.###\n#..##\n#####");
+    }
+}
+
+#[cfg(test)]
+mod exact_dimensions_tests {
+    use super::*;
+
+    const CONTENT: [Color; 4] = [Color::Dark, Color::Light, Color::Light, Color::Dark];
+
+    #[test]
+    fn test_centered() {
+        let mut renderer = Renderer::new(&CONTENT, 2, 2, 1);
+        renderer
+            .dark_color('#')
+            .light_color('.')
+            .exact_dimensions(9, 5);
+        assert_eq!(renderer.module_size, (1, 1));
+        assert_eq!(renderer.image_layout(), ((9, 5), (2, 0)));
+        assert_eq!(
+            renderer.build(),
+            ".........\n...#.....\n....#....\n.........\n........."
+        );
+    }
+
+    #[test]
+    fn test_scaled() {
+        let mut renderer = Renderer::<char>::new(&CONTENT, 2, 2, 1);
+        renderer.exact_dimensions(9, 13);
+        assert_eq!(renderer.module_size, (2, 2));
+        assert_eq!(renderer.image_layout(), ((9, 13), (0, 2)));
+        renderer.module_dimensions(3, 3);
+        assert_eq!(renderer.image_layout(), ((12, 13), (0, 0)));
+    }
+
+    #[test]
+    fn test_too_small() {
+        let mut renderer = Renderer::<char>::new(&CONTENT, 2, 2, 1);
+        renderer.exact_dimensions(2, 6);
+        assert_eq!(renderer.module_size, (1, 1));
+        assert_eq!(renderer.image_layout(), ((4, 6), (0, 1)));
+    }
+}
+
+#[cfg(test)]
+mod write_tests {
+    use super::*;
+    use crate::QrCode;
+
+    #[test]
+    fn test_write_to() {
+        let code = QrCode::new(b"Hello").unwrap();
+        let mut renderer = code.render::<&str>();
+        renderer
+            .dark_color("##")
+            .line_ending(LineEnding::CrLf)
+            .trailing_newline(true);
+        let mut image = String::new();
+        renderer.write_to(&mut image).unwrap();
+        assert_eq!(image, renderer.build());
+    }
+
+    #[cfg(feature = "eps")]
+    #[test]
+    fn test_write_to_eps() {
+        let code = QrCode::new(b"Hello").unwrap();
+        let renderer = code.render::<eps::Color>();
+        let mut image = String::new();
+        renderer.write_to(&mut image).unwrap();
+        assert_eq!(image, renderer.build());
+    }
+
+    #[cfg(feature = "pic")]
+    #[test]
+    fn test_write_to_pic() {
+        let code = QrCode::new(b"Hello").unwrap();
+        let renderer = code.render::<pic::Color>();
+        let mut image = String::new();
+        renderer.write_to(&mut image).unwrap();
+        assert_eq!(image, renderer.build());
+    }
 
-        canvas.into_image()
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_write_to_io_error() {
+        let code = QrCode::new(b"Hello").unwrap();
+        let mut buf = [0; 16];
+        let err = code.render::<char>().write_to_io(&mut buf[..]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
     }
 }
@@ -0,0 +1,273 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Animated GIF showing how a QR code is constructed.
+//!
+//! The animation goes through the stages of the construction of the symbol:
+//!
+//! 1. The functional patterns are drawn, and the format information area is
+//!    reserved.
+//! 2. The data and error correction bits are placed in the zig-zag order,
+//!    before masking.
+//! 3. The mask is applied and the format information is filled in.
+//!
+//! Modules which are not drawn yet are shown in a separate color. This is
+//! meant for documentation, talks, and teaching material.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, render::animation::Animation};
+//!
+//! let code = QrCode::new(b"Hello").unwrap();
+//! let mut gif = Vec::new();
+//! Animation::new(&code).write_gif(&mut gif).unwrap();
+//! assert!(gif.starts_with(b"GIF89a"));
+//! ```
+
+use alloc::vec::Vec;
+use std::io::Write;
+
+use image::{
+    Delay, Frame, ImageResult, Rgba, RgbaImage,
+    codecs::gif::{GifEncoder, Repeat},
+};
+
+use crate::{
+    QrCode,
+    canvas::{self, Canvas, MaskPattern, Module},
+    cast::As,
+    types::Color,
+};
+
+/// A builder for the construction animation of a QR code.
+#[derive(Clone, Copy, Debug)]
+pub struct Animation<'a> {
+    code: &'a QrCode,
+    module_size: u32,
+    placement_steps: usize,
+    frame_delay: u32,
+    dark_color: Rgba<u8>,
+    light_color: Rgba<u8>,
+    empty_color: Rgba<u8>,
+}
+
+impl<'a> Animation<'a> {
+    /// Creates a new builder for the QR code.
+    ///
+    /// The defaults are 8 pixels per module, 8 frames for the data placement,
+    /// 500 milliseconds per frame, and black and white modules with gray for
+    /// the modules which are not drawn yet.
+    #[must_use]
+    pub const fn new(code: &'a QrCode) -> Self {
+        Self {
+            code,
+            module_size: 8,
+            placement_steps: 8,
+            frame_delay: 500,
+            dark_color: Rgba([0, 0, 0, 255]),
+            light_color: Rgba([255, 255, 255, 255]),
+            empty_color: Rgba([160, 160, 160, 255]),
+        }
+    }
+
+    /// Sets the size of each module in pixels.
+    #[inline]
+    pub const fn module_size(&mut self, module_size: u32) -> &mut Self {
+        self.module_size = module_size;
+        self
+    }
+
+    /// Sets the number of frames in which the data modules are placed. The
+    /// value is at least 1.
+    #[inline]
+    pub fn placement_steps(&mut self, steps: usize) -> &mut Self {
+        self.placement_steps = steps.max(1);
+        self
+    }
+
+    /// Sets the duration of each frame in milliseconds.
+    #[inline]
+    pub const fn frame_delay(&mut self, milliseconds: u32) -> &mut Self {
+        self.frame_delay = milliseconds;
+        self
+    }
+
+    /// Sets the color of dark modules.
+    #[inline]
+    pub const fn dark_color(&mut self, color: Rgba<u8>) -> &mut Self {
+        self.dark_color = color;
+        self
+    }
+
+    /// Sets the color of light modules and the quiet zone.
+    #[inline]
+    pub const fn light_color(&mut self, color: Rgba<u8>) -> &mut Self {
+        self.light_color = color;
+        self
+    }
+
+    /// Sets the color of the modules which are not drawn yet.
+    #[inline]
+    pub const fn empty_color(&mut self, color: Rgba<u8>) -> &mut Self {
+        self.empty_color = color;
+        self
+    }
+
+    /// Renders the stages of the construction as images.
+    ///
+    /// The first image shows the functional patterns, the following
+    /// [`placement_steps`](Self::placement_steps) images show the data
+    /// placement, and the last image is the final symbol.
+    #[must_use]
+    pub fn images(&self) -> Vec<RgbaImage> {
+        let code = self.code;
+        let width = code.width();
+        let colors = code.to_colors();
+        let mut functional = Canvas::new(code.version(), code.error_correction_level());
+        functional.draw_all_functional_patterns();
+        let order = functional.data_placement_order();
+        let unmasked = unmask(code, &functional).map_or_else(|| colors.clone(), |(_, c)| c);
+
+        let mut stage: Vec<_> = (0..width.as_i16() * code.height().as_i16())
+            .map(|i| functional.get(i % width.as_i16(), i / width.as_i16()))
+            .map(|module| match module {
+                Module::Empty => None,
+                module => Some(Color::from(module)),
+            })
+            .collect();
+        let mut images = Vec::with_capacity(self.placement_steps + 2);
+        images.push(self.draw(&stage));
+        let mut placed = 0;
+        for step in 1..=self.placement_steps {
+            let end = order.len() * step / self.placement_steps;
+            for &(x, y) in &order[placed..end] {
+                let i = y.as_usize() * width + x.as_usize();
+                stage[i] = Some(unmasked[i]);
+            }
+            placed = end;
+            images.push(self.draw(&stage));
+        }
+        let stage: Vec<_> = colors.into_iter().map(Some).collect();
+        images.push(self.draw(&stage));
+        images
+    }
+
+    /// Encodes the animation as a GIF which loops forever.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the GIF cannot be encoded or written.
+    pub fn write_gif<W: Write>(&self, writer: W) -> ImageResult<()> {
+        let delay = Delay::from_numer_denom_ms(self.frame_delay, 1);
+        let mut encoder = GifEncoder::new(writer);
+        encoder.set_repeat(Repeat::Infinite)?;
+        encoder.encode_frames(
+            self.images()
+                .into_iter()
+                .map(|image| Frame::from_parts(image, 0, 0, delay)),
+        )
+    }
+
+    /// Draws a stage, where [`None`] is a module which is not drawn yet.
+    fn draw(&self, stage: &[Option<Color>]) -> RgbaImage {
+        let code = self.code;
+        let quiet_zone = if code.version().is_normal() { 4 } else { 2 };
+        let (width, height) = (code.width().as_u32(), code.height().as_u32());
+        let image_width = (width + 2 * quiet_zone) * self.module_size;
+        let image_height = (height + 2 * quiet_zone) * self.module_size;
+        RgbaImage::from_fn(image_width, image_height, |x, y| {
+            let (x, y) = (x / self.module_size, y / self.module_size);
+            if !(quiet_zone..quiet_zone + width).contains(&x)
+                || !(quiet_zone..quiet_zone + height).contains(&y)
+            {
+                return self.light_color;
+            }
+            let i = (y - quiet_zone) * width + (x - quiet_zone);
+            match stage[i.as_usize()] {
+                Some(Color::Dark) => self.dark_color,
+                Some(Color::Light) => self.light_color,
+                None => self.empty_color,
+            }
+        })
+    }
+}
+
+/// Recovers the mask pattern and the colors of the modules before masking.
+///
+/// Each candidate mask pattern is applied to the final colors of the data
+/// modules, and only the right one also reproduces the format information.
+fn unmask(code: &QrCode, functional: &Canvas) -> Option<(MaskPattern, Vec<Color>)> {
+    let colors = code.to_colors();
+    let (width, height) = (code.width().as_i16(), code.height().as_i16());
+    let coords = || (0..height).flat_map(move |y| (0..width).map(move |x| (x, y)));
+    let index = |x: i16, y: i16| y.as_usize() * code.width() + x.as_usize();
+    canvas::mask_patterns(code.version())
+        .iter()
+        .find_map(|&pattern| {
+            let mut canvas = functional.clone();
+            for (x, y) in coords() {
+                let module = canvas.get_mut(x, y);
+                if *module == Module::Empty {
+                    *module = Module::Unmasked(colors[index(x, y)]);
+                }
+            }
+            canvas.apply_mask(pattern);
+            coords()
+                .all(|(x, y)| {
+                    functional.get(x, y) == Module::Empty
+                        || Color::from(canvas.get(x, y)) == colors[index(x, y)]
+                })
+                .then(|| (pattern, canvas.into_colors()))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stages() {
+        let code = QrCode::new(b"01234567").unwrap();
+        let images = Animation::new(&code)
+            .module_size(1)
+            .placement_steps(2)
+            .images();
+        assert_eq!(images.len(), 4);
+        assert!(images.iter().all(|image| image.dimensions() == (29, 29)));
+
+        let gray = Rgba([160, 160, 160, 255]);
+        let count = |image: &RgbaImage| image.pixels().filter(|p| **p == gray).count();
+        // The functional patterns leave 208 modules for the data and error
+        // correction bits.
+        assert_eq!(count(&images[0]), 208);
+        assert!(count(&images[1]) > 0 && count(&images[1]) < 208);
+        assert_eq!(count(&images[2]), 0);
+        assert_eq!(count(&images[3]), 0);
+        // The finder pattern is there from the beginning.
+        assert_eq!(images[0].get_pixel(4, 4), &Rgba([0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_unmask() {
+        for code in [
+            QrCode::new(b"01234567").unwrap(),
+            QrCode::new_micro(b"01234567").unwrap(),
+            QrCode::new_rect_micro(b"01234567").unwrap(),
+        ] {
+            let mut functional = Canvas::new(code.version(), code.error_correction_level());
+            functional.draw_all_functional_patterns();
+            let (pattern, unmasked) = unmask(&code, &functional).unwrap();
+            assert_ne!(unmasked, code.to_colors());
+
+            let mut canvas = functional.clone();
+            for (x, y) in functional.data_placement_order() {
+                let i = y.as_usize() * code.width() + x.as_usize();
+                *canvas.get_mut(x, y) = Module::Unmasked(unmasked[i]);
+            }
+            canvas.apply_mask(pattern);
+            assert_eq!(canvas.into_colors(), code.to_colors());
+        }
+    }
+}
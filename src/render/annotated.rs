@@ -0,0 +1,206 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! [SVG] rendering with one element per module.
+//!
+//! Each module is drawn as its own `<rect>` element with the `data-x`,
+//! `data-y` and `data-role` attributes, so that interactive web visualizations
+//! and editors can bind event handlers to the modules without deriving the
+//! geometry again. The role is the [`ModuleKind`] of the module in kebab case,
+//! e.g. `finder` or `error-correction`.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, render::annotated::AnnotatedSvg};
+//!
+//! let code = QrCode::new(b"Hello").unwrap();
+//! let svg_xml = AnnotatedSvg::new(&code).build();
+//! assert!(svg_xml.contains(r#"data-x="0" data-y="0" data-role="finder""#));
+//! ```
+//!
+//! [SVG]: https://www.w3.org/Graphics/SVG/
+
+use alloc::{format, string::String};
+use core::fmt::Write;
+
+use crate::{
+    QrCode, canvas::ModuleKind, cast::As, render::svg::Color, types::Color as ModuleColor,
+};
+
+/// A builder for SVG images with one element per module.
+#[derive(Clone, Copy, Debug)]
+pub struct AnnotatedSvg<'a> {
+    code: &'a QrCode,
+    module_size: u32,
+    dark_color: Color<'a>,
+    light_color: Color<'a>,
+    is_data_only: bool,
+}
+
+impl<'a> AnnotatedSvg<'a> {
+    /// Creates a new builder for the QR code.
+    ///
+    /// The defaults are 8 pixels per module, black on white, and an element
+    /// for every module.
+    #[must_use]
+    pub const fn new(code: &'a QrCode) -> Self {
+        Self {
+            code,
+            module_size: 8,
+            dark_color: Color("#000"),
+            light_color: Color("#fff"),
+            is_data_only: false,
+        }
+    }
+
+    /// Sets the size of each module in pixels.
+    #[inline]
+    pub const fn module_size(&mut self, module_size: u32) -> &mut Self {
+        self.module_size = module_size;
+        self
+    }
+
+    /// Sets the color of dark modules.
+    #[inline]
+    pub const fn dark_color(&mut self, color: Color<'a>) -> &mut Self {
+        self.dark_color = color;
+        self
+    }
+
+    /// Sets the color of light modules.
+    #[inline]
+    pub const fn light_color(&mut self, color: Color<'a>) -> &mut Self {
+        self.light_color = color;
+        self
+    }
+
+    /// Sets whether only the modules of the data and error correction
+    /// codewords and the remainder bits get their own elements. The other
+    /// modules are then drawn as a single path without attributes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, render::annotated::AnnotatedSvg};
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let svg_xml = AnnotatedSvg::new(&code).data_only(true).build();
+    /// assert!(!svg_xml.contains(r#"data-role="finder""#));
+    /// assert!(svg_xml.contains(r#"data-role="data""#));
+    /// ```
+    #[inline]
+    pub const fn data_only(&mut self, is_data_only: bool) -> &mut Self {
+        self.is_data_only = is_data_only;
+        self
+    }
+
+    /// Renders the QR code into an SVG image.
+    #[must_use]
+    pub fn build(&self) -> String {
+        let code = self.code;
+        let quiet_zone = if code.version().is_normal() { 4 } else { 2 };
+        let (width, height) = (code.width().as_u32(), code.height().as_u32());
+        let (total_width, total_height) = (width + 2 * quiet_zone, height + 2 * quiet_zone);
+        let mut svg = format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+                r#"<svg xmlns="http://www.w3.org/2000/svg" version="1.1""#,
+                r#" width="{pw}" height="{ph}" viewBox="0 0 {w} {h}""#,
+                r#" shape-rendering="crispEdges">"#,
+                r#"<path d="M0 0h{w}v{h}H0z" fill="{bg}"/>"#
+            ),
+            pw = total_width * self.module_size,
+            ph = total_height * self.module_size,
+            w = total_width,
+            h = total_height,
+            bg = self.light_color.0
+        );
+
+        let kinds = code.module_kinds();
+        let mut path = String::new();
+        let mut rects = String::new();
+        for (i, (kind, color)) in kinds.into_iter().zip(code.to_colors()).enumerate() {
+            let x = (i % code.width()).as_u32();
+            let y = (i / code.width()).as_u32();
+            let is_annotated = !self.is_data_only
+                || matches!(
+                    kind,
+                    ModuleKind::Data | ModuleKind::ErrorCorrection | ModuleKind::Remainder
+                );
+            if is_annotated {
+                write!(
+                    rects,
+                    concat!(
+                        r#"<rect x="{left}" y="{top}" width="1" height="1" fill="{fill}""#,
+                        r#" data-x="{x}" data-y="{y}" data-role="{role}"/>"#
+                    ),
+                    left = x + quiet_zone,
+                    top = y + quiet_zone,
+                    fill = color.select(self.dark_color, self.light_color).0,
+                    x = x,
+                    y = y,
+                    role = role(kind)
+                )
+                .unwrap();
+            } else if color == ModuleColor::Dark {
+                write!(
+                    path,
+                    "M{left} {top}h1v1h-1z",
+                    left = x + quiet_zone,
+                    top = y + quiet_zone
+                )
+                .unwrap();
+            }
+        }
+        if !path.is_empty() {
+            write!(svg, r#"<path fill="{}" d="{path}"/>"#, self.dark_color.0).unwrap();
+        }
+        svg.push_str(&rects);
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+/// Returns the value of the `data-role` attribute.
+const fn role(kind: ModuleKind) -> &'static str {
+    match kind {
+        ModuleKind::Finder => "finder",
+        ModuleKind::Separator => "separator",
+        ModuleKind::Timing => "timing",
+        ModuleKind::Alignment => "alignment",
+        ModuleKind::FormatInfo => "format-info",
+        ModuleKind::VersionInfo => "version-info",
+        ModuleKind::Data => "data",
+        ModuleKind::ErrorCorrection => "error-correction",
+        ModuleKind::Remainder => "remainder",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_modules() {
+        let code = QrCode::new_micro(b"01234567").unwrap();
+        let svg = AnnotatedSvg::new(&code).build();
+        assert_eq!(svg.matches("<rect ").count(), 13 * 13);
+        assert!(svg.contains(concat!(
+            r##"<rect x="9" y="2" width="1" height="1" fill="#fff""##,
+            r#" data-x="7" data-y="0" data-role="separator"/>"#
+        )));
+        assert!(!svg.contains("<path fill="));
+    }
+
+    #[test]
+    fn test_data_only() {
+        let code = QrCode::new(b"01234567").unwrap();
+        let svg = AnnotatedSvg::new(&code).data_only(true).build();
+        // 26 codewords of version 1.
+        assert_eq!(svg.matches("<rect ").count(), 26 * 8);
+        assert!(svg.contains(r##"<path fill="#000" d="M4 4h1v1h-1z"##));
+        assert!(svg.contains(r#"data-x="20" data-y="20" data-role="data"/>"#));
+    }
+}
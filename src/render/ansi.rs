@@ -0,0 +1,308 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Colored terminal rendering with ANSI escape sequences.
+//!
+//! Each character holds 2 pixels stacked vertically like
+//! [`Dense1x2`](crate::render::unicode::Dense1x2), but the colors are set with
+//! escape sequences instead of relying on the colors of the terminal, so the
+//! symbol has the right contrast on both dark and light terminals. Colors can
+//! be given as 24-bit RGB or as an index of the 256-color palette.
+//!
+//! Terminals which cannot display the half block character can use
+//! [`BlockStyle::Background`] instead, which only prints spaces with a
+//! background color.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, render::ansi::Color};
+//!
+//! let code = QrCode::new(b"Hello").unwrap();
+//! let s = code
+//!     .render::<Color>()
+//!     .dark_color(Color::Rgb(0, 0, 128))
+//!     .build();
+//! println!("{s}");
+//! ```
+
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt::Write;
+
+use crate::{
+    render::{self, Canvas as RenderCanvas, CanvasOptions, Pixel, Renderer},
+    types::Color as ModuleColor,
+};
+
+/// The upper half block, drawn with the upper pixel as the foreground and the
+/// lower pixel as the background.
+const UPPER_HALF_BLOCK: char = '\u{2580}';
+
+/// The escape sequence which resets all attributes.
+const RESET: &str = "\x1b[0m";
+
+/// A terminal color.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Color {
+    /// A 24-bit color, for terminals supporting true color.
+    Rgb(u8, u8, u8),
+
+    /// An index of the 256-color palette. The indices from 0 to 15 are the
+    /// standard colors, which depend on the theme of the terminal.
+    Palette(u8),
+}
+
+impl Pixel for Color {
+    type Image = String;
+    type Canvas = Canvas;
+
+    /// Returns black and white from the 256-color palette, which are also
+    /// supported by true color terminals.
+    #[inline]
+    fn default_color(color: ModuleColor) -> Self {
+        color.select(Self::Palette(16), Self::Palette(231))
+    }
+
+    #[inline]
+    fn default_unit_size() -> (u32, u32) {
+        (1, 1)
+    }
+
+    /// Obtains the luminance of the color. The standard colors of the palette
+    /// depend on the terminal, so they have no luminance.
+    fn luminance(self) -> Option<f64> {
+        let [r, g, b] = match self {
+            Self::Rgb(r, g, b) => [r, g, b],
+            Self::Palette(0..=15) => return None,
+            Self::Palette(index @ 16..=231) => {
+                // The 6×6×6 color cube.
+                let level = |n| if n == 0 { 0 } else { 55 + 40 * n };
+                let index = index - 16;
+                [level(index / 36), level(index / 6 % 6), level(index % 6)]
+            }
+            Self::Palette(index @ 232..=255) => {
+                let gray = 8 + 10 * (index - 232);
+                [gray, gray, gray]
+            }
+        }
+        .map(|c| f64::from(c) / 255.0);
+        Some(render::luma(r, g, b))
+    }
+}
+
+impl Color {
+    /// Writes the parameters of the SGR sequence which sets the color as the
+    /// foreground (`38`) or the background (`48`).
+    fn write_sgr(self, s: &mut String, target: u8) {
+        match self {
+            Self::Rgb(r, g, b) => write!(s, "{target};2;{r};{g};{b}"),
+            Self::Palette(index) => write!(s, "{target};5;{index}"),
+        }
+        .unwrap();
+    }
+}
+
+/// How each pixel is drawn.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum BlockStyle {
+    /// Upper half blocks with 1×2 pixels per character.
+    #[default]
+    HalfBlock,
+
+    /// Spaces with a background color, 2 characters per pixel so that the
+    /// pixels are roughly square. This does not need Unicode, and works on
+    /// terminals which draw block characters with gaps.
+    Background,
+}
+
+/// A canvas for colored terminal rendering.
+#[derive(Debug)]
+pub struct Canvas {
+    pixels: Vec<bool>,
+    width: u32,
+    dark_pixel: Color,
+    light_pixel: Color,
+    options: CanvasOptions,
+}
+
+impl RenderCanvas for Canvas {
+    type Pixel = Color;
+    type Image = String;
+
+    fn new(width: u32, height: u32, dark_pixel: Self::Pixel, light_pixel: Self::Pixel) -> Self {
+        Self {
+            pixels: vec![false; (width * height) as usize],
+            width,
+            dark_pixel,
+            light_pixel,
+            options: CanvasOptions::default(),
+        }
+    }
+
+    #[inline]
+    fn draw_dark_pixel(&mut self, x: u32, y: u32) {
+        self.pixels[(x + y * self.width) as usize] = true;
+    }
+
+    #[inline]
+    fn configure(&mut self, options: &CanvasOptions) {
+        self.options = options.clone();
+    }
+
+    fn into_image(self) -> Self::Image {
+        let color = |is_dark: bool| {
+            if is_dark {
+                self.dark_pixel
+            } else {
+                self.light_pixel
+            }
+        };
+        let rows = self.pixels.chunks_exact(self.width as usize);
+        let lines = match self.options.ansi_block_style {
+            BlockStyle::HalfBlock => rows
+                .collect::<Vec<_>>()
+                .chunks(2)
+                .map(|rows| {
+                    let mut line = String::new();
+                    let mut current = None;
+                    for (x, top) in rows[0].iter().enumerate() {
+                        // The lower half of the last line has no pixels.
+                        let bottom = rows.get(1).map(|row| color(row[x]));
+                        let colors = (color(*top), bottom);
+                        if current != Some(colors) {
+                            line.push_str("\x1b[");
+                            colors.0.write_sgr(&mut line, 38);
+                            match colors.1 {
+                                Some(bottom) => {
+                                    line.push(';');
+                                    bottom.write_sgr(&mut line, 48);
+                                }
+                                None => line.push_str(";49"),
+                            }
+                            line.push('m');
+                            current = Some(colors);
+                        }
+                        line.push(UPPER_HALF_BLOCK);
+                    }
+                    line.push_str(RESET);
+                    line
+                })
+                .collect::<Vec<_>>(),
+            BlockStyle::Background => rows
+                .map(|row| {
+                    let mut line = String::new();
+                    for run in row.chunk_by(|a, b| a == b) {
+                        line.push_str("\x1b[");
+                        color(run[0]).write_sgr(&mut line, 48);
+                        line.push('m');
+                        line.extend(core::iter::repeat_n(' ', 2 * run.len()));
+                    }
+                    line.push_str(RESET);
+                    line
+                })
+                .collect(),
+        };
+        let line_ending = self.options.line_ending.as_str();
+        let mut image = lines.join(line_ending);
+        if self.options.trailing_newline {
+            image.push_str(line_ending);
+        }
+        image
+    }
+}
+
+impl Renderer<'_, Color> {
+    /// Sets how each pixel is drawn. Default is [`BlockStyle::HalfBlock`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{
+    /// #     QrCode,
+    /// #     render::ansi::{BlockStyle, Color},
+    /// # };
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let s = code
+    ///     .render::<Color>()
+    ///     .block_style(BlockStyle::Background)
+    ///     .build();
+    /// assert_eq!(s.lines().count(), 21 + 2 * 4);
+    /// assert!(s.is_ascii());
+    /// ```
+    #[inline]
+    pub const fn block_style(&mut self, block_style: BlockStyle) -> &mut Self {
+        self.canvas_options.ansi_block_style = block_style;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::LineEnding;
+
+    const COLORS: [ModuleColor; 4] = [
+        ModuleColor::Dark,
+        ModuleColor::Light,
+        ModuleColor::Light,
+        ModuleColor::Dark,
+    ];
+
+    #[test]
+    fn test_half_block() {
+        let image = Renderer::<Color>::new(&COLORS, 2, 2, 0)
+            .dark_color(Color::Rgb(1, 2, 3))
+            .build();
+        assert_eq!(
+            image,
+            concat!(
+                "\x1b[38;2;1;2;3;48;5;231m▀",
+                "\x1b[38;5;231;48;2;1;2;3m▀",
+                "\x1b[0m"
+            )
+        );
+    }
+
+    #[test]
+    fn test_half_block_odd_height() {
+        let image = Renderer::<Color>::new(&COLORS[..1], 1, 1, 1)
+            .line_ending(LineEnding::CrLf)
+            .trailing_newline(true)
+            .build();
+        let lines = image.split_terminator("\r\n").collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                "\x1b[38;5;231;48;5;231m▀\x1b[38;5;231;48;5;16m▀\x1b[38;5;231;48;5;231m▀\x1b[0m",
+                "\x1b[38;5;231;49m▀▀▀\x1b[0m"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_background() {
+        let image = Renderer::<Color>::new(&COLORS, 2, 2, 0)
+            .block_style(BlockStyle::Background)
+            .build();
+        assert_eq!(
+            image,
+            concat!(
+                "\x1b[48;5;16m  \x1b[48;5;231m  \x1b[0m\n",
+                "\x1b[48;5;231m  \x1b[48;5;16m  \x1b[0m"
+            )
+        );
+    }
+
+    #[test]
+    fn test_luminance() {
+        assert_eq!(Color::Palette(16).luminance(), Some(0.0));
+        assert_eq!(Color::Palette(231).luminance(), Some(1.0));
+        assert_eq!(Color::Palette(7).luminance(), None);
+        assert_eq!(
+            Color::Palette(244).luminance(),
+            Color::Rgb(128, 128, 128).luminance()
+        );
+    }
+}
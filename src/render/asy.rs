@@ -0,0 +1,187 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! [Asymptote] rendering support.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, render::asy::Color};
+//!
+//! let code = QrCode::new(b"Hello").unwrap();
+//! let asy = code.render::<Color>().build();
+//! println!("{asy}");
+//! ```
+//!
+//! [Asymptote]: https://asymptote.sourceforge.io/
+
+use alloc::string::String;
+use core::{cmp, fmt::Write};
+
+use crate::{
+    render::{self, Background, Canvas as RenderCanvas, CanvasOptions, Pixel, Renderer, Unit},
+    types::Color as ModuleColor,
+};
+
+/// An Asymptote pen color (`[R, G, B]`).
+///
+/// <div class="warning">
+///
+/// Each value must be in the range of 0.0 to 1.0.
+///
+/// </div>
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct Color(pub [f64; 3]);
+
+impl Pixel for Color {
+    type Image = String;
+    type Canvas = Canvas;
+
+    #[inline]
+    fn default_color(color: ModuleColor) -> Self {
+        Self(color.select(Default::default(), [1.0; 3]))
+    }
+
+    #[inline]
+    fn luminance(self) -> Option<f64> {
+        let [r, g, b] = self.0;
+        Some(render::luma(r, g, b))
+    }
+}
+
+/// A canvas for Asymptote rendering.
+#[derive(Debug)]
+pub struct Canvas {
+    asy: String,
+    width: u32,
+    height: u32,
+    dark_pixel: Color,
+    light_pixel: Color,
+    options: CanvasOptions,
+}
+
+impl RenderCanvas for Canvas {
+    type Pixel = Color;
+    type Image = String;
+
+    #[inline]
+    fn new(width: u32, height: u32, dark_pixel: Self::Pixel, light_pixel: Self::Pixel) -> Self {
+        Self {
+            asy: String::new(),
+            width,
+            height,
+            dark_pixel,
+            light_pixel,
+            options: CanvasOptions::default(),
+        }
+    }
+
+    #[inline]
+    fn draw_dark_pixel(&mut self, x: u32, y: u32) {
+        self.draw_dark_rect(x, y, 1, 1);
+    }
+
+    #[inline]
+    fn draw_dark_rect(&mut self, left: u32, top: u32, width: u32, height: u32) {
+        let (right, bottom) = (left + width, self.height - top - height);
+        let top = self.height - top;
+        writeln!(
+            self.asy,
+            "fill(box(({left},{bottom}),({right},{top})),dark);"
+        )
+        .unwrap();
+    }
+
+    #[inline]
+    fn configure(&mut self, options: &CanvasOptions) {
+        self.options = options.clone();
+    }
+
+    fn into_image(self) -> Self::Image {
+        let (w, h) = (self.width, self.height);
+        let mut asy = String::new();
+        if let Some((size, unit)) = self.options.physical_module_size {
+            let (size, unit) = unit.to_tex_length(size, "inch");
+            let (module_width, _) = self.options.module_size;
+            let module_width = cmp::max(module_width, 1);
+            writeln!(asy, "unitsize({size}{unit}/{module_width});").unwrap();
+        }
+        let Color([fgr, fgg, fgb]) = self.dark_pixel;
+        writeln!(asy, "pen dark=rgb({fgr},{fgg},{fgb});").unwrap();
+        let bg = match self.options.background {
+            Background::Light => Some(self.light_pixel),
+            Background::None => None,
+            Background::White => Some(Color([1.0; 3])),
+        };
+        if let Some(Color([bgr, bgg, bgb])) = bg {
+            writeln!(asy, "fill(box((0,0),({w},{h})),rgb({bgr},{bgg},{bgb}));").unwrap();
+        }
+        asy.push_str(&self.asy);
+        asy
+    }
+}
+
+impl Renderer<'_, Color> {
+    /// Sets the physical size of each module, so that the picture is scaled
+    /// with `unitsize`. [`Unit::Px`] is converted into `bp`.
+    ///
+    /// By default, each pixel is 1 PostScript point (`bp`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{
+    /// #     QrCode,
+    /// #     render::{Unit, asy::Color},
+    /// # };
+    /// #
+    /// let code = QrCode::new(b"01234567").unwrap();
+    /// let asy = code
+    ///     .render::<Color>()
+    ///     .physical_module_size(0.5, Unit::Mm)
+    ///     .build();
+    /// // Each module is 8 pixels wide.
+    /// assert!(asy.starts_with("unitsize(0.5mm/8);\n"));
+    /// ```
+    #[inline]
+    pub const fn physical_module_size(&mut self, size: f64, unit: Unit) -> &mut Self {
+        self.canvas_options.physical_module_size = Some((size, unit));
+        self
+    }
+
+    /// Sets how the background is painted. Default is [`Background::Light`].
+    #[inline]
+    pub const fn background(&mut self, background: Background) -> &mut Self {
+        self.canvas_options.background = background;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let content = [
+            ModuleColor::Dark,
+            ModuleColor::Light,
+            ModuleColor::Light,
+            ModuleColor::Dark,
+        ];
+        let asy = Renderer::<Color>::new(&content, 2, 2, 1)
+            .module_dimensions(1, 1)
+            .background(Background::None)
+            .build();
+        assert_eq!(
+            asy,
+            concat!(
+                "pen dark=rgb(0,0,0);\n",
+                "fill(box((1,2),(2,3)),dark);\n",
+                "fill(box((2,1),(3,2)),dark);\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,308 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Packed 1-bit bitmap rendering.
+//!
+//! Each row of pixels is packed into bytes with the leftmost pixel in the most
+//! significant bit, which is the layout of most monochrome e-paper and OLED
+//! framebuffers. This does not need the `image` crate.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, render::bitmap::Bit};
+//!
+//! let code = QrCode::new(b"Hello").unwrap();
+//! let bitmap = code.render::<Bit>().module_dimensions(1, 1).build();
+//! assert_eq!((bitmap.width(), bitmap.height()), (29, 29));
+//! assert_eq!(bitmap.stride(), 4);
+//! // The first row of the symbol, after the 4 rows of quiet zone.
+//! assert_eq!(
+//!     bitmap.row(4),
+//!     [0b0000_1111, 0b1110_0111, 0b1011_1111, 0b1000_0000]
+//! );
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    cast::As,
+    render::{Canvas as RenderCanvas, CanvasOptions, Pixel, Renderer},
+    types::Color,
+};
+
+/// The value of a pixel in the bitmap.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Bit {
+    /// A cleared bit.
+    Zero,
+
+    /// A set bit.
+    One,
+}
+
+impl Pixel for Bit {
+    type Image = Bitmap;
+    type Canvas = Canvas;
+
+    /// Returns [`Bit::One`] for dark and [`Bit::Zero`] for light modules. Use
+    /// [`Renderer::dark_color`] and [`Renderer::light_color`] to invert this
+    /// for displays where a set bit is a lit pixel.
+    #[inline]
+    fn default_color(color: Color) -> Self {
+        color.select(Self::One, Self::Zero)
+    }
+}
+
+impl Bit {
+    /// Returns the byte with all bits set to this value.
+    const fn fill(self) -> u8 {
+        match self {
+            Self::Zero => 0x00,
+            Self::One => 0xff,
+        }
+    }
+}
+
+/// The number of bytes from the start of a row to the start of the next row.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Stride {
+    /// Each row takes as few bytes as possible.
+    #[default]
+    Packed,
+
+    /// Each row is padded to a multiple of the given number of bytes.
+    Aligned(usize),
+
+    /// Each row takes the given number of bytes, e.g. the stride of a
+    /// framebuffer which is wider than the symbol. This is raised to the
+    /// [`Stride::Packed`] stride if it is smaller.
+    Fixed(usize),
+}
+
+impl Stride {
+    /// Returns the number of bytes of a row of `width` pixels.
+    fn bytes(self, width: u32) -> usize {
+        let packed = width.as_usize().div_ceil(8);
+        match self {
+            Self::Packed => packed,
+            Self::Aligned(alignment) => packed.next_multiple_of(alignment.max(1)),
+            Self::Fixed(stride) => stride.max(packed),
+        }
+    }
+}
+
+/// A packed 1-bit bitmap.
+///
+/// The bits after the last pixel of each row have the light value.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Bitmap {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+    stride: usize,
+}
+
+impl Bitmap {
+    /// Returns the width in pixels.
+    #[must_use]
+    #[inline]
+    pub const fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height in pixels.
+    #[must_use]
+    #[inline]
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the number of bytes from the start of a row to the start of the
+    /// next row.
+    #[must_use]
+    #[inline]
+    pub const fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns the bytes of the row at `y`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y` is not less than the height.
+    #[must_use]
+    #[inline]
+    pub fn row(&self, y: u32) -> &[u8] {
+        let start = y.as_usize() * self.stride;
+        &self.data[start..start + self.stride]
+    }
+
+    /// Returns the value of the pixel at the (`x`, `y`) coordinate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the coordinate is outside of the bitmap.
+    #[must_use]
+    #[inline]
+    pub fn get(&self, x: u32, y: u32) -> Bit {
+        assert!(
+            x < self.width,
+            "the coordinate should be inside of the bitmap"
+        );
+        if self.row(y)[(x / 8).as_usize()] & (0x80 >> (x % 8)) == 0 {
+            Bit::Zero
+        } else {
+            Bit::One
+        }
+    }
+
+    /// Returns the bytes of all rows.
+    #[must_use]
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Converts the bitmap into the bytes of all rows.
+    #[must_use]
+    #[inline]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+/// A canvas for packed bitmap rendering.
+#[derive(Debug)]
+pub struct Canvas {
+    bitmap: Bitmap,
+    dark_pixel: Bit,
+    light_pixel: Bit,
+}
+
+impl RenderCanvas for Canvas {
+    type Pixel = Bit;
+    type Image = Bitmap;
+
+    fn new(width: u32, height: u32, dark_pixel: Self::Pixel, light_pixel: Self::Pixel) -> Self {
+        let stride = Stride::Packed.bytes(width);
+        Self {
+            bitmap: Bitmap {
+                data: vec![light_pixel.fill(); stride * height.as_usize()],
+                width,
+                height,
+                stride,
+            },
+            dark_pixel,
+            light_pixel,
+        }
+    }
+
+    #[inline]
+    fn draw_dark_pixel(&mut self, x: u32, y: u32) {
+        let bitmap = &mut self.bitmap;
+        let byte = &mut bitmap.data[y.as_usize() * bitmap.stride + (x / 8).as_usize()];
+        let mask = 0x80 >> (x % 8);
+        match self.dark_pixel {
+            Bit::Zero => *byte &= !mask,
+            Bit::One => *byte |= mask,
+        }
+    }
+
+    fn configure(&mut self, options: &CanvasOptions) {
+        let bitmap = &mut self.bitmap;
+        bitmap.stride = options.bitmap_stride.bytes(bitmap.width);
+        bitmap.data = vec![self.light_pixel.fill(); bitmap.stride * bitmap.height.as_usize()];
+    }
+
+    #[inline]
+    fn into_image(self) -> Self::Image {
+        self.bitmap
+    }
+}
+
+impl Renderer<'_, Bit> {
+    /// Sets the number of bytes of each row. Default is [`Stride::Packed`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{
+    /// #     QrCode,
+    /// #     render::bitmap::{Bit, Stride},
+    /// # };
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let bitmap = code
+    ///     .render::<Bit>()
+    ///     .module_dimensions(2, 2)
+    ///     .stride(Stride::Fixed(16))
+    ///     .build();
+    /// assert_eq!(bitmap.width(), 58);
+    /// assert_eq!(bitmap.as_bytes().len(), 16 * 58);
+    /// ```
+    #[inline]
+    pub const fn stride(&mut self, stride: Stride) -> &mut Self {
+        self.canvas_options.bitmap_stride = stride;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLORS: [Color; 4] = [Color::Dark, Color::Light, Color::Light, Color::Dark];
+
+    #[test]
+    fn test_packed() {
+        let bitmap = Renderer::<Bit>::new(&COLORS, 2, 2, 4)
+            .module_dimensions(1, 1)
+            .build();
+        assert_eq!(
+            (bitmap.width(), bitmap.height(), bitmap.stride()),
+            (10, 10, 2)
+        );
+        assert_eq!(bitmap.row(4), [0b0000_1000, 0b0000_0000]);
+        assert_eq!(bitmap.row(5), [0b0000_0100, 0b0000_0000]);
+        assert_eq!(bitmap.get(4, 4), Bit::One);
+        assert_eq!(bitmap.get(5, 4), Bit::Zero);
+        assert_eq!(
+            bitmap
+                .as_bytes()
+                .iter()
+                .map(|b| b.count_ones())
+                .sum::<u32>(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_inverted() {
+        let bitmap = Renderer::<Bit>::new(&COLORS, 2, 2, 0)
+            .module_dimensions(3, 1)
+            .dark_color(Bit::Zero)
+            .light_color(Bit::One)
+            .build();
+        assert_eq!(bitmap.into_bytes(), [0b0001_1111, 0b1110_0011]);
+    }
+
+    #[test]
+    fn test_stride() {
+        let mut renderer = Renderer::<Bit>::new(&COLORS, 2, 2, 0);
+        renderer.module_dimensions(1, 1);
+        for (stride, expected) in [
+            (Stride::Packed, 1),
+            (Stride::Aligned(0), 1),
+            (Stride::Aligned(4), 4),
+            (Stride::Fixed(0), 1),
+            (Stride::Fixed(3), 3),
+        ] {
+            let bitmap = renderer.stride(stride).build();
+            assert_eq!(bitmap.stride(), expected);
+            assert_eq!(bitmap.as_bytes().len(), 2 * expected);
+            assert_eq!(bitmap.row(1)[0], 0b0100_0000);
+        }
+    }
+}
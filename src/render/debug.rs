@@ -0,0 +1,436 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Debug rendering which colors each module by its kind.
+//!
+//! Each [`ModuleKind`] (finder pattern, timing pattern, data, error correction,
+//! and so on) is drawn in its own color. Dark modules use the color as is, and
+//! light modules use a pale tint of it, so both the kind and the color of
+//! every module remain visible. This helps to review the placement of the
+//! modules against the specification, especially for rMQR code.
+//!
+//! A grid of the module boundaries and axis labels with the module
+//! coordinates can be drawn on top, so that misplaced modules can be located
+//! without counting pixels.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, canvas::ModuleKind, render::debug::DebugRenderer};
+//!
+//! let code = QrCode::new(b"Hello").unwrap();
+//! let svg_xml = DebugRenderer::new(&code)
+//!     .kind_color(ModuleKind::Data, [0, 0, 255])
+//!     .build_svg();
+//! assert!(svg_xml.contains(r##"fill="#0000ff""##));
+//! ```
+
+#[cfg(feature = "image")]
+use alloc::string::ToString;
+use alloc::vec::Vec;
+#[cfg(feature = "svg")]
+use alloc::{format, string::String};
+#[cfg(feature = "image")]
+use core::cmp;
+#[cfg(feature = "svg")]
+use core::fmt::Write;
+
+#[cfg(feature = "image")]
+use image::{Rgb, RgbImage};
+
+#[cfg(feature = "svg")]
+use crate::render::Length;
+use crate::{QrCode, canvas::ModuleKind, cast::As, types::Color};
+
+/// The default colors of the module kinds.
+const DEFAULT_PALETTE: [[u8; 3]; 9] = [
+    [0xd6, 0x27, 0x28],
+    [0xff, 0x98, 0x96],
+    [0x2c, 0xa0, 0x2c],
+    [0x1f, 0x77, 0xb4],
+    [0xff, 0x7f, 0x0e],
+    [0x94, 0x67, 0xbd],
+    [0x00, 0x00, 0x00],
+    [0x17, 0xbe, 0xcf],
+    [0x7f, 0x7f, 0x7f],
+];
+
+const fn palette_index(kind: ModuleKind) -> usize {
+    match kind {
+        ModuleKind::Finder => 0,
+        ModuleKind::Separator => 1,
+        ModuleKind::Timing => 2,
+        ModuleKind::Alignment => 3,
+        ModuleKind::FormatInfo => 4,
+        ModuleKind::VersionInfo => 5,
+        ModuleKind::Data => 6,
+        ModuleKind::ErrorCorrection => 7,
+        ModuleKind::Remainder => 8,
+    }
+}
+
+/// A builder for debug images which color each module by its kind.
+#[derive(Clone, Copy, Debug)]
+pub struct DebugRenderer<'a> {
+    code: &'a QrCode,
+    module_size: u32,
+    palette: [[u8; 3]; 9],
+    has_grid: bool,
+    grid_color: [u8; 3],
+    label_interval: u32,
+}
+
+impl<'a> DebugRenderer<'a> {
+    /// Creates a new builder for the QR code.
+    ///
+    /// The default is 8 pixels per module, with a distinct color for each
+    /// module kind, and neither a grid nor axis labels.
+    #[must_use]
+    pub const fn new(code: &'a QrCode) -> Self {
+        Self {
+            code,
+            module_size: 8,
+            palette: DEFAULT_PALETTE,
+            has_grid: false,
+            grid_color: [0x80; 3],
+            label_interval: 0,
+        }
+    }
+
+    /// Sets the size of each module in pixels.
+    #[inline]
+    pub const fn module_size(&mut self, module_size: u32) -> &mut Self {
+        self.module_size = module_size;
+        self
+    }
+
+    /// Sets the color of dark modules of the kind, as `[R, G, B]`.
+    #[inline]
+    pub const fn kind_color(&mut self, kind: ModuleKind, color: [u8; 3]) -> &mut Self {
+        self.palette[palette_index(kind)] = color;
+        self
+    }
+
+    /// Sets whether to draw the boundaries of the modules.
+    #[inline]
+    pub const fn grid(&mut self, has_grid: bool) -> &mut Self {
+        self.has_grid = has_grid;
+        self
+    }
+
+    /// Sets the color of the grid and the axis labels, as `[R, G, B]`.
+    #[inline]
+    pub const fn grid_color(&mut self, color: [u8; 3]) -> &mut Self {
+        self.grid_color = color;
+        self
+    }
+
+    /// Sets the interval of the axis labels in modules. The columns and the
+    /// rows whose coordinate is a multiple of the interval are labeled in the
+    /// quiet zone above and to the left of the symbol. `0` disables the
+    /// labels.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, render::debug::DebugRenderer};
+    /// #
+    /// let code = QrCode::new(b"01234567").unwrap();
+    /// let svg_xml = DebugRenderer::new(&code).axis_labels(5).build_svg();
+    /// assert!(svg_xml.contains(r#"<text x="9.5" y="2">5</text>"#));
+    /// ```
+    #[inline]
+    pub const fn axis_labels(&mut self, interval: u32) -> &mut Self {
+        self.label_interval = interval;
+        self
+    }
+
+    /// Returns the coordinates which are labeled, up to `len`.
+    fn labels(&self, len: u32) -> impl Iterator<Item = u32> {
+        let interval = self.label_interval;
+        (0..len).filter(move |i| interval != 0 && i % interval == 0)
+    }
+
+    /// Returns the color of each module, in row major order.
+    fn module_colors(&self) -> Vec<[u8; 3]> {
+        let code = self.code;
+        let kinds = code.module_kinds();
+        kinds
+            .into_iter()
+            .zip(code.to_colors())
+            .map(|(kind, color)| {
+                let rgb = self.palette[palette_index(kind)];
+                match color {
+                    Color::Dark => rgb,
+                    Color::Light => rgb.map(tint),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the size of the quiet zone in modules.
+    const fn quiet_zone(&self) -> u32 {
+        if self.code.version().is_normal() {
+            4
+        } else {
+            2
+        }
+    }
+
+    /// Renders the QR code into an SVG image.
+    ///
+    /// Horizontally adjacent modules with the same color are merged into a
+    /// single rectangle.
+    #[cfg(feature = "svg")]
+    #[must_use]
+    pub fn build_svg(&self) -> String {
+        let code = self.code;
+        let quiet_zone = self.quiet_zone();
+        let (width, height) = (code.width().as_u32(), code.height().as_u32());
+        let (total_width, total_height) = (width + 2 * quiet_zone, height + 2 * quiet_zone);
+        let mut svg = format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+                r#"<svg xmlns="http://www.w3.org/2000/svg" version="1.1""#,
+                r#" width="{pw}" height="{ph}" viewBox="0 0 {w} {h}""#,
+                r#" shape-rendering="crispEdges">"#,
+                r##"<path d="M0 0h{w}v{h}H0z" fill="#fff"/>"##
+            ),
+            pw = total_width * self.module_size,
+            ph = total_height * self.module_size,
+            w = total_width,
+            h = total_height
+        );
+        let colors = self.module_colors();
+        for (y, row) in colors.chunks(code.width()).enumerate() {
+            let y = y.as_u32() + quiet_zone;
+            let mut x = quiet_zone;
+            for run in row.chunk_by(|a, b| a == b) {
+                let len = run.len().as_u32();
+                let [red, green, blue] = run[0];
+                write!(
+                    svg,
+                    r##"<path d="M{x} {y}h{len}v1h-{len}z" fill="#{red:02x}{green:02x}{blue:02x}"/>"##
+                )
+                .unwrap();
+                x += len;
+            }
+        }
+
+        let [red, green, blue] = self.grid_color;
+        if self.has_grid {
+            write!(
+                svg,
+                r##"<path fill="none" stroke="#{red:02x}{green:02x}{blue:02x}" stroke-width="0.05" d=""##
+            )
+            .unwrap();
+            for i in 0..=height {
+                write!(svg, "M{quiet_zone} {y}h{width}", y = quiet_zone + i).unwrap();
+            }
+            for i in 0..=width {
+                write!(svg, "M{x} {quiet_zone}v{height}", x = quiet_zone + i).unwrap();
+            }
+            svg.push_str(r#""/>"#);
+        }
+        if self.label_interval != 0 {
+            write!(
+                svg,
+                concat!(
+                    r##"<g fill="#{red:02x}{green:02x}{blue:02x}" font-family="monospace""##,
+                    r##" font-size="1" dominant-baseline="central">"##,
+                    r#"<g text-anchor="middle">"#
+                ),
+                red = red,
+                green = green,
+                blue = blue
+            )
+            .unwrap();
+            let center = f64::from(quiet_zone) / 2.0;
+            for i in self.labels(width) {
+                let x = Length(f64::from(quiet_zone + i) + 0.5);
+                write!(
+                    svg,
+                    r#"<text x="{x}" y="{y}">{i}</text>"#,
+                    y = Length(center)
+                )
+                .unwrap();
+            }
+            svg.push_str(r#"</g><g text-anchor="end">"#);
+            for i in self.labels(height) {
+                let y = Length(f64::from(quiet_zone + i) + 0.5);
+                let x = Length(f64::from(quiet_zone) - 0.25);
+                write!(svg, r#"<text x="{x}" y="{y}">{i}</text>"#).unwrap();
+            }
+            svg.push_str("</g></g>");
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+
+    /// Renders the QR code into a raster image.
+    #[cfg(feature = "image")]
+    #[must_use]
+    pub fn build_image(&self) -> RgbImage {
+        let code = self.code;
+        let quiet_zone = self.quiet_zone();
+        let (width, height) = (code.width().as_u32(), code.height().as_u32());
+        let module_size = self.module_size;
+        let colors = self.module_colors();
+        let mut image = RgbImage::from_fn(
+            (width + 2 * quiet_zone) * module_size,
+            (height + 2 * quiet_zone) * module_size,
+            |x, y| {
+                let (x, y) = (x / module_size, y / module_size);
+                if !(quiet_zone..quiet_zone + width).contains(&x)
+                    || !(quiet_zone..quiet_zone + height).contains(&y)
+                {
+                    return Rgb([255; 3]);
+                }
+                let i = (y - quiet_zone) * width + (x - quiet_zone);
+                Rgb(colors[i.as_usize()])
+            },
+        );
+
+        let grid_color = Rgb(self.grid_color);
+        let (left, top) = (quiet_zone * module_size, quiet_zone * module_size);
+        let (right, bottom) = (left + width * module_size, top + height * module_size);
+        if self.has_grid {
+            for y in top..=bottom {
+                for x in left..=right {
+                    if (x - left) % module_size == 0 || (y - top) % module_size == 0 {
+                        image.put_pixel(x, y, grid_color);
+                    }
+                }
+            }
+        }
+        // Each digit is 4 pixels wide including the spacing, and 5 pixels high.
+        let scale = cmp::max(module_size / 8, 1);
+        let label_width = |i: u32| (i.checked_ilog10().unwrap_or_default() + 1) * 4 * scale - scale;
+        for i in self.labels(width) {
+            let x = (left + i * module_size + module_size / 2).saturating_sub(label_width(i) / 2);
+            let y = (top / 2).saturating_sub(5 * scale / 2);
+            draw_number(&mut image, x, y, i, scale, grid_color);
+        }
+        for i in self.labels(height) {
+            let x = left.saturating_sub(label_width(i) + scale * 2);
+            let y = (top + i * module_size + module_size / 2).saturating_sub(5 * scale / 2);
+            draw_number(&mut image, x, y, i, scale, grid_color);
+        }
+        image
+    }
+}
+
+/// The digits of the axis labels in raster images, as 3×5 bitmaps in row
+/// major order, where the most significant bit is the top-left pixel.
+#[cfg(feature = "image")]
+const DIGITS: [u16; 10] = [
+    0b111_101_101_101_111,
+    0b010_110_010_010_111,
+    0b111_001_111_100_111,
+    0b111_001_111_001_111,
+    0b101_101_111_001_001,
+    0b111_100_111_001_111,
+    0b111_100_111_101_111,
+    0b111_001_001_010_010,
+    0b111_101_111_101_111,
+    0b111_101_111_001_111,
+];
+
+/// Draws a number with the top-left corner at (x, y). Pixels outside the
+/// image are skipped.
+#[cfg(feature = "image")]
+fn draw_number(image: &mut RgbImage, x: u32, y: u32, number: u32, scale: u32, color: Rgb<u8>) {
+    let digits = number.to_string();
+    for (n, digit) in digits.bytes().enumerate() {
+        let bitmap = DIGITS[usize::from(digit - b'0')];
+        for (i, j) in (0..5).flat_map(|j| (0..3).map(move |i| (i, j))) {
+            if bitmap & 1 << (14 - (j * 3 + i)) == 0 {
+                continue;
+            }
+            let left = x + (n.as_u32() * 4 + i) * scale;
+            let top = y + j * scale;
+            for (dx, dy) in (0..scale).flat_map(|dy| (0..scale).map(move |dx| (dx, dy))) {
+                if left + dx < image.width() && top + dy < image.height() {
+                    image.put_pixel(left + dx, top + dy, color);
+                }
+            }
+        }
+    }
+}
+
+/// Mixes the color component with 75% of white.
+const fn tint(c: u8) -> u8 {
+    255 - (255 - c) / 4
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "svg")]
+    #[test]
+    fn test_svg() {
+        let code = QrCode::new_rect_micro(b"01234567").unwrap();
+        let svg = DebugRenderer::new(&code).module_size(1).build_svg();
+        assert!(svg.contains(r#"width="31" height="15""#));
+        // The first row starts with the finder pattern and the separator.
+        assert!(svg.contains(concat!(
+            r##"<path d="M2 2h7v1h-7z" fill="#d62728"/>"##,
+            r##"<path d="M9 2h1v1h-1z" fill="#ffe6e5"/>"##
+        )));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_image() {
+        let code = QrCode::new(b"01234567").unwrap();
+        let image = DebugRenderer::new(&code)
+            .module_size(2)
+            .kind_color(ModuleKind::Timing, [0, 255, 0])
+            .build_image();
+        assert_eq!(image.dimensions(), (58, 58));
+        // Dark and light modules of the timing pattern.
+        assert_eq!(image.get_pixel(2 * 12, 2 * 10), &Rgb([0, 255, 0]));
+        assert_eq!(image.get_pixel(2 * 13, 2 * 10), &Rgb([192, 255, 192]));
+        assert_eq!(image.get_pixel(0, 0), &Rgb([255, 255, 255]));
+    }
+
+    #[cfg(feature = "svg")]
+    #[test]
+    fn test_svg_grid() {
+        let code = QrCode::new_micro(b"1").unwrap();
+        let svg = DebugRenderer::new(&code)
+            .module_size(1)
+            .grid(true)
+            .axis_labels(10)
+            .build_svg();
+        assert!(svg.contains(r##"stroke="#808080" stroke-width="0.05" d="M2 2h13M2 3h13"##));
+        assert!(svg.contains("M15 2v13\"/>"));
+        assert!(svg.contains(concat!(
+            r#"<g text-anchor="middle"><text x="2.5" y="1">0</text>"#,
+            r#"<text x="12.5" y="1">10</text></g>"#,
+            r#"<g text-anchor="end"><text x="1.75" y="2.5">0</text>"#,
+            r#"<text x="1.75" y="12.5">10</text></g>"#
+        )));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_image_grid() {
+        let code = QrCode::new(b"01234567").unwrap();
+        let image = DebugRenderer::new(&code)
+            .grid(true)
+            .grid_color([255, 0, 0])
+            .axis_labels(20)
+            .build_image();
+        let red = Rgb([255, 0, 0]);
+        // The corners of the grid.
+        assert_eq!(image.get_pixel(32, 32), &red);
+        assert_eq!(image.get_pixel(200, 200), &red);
+        assert_ne!(image.get_pixel(33, 33), &red);
+        // The label "0" of the first column is drawn above the symbol.
+        assert_eq!(image.get_pixel(35, 14), &red);
+        assert_eq!(image.get_pixel(36, 15), &Rgb([255, 255, 255]));
+    }
+}
@@ -0,0 +1,233 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Dotted art style [SVG] rendering.
+//!
+//! Dark data modules are drawn as dots of varying radius, slightly moved from
+//! the center of the module, while the finder, alignment and timing patterns
+//! and the format and version information are drawn as regular squares. The
+//! variation is pseudo-random but reproducible from a seed.
+//!
+//! Every dot covers at least the minimum ink coverage of its module and stays
+//! inside the module, so neighboring modules are never touched.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, render::dotted::Dotted};
+//!
+//! let code = QrCode::new(b"Hello").unwrap();
+//! let svg_xml = Dotted::new(&code).seed(42).build();
+//! println!("{svg_xml}");
+//! ```
+//!
+//! [SVG]: https://www.w3.org/Graphics/SVG/
+
+use alloc::{format, string::String};
+use core::{f64::consts::PI, fmt::Write};
+
+use crate::{
+    QrCode,
+    cast::As,
+    render::{Length, svg::Color},
+    rng::SplitMix64,
+    types::Color as ModuleColor,
+};
+
+/// The largest ink coverage of a dot, which is reached when the diameter of
+/// the dot equals the size of the module.
+pub const MAX_COVERAGE: f64 = PI / 4.0;
+
+/// A builder for dotted art style SVG images.
+#[derive(Clone, Copy, Debug)]
+pub struct Dotted<'a> {
+    code: &'a QrCode,
+    module_size: u32,
+    dark_color: Color<'a>,
+    light_color: Color<'a>,
+    min_coverage: f64,
+    jitter: f64,
+    seed: u64,
+}
+
+impl<'a> Dotted<'a> {
+    /// Creates a new builder for the QR code.
+    ///
+    /// The defaults are 8 pixels per module, black on white, a minimum ink
+    /// coverage of 0.4 and a jitter of 0.1 modules.
+    #[must_use]
+    pub const fn new(code: &'a QrCode) -> Self {
+        Self {
+            code,
+            module_size: 8,
+            dark_color: Color("#000"),
+            light_color: Color("#fff"),
+            min_coverage: 0.4,
+            jitter: 0.1,
+            seed: 0,
+        }
+    }
+
+    /// Sets the size of each module in pixels.
+    #[inline]
+    pub const fn module_size(&mut self, module_size: u32) -> &mut Self {
+        self.module_size = module_size;
+        self
+    }
+
+    /// Sets the color of dark modules.
+    #[inline]
+    pub const fn dark_color(&mut self, color: Color<'a>) -> &mut Self {
+        self.dark_color = color;
+        self
+    }
+
+    /// Sets the color of light modules.
+    #[inline]
+    pub const fn light_color(&mut self, color: Color<'a>) -> &mut Self {
+        self.light_color = color;
+        self
+    }
+
+    /// Sets the minimum fraction of each dark data module which is covered by
+    /// its dot. The value is clamped to between 0.1 and [`MAX_COVERAGE`].
+    #[inline]
+    pub const fn min_coverage(&mut self, min_coverage: f64) -> &mut Self {
+        self.min_coverage = min_coverage.clamp(0.1, MAX_COVERAGE);
+        self
+    }
+
+    /// Sets the largest distance in modules by which a dot may be moved from
+    /// the center of its module. Dots are never moved outside their module.
+    #[inline]
+    pub const fn jitter(&mut self, jitter: f64) -> &mut Self {
+        self.jitter = jitter.clamp(0.0, 0.5);
+        self
+    }
+
+    /// Sets the seed of the pseudo-random variation.
+    #[inline]
+    pub const fn seed(&mut self, seed: u64) -> &mut Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Renders the QR code into an SVG image.
+    #[must_use]
+    pub fn build(&self) -> String {
+        let code = self.code;
+        let quiet_zone = if code.version().is_normal() { 4 } else { 2 };
+        let (width, height) = (code.width().as_u32(), code.height().as_u32());
+        let (total_width, total_height) = (width + 2 * quiet_zone, height + 2 * quiet_zone);
+        let mut svg = format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+                r#"<svg xmlns="http://www.w3.org/2000/svg" version="1.1""#,
+                r#" width="{pw}" height="{ph}" viewBox="0 0 {w} {h}">"#,
+                r#"<path d="M0 0h{w}v{h}H0z" fill="{bg}"/>"#,
+                r#"<path fill="{fg}" d=""#
+            ),
+            pw = total_width * self.module_size,
+            ph = total_height * self.module_size,
+            w = total_width,
+            h = total_height,
+            fg = self.dark_color.0,
+            bg = self.light_color.0
+        );
+
+        let colors = code.to_colors();
+        let functional = code.functional_modules();
+        let mut dots = String::new();
+        let mut rng = SplitMix64(self.seed);
+        let min_radius = (self.min_coverage / PI).sqrt();
+        for (i, (color, is_functional)) in colors.iter().zip(functional).enumerate() {
+            if *color == ModuleColor::Light {
+                continue;
+            }
+            let x = (i % code.width()).as_u32() + quiet_zone;
+            let y = (i / code.width()).as_u32() + quiet_zone;
+            if is_functional {
+                write!(svg, "M{x} {y}h1v1h-1z").unwrap();
+                continue;
+            }
+            let radius = (0.5 - min_radius).mul_add(rng.next_f64(), min_radius);
+            let max_offset = self.jitter.min(0.5 - radius);
+            let dx = max_offset * rng.next_f64().mul_add(2.0, -1.0);
+            let dy = max_offset * rng.next_f64().mul_add(2.0, -1.0);
+            write!(
+                dots,
+                r#"<circle cx="{}" cy="{}" r="{}"/>"#,
+                Length(f64::from(x) + 0.5 + dx),
+                Length(f64::from(y) + 0.5 + dy),
+                Length(radius)
+            )
+            .unwrap();
+        }
+        write!(
+            svg,
+            r#""/><g fill="{}">{dots}</g></svg>"#,
+            self.dark_color.0
+        )
+        .unwrap();
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses the circles as `(cx, cy, r)`.
+    fn circles(svg: &str) -> alloc::vec::Vec<(f64, f64, f64)> {
+        svg.split("<circle ")
+            .skip(1)
+            .map(|c| {
+                let attr = |name: &str| {
+                    let start = c.find(&format!("{name}=\"")).unwrap() + name.len() + 2;
+                    let len = c[start..].find('"').unwrap();
+                    c[start..start + len].parse::<f64>().unwrap()
+                };
+                (attr("cx"), attr("cy"), attr("r"))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_dots_stay_in_modules() {
+        let code = QrCode::new(b"https://example.com/dotted").unwrap();
+        let svg = Dotted::new(&code).jitter(0.5).min_coverage(0.3).build();
+        let circles = circles(&svg);
+        assert!(!circles.is_empty());
+        let min_radius = (0.3 / PI).sqrt() - 1e-4;
+        for (cx, cy, r) in circles {
+            assert!(r >= min_radius && r <= 0.5);
+            assert!(cx - r >= cx.floor() - 1e-4 && cx + r <= cx.floor() + 1.0 + 1e-4);
+            assert!(cy - r >= cy.floor() - 1e-4 && cy + r <= cy.floor() + 1.0 + 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_functional_patterns_are_squares() {
+        let code = QrCode::new(b"01234567").unwrap();
+        let svg = Dotted::new(&code).build();
+        // The top-left corner of the finder pattern.
+        assert!(svg.contains(r##"<path fill="#000" d="M4 4h1v1h-1z"##));
+        let dark = code
+            .to_colors()
+            .iter()
+            .filter(|c| **c == ModuleColor::Dark)
+            .count();
+        let squares = svg.matches("h1v1h-1z").count();
+        assert_eq!(squares + circles(&svg).len(), dark);
+    }
+
+    #[test]
+    fn test_seed() {
+        let code = QrCode::new_micro(b"01234567").unwrap();
+        let mut dotted = Dotted::new(&code);
+        let a = dotted.seed(1).build();
+        assert_eq!(a, dotted.seed(1).build());
+        assert_ne!(a, dotted.seed(2).build());
+    }
+}
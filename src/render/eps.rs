@@ -16,11 +16,13 @@
 //!
 //! [EPS]: https://en.wikipedia.org/wiki/Encapsulated_PostScript
 
-use alloc::{format, string::String};
-use core::fmt::Write;
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Write};
 
 use crate::{
-    render::{Canvas as RenderCanvas, Pixel},
+    render::{
+        self, Background, Canvas as RenderCanvas, CanvasOptions, Pixel, Renderer, WriteCanvas,
+    },
     types::Color as ModuleColor,
 };
 
@@ -32,6 +34,7 @@ use crate::{
 ///
 /// </div>
 #[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct Color(pub [f64; 3]);
 
 impl Pixel for Color {
@@ -42,13 +45,23 @@ impl Pixel for Color {
     fn default_color(color: ModuleColor) -> Self {
         Self(color.select(Default::default(), [1.0; 3]))
     }
+
+    #[inline]
+    fn luminance(self) -> Option<f64> {
+        let [r, g, b] = self.0;
+        Some(render::luma(r, g, b))
+    }
 }
 
 /// A canvas for EPS rendering.
 #[derive(Debug)]
 pub struct Canvas {
-    eps: String,
+    rects: Vec<(u32, u32, u32, u32)>,
+    width: u32,
     height: u32,
+    dark_pixel: Color,
+    light_pixel: Color,
+    background: Background,
 }
 
 impl RenderCanvas for Canvas {
@@ -57,28 +70,14 @@ impl RenderCanvas for Canvas {
 
     #[inline]
     fn new(width: u32, height: u32, dark_pixel: Self::Pixel, light_pixel: Self::Pixel) -> Self {
-        let eps = format!(
-            concat!(
-                "%!PS-Adobe-3.0 EPSF-3.0\n",
-                "%%BoundingBox: 0 0 {w} {h}\n",
-                "%%Pages: 1\n",
-                "%%EndComments\n",
-                "gsave\n",
-                "{bgr} {bgg} {bgb} setrgbcolor\n",
-                "0 0 {w} {h} rectfill\n",
-                "grestore\n",
-                "{fgr} {fgg} {fgb} setrgbcolor\n"
-            ),
-            w = width,
-            h = height,
-            fgr = dark_pixel.0[0],
-            fgg = dark_pixel.0[1],
-            fgb = dark_pixel.0[2],
-            bgr = light_pixel.0[0],
-            bgg = light_pixel.0[1],
-            bgb = light_pixel.0[2]
-        );
-        Self { eps, height }
+        Self {
+            rects: Vec::new(),
+            width,
+            height,
+            dark_pixel,
+            light_pixel,
+            background: Background::default(),
+        }
     }
 
     #[inline]
@@ -88,13 +87,76 @@ impl RenderCanvas for Canvas {
 
     #[inline]
     fn draw_dark_rect(&mut self, left: u32, top: u32, width: u32, height: u32) {
-        let bottom = self.height - top;
-        writeln!(self.eps, "{left} {bottom} {width} {height} rectfill").unwrap();
+        self.rects.push((left, self.height - top, width, height));
+    }
+
+    #[inline]
+    fn configure(&mut self, options: &CanvasOptions) {
+        self.background = options.background;
     }
 
     #[inline]
-    fn into_image(mut self) -> Self::Image {
-        self.eps.push_str("%%EOF");
-        self.eps
+    fn into_image(self) -> Self::Image {
+        render::write_image_to_string(self)
+    }
+}
+
+impl WriteCanvas for Canvas {
+    fn write_image<W: Write + ?Sized>(self, eps: &mut W) -> fmt::Result {
+        let (w, h) = (self.width, self.height);
+        write!(
+            eps,
+            concat!(
+                "%!PS-Adobe-3.0 EPSF-3.0\n",
+                "%%BoundingBox: 0 0 {w} {h}\n",
+                "%%Pages: 1\n",
+                "%%EndComments\n",
+            ),
+            w = w,
+            h = h
+        )?;
+        let bg = match self.background {
+            Background::Light => Some(self.light_pixel),
+            Background::None => None,
+            Background::White => Some(Color([1.0; 3])),
+        };
+        if let Some(Color([bgr, bgg, bgb])) = bg {
+            write!(
+                eps,
+                "gsave\n{bgr} {bgg} {bgb} setrgbcolor\n0 0 {w} {h} rectfill\ngrestore\n"
+            )?;
+        }
+        let Color([fgr, fgg, fgb]) = self.dark_pixel;
+        writeln!(eps, "{fgr} {fgg} {fgb} setrgbcolor")?;
+        for (left, bottom, width, height) in self.rects {
+            writeln!(eps, "{left} {bottom} {width} {height} rectfill")?;
+        }
+        eps.write_str("%%EOF")
+    }
+}
+
+impl Renderer<'_, Color> {
+    /// Sets how the background is painted. Default is [`Background::Light`].
+    ///
+    /// With [`Background::None`], the background `rectfill` is omitted, so the
+    /// code can be placed over pre-printed stock. [`Background::White`] paints
+    /// an opaque white background instead of the light color.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{
+    /// #     QrCode,
+    /// #     render::{Background, eps::Color},
+    /// # };
+    /// #
+    /// let code = QrCode::new(b"01234567").unwrap();
+    /// let eps = code.render::<Color>().background(Background::None).build();
+    /// assert!(!eps.contains("gsave"));
+    /// ```
+    #[inline]
+    pub const fn background(&mut self, background: Background) -> &mut Self {
+        self.canvas_options.background = background;
+        self
     }
 }
@@ -0,0 +1,306 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! [HTML] rendering support.
+//!
+//! The output is a self-contained HTML snippet which only uses inline styles,
+//! so that it survives email clients which strip SVG, external images and
+//! style sheets. Horizontally adjacent dark modules are merged into a single
+//! table cell or `<div>`.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, render::html::Color};
+//!
+//! let code = QrCode::new(b"Hello").unwrap();
+//! let html = code.render::<Color>().module_dimensions(4, 4).build();
+//! assert!(html.starts_with("<table "));
+//! ```
+//!
+//! [HTML]: https://html.spec.whatwg.org/
+
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::Write;
+
+use crate::{
+    cast::As,
+    render::{self, Canvas as RenderCanvas, CanvasOptions, Pixel, Renderer},
+    types::Color as ModuleColor,
+};
+
+/// A CSS color.
+///
+/// <div class="warning">
+///
+/// The color value must comply with the W3C's [CSS Color Module Level 4].
+/// Email clients often only support the `#rrggbb` form.
+///
+/// </div>
+///
+/// [CSS Color Module Level 4]: https://www.w3.org/TR/css-color-4/
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Color<'a>(pub &'a str);
+
+impl<'a> Pixel for Color<'a> {
+    type Image = String;
+    type Canvas = Canvas<'a>;
+
+    #[inline]
+    fn default_color(color: ModuleColor) -> Self {
+        Color(color.select("#000000", "#ffffff"))
+    }
+
+    /// Obtains the luminance of the color if it is in the `#rgb` or `#rrggbb`
+    /// form.
+    #[inline]
+    fn luminance(self) -> Option<f64> {
+        render::css_luminance(self.0)
+    }
+}
+
+/// How the modules are laid out.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Layout {
+    /// A `<table>` with a cell for each run of modules of the same color. This
+    /// is the most widely supported layout in email clients.
+    #[default]
+    Table,
+
+    /// A `<div>` with a light background, containing an absolutely positioned
+    /// `<div>` for each run of dark modules. This is smaller than a table, but
+    /// some email clients drop the positioning.
+    Divs,
+}
+
+/// A canvas for HTML rendering.
+#[derive(Debug)]
+pub struct Canvas<'a> {
+    width: u32,
+    height: u32,
+    dark_pixel: Color<'a>,
+    light_pixel: Color<'a>,
+    rects: Vec<(u32, u32, u32, u32)>,
+    options: CanvasOptions,
+}
+
+impl<'a> RenderCanvas for Canvas<'a> {
+    type Pixel = Color<'a>;
+    type Image = String;
+
+    #[inline]
+    fn new(width: u32, height: u32, dark_pixel: Self::Pixel, light_pixel: Self::Pixel) -> Self {
+        Self {
+            width,
+            height,
+            dark_pixel,
+            light_pixel,
+            rects: Vec::new(),
+            options: CanvasOptions::default(),
+        }
+    }
+
+    #[inline]
+    fn draw_dark_pixel(&mut self, x: u32, y: u32) {
+        self.draw_dark_rect(x, y, 1, 1);
+    }
+
+    /// Draws a dark rectangle, extending the previous one if it ends where
+    /// this one starts on the same rows.
+    #[inline]
+    fn draw_dark_rect(&mut self, left: u32, top: u32, width: u32, height: u32) {
+        if let Some((l, t, w, h)) = self.rects.last_mut() {
+            if *t == top && *h == height && *l + *w == left {
+                *w += width;
+                return;
+            }
+        }
+        self.rects.push((left, top, width, height));
+    }
+
+    #[inline]
+    fn configure(&mut self, options: &CanvasOptions) {
+        self.options = options.clone();
+    }
+
+    fn into_image(self) -> Self::Image {
+        let mut html = match self.options.html_layout {
+            Layout::Table => self.table(),
+            Layout::Divs => self.divs(),
+        };
+        if self.options.trailing_newline {
+            html.push_str(self.options.line_ending.as_str());
+        }
+        html
+    }
+}
+
+impl Canvas<'_> {
+    /// Lays out the modules as a table with a row for each row of modules.
+    fn table(&self) -> String {
+        let (mw, mh) = self.options.module_size;
+        let (mw, mh) = (mw.max(1), mh.max(1));
+        let (columns, rows) = (self.width / mw, self.height / mh);
+        let mut is_dark = vec![false; (columns * rows).as_usize()];
+        for &(left, top, width, height) in &self.rects {
+            for y in top / mh..(top + height) / mh {
+                let row = (y * columns).as_usize();
+                is_dark[row + (left / mw).as_usize()..row + ((left + width) / mw).as_usize()]
+                    .fill(true);
+            }
+        }
+
+        let mut html = format!(
+            concat!(
+                r#"<table cellpadding="0" cellspacing="0" border="0""#,
+                r#" style="border-collapse:collapse;border-spacing:0;"#,
+                r#"width:{width}px;height:{height}px">"#
+            ),
+            width = columns * mw,
+            height = rows * mh
+        );
+        for row in is_dark.chunks_exact(columns.as_usize()) {
+            write!(html, r#"<tr style="height:{mh}px">"#).unwrap();
+            for run in row.chunk_by(|a, b| a == b) {
+                let color = if run[0] {
+                    self.dark_pixel
+                } else {
+                    self.light_pixel
+                };
+                let span = run.len().as_u32();
+                if span > 1 {
+                    write!(html, r#"<td colspan="{span}""#).unwrap();
+                } else {
+                    html.push_str("<td");
+                }
+                write!(
+                    html,
+                    r#" style="width:{width}px;height:{mh}px;padding:0;background-color:{color}"></td>"#,
+                    width = span * mw,
+                    color = color.0
+                )
+                .unwrap();
+            }
+            html.push_str("</tr>");
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    /// Lays out the dark modules as absolutely positioned boxes over a light
+    /// box.
+    fn divs(&self) -> String {
+        let mut html = format!(
+            r#"<div style="position:relative;width:{}px;height:{}px;background-color:{}">"#,
+            self.width, self.height, self.light_pixel.0
+        );
+        for &(left, top, width, height) in &self.rects {
+            write!(
+                html,
+                concat!(
+                    r#"<div style="position:absolute;left:{left}px;top:{top}px;"#,
+                    r#"width:{width}px;height:{height}px;background-color:{color}"></div>"#
+                ),
+                left = left,
+                top = top,
+                width = width,
+                height = height,
+                color = self.dark_pixel.0
+            )
+            .unwrap();
+        }
+        html.push_str("</div>");
+        html
+    }
+}
+
+impl<'a> Renderer<'a, Color<'a>> {
+    /// Sets how the modules are laid out. Default is [`Layout::Table`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{
+    /// #     QrCode,
+    /// #     render::html::{Color, Layout},
+    /// # };
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let html = code.render::<Color>().layout(Layout::Divs).build();
+    /// assert!(html.starts_with(r#"<div style="position:relative;width:232px;height:232px;"#));
+    /// ```
+    #[inline]
+    pub const fn layout(&mut self, layout: Layout) -> &mut Self {
+        self.canvas_options.html_layout = layout;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLORS: [ModuleColor; 4] = [
+        ModuleColor::Dark,
+        ModuleColor::Dark,
+        ModuleColor::Light,
+        ModuleColor::Dark,
+    ];
+
+    #[test]
+    fn test_table() {
+        let html = Renderer::<Color<'_>>::new(&COLORS, 2, 2, 1)
+            .module_dimensions(2, 3)
+            .build();
+        let cell = |span: u32, color: &str| {
+            let colspan = if span > 1 {
+                format!(r#" colspan="{span}""#)
+            } else {
+                String::new()
+            };
+            format!(
+                r#"<td{colspan} style="width:{}px;height:3px;padding:0;background-color:{color}"></td>"#,
+                span * 2
+            )
+        };
+        let row = |cells: &[(u32, &str)]| {
+            let cells = cells
+                .iter()
+                .map(|&(span, color)| cell(span, color))
+                .collect::<String>();
+            format!(r#"<tr style="height:3px">{cells}</tr>"#)
+        };
+        let expected = [
+            String::from(concat!(
+                r#"<table cellpadding="0" cellspacing="0" border="0""#,
+                r#" style="border-collapse:collapse;border-spacing:0;width:8px;height:12px">"#
+            )),
+            row(&[(4, "#ffffff")]),
+            row(&[(1, "#ffffff"), (2, "#000000"), (1, "#ffffff")]),
+            row(&[(2, "#ffffff"), (1, "#000000"), (1, "#ffffff")]),
+            row(&[(4, "#ffffff")]),
+            String::from("</table>"),
+        ]
+        .concat();
+        assert_eq!(html, expected);
+    }
+
+    #[test]
+    fn test_divs() {
+        let html = Renderer::<Color<'_>>::new(&COLORS, 2, 2, 0)
+            .module_dimensions(2, 2)
+            .dark_color(Color("navy"))
+            .layout(Layout::Divs)
+            .build();
+        assert_eq!(
+            html,
+            concat!(
+                r#"<div style="position:relative;width:4px;height:4px;background-color:#ffffff">"#,
+                r#"<div style="position:absolute;left:0px;top:0px;width:4px;height:2px;background-color:navy"></div>"#,
+                r#"<div style="position:absolute;left:2px;top:2px;width:2px;height:2px;background-color:navy"></div>"#,
+                "</div>"
+            )
+        );
+    }
+}
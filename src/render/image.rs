@@ -21,11 +21,12 @@
 
 use alloc::vec::Vec;
 
-use image::{ImageBuffer, Luma, LumaA, Primitive, Rgb, Rgba};
+use image::{ImageBuffer, Luma, LumaA, Primitive, Rgb, Rgba, imageops};
 
 use crate::{
-    render::{Canvas, Pixel},
-    types::Color,
+    cast::As,
+    render::{Canvas, ModuleShape, Pixel, Renderer},
+    types::{Color, QrError, QrResult, Region},
 };
 
 impl<S> Pixel for Luma<S>
@@ -41,6 +42,11 @@ where
         let p = color.select(S::zero(), S::max_value());
         Self([p])
     }
+
+    #[inline]
+    fn luminance(self) -> Option<f64> {
+        luminance(self)
+    }
 }
 
 impl<S> Pixel for LumaA<S>
@@ -56,6 +62,11 @@ where
         let p = color.select(S::zero(), S::max_value());
         Self([p, S::max_value()])
     }
+
+    #[inline]
+    fn luminance(self) -> Option<f64> {
+        luminance(self)
+    }
 }
 
 impl<S> Pixel for Rgb<S>
@@ -71,6 +82,11 @@ where
         let p = color.select(S::zero(), S::max_value());
         Self([p, p, p])
     }
+
+    #[inline]
+    fn luminance(self) -> Option<f64> {
+        luminance(self)
+    }
 }
 
 impl<S> Pixel for Rgba<S>
@@ -86,6 +102,22 @@ where
         let p = color.select(S::zero(), S::max_value());
         Self([p, p, p, S::max_value()])
     }
+
+    #[inline]
+    fn luminance(self) -> Option<f64> {
+        luminance(self)
+    }
+}
+
+/// Computes the luma of the pixel, normalized to 0.0–1.0.
+fn luminance<P, S>(pixel: P) -> Option<f64>
+where
+    P: image::Pixel<Subpixel = S>,
+    S: Primitive,
+{
+    let luma = image::Pixel::to_luma(&pixel).0[0].to_f64()?;
+    let max = S::DEFAULT_MAX_VALUE.to_f64()?;
+    Some(luma / max)
 }
 
 impl<P: image::Pixel + 'static> Canvas for (P, ImageBuffer<P, Vec<P::Subpixel>>) {
@@ -105,16 +137,176 @@ impl<P: image::Pixel + 'static> Canvas for (P, ImageBuffer<P, Vec<P::Subpixel>>)
         self.1.put_pixel(x, y, self.0);
     }
 
+    #[inline]
+    fn draw_pixel(&mut self, x: u32, y: u32, pixel: Self::Pixel) {
+        self.1.put_pixel(x, y, pixel);
+    }
+
+    #[inline]
+    fn draw_rect(&mut self, left: u32, top: u32, width: u32, height: u32, pixel: Self::Pixel) {
+        for y in top..(top + height) {
+            for x in left..(left + width) {
+                self.draw_pixel(x, y, pixel);
+            }
+        }
+    }
+
     #[inline]
     fn into_image(self) -> Self::Image {
         self.1
     }
 }
 
+impl<P> Renderer<'_, P>
+where
+    P: Pixel<Image = ImageBuffer<P, Vec<<P as image::Pixel>::Subpixel>>> + image::Pixel,
+{
+    /// Sets the shape of each dark module. Default is [`ModuleShape::Square`].
+    ///
+    /// Every dark module is drawn with the shape, including the finder
+    /// patterns. The shape should cover most of the module, since scanners
+    /// sample the center of each module.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, image::Luma, render::ModuleShape};
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let image = code
+    ///     .render::<Luma<u8>>()
+    ///     .module_dimensions(10, 10)
+    ///     .module_shape(ModuleShape::Circle)
+    ///     .build();
+    /// // The corner of the top left module of the finder pattern is light.
+    /// assert_eq!(image.get_pixel(40, 40).0, [255]);
+    /// assert_eq!(image.get_pixel(45, 45).0, [0]);
+    /// ```
+    #[inline]
+    pub const fn module_shape(&mut self, module_shape: ModuleShape) -> &mut Self {
+        self.module_shape = module_shape;
+        self
+    }
+
+    /// Renders the QR code and composites the image over it, with the top left
+    /// corner of the image at the (`x`, `y`) pixel of the output, e.g. to place
+    /// a logo in the center.
+    ///
+    /// The modules which the image touches are treated as lost. They may only
+    /// hold data or error correction codewords, and the number of damaged
+    /// codewords is estimated in the same way as [`Renderer::audit`]. The image
+    /// is blended with its alpha channel, if any, but transparent pixels still
+    /// count as covering the module.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] with [`QrError::InvalidOverlay`] if the image covers a
+    /// functional pattern or damages more codewords than the error correction
+    /// can recover, or if the renderer is not created by
+    /// [`QrCode::render`](crate::QrCode::render), since the symbol is needed
+    /// for the check.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{
+    /// #     EcLevel, QrCode, Version,
+    /// #     image::{Rgb, RgbImage},
+    /// # };
+    /// #
+    /// let code = QrCode::with_version(b"Hello", Version::Normal(5), EcLevel::H).unwrap();
+    /// let logo = RgbImage::from_pixel(40, 40, Rgb([255, 0, 0]));
+    /// // The symbol is 37 modules wide with a quiet zone of 4, 8 pixels each.
+    /// let image = code.render::<Rgb<u8>>().overlay(&logo, 160, 160).unwrap();
+    /// assert_eq!(image.get_pixel(180, 180).0, [255, 0, 0]);
+    ///
+    /// // Covering the finder pattern is rejected.
+    /// assert!(code.render::<Rgb<u8>>().overlay(&logo, 32, 32).is_err());
+    /// ```
+    pub fn overlay(&self, image: &P::Image, x: u32, y: u32) -> QrResult<P::Image> {
+        let (version, ec_level) = self.symbol.ok_or(QrError::InvalidOverlay)?;
+        let region = self.covered_region(x, y, image.width(), image.height());
+        if region.area() > 0 {
+            crate::check_reserved_region(version, ec_level, region).map_err(|err| match err {
+                QrError::InvalidReservedRegion => QrError::InvalidOverlay,
+                err => err,
+            })?;
+        }
+        let mut output = self.build();
+        imageops::overlay(&mut output, image, x.into(), y.into());
+        Ok(output)
+    }
+
+    /// Returns the modules of the symbol which the rectangle of pixels touches.
+    fn covered_region(&self, x: u32, y: u32, width: u32, height: u32) -> Region {
+        let qz = self.quiet_zone_sides_drawn();
+        let (mw, mh) = self.module_size;
+        let (_, (padding_x, padding_y)) = self.image_layout();
+        let modules = |start: u32, len: u32, padding: u32, size: u32, offset: u32, count: u32| {
+            // Pixels of the margin around the quiet zone are outside of the
+            // symbol.
+            let (start, end) = (
+                start.saturating_sub(padding),
+                (start + len).saturating_sub(padding),
+            );
+            let end = end.div_ceil(size).saturating_sub(offset).min(count);
+            let start = (start / size).saturating_sub(offset).min(end);
+            (start.as_usize(), (end - start).as_usize())
+        };
+        let (w, h) = self.drawn_modules_count();
+        let (x, width) = modules(x, width, padding_x, mw, qz.left, w);
+        let (y, height) = modules(y, height, padding_y, mh, qz.top, h);
+        if width == 0 || height == 0 {
+            return Region {
+                x,
+                y,
+                width,
+                height,
+            };
+        }
+        // Map the opposite corners back to the symbol, which may be rotated or
+        // mirrored.
+        let (x0, y0) = self.source_module(x.as_u32(), y.as_u32());
+        let (x1, y1) = self.source_module((x + width - 1).as_u32(), (y + height - 1).as_u32());
+        Region {
+            x: x0.min(x1).as_usize(),
+            y: y0.min(y1).as_usize(),
+            width: x0.abs_diff(x1).as_usize() + 1,
+            height: y0.abs_diff(y1).as_usize() + 1,
+        }
+    }
+}
+
 #[cfg(test)]
 mod render_tests {
     use super::*;
-    use crate::render::Renderer;
+    use crate::{
+        EcLevel, QrCode, Version,
+        render::{AuditWarning, Rotation},
+    };
+
+    #[test]
+    fn test_audit() {
+        let content = [Color::Dark; 4];
+        let mut renderer = Renderer::<Rgb<u8>>::new(&content, 2, 2, 1);
+        renderer
+            .dark_color(Rgb([255, 255, 255]))
+            .light_color(Rgb([0, 0, 0]));
+        assert!(renderer.audit().is_empty());
+        renderer.has_quiet_zone(false).module_dimensions(2, 3);
+        assert_eq!(
+            renderer.audit(),
+            [
+                AuditWarning::QuietZoneDisabled,
+                AuditWarning::SmallModules {
+                    width: 2,
+                    height: 3,
+                    minimum: 4
+                },
+                AuditWarning::InvertedColorsWithoutQuietZone
+            ]
+        );
+    }
 
     #[test]
     fn test_render_luma8_unsized() {
@@ -167,6 +359,206 @@ mod render_tests {
         assert_eq!(image.into_raw(), expected);
     }
 
+    fn render_shape(shape: ModuleShape) -> Vec<u8> {
+        Renderer::<Luma<u8>>::new(&[Color::Dark], 1, 1, 0)
+            .module_dimensions(5, 5)
+            .module_shape(shape)
+            .build()
+            .into_raw()
+    }
+
+    #[test]
+    fn test_module_shape_circle() {
+        #[rustfmt::skip]
+        let expected = [
+            255, 0, 0, 0, 255,
+            0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0,
+            255, 0, 0, 0, 255,
+        ];
+        assert_eq!(render_shape(ModuleShape::Circle), expected);
+    }
+
+    #[test]
+    fn test_module_shape_rounded_rect() {
+        assert_eq!(
+            render_shape(ModuleShape::RoundedRect { radius: 0.5 }),
+            render_shape(ModuleShape::Circle)
+        );
+        assert_eq!(
+            render_shape(ModuleShape::RoundedRect { radius: 0.0 }),
+            [0; 25]
+        );
+        // Corners with a radius of 1.5 pixels still cover the corner pixels.
+        assert_eq!(
+            render_shape(ModuleShape::RoundedRect { radius: 0.3 }),
+            [0; 25]
+        );
+    }
+
+    #[test]
+    fn test_module_shape_custom() {
+        // A diagonal cross.
+        let shape = ModuleShape::Custom(|x, y, width, _| x == y || x + y == width - 1);
+        #[rustfmt::skip]
+        let expected = [
+            0, 255, 255, 255, 0,
+            255, 0, 255, 0, 255,
+            255, 255, 0, 255, 255,
+            255, 0, 255, 0, 255,
+            0, 255, 255, 255, 0,
+        ];
+        assert_eq!(render_shape(shape), expected);
+    }
+
+    #[test]
+    fn test_covered_region() {
+        let code = QrCode::new(b"Hello").unwrap();
+        let mut renderer = code.render::<Luma<u8>>();
+        renderer.module_dimensions(4, 4);
+        // Partly covered modules count, and the quiet zone does not.
+        assert_eq!(
+            renderer.covered_region(10, 17, 7, 100),
+            Region {
+                x: 0,
+                y: 0,
+                width: 1,
+                height: 21
+            }
+        );
+        assert_eq!(
+            renderer.covered_region(40, 41, 8, 8),
+            Region {
+                x: 6,
+                y: 6,
+                width: 2,
+                height: 3
+            }
+        );
+        assert_eq!(renderer.covered_region(0, 0, 16, 16).area(), 0);
+        assert_eq!(renderer.covered_region(200, 200, 16, 16).area(), 0);
+    }
+
+    #[test]
+    fn test_overlay() {
+        let code = QrCode::with_version(b"Hello", Version::Normal(5), EcLevel::H).unwrap();
+        let mut renderer = code.render::<Luma<u8>>();
+        renderer.module_dimensions(1, 1);
+        let logo = ImageBuffer::from_pixel(9, 9, Luma([128]));
+        let image = renderer.overlay(&logo, 18, 18).unwrap();
+        assert_eq!(image.get_pixel(26, 26).0, [128]);
+        assert_eq!(image.get_pixel(27, 27).0, code[(23, 23)].select([0], [255]));
+
+        // Within the quiet zone only.
+        assert!(renderer.overlay(&ImageBuffer::new(4, 4), 0, 0).is_ok());
+
+        // Too many damaged codewords.
+        let logo = ImageBuffer::from_pixel(15, 15, Luma([128]));
+        assert_eq!(
+            renderer.overlay(&logo, 15, 15),
+            Err(QrError::InvalidOverlay)
+        );
+
+        // The symbol is unknown.
+        let content = [Color::Dark; 4];
+        let renderer = Renderer::<Luma<u8>>::new(&content, 2, 2, 1);
+        assert_eq!(
+            renderer.overlay(&ImageBuffer::new(1, 1), 0, 0),
+            Err(QrError::InvalidOverlay)
+        );
+    }
+
+    #[test]
+    fn test_covered_region_rotated() {
+        let content = [Color::Light; 43 * 7];
+        let mut renderer = Renderer::<Luma<u8>>::new(&content, 43, 7, 2);
+        renderer.module_dimensions(1, 1).rotate(Rotation::Deg90);
+        let region = |x, y| Region {
+            x,
+            y,
+            width: 3,
+            height: 2,
+        };
+        // The output is 7 modules wide, and the top of the output is the left
+        // of the symbol.
+        assert_eq!(renderer.covered_region(3, 2, 2, 3), region(0, 4));
+        renderer.flip_horizontal();
+        assert_eq!(renderer.covered_region(3, 2, 2, 3), region(40, 4));
+    }
+
+    #[test]
+    fn test_covered_region_exact_dimensions() {
+        let content = [Color::Light; 21 * 21];
+        let mut renderer = Renderer::<Luma<u8>>::new(&content, 21, 21, 2);
+        renderer.exact_dimensions(75, 50);
+        // The modules are 2×2, and the symbol is centered horizontally.
+        assert_eq!(renderer.module_size, (2, 2));
+        assert_eq!(renderer.build().dimensions(), (75, 50));
+        let region = |x, y| Region {
+            x,
+            y,
+            width: 2,
+            height: 2,
+        };
+        assert_eq!(renderer.covered_region(16, 4, 4, 4), region(0, 0));
+        // The margin on the left is outside of the symbol.
+        assert_eq!(
+            renderer.covered_region(0, 0, 17, 5),
+            Region {
+                x: 0,
+                y: 0,
+                width: 1,
+                height: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_audit_inverted_colors() {
+        let content = [Color::Dark; 4];
+        let mut renderer = Renderer::<Luma<u8>>::new(&content, 2, 2, 0);
+        renderer.module_dimensions(4, 4);
+        assert_eq!(renderer.audit(), [AuditWarning::QuietZoneDisabled]);
+        renderer.invert_colors(true);
+        assert_eq!(
+            renderer.audit(),
+            [
+                AuditWarning::QuietZoneDisabled,
+                AuditWarning::InvertedColorsWithoutQuietZone
+            ]
+        );
+        assert_eq!(renderer.build().into_raw(), [255; 64]);
+    }
+
+    #[test]
+    fn test_alignment_dark_color_rotated() {
+        let code = QrCode::with_version(b"01234567", Version::Normal(2), EcLevel::L).unwrap();
+        let image = code
+            .render::<Luma<u8>>()
+            .module_dimensions(1, 1)
+            .quiet_zone(0)
+            .alignment_dark_color(Luma([100]))
+            .rotate(Rotation::Deg90)
+            .build();
+        // The alignment pattern centered at (18, 18) moves to (6, 18), and its
+        // light ring keeps the light color.
+        assert_eq!(image.get_pixel(6, 18).0, [100]);
+        assert_eq!(image.get_pixel(4, 16).0, [100]);
+        assert_eq!(image.get_pixel(5, 17).0, [255]);
+        assert_eq!(image.get_pixel(0, 0).0, [0]);
+    }
+
+    #[test]
+    fn test_region_colors_without_symbol() {
+        let content = [Color::Dark; 4];
+        let image = Renderer::<Luma<u8>>::new(&content, 2, 2, 0)
+            .module_dimensions(1, 1)
+            .finder_dark_color(Luma([100]))
+            .build();
+        assert_eq!(image.into_raw(), [0; 4]);
+    }
+
     #[test]
     fn test_render_resized_min() {
         let image = Renderer::<Luma<u8>>::new(
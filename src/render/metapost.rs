@@ -0,0 +1,219 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! [MetaPost] rendering support.
+//!
+//! Horizontally adjacent dark modules are merged into a single rectangle, so
+//! each run of dark modules is drawn by one `fill` command.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, render::metapost::Color};
+//!
+//! let code = QrCode::new(b"Hello").unwrap();
+//! let mp = code.render::<Color>().build();
+//! println!("{mp}");
+//! ```
+//!
+//! [MetaPost]: https://www.tug.org/metapost.html
+
+use alloc::string::String;
+use core::{cmp, fmt::Write};
+
+use crate::{
+    render::{self, Background, Canvas as RenderCanvas, CanvasOptions, Pixel, Renderer, Unit},
+    types::Color as ModuleColor,
+};
+
+/// A MetaPost color (`(R, G, B)`).
+///
+/// <div class="warning">
+///
+/// Each value must be in the range of 0.0 to 1.0.
+///
+/// </div>
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct Color(pub [f64; 3]);
+
+impl Pixel for Color {
+    type Image = String;
+    type Canvas = Canvas;
+
+    #[inline]
+    fn default_color(color: ModuleColor) -> Self {
+        Self(color.select(Default::default(), [1.0; 3]))
+    }
+
+    #[inline]
+    fn luminance(self) -> Option<f64> {
+        let [r, g, b] = self.0;
+        Some(render::luma(r, g, b))
+    }
+}
+
+/// A canvas for MetaPost rendering.
+#[derive(Debug)]
+pub struct Canvas {
+    mp: String,
+    width: u32,
+    height: u32,
+    dark_pixel: Color,
+    light_pixel: Color,
+    options: CanvasOptions,
+    pending: Option<(u32, u32, u32, u32)>,
+}
+
+impl Canvas {
+    /// Writes the pending rectangle.
+    fn flush(&mut self) {
+        if let Some((left, top, width, height)) = self.pending.take() {
+            let bottom = self.height - top - height;
+            writeln!(
+                self.mp,
+                "fill unitsquare xscaled {width}u yscaled {height}u shifted ({left}u,{bottom}u) withcolor dark;"
+            )
+            .unwrap();
+        }
+    }
+}
+
+impl RenderCanvas for Canvas {
+    type Pixel = Color;
+    type Image = String;
+
+    #[inline]
+    fn new(width: u32, height: u32, dark_pixel: Self::Pixel, light_pixel: Self::Pixel) -> Self {
+        Self {
+            mp: String::new(),
+            width,
+            height,
+            dark_pixel,
+            light_pixel,
+            options: CanvasOptions::default(),
+            pending: None,
+        }
+    }
+
+    #[inline]
+    fn draw_dark_pixel(&mut self, x: u32, y: u32) {
+        self.draw_dark_rect(x, y, 1, 1);
+    }
+
+    fn draw_dark_rect(&mut self, left: u32, top: u32, width: u32, height: u32) {
+        if let Some((l, t, w, h)) = self.pending.as_mut() {
+            if *t == top && *h == height && *l + *w == left {
+                *w += width;
+                return;
+            }
+        }
+        self.flush();
+        self.pending = Some((left, top, width, height));
+    }
+
+    #[inline]
+    fn configure(&mut self, options: &CanvasOptions) {
+        self.options = options.clone();
+    }
+
+    fn into_image(mut self) -> Self::Image {
+        self.flush();
+        let (w, h) = (self.width, self.height);
+        let mut mp = String::from("beginfig(1);\n");
+        match self.options.physical_module_size {
+            Some((size, unit)) => {
+                let (size, unit) = unit.to_tex_length(size, "in");
+                let (module_width, _) = self.options.module_size;
+                let module_width = cmp::max(module_width, 1);
+                writeln!(mp, "u:={size}{unit}/{module_width};").unwrap();
+            }
+            None => mp.push_str("u:=1bp;\n"),
+        }
+        let Color([fgr, fgg, fgb]) = self.dark_pixel;
+        writeln!(mp, "color dark;dark:=({fgr},{fgg},{fgb});").unwrap();
+        let bg = match self.options.background {
+            Background::Light => Some(self.light_pixel),
+            Background::None => None,
+            Background::White => Some(Color([1.0; 3])),
+        };
+        if let Some(Color([bgr, bgg, bgb])) = bg {
+            writeln!(
+                mp,
+                "fill unitsquare xscaled {w}u yscaled {h}u withcolor ({bgr},{bgg},{bgb});"
+            )
+            .unwrap();
+        }
+        mp.push_str(&self.mp);
+        mp.push_str("endfig;\nend");
+        mp
+    }
+}
+
+impl Renderer<'_, Color> {
+    /// Sets the physical size of each module, which determines the unit
+    /// length `u` of the picture. [`Unit::Px`] is converted into `bp`.
+    ///
+    /// By default, each pixel is 1 PostScript point (`bp`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{
+    /// #     QrCode,
+    /// #     render::{Unit, metapost::Color},
+    /// # };
+    /// #
+    /// let code = QrCode::new(b"01234567").unwrap();
+    /// let mp = code
+    ///     .render::<Color>()
+    ///     .physical_module_size(0.5, Unit::Mm)
+    ///     .build();
+    /// // Each module is 8 pixels wide.
+    /// assert!(mp.contains("u:=0.5mm/8;\n"));
+    /// ```
+    #[inline]
+    pub const fn physical_module_size(&mut self, size: f64, unit: Unit) -> &mut Self {
+        self.canvas_options.physical_module_size = Some((size, unit));
+        self
+    }
+
+    /// Sets how the background is painted. Default is [`Background::Light`].
+    #[inline]
+    pub const fn background(&mut self, background: Background) -> &mut Self {
+        self.canvas_options.background = background;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_rectangles() {
+        let content = [
+            ModuleColor::Dark,
+            ModuleColor::Dark,
+            ModuleColor::Light,
+            ModuleColor::Dark,
+        ];
+        let mp = Renderer::<Color>::new(&content, 2, 2, 1)
+            .module_dimensions(1, 1)
+            .background(Background::None)
+            .build();
+        assert_eq!(
+            mp,
+            concat!(
+                "beginfig(1);\n",
+                "u:=1bp;\n",
+                "color dark;dark:=(0,0,0);\n",
+                "fill unitsquare xscaled 2u yscaled 1u shifted (1u,2u) withcolor dark;\n",
+                "fill unitsquare xscaled 1u yscaled 1u shifted (2u,1u) withcolor dark;\n",
+                "endfig;\n",
+                "end"
+            )
+        );
+    }
+}
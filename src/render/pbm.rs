@@ -0,0 +1,327 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! [Netpbm] and [XBM] rendering support.
+//!
+//! These are simple image formats which most image tools can read, and which
+//! do not need the `image` crate to write.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, render::pbm::Gray};
+//!
+//! let code = QrCode::new(b"Hello").unwrap();
+//! let pbm = code.render::<Gray>().build();
+//! assert!(pbm.starts_with(b"P4\n232 232\n"));
+//! ```
+//!
+//! [Netpbm]: https://netpbm.sourceforge.net/doc/
+//! [XBM]: https://en.wikipedia.org/wiki/X_BitMap
+
+use alloc::{format, vec, vec::Vec};
+
+use crate::{
+    cast::As,
+    render::{Canvas as RenderCanvas, CanvasOptions, Pixel, Renderer},
+    types::Color,
+};
+
+/// The maximum number of characters in a line of the plain formats.
+const MAX_LINE_LENGTH: usize = 70;
+
+/// The number of bytes in a line of the XBM array.
+const XBM_BYTES_PER_LINE: usize = 12;
+
+/// A gray level, from 0 (black) to 255 (white).
+///
+/// The bilevel formats draw the pixels darker than the middle gray as black.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Gray(pub u8);
+
+impl Pixel for Gray {
+    type Image = Vec<u8>;
+    type Canvas = Canvas;
+
+    #[inline]
+    fn default_color(color: Color) -> Self {
+        Self(color.select(0, 255))
+    }
+
+    #[inline]
+    fn luminance(self) -> Option<f64> {
+        Some(f64::from(self.0) / 255.0)
+    }
+}
+
+impl Gray {
+    /// Returns whether the pixel is black in the bilevel formats.
+    const fn is_black(self) -> bool {
+        self.0 < 128
+    }
+}
+
+/// The image format.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Format {
+    /// The plain PBM format (`P1`), with a digit for each pixel.
+    PlainPbm,
+
+    /// The raw PBM format (`P4`), with a bit for each pixel.
+    #[default]
+    RawPbm,
+
+    /// The plain PGM format (`P2`), with a decimal number for each pixel.
+    PlainPgm,
+
+    /// The raw PGM format (`P5`), with a byte for each pixel.
+    RawPgm,
+
+    /// The XBM format, a C source file defining `qrcode_width`,
+    /// `qrcode_height` and `qrcode_bits`.
+    Xbm,
+}
+
+/// A canvas for Netpbm and XBM rendering.
+#[derive(Debug)]
+pub struct Canvas {
+    pixels: Vec<bool>,
+    width: u32,
+    height: u32,
+    dark_pixel: Gray,
+    light_pixel: Gray,
+    format: Format,
+}
+
+impl RenderCanvas for Canvas {
+    type Pixel = Gray;
+    type Image = Vec<u8>;
+
+    fn new(width: u32, height: u32, dark_pixel: Self::Pixel, light_pixel: Self::Pixel) -> Self {
+        Self {
+            pixels: vec![false; (width * height).as_usize()],
+            width,
+            height,
+            dark_pixel,
+            light_pixel,
+            format: Format::default(),
+        }
+    }
+
+    #[inline]
+    fn draw_dark_pixel(&mut self, x: u32, y: u32) {
+        self.pixels[(x + y * self.width).as_usize()] = true;
+    }
+
+    #[inline]
+    fn configure(&mut self, options: &CanvasOptions) {
+        self.format = options.pbm_format;
+    }
+
+    fn into_image(self) -> Self::Image {
+        let (width, height) = (self.width, self.height);
+        let gray = |is_dark: bool| {
+            if is_dark {
+                self.dark_pixel
+            } else {
+                self.light_pixel
+            }
+        };
+        let rows = self.pixels.chunks_exact(width.as_usize().max(1));
+        match self.format {
+            Format::PlainPbm => {
+                let mut image = format!("P1\n{width} {height}\n").into_bytes();
+                for row in rows {
+                    let digits = row
+                        .iter()
+                        .map(|&is_dark| if gray(is_dark).is_black() { b'1' } else { b'0' })
+                        .collect::<Vec<_>>();
+                    for line in digits.chunks(MAX_LINE_LENGTH) {
+                        image.extend(line);
+                        image.push(b'\n');
+                    }
+                }
+                image
+            }
+            Format::RawPbm => {
+                let mut image = format!("P4\n{width} {height}\n").into_bytes();
+                for row in rows {
+                    image.extend(pack(row.iter().map(|&d| gray(d).is_black()), true));
+                }
+                image
+            }
+            Format::PlainPgm => {
+                let mut image = format!("P2\n{width} {height}\n255\n").into_bytes();
+                for row in rows {
+                    let mut line = Vec::new();
+                    for &is_dark in row {
+                        let value = format!("{}", gray(is_dark).0);
+                        if !line.is_empty() && line.len() + 1 + value.len() > MAX_LINE_LENGTH {
+                            image.append(&mut line);
+                            image.push(b'\n');
+                        }
+                        if !line.is_empty() {
+                            line.push(b' ');
+                        }
+                        line.extend(value.bytes());
+                    }
+                    image.append(&mut line);
+                    image.push(b'\n');
+                }
+                image
+            }
+            Format::RawPgm => {
+                let mut image = format!("P5\n{width} {height}\n255\n").into_bytes();
+                image.extend(self.pixels.iter().map(|&is_dark| gray(is_dark).0));
+                image
+            }
+            Format::Xbm => {
+                let mut image = format!(
+                    "#define qrcode_width {width}\n#define qrcode_height {height}\nstatic unsigned char qrcode_bits[] = {{\n"
+                )
+                .into_bytes();
+                let bytes = rows
+                    .flat_map(|row| pack(row.iter().map(|&d| gray(d).is_black()), false))
+                    .map(|byte| format!("0x{byte:02x}"))
+                    .collect::<Vec<_>>();
+                for (i, line) in bytes.chunks(XBM_BYTES_PER_LINE).enumerate() {
+                    if i > 0 {
+                        image.extend(b",\n");
+                    }
+                    image.extend(b"   ");
+                    image.extend(line.join(", ").bytes());
+                }
+                image.extend(b"};\n");
+                image
+            }
+        }
+    }
+}
+
+/// Packs a row of bits into bytes, with the leftmost bit in the most
+/// significant bit if `msb_first` is `true`, or in the least significant bit
+/// otherwise. The last byte is padded with zeros.
+fn pack(bits: impl Iterator<Item = bool>, msb_first: bool) -> Vec<u8> {
+    let bits = bits.collect::<Vec<_>>();
+    bits.chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .filter(|(_, bit)| **bit)
+                .fold(0, |byte, (i, _)| {
+                    byte | if msb_first { 0x80 >> i } else { 1 << i }
+                })
+        })
+        .collect()
+}
+
+impl Renderer<'_, Gray> {
+    /// Sets the image format. Default is [`Format::RawPbm`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{
+    /// #     QrCode,
+    /// #     render::pbm::{Format, Gray},
+    /// # };
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let xbm = code
+    ///     .render::<Gray>()
+    ///     .module_dimensions(1, 1)
+    ///     .format(Format::Xbm)
+    ///     .build();
+    /// let xbm = String::from_utf8(xbm).unwrap();
+    /// assert!(xbm.starts_with("#define qrcode_width 29\n"));
+    /// ```
+    #[inline]
+    pub const fn format(&mut self, format: Format) -> &mut Self {
+        self.canvas_options.pbm_format = format;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLORS: [Color; 4] = [Color::Dark, Color::Light, Color::Light, Color::Dark];
+
+    fn render(format: Format) -> Vec<u8> {
+        Renderer::<Gray>::new(&COLORS, 2, 2, 0)
+            .module_dimensions(5, 1)
+            .light_color(Gray(200))
+            .format(format)
+            .build()
+    }
+
+    #[test]
+    fn test_plain_pbm() {
+        assert_eq!(
+            render(Format::PlainPbm),
+            b"P1\n10 2\n1111100000\n0000011111\n"
+        );
+    }
+
+    #[test]
+    fn test_plain_pbm_long_rows() {
+        let image = Renderer::<Gray>::new(&COLORS[..1], 1, 1, 0)
+            .module_dimensions(75, 1)
+            .format(Format::PlainPbm)
+            .build();
+        let lines = image.split(|&b| b == b'\n').collect::<Vec<_>>();
+        assert_eq!(lines[2], [b'1'; 70]);
+        assert_eq!(lines[3], [b'1'; 5]);
+    }
+
+    #[test]
+    fn test_raw_pbm() {
+        assert_eq!(render(Format::RawPbm), b"P4\n10 2\n\xf8\x00\x07\xc0");
+    }
+
+    #[test]
+    fn test_plain_pgm() {
+        assert_eq!(
+            render(Format::PlainPgm),
+            b"P2\n10 2\n255\n0 0 0 0 0 200 200 200 200 200\n200 200 200 200 200 0 0 0 0 0\n"
+        );
+    }
+
+    #[test]
+    fn test_raw_pgm() {
+        let image = render(Format::RawPgm);
+        let (header, pixels) = image.split_at(12);
+        assert_eq!(header, b"P5\n10 2\n255\n");
+        assert_eq!(pixels[..5], [0; 5]);
+        assert_eq!(pixels[5..15], [200; 10]);
+        assert_eq!(pixels[15..], [0; 5]);
+    }
+
+    #[test]
+    fn test_xbm() {
+        assert_eq!(
+            render(Format::Xbm),
+            concat!(
+                "#define qrcode_width 10\n",
+                "#define qrcode_height 2\n",
+                "static unsigned char qrcode_bits[] = {\n",
+                "   0x1f, 0x00, 0xe0, 0x03};\n"
+            )
+            .as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_light_dark_color() {
+        let image = Renderer::<Gray>::new(&COLORS, 2, 2, 0)
+            .module_dimensions(1, 1)
+            .dark_color(Gray(100))
+            .light_color(Gray(128))
+            .format(Format::PlainPbm)
+            .build();
+        assert_eq!(image, b"P1\n2 2\n10\n01\n");
+    }
+}
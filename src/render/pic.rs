@@ -19,16 +19,20 @@
 //!
 //! [PIC]: https://en.wikipedia.org/wiki/PIC_(markup_language)
 
-use alloc::{format, string::String};
-use core::fmt::Write;
+use alloc::{format, string::String, vec::Vec};
+use core::{
+    cmp,
+    fmt::{self, Write},
+};
 
 use crate::{
-    render::{Canvas as RenderCanvas, Pixel},
+    render::{self, Canvas as RenderCanvas, CanvasOptions, Length, Pixel, Renderer, WriteCanvas},
     types::Color as ModuleColor,
 };
 
 /// A PIC color.
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct Color;
 
 impl Pixel for Color {
@@ -44,7 +48,10 @@ impl Pixel for Color {
 /// A canvas for PIC rendering.
 #[derive(Debug)]
 pub struct Canvas {
-    pic: String,
+    rects: Vec<(u32, u32, u32, u32)>,
+    width: u32,
+    height: u32,
+    options: CanvasOptions,
 }
 
 impl RenderCanvas for Canvas {
@@ -53,16 +60,12 @@ impl RenderCanvas for Canvas {
 
     #[inline]
     fn new(width: u32, height: u32, _dark_pixel: Self::Pixel, _light_pixel: Self::Pixel) -> Self {
-        let pic = format!(
-            concat!(
-                "maxpswid={w};maxpsht={h};movewid=0;moveht=1;boxwid=1;boxht=1\n",
-                "define p {{ box wid $3 ht $4 fill 1 thickness 0.1 with .nw at $1,-$2 }}\n",
-                "box wid maxpswid ht maxpsht with .nw at 0,0\n"
-            ),
-            w = width,
-            h = height
-        );
-        Self { pic }
+        Self {
+            rects: Vec::new(),
+            width,
+            height,
+            options: CanvasOptions::default(),
+        }
     }
 
     #[inline]
@@ -72,12 +75,117 @@ impl RenderCanvas for Canvas {
 
     #[inline]
     fn draw_dark_rect(&mut self, left: u32, top: u32, width: u32, height: u32) {
-        writeln!(self.pic, "p({left},{top},{width},{height})").unwrap();
+        self.rects.push((left, top, width, height));
+    }
+
+    #[inline]
+    fn configure(&mut self, options: &CanvasOptions) {
+        self.options = options.clone();
+    }
+
+    #[inline]
+    fn into_image(self) -> Self::Image {
+        render::write_image_to_string(self)
+    }
+}
+
+impl WriteCanvas for Canvas {
+    fn write_image<W: Write + ?Sized>(self, pic: &mut W) -> fmt::Result {
+        let options = &self.options;
+        // The number of pixels per inch, which is 1 unless the scale is set.
+        let scale = options.inches_per_module.map(|inches| {
+            let (module_width, _) = options.module_size;
+            f64::from(cmp::max(module_width, 1)) / inches
+        });
+        match scale {
+            Some(scale) => write!(
+                pic,
+                "maxpswid={};maxpsht={};scale={};",
+                Length(f64::from(self.width) / scale),
+                Length(f64::from(self.height) / scale),
+                Length(scale)
+            ),
+            None => write!(pic, "maxpswid={};maxpsht={};", self.width, self.height),
+        }?;
+        pic.write_str("movewid=0;moveht=1;boxwid=1;boxht=1\n")?;
+
+        let (x, y) = options.origin_offset;
+        let (x, y) = scale.map_or((x, y), |scale| (x * scale, y * scale));
+        let (origin, position) = if x == 0.0 && y == 0.0 {
+            (String::from("0,0"), String::from("$1,-$2"))
+        } else {
+            (
+                format!("{},-{}", Length(x), Length(y)),
+                format!("{}+$1,-{}-$2", Length(x), Length(y)),
+            )
+        };
+        let fill = if options.outline_modules {
+            ""
+        } else {
+            " fill 1"
+        };
+        let thickness = Length(options.line_thickness.unwrap_or(0.1));
+        writeln!(
+            pic,
+            "define p {{ box wid $3 ht $4{fill} thickness {thickness} with .nw at {position} }}"
+        )?;
+        // `maxpswid` and `maxpsht` are in inches, so the frame needs the size in
+        // pixels once the scale is set.
+        let size = match scale {
+            Some(_) => format!("{} ht {}", self.width, self.height),
+            None => String::from("maxpswid ht maxpsht"),
+        };
+        writeln!(pic, "box wid {size} with .nw at {origin}")?;
+        for (i, (left, top, width, height)) in self.rects.into_iter().enumerate() {
+            if i != 0 {
+                pic.write_char('\n')?;
+            }
+            write!(pic, "p({left},{top},{width},{height})")?;
+        }
+        Ok(())
+    }
+}
+
+impl Renderer<'_, Color> {
+    /// Sets the physical size of each module in inches. By default, each pixel
+    /// is 1 inch, so the printed size depends on the module dimensions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, render::pic::Color};
+    /// #
+    /// let code = QrCode::new(b"01234567").unwrap();
+    /// let pic = code.render::<Color>().inches_per_module(0.05).build();
+    /// // 29 modules including the quiet zone, each 8 pixels wide.
+    /// assert!(pic.starts_with("maxpswid=1.45;maxpsht=1.45;scale=160;"));
+    /// ```
+    #[inline]
+    pub const fn inches_per_module(&mut self, inches: f64) -> &mut Self {
+        self.canvas_options.inches_per_module = Some(inches);
+        self
+    }
+
+    /// Sets the offset of the upper-left corner of the picture in inches.
+    /// Default is `(0.0, 0.0)`.
+    #[inline]
+    pub const fn origin_offset(&mut self, x: f64, y: f64) -> &mut Self {
+        self.canvas_options.origin_offset = (x, y);
+        self
+    }
+
+    /// Sets whether modules are drawn as outlined boxes instead of filled
+    /// boxes. Default is [`false`].
+    #[inline]
+    pub const fn outline_modules(&mut self, outline_modules: bool) -> &mut Self {
+        self.canvas_options.outline_modules = outline_modules;
+        self
     }
 
+    /// Sets the line thickness in points. Default is `0.1`.
     #[inline]
-    fn into_image(mut self) -> Self::Image {
-        self.pic.pop();
-        self.pic
+    pub const fn line_thickness(&mut self, thickness: f64) -> &mut Self {
+        self.canvas_options.line_thickness = Some(thickness);
+        self
     }
 }
@@ -0,0 +1,184 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Rendering one scanline at a time.
+//!
+//! [`Scanlines`] calls a function once per row of pixels instead of building
+//! the whole image, so that a microcontroller can stream the QR code to a
+//! display or a printer with a buffer of a single row. The row is either packed
+//! into bits, where the most significant bit of each byte is the leftmost
+//! pixel and `1` is dark, or given as [`Color`]s.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, render::scanline::Scanlines};
+//!
+//! let code = QrCode::new(b"Hello").unwrap();
+//! let scanlines = Scanlines::new(&code);
+//! let mut buf = [0; 32];
+//! scanlines.for_each_packed_row(&mut buf, |_, row| {
+//!     // Send the row to the printer.
+//!     assert_eq!(row.len(), 29usize.div_ceil(8));
+//! });
+//! ```
+
+use crate::{QrCode, cast::As, types::Color};
+
+/// A renderer which outputs one row of pixels at a time.
+#[derive(Clone, Copy, Debug)]
+pub struct Scanlines<'a> {
+    code: &'a QrCode,
+    module_size: u32,
+    quiet_zone: u32,
+}
+
+impl<'a> Scanlines<'a> {
+    /// Creates a new renderer for the QR code.
+    ///
+    /// The defaults are 1 pixel per module and the standard quiet zone, i.e. 4
+    /// modules for QR code and 2 modules for Micro QR code and rMQR code.
+    #[must_use]
+    pub const fn new(code: &'a QrCode) -> Self {
+        let quiet_zone = if code.version().is_normal() { 4 } else { 2 };
+        Self {
+            code,
+            module_size: 1,
+            quiet_zone,
+        }
+    }
+
+    /// Sets the size of each module in pixels. The value is at least 1.
+    #[inline]
+    pub fn module_size(&mut self, module_size: u32) -> &mut Self {
+        self.module_size = module_size.max(1);
+        self
+    }
+
+    /// Sets the width of the quiet zone in modules.
+    #[inline]
+    pub const fn quiet_zone(&mut self, quiet_zone: u32) -> &mut Self {
+        self.quiet_zone = quiet_zone;
+        self
+    }
+
+    /// Returns the width of the image in pixels.
+    #[must_use]
+    #[inline]
+    pub fn width(&self) -> u32 {
+        (self.code.width().as_u32() + 2 * self.quiet_zone) * self.module_size
+    }
+
+    /// Returns the height of the image in pixels.
+    #[must_use]
+    #[inline]
+    pub fn height(&self) -> u32 {
+        (self.code.height().as_u32() + 2 * self.quiet_zone) * self.module_size
+    }
+
+    /// Returns the number of bytes of a row packed into bits.
+    #[must_use]
+    #[inline]
+    pub fn packed_row_len(&self) -> usize {
+        self.width().as_usize().div_ceil(8)
+    }
+
+    /// Calls `f` with the index and the colors of each row of pixels, from top
+    /// to bottom.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buf` is shorter than [`Scanlines::width`].
+    pub fn for_each_row<F>(&self, buf: &mut [Color], mut f: F)
+    where
+        F: FnMut(u32, &[Color]),
+    {
+        let row = &mut buf[..self.width().as_usize()];
+        for y in 0..self.height() {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                *pixel = self.color(x.as_u32(), y);
+            }
+            f(y, row);
+        }
+    }
+
+    /// Calls `f` with the index and the packed bits of each row of pixels,
+    /// from top to bottom. The unused bits at the end of the row are `0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buf` is shorter than [`Scanlines::packed_row_len`].
+    pub fn for_each_packed_row<F>(&self, buf: &mut [u8], mut f: F)
+    where
+        F: FnMut(u32, &[u8]),
+    {
+        let width = self.width();
+        let row = &mut buf[..self.packed_row_len()];
+        for y in 0..self.height() {
+            row.fill(0);
+            for x in 0..width {
+                if self.color(x, y) == Color::Dark {
+                    row[(x / 8).as_usize()] |= 0x80 >> (x % 8);
+                }
+            }
+            f(y, row);
+        }
+    }
+
+    /// Returns the color of the pixel.
+    fn color(&self, x: u32, y: u32) -> Color {
+        let (x, y) = (x / self.module_size, y / self.module_size);
+        let (width, height) = (self.code.width().as_u32(), self.code.height().as_u32());
+        if (self.quiet_zone..self.quiet_zone + width).contains(&x)
+            && (self.quiet_zone..self.quiet_zone + height).contains(&y)
+        {
+            let (x, y) = (x - self.quiet_zone, y - self.quiet_zone);
+            self.code[(x.as_usize(), y.as_usize())]
+        } else {
+            Color::Light
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    #[test]
+    fn test_rows_match_colors() {
+        let code = QrCode::new_micro(b"01234567").unwrap();
+        let scanlines = Scanlines::new(&code);
+        assert_eq!((scanlines.width(), scanlines.height()), (17, 17));
+        let mut buf = [Color::Light; 17];
+        let mut rows = Vec::new();
+        scanlines.for_each_row(&mut buf, |y, row| {
+            assert_eq!(y.as_usize(), rows.len());
+            rows.push(row.to_vec());
+        });
+        assert_eq!(rows.len(), 17);
+        for (y, row) in rows[2..15].iter().enumerate() {
+            assert_eq!(row[2..15], code.to_colors()[y * 13..(y + 1) * 13]);
+            assert_eq!(row[..2], [Color::Light; 2]);
+        }
+    }
+
+    #[test]
+    fn test_packed_rows() {
+        let code = QrCode::new(b"01234567").unwrap();
+        let mut scanlines = Scanlines::new(&code);
+        scanlines.module_size(2).quiet_zone(1);
+        assert_eq!(scanlines.width(), 46);
+        assert_eq!(scanlines.packed_row_len(), 6);
+        let mut buf = [0xff; 8];
+        let mut rows = Vec::new();
+        scanlines.for_each_packed_row(&mut buf, |_, row| rows.push(row.to_vec()));
+        assert_eq!(rows.len(), 46);
+        assert_eq!(rows[0], [0; 6]);
+        // The first row of modules is `#######..#.##.#######` with 2 pixels per
+        // module, after a quiet zone of 2 pixels.
+        assert_eq!(rows[2], [0x3f, 0xff, 0x0c, 0xf3, 0xff, 0xf0]);
+    }
+}
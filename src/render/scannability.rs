@@ -0,0 +1,335 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check whether render settings are likely to produce a scannable image.
+//!
+//! Styled QR codes often fail to scan because of low contrast, tiny modules, a
+//! missing quiet zone, or a logo which covers more modules than the error
+//! correction can recover. The [`Checker`] evaluates these settings against
+//! common scanner guidelines, so such problems are caught before printing.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{EcLevel, Version, render::scannability::Checker};
+//!
+//! let report = Checker::new(Version::Normal(3), EcLevel::M)
+//!     .colors([0x33, 0x33, 0x33], [0xff, 0xff, 0xff])
+//!     .module_dimensions(6, 6)
+//!     .build();
+//! assert!(report.is_scannable());
+//!
+//! let report = Checker::new(Version::Normal(3), EcLevel::M)
+//!     .colors([0x99, 0x99, 0x99], [0xcc, 0xcc, 0xcc])
+//!     .build();
+//! assert!(!report.is_scannable());
+//! ```
+
+use alloc::vec::Vec;
+use core::{cmp, fmt};
+
+use crate::{
+    ec,
+    render::{self, MIN_MODULE_SIZE},
+    types::{EcLevel, Version},
+};
+
+/// The minimum contrast ratio between the light and dark colors.
+pub const MIN_CONTRAST_RATIO: f64 = 3.0;
+
+/// A problem found by the [`Checker`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Warning {
+    /// The contrast ratio between the light and dark colors is below
+    /// [`MIN_CONTRAST_RATIO`].
+    LowContrast {
+        /// The contrast ratio, from 1 to 21.
+        ratio: f64,
+    },
+
+    /// The dark modules are lighter than the light modules. Many scanners
+    /// cannot read such inverted codes.
+    InvertedColors,
+
+    /// The module is smaller than [`MIN_MODULE_SIZE`].
+    SmallModules {
+        /// The smaller dimension of the module in pixels.
+        pixels: u32,
+    },
+
+    /// The quiet zone is narrower than required by the standard.
+    NarrowQuietZone {
+        /// The width of the quiet zone in modules.
+        modules: u32,
+
+        /// The required width of the quiet zone in modules.
+        required: u32,
+    },
+
+    /// The logo is estimated to damage more codewords than the error
+    /// correction can recover.
+    LogoTooLarge {
+        /// The estimated number of damaged codewords.
+        codewords: usize,
+
+        /// The number of codewords which can be recovered.
+        budget: usize,
+    },
+}
+
+impl Warning {
+    /// Returns how many points this warning deducts from the score.
+    const fn penalty(&self) -> u8 {
+        match self {
+            Self::LowContrast { .. } | Self::LogoTooLarge { .. } => 40,
+            Self::NarrowQuietZone { .. } => 30,
+            Self::InvertedColors | Self::SmallModules { .. } => 20,
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LowContrast { ratio } => write!(f, "contrast ratio {ratio:.2}:1 is too low"),
+            Self::InvertedColors => write!(f, "dark modules are lighter than light modules"),
+            Self::SmallModules { pixels } => write!(f, "modules of {pixels} pixels are too small"),
+            Self::NarrowQuietZone { modules, required } => write!(
+                f,
+                "quiet zone of {modules} modules is narrower than {required} modules"
+            ),
+            Self::LogoTooLarge { codewords, budget } => write!(
+                f,
+                "logo damages about {codewords} codewords, but only {budget} can be recovered"
+            ),
+        }
+    }
+}
+
+/// The result of [`Checker::build`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    contrast_ratio: f64,
+    warnings: Vec<Warning>,
+}
+
+impl Report {
+    /// Returns the contrast ratio between the light and dark colors, as
+    /// defined by WCAG 2. The value ranges from 1 to 21.
+    #[must_use]
+    #[inline]
+    pub const fn contrast_ratio(&self) -> f64 {
+        self.contrast_ratio
+    }
+
+    /// Returns the problems found.
+    #[must_use]
+    #[inline]
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Returns a score from 0 to 100, where 100 means no problem is found.
+    ///
+    /// The score is a heuristic: each warning deducts points depending on how
+    /// likely it is to make the code unreadable.
+    #[must_use]
+    pub fn score(&self) -> u8 {
+        self.warnings
+            .iter()
+            .fold(100, |score, w| score.saturating_sub(w.penalty()))
+    }
+
+    /// Returns [`true`] if no problem is found.
+    #[must_use]
+    #[inline]
+    pub fn is_scannable(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+/// A builder which checks the scannability of render settings.
+#[derive(Clone, Copy, Debug)]
+pub struct Checker {
+    version: Version,
+    ec_level: EcLevel,
+    dark_color: [u8; 3],
+    light_color: [u8; 3],
+    module_size: (u32, u32),
+    quiet_zone: u32,
+    logo_modules: usize,
+}
+
+impl Checker {
+    /// Creates a new checker for a QR code of the given version and error
+    /// correction level.
+    ///
+    /// The defaults match [`QrCode::render`](crate::QrCode::render): black
+    /// on white, 8×8 modules and the standard quiet zone, without logo.
+    #[must_use]
+    pub const fn new(version: Version, ec_level: EcLevel) -> Self {
+        Self {
+            version,
+            ec_level,
+            dark_color: [0x00; 3],
+            light_color: [0xff; 3],
+            module_size: (8, 8),
+            quiet_zone: required_quiet_zone(version),
+            logo_modules: 0,
+        }
+    }
+
+    /// Sets the dark and light colors as sRGB values.
+    #[inline]
+    pub const fn colors(&mut self, dark: [u8; 3], light: [u8; 3]) -> &mut Self {
+        self.dark_color = dark;
+        self.light_color = light;
+        self
+    }
+
+    /// Sets the size of each module in pixels.
+    #[inline]
+    pub const fn module_dimensions(&mut self, width: u32, height: u32) -> &mut Self {
+        self.module_size = (width, height);
+        self
+    }
+
+    /// Sets the size of the quiet zone in modules. Use 0 if the quiet zone is
+    /// disabled.
+    #[inline]
+    pub const fn quiet_zone(&mut self, quiet_zone: u32) -> &mut Self {
+        self.quiet_zone = quiet_zone;
+        self
+    }
+
+    /// Sets the number of modules covered by a logo.
+    ///
+    /// The number of damaged codewords is estimated in the same way as
+    /// [`Renderer::audit`](crate::render::Renderer::audit).
+    #[inline]
+    pub const fn logo_modules(&mut self, modules: usize) -> &mut Self {
+        self.logo_modules = modules;
+        self
+    }
+
+    /// Evaluates the settings.
+    #[must_use]
+    pub fn build(&self) -> Report {
+        let mut warnings = Vec::new();
+
+        let dark = relative_luminance(self.dark_color);
+        let light = relative_luminance(self.light_color);
+        let contrast_ratio = (dark.max(light) + 0.05) / (dark.min(light) + 0.05);
+        if contrast_ratio < MIN_CONTRAST_RATIO {
+            warnings.push(Warning::LowContrast {
+                ratio: contrast_ratio,
+            });
+        }
+        if dark > light {
+            warnings.push(Warning::InvertedColors);
+        }
+
+        let pixels = cmp::min(self.module_size.0, self.module_size.1);
+        if pixels < MIN_MODULE_SIZE {
+            warnings.push(Warning::SmallModules { pixels });
+        }
+
+        let required = required_quiet_zone(self.version);
+        if self.quiet_zone < required {
+            warnings.push(Warning::NarrowQuietZone {
+                modules: self.quiet_zone,
+                required,
+            });
+        }
+
+        if self.logo_modules > 0 {
+            let codewords = render::estimate_damaged_codewords(self.logo_modules);
+            let budget = ec::max_allowed_errors(self.version, self.ec_level).unwrap_or(0);
+            if codewords > budget {
+                warnings.push(Warning::LogoTooLarge { codewords, budget });
+            }
+        }
+
+        Report {
+            contrast_ratio,
+            warnings,
+        }
+    }
+}
+
+/// Returns the required width of the quiet zone in modules.
+const fn required_quiet_zone(version: Version) -> u32 {
+    if version.is_normal() { 4 } else { 2 }
+}
+
+/// Computes the relative luminance of a sRGB color, as defined by WCAG 2.
+fn relative_luminance(color: [u8; 3]) -> f64 {
+    let linearize = |c: u8| {
+        let c = f64::from(c) / 255.0;
+        if c <= 0.040_45 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let [r, g, b] = color.map(linearize);
+    0.0722f64.mul_add(b, 0.2126f64.mul_add(r, 0.7152 * g))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_scannable() {
+        let report = Checker::new(Version::Normal(1), EcLevel::M).build();
+        assert!(report.is_scannable());
+        assert_eq!(report.score(), 100);
+        assert!((report.contrast_ratio() - 21.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_low_contrast_and_inverted() {
+        let report = Checker::new(Version::Normal(1), EcLevel::M)
+            .colors([0xff; 3], [0xdd; 3])
+            .build();
+        assert!(matches!(report.warnings()[0], Warning::LowContrast { .. }));
+        assert_eq!(report.warnings()[1], Warning::InvertedColors);
+        assert_eq!(report.score(), 40);
+    }
+
+    #[test]
+    fn test_small_modules_and_quiet_zone() {
+        let report = Checker::new(Version::Micro(2), EcLevel::L)
+            .module_dimensions(8, 2)
+            .quiet_zone(1)
+            .build();
+        assert_eq!(
+            report.warnings(),
+            [
+                Warning::SmallModules { pixels: 2 },
+                Warning::NarrowQuietZone {
+                    modules: 1,
+                    required: 2
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_logo() {
+        let mut checker = Checker::new(Version::Normal(5), EcLevel::H);
+        assert!(checker.logo_modules(100).build().is_scannable());
+        let report = checker.logo_modules(400).build();
+        assert_eq!(
+            report.warnings(),
+            [Warning::LogoTooLarge {
+                codewords: 100,
+                budget: 44
+            }]
+        );
+        assert_eq!(report.score(), 60);
+    }
+}
@@ -0,0 +1,562 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Rendering of multiple symbols into a single grid image.
+//!
+//! [`Sheet`] lays a batch of symbols, e.g. a structured append set or a batch
+//! of tickets, out in rows of a fixed number of columns, with an optional text
+//! label below each symbol. All cells have the same size, which fits the
+//! largest symbol with its quiet zone, so the position of each symbol only
+//! depends on its index. The sheet is rendered as [SVG] or as a raster image.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "svg")]
+//! # {
+//! use qrcode2::{QrCode, render::sheet::Sheet};
+//!
+//! let tickets = ["A-1", "A-2", "A-3"];
+//! let codes = tickets
+//!     .iter()
+//!     .map(|ticket| QrCode::new(ticket).unwrap())
+//!     .collect::<Vec<_>>();
+//! let svg_xml = Sheet::new(&codes)
+//!     .columns(2)
+//!     .gap(2, 2)
+//!     .labels(&tickets)
+//!     .build_svg();
+//! assert!(svg_xml.contains(">A-3</text>"));
+//! # }
+//! ```
+//!
+//! [SVG]: https://www.w3.org/Graphics/SVG/
+
+#[cfg(feature = "svg")]
+use alloc::{format, string::String};
+#[cfg(feature = "svg")]
+use core::fmt::Write;
+
+#[cfg(feature = "image")]
+use image::{Rgb, RgbImage};
+
+#[cfg(feature = "svg")]
+use crate::render::svg::escape_xml;
+use crate::{QrCode, cast::As, types::Color};
+
+/// The height of the labels in modules.
+const LABEL_HEIGHT: u32 = 4;
+
+/// A builder which renders multiple symbols into a single grid image.
+#[derive(Clone, Copy, Debug)]
+pub struct Sheet<'a> {
+    codes: &'a [QrCode],
+    labels: &'a [&'a str],
+    columns: u32,
+    column_gap: u32,
+    row_gap: u32,
+    module_size: u32,
+    dark_color: [u8; 3],
+    light_color: [u8; 3],
+}
+
+/// The position and the size of a cell in modules.
+#[derive(Clone, Copy, Debug)]
+struct Cell {
+    left: u32,
+    top: u32,
+    width: u32,
+    height: u32,
+}
+
+impl<'a> Sheet<'a> {
+    /// Creates a new builder for the symbols.
+    ///
+    /// The defaults are 4 columns without gaps, 8 pixels per module, black on
+    /// white, and no labels.
+    #[must_use]
+    pub const fn new(codes: &'a [QrCode]) -> Self {
+        Self {
+            codes,
+            labels: &[],
+            columns: 4,
+            column_gap: 0,
+            row_gap: 0,
+            module_size: 8,
+            dark_color: [0; 3],
+            light_color: [255; 3],
+        }
+    }
+
+    /// Sets the number of columns. The value is at least 1.
+    #[inline]
+    pub fn columns(&mut self, columns: u32) -> &mut Self {
+        self.columns = columns.max(1);
+        self
+    }
+
+    /// Sets the gaps between the columns and between the rows in modules. The
+    /// gaps are added to the quiet zones of the symbols.
+    #[inline]
+    pub const fn gap(&mut self, column_gap: u32, row_gap: u32) -> &mut Self {
+        self.column_gap = column_gap;
+        self.row_gap = row_gap;
+        self
+    }
+
+    /// Sets the size of each module in pixels. This is the same for all
+    /// symbols.
+    #[inline]
+    pub const fn module_size(&mut self, module_size: u32) -> &mut Self {
+        self.module_size = module_size;
+        self
+    }
+
+    /// Sets the color of dark modules and the labels, as `[R, G, B]`.
+    #[inline]
+    pub const fn dark_color(&mut self, color: [u8; 3]) -> &mut Self {
+        self.dark_color = color;
+        self
+    }
+
+    /// Sets the color of light modules and the background, as `[R, G, B]`.
+    #[inline]
+    pub const fn light_color(&mut self, color: [u8; 3]) -> &mut Self {
+        self.light_color = color;
+        self
+    }
+
+    /// Sets the labels drawn below the symbols, in the same order as the
+    /// symbols. Symbols without a label are left unlabeled.
+    ///
+    /// A label wider than its cell is cut off in raster images, and overlaps
+    /// the neighboring cells in SVG images.
+    #[inline]
+    pub const fn labels(&mut self, labels: &'a [&'a str]) -> &mut Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Returns the width of the quiet zone of the symbol in modules.
+    const fn quiet_zone(code: &QrCode) -> u32 {
+        if code.version().is_normal() { 4 } else { 2 }
+    }
+
+    /// Returns the size of every cell in modules, without the label.
+    fn cell_size(&self) -> (u32, u32) {
+        self.codes.iter().fold((0, 0), |(width, height), code| {
+            let quiet_zone = Self::quiet_zone(code);
+            (
+                width.max(code.width().as_u32() + 2 * quiet_zone),
+                height.max(code.height().as_u32() + 2 * quiet_zone),
+            )
+        })
+    }
+
+    /// Returns the height of the labels in modules.
+    const fn label_height(&self) -> u32 {
+        if self.labels.is_empty() {
+            0
+        } else {
+            LABEL_HEIGHT
+        }
+    }
+
+    /// Returns the size of the sheet in modules.
+    fn size(&self) -> (u32, u32) {
+        let count = self.codes.len().as_u32();
+        if count == 0 {
+            return (0, 0);
+        }
+        let (columns, rows) = (self.columns.min(count), count.div_ceil(self.columns));
+        let (width, height) = self.cell_size();
+        let height = height + self.label_height();
+        (
+            columns * width + (columns - 1) * self.column_gap,
+            rows * height + (rows - 1) * self.row_gap,
+        )
+    }
+
+    /// Returns the cell of the symbol at the index, without the label.
+    fn cell(&self, index: usize) -> Cell {
+        let (width, height) = self.cell_size();
+        let (column, row) = (index.as_u32() % self.columns, index.as_u32() / self.columns);
+        Cell {
+            left: column * (width + self.column_gap),
+            top: row * (height + self.label_height() + self.row_gap),
+            width,
+            height,
+        }
+    }
+
+    /// Returns the position of the top-left module of the symbol in modules.
+    /// The symbol is centered horizontally at the top of its cell.
+    fn origin(&self, index: usize) -> (u32, u32) {
+        let code = &self.codes[index];
+        let cell = self.cell(index);
+        let quiet_zone = Self::quiet_zone(code);
+        let width = code.width().as_u32() + 2 * quiet_zone;
+        (
+            cell.left + (cell.width - width) / 2 + quiet_zone,
+            cell.top + quiet_zone,
+        )
+    }
+
+    /// Renders the symbols into an SVG image.
+    #[cfg(feature = "svg")]
+    #[must_use]
+    pub fn build_svg(&self) -> String {
+        let (width, height) = self.size();
+        let mut path = String::new();
+        let mut labels = String::new();
+        for (i, code) in self.codes.iter().enumerate() {
+            let (left, top) = self.origin(i);
+            for (y, row) in code.to_colors().chunks(code.width()).enumerate() {
+                let y = top + y.as_u32();
+                let mut x = left;
+                for run in row.chunk_by(|a, b| a == b) {
+                    let len = run.len().as_u32();
+                    if run[0] == Color::Dark {
+                        write!(path, "M{x} {y}h{len}v1h-{len}z").unwrap();
+                    }
+                    x += len;
+                }
+            }
+            if let Some(label) = self.labels.get(i) {
+                let cell = self.cell(i);
+                write!(
+                    labels,
+                    r#"<text x="{x}" y="{y}">{label}</text>"#,
+                    x = f64::from(cell.left) + f64::from(cell.width) / 2.0,
+                    y = cell.top + cell.height + LABEL_HEIGHT / 2,
+                    label = escape_xml(label)
+                )
+                .unwrap();
+            }
+        }
+
+        let [dark, light] =
+            [self.dark_color, self.light_color].map(|[r, g, b]| format!("#{r:02x}{g:02x}{b:02x}"));
+        let mut svg = format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+                r#"<svg xmlns="http://www.w3.org/2000/svg" version="1.1""#,
+                r#" width="{pw}" height="{ph}" viewBox="0 0 {w} {h}">"#,
+                r#"<path d="M0 0h{w}v{h}H0z" fill="{light}"/>"#,
+                r#"<path fill="{dark}" shape-rendering="crispEdges""#,
+                r#" d="{path}"/>"#
+            ),
+            pw = width * self.module_size,
+            ph = height * self.module_size,
+            w = width,
+            h = height,
+            light = light,
+            dark = dark,
+            path = path
+        );
+        if !labels.is_empty() {
+            write!(
+                svg,
+                concat!(
+                    r#"<g fill="{dark}" font-family="sans-serif""#,
+                    r#" font-size="2" text-anchor="middle">{labels}</g>"#
+                ),
+                dark = dark,
+                labels = labels
+            )
+            .unwrap();
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+
+    /// Renders the symbols into a raster image.
+    ///
+    /// The labels are drawn with a built-in 5×7 pixel font, which covers the
+    /// printable ASCII characters. Other characters are drawn as `?`.
+    #[cfg(feature = "image")]
+    #[must_use]
+    pub fn build_image(&self) -> RgbImage {
+        let module_size = self.module_size;
+        let (width, height) = self.size();
+        let mut image = RgbImage::from_pixel(
+            width * module_size,
+            height * module_size,
+            Rgb(self.light_color),
+        );
+        let dark_color = Rgb(self.dark_color);
+        for (i, code) in self.codes.iter().enumerate() {
+            let (left, top) = self.origin(i);
+            for (y, row) in code.to_colors().chunks(code.width()).enumerate() {
+                for (x, color) in row.iter().enumerate() {
+                    if *color == Color::Light {
+                        continue;
+                    }
+                    let (x, y) = (
+                        (left + x.as_u32()) * module_size,
+                        (top + y.as_u32()) * module_size,
+                    );
+                    for (dx, dy) in
+                        (0..module_size).flat_map(|dy| (0..module_size).map(move |dx| (dx, dy)))
+                    {
+                        image.put_pixel(x + dx, y + dy, dark_color);
+                    }
+                }
+            }
+            if let Some(label) = self.labels.get(i) {
+                draw_label(&mut image, self.cell(i), label, module_size, dark_color);
+            }
+        }
+        image
+    }
+}
+
+/// The glyphs of the printable ASCII characters from `' '` to `'~'`, as 5×7
+/// bitmaps. Each byte is a column from left to right, where the least
+/// significant bit is the top pixel.
+#[cfg(feature = "image")]
+const FONT: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x5f, 0x00, 0x00],
+    [0x00, 0x07, 0x00, 0x07, 0x00],
+    [0x14, 0x7f, 0x14, 0x7f, 0x14],
+    [0x24, 0x2a, 0x7f, 0x2a, 0x12],
+    [0x23, 0x13, 0x08, 0x64, 0x62],
+    [0x36, 0x49, 0x55, 0x22, 0x50],
+    [0x00, 0x05, 0x03, 0x00, 0x00],
+    [0x00, 0x1c, 0x22, 0x41, 0x00],
+    [0x00, 0x41, 0x22, 0x1c, 0x00],
+    [0x08, 0x2a, 0x1c, 0x2a, 0x08],
+    [0x08, 0x08, 0x3e, 0x08, 0x08],
+    [0x00, 0x50, 0x30, 0x00, 0x00],
+    [0x08, 0x08, 0x08, 0x08, 0x08],
+    [0x00, 0x60, 0x60, 0x00, 0x00],
+    [0x20, 0x10, 0x08, 0x04, 0x02],
+    [0x3e, 0x51, 0x49, 0x45, 0x3e],
+    [0x00, 0x42, 0x7f, 0x40, 0x00],
+    [0x42, 0x61, 0x51, 0x49, 0x46],
+    [0x21, 0x41, 0x45, 0x4b, 0x31],
+    [0x18, 0x14, 0x12, 0x7f, 0x10],
+    [0x27, 0x45, 0x45, 0x45, 0x39],
+    [0x3c, 0x4a, 0x49, 0x49, 0x30],
+    [0x01, 0x71, 0x09, 0x05, 0x03],
+    [0x36, 0x49, 0x49, 0x49, 0x36],
+    [0x06, 0x49, 0x49, 0x29, 0x1e],
+    [0x00, 0x36, 0x36, 0x00, 0x00],
+    [0x00, 0x56, 0x36, 0x00, 0x00],
+    [0x08, 0x14, 0x22, 0x41, 0x00],
+    [0x14, 0x14, 0x14, 0x14, 0x14],
+    [0x00, 0x41, 0x22, 0x14, 0x08],
+    [0x02, 0x01, 0x51, 0x09, 0x06],
+    [0x32, 0x49, 0x79, 0x41, 0x3e],
+    [0x7e, 0x11, 0x11, 0x11, 0x7e],
+    [0x7f, 0x49, 0x49, 0x49, 0x36],
+    [0x3e, 0x41, 0x41, 0x41, 0x22],
+    [0x7f, 0x41, 0x41, 0x22, 0x1c],
+    [0x7f, 0x49, 0x49, 0x49, 0x41],
+    [0x7f, 0x09, 0x09, 0x09, 0x01],
+    [0x3e, 0x41, 0x49, 0x49, 0x7a],
+    [0x7f, 0x08, 0x08, 0x08, 0x7f],
+    [0x00, 0x41, 0x7f, 0x41, 0x00],
+    [0x20, 0x40, 0x41, 0x3f, 0x01],
+    [0x7f, 0x08, 0x14, 0x22, 0x41],
+    [0x7f, 0x40, 0x40, 0x40, 0x40],
+    [0x7f, 0x02, 0x0c, 0x02, 0x7f],
+    [0x7f, 0x04, 0x08, 0x10, 0x7f],
+    [0x3e, 0x41, 0x41, 0x41, 0x3e],
+    [0x7f, 0x09, 0x09, 0x09, 0x06],
+    [0x3e, 0x41, 0x51, 0x21, 0x5e],
+    [0x7f, 0x09, 0x19, 0x29, 0x46],
+    [0x46, 0x49, 0x49, 0x49, 0x31],
+    [0x01, 0x01, 0x7f, 0x01, 0x01],
+    [0x3f, 0x40, 0x40, 0x40, 0x3f],
+    [0x1f, 0x20, 0x40, 0x20, 0x1f],
+    [0x3f, 0x40, 0x38, 0x40, 0x3f],
+    [0x63, 0x14, 0x08, 0x14, 0x63],
+    [0x07, 0x08, 0x70, 0x08, 0x07],
+    [0x61, 0x51, 0x49, 0x45, 0x43],
+    [0x00, 0x7f, 0x41, 0x41, 0x00],
+    [0x02, 0x04, 0x08, 0x10, 0x20],
+    [0x00, 0x41, 0x41, 0x7f, 0x00],
+    [0x04, 0x02, 0x01, 0x02, 0x04],
+    [0x40, 0x40, 0x40, 0x40, 0x40],
+    [0x00, 0x01, 0x02, 0x04, 0x00],
+    [0x20, 0x54, 0x54, 0x54, 0x78],
+    [0x7f, 0x48, 0x44, 0x44, 0x38],
+    [0x38, 0x44, 0x44, 0x44, 0x20],
+    [0x38, 0x44, 0x44, 0x48, 0x7f],
+    [0x38, 0x54, 0x54, 0x54, 0x18],
+    [0x08, 0x7e, 0x09, 0x01, 0x02],
+    [0x0c, 0x52, 0x52, 0x52, 0x3e],
+    [0x7f, 0x08, 0x04, 0x04, 0x78],
+    [0x00, 0x44, 0x7d, 0x40, 0x00],
+    [0x20, 0x40, 0x44, 0x3d, 0x00],
+    [0x7f, 0x10, 0x28, 0x44, 0x00],
+    [0x00, 0x41, 0x7f, 0x40, 0x00],
+    [0x7c, 0x04, 0x18, 0x04, 0x78],
+    [0x7c, 0x08, 0x04, 0x04, 0x78],
+    [0x38, 0x44, 0x44, 0x44, 0x38],
+    [0x7c, 0x14, 0x14, 0x14, 0x08],
+    [0x08, 0x14, 0x14, 0x18, 0x7c],
+    [0x7c, 0x08, 0x04, 0x04, 0x08],
+    [0x48, 0x54, 0x54, 0x54, 0x20],
+    [0x04, 0x3f, 0x44, 0x40, 0x20],
+    [0x3c, 0x40, 0x40, 0x20, 0x7c],
+    [0x1c, 0x20, 0x40, 0x20, 0x1c],
+    [0x3c, 0x40, 0x30, 0x40, 0x3c],
+    [0x44, 0x28, 0x10, 0x28, 0x44],
+    [0x0c, 0x50, 0x50, 0x50, 0x3c],
+    [0x44, 0x64, 0x54, 0x4c, 0x44],
+    [0x00, 0x08, 0x36, 0x41, 0x00],
+    [0x00, 0x00, 0x7f, 0x00, 0x00],
+    [0x00, 0x41, 0x36, 0x08, 0x00],
+    [0x08, 0x04, 0x08, 0x10, 0x08],
+];
+
+/// Draws the label centered below the cell, cut off at the edges of the cell.
+#[cfg(feature = "image")]
+fn draw_label(image: &mut RgbImage, cell: Cell, label: &str, module_size: u32, color: Rgb<u8>) {
+    // Each glyph is 6 pixels wide including the spacing, and 7 pixels high,
+    // so that the text is about 2 modules high.
+    let scale = (2 * module_size / 7).max(1);
+    let text_width = (label.chars().count().as_u32() * 6 * scale).saturating_sub(scale);
+    let (cell_left, cell_right) = (
+        cell.left * module_size,
+        (cell.left + cell.width) * module_size,
+    );
+    let center = u32::midpoint(cell_left, cell_right);
+    let left = center.saturating_sub(text_width / 2);
+    let top = ((cell.top + cell.height + LABEL_HEIGHT / 2) * module_size).saturating_sub(7 * scale);
+    for (n, c) in label.chars().enumerate() {
+        let c = if (' '..='~').contains(&c) { c } else { '?' };
+        let index = u32::from(c) - u32::from(' ');
+        for (i, column) in FONT[index.as_usize()].iter().enumerate() {
+            for j in 0..7 {
+                if column & 1 << j == 0 {
+                    continue;
+                }
+                let x = left + (n.as_u32() * 6 + i.as_u32()) * scale;
+                let y = top + j * scale;
+                for (dx, dy) in (0..scale).flat_map(|dy| (0..scale).map(move |dx| (dx, dy))) {
+                    if (cell_left..cell_right).contains(&(x + dx)) && y + dy < image.height() {
+                        image.put_pixel(x + dx, y + dy, color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "svg")]
+    #[test]
+    fn test_svg() {
+        let codes = [
+            QrCode::new(b"01234567").unwrap(),
+            QrCode::new_micro(b"01234567").unwrap(),
+            QrCode::new(b"01234567").unwrap(),
+        ];
+        let svg = Sheet::new(&codes)
+            .columns(2)
+            .gap(3, 1)
+            .module_size(1)
+            .build_svg();
+        // 2 columns of 29 modules, and 2 rows of 29 modules.
+        assert!(svg.contains(r#"width="61" height="59" viewBox="0 0 61 59""#));
+        // The finder patterns of all symbols. The Micro QR code is centered in
+        // its cell.
+        assert!(svg.contains("M4 4h7v1h-7z"));
+        assert!(svg.contains("M40 2h7v1h-7z"));
+        assert!(svg.contains("M4 34h7v1h-7z"));
+        assert!(!svg.contains("<text"));
+    }
+
+    #[cfg(feature = "svg")]
+    #[test]
+    fn test_svg_with_labels() {
+        let codes = [
+            QrCode::new(b"01234567").unwrap(),
+            QrCode::new(b"01234567").unwrap(),
+        ];
+        let svg = Sheet::new(&codes)
+            .columns(1)
+            .labels(&["A&B"])
+            .dark_color([0x12, 0x34, 0x56])
+            .build_svg();
+        assert!(svg.contains(r#"width="232" height="528" viewBox="0 0 29 66""#));
+        assert!(svg.contains("M4 37h7v1h-7z"));
+        assert!(svg.contains(r##"<g fill="#123456""##));
+        assert!(svg.contains(r#"<text x="14.5" y="31">A&amp;B</text></g>"#));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_image() {
+        let codes = [
+            QrCode::new(b"01234567").unwrap(),
+            QrCode::new_rect_micro(b"01234567").unwrap(),
+        ];
+        let image = Sheet::new(&codes)
+            .gap(1, 0)
+            .module_size(2)
+            .labels(&["1", "2"])
+            .build_image();
+        // Each cell is 31×29 modules, with 4 modules for the label below.
+        assert_eq!(image.dimensions(), (2 * (2 * 31 + 1), 2 * 33));
+        assert_eq!(image.get_pixel(0, 0), &Rgb([255; 3]));
+        // The top-left corners of the finder patterns.
+        assert_eq!(image.get_pixel(2 * 5, 2 * 4), &Rgb([0; 3]));
+        assert_eq!(image.get_pixel(2 * 34, 2 * 2), &Rgb([0; 3]));
+        // The labels are drawn below the symbols.
+        let is_dark = |x0: u32, x1: u32| {
+            (x0..x1).any(|x| (2 * 29..2 * 31).any(|y| image.get_pixel(x, y) == &Rgb([0; 3])))
+        };
+        assert!(is_dark(0, 2 * 31));
+        assert!(is_dark(2 * 32, 2 * 63));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_font() {
+        let mut image = RgbImage::from_pixel(12, 8, Rgb([255; 3]));
+        let cell = Cell {
+            left: 0,
+            top: 0,
+            width: 12,
+            height: 0,
+        };
+        draw_label(&mut image, cell, "Hé", 1, Rgb([0; 3]));
+        let rows = (0..8)
+            .map(|y| {
+                (0..12)
+                    .map(|x| {
+                        if image.get_pixel(x, y)[0] == 0 {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        // "H" followed by "?" for the non-ASCII character, centered in the 12
+        // pixels.
+        assert_eq!(
+            rows,
+            [
+                ".#...#..###.",
+                ".#...#.#...#",
+                ".#...#.....#",
+                ".#####....#.",
+                ".#...#...#..",
+                ".#...#......",
+                ".#...#...#..",
+                "............",
+            ]
+        );
+    }
+}
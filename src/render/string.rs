@@ -18,10 +18,11 @@
 //! ```
 
 use alloc::{string::String, vec, vec::Vec};
+use core::{cmp, fmt};
 
 use crate::{
     cast::As,
-    render::{Canvas as RenderCanvas, Pixel},
+    render::{Canvas as RenderCanvas, CanvasOptions, Pixel, Renderer, WriteCanvas},
     types::Color,
 };
 
@@ -35,6 +36,21 @@ pub trait Element: Copy {
 
     /// Appends `self` to the end of the given `string`.
     fn append_to_string(self, string: &mut String);
+
+    /// Writes `self` into the given `writer`.
+    ///
+    /// The default implementation appends `self` to a temporary [`String`]
+    /// first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing into `writer` fails.
+    #[inline]
+    fn write_to<W: fmt::Write + ?Sized>(self, writer: &mut W) -> fmt::Result {
+        let mut string = String::with_capacity(self.str_len());
+        self.append_to_string(&mut string);
+        writer.write_str(&string)
+    }
 }
 
 impl Element for char {
@@ -52,6 +68,11 @@ impl Element for char {
     fn append_to_string(self, string: &mut String) {
         string.push(self);
     }
+
+    #[inline]
+    fn write_to<W: fmt::Write + ?Sized>(self, writer: &mut W) -> fmt::Result {
+        writer.write_char(self)
+    }
 }
 
 impl Element for &str {
@@ -69,6 +90,11 @@ impl Element for &str {
     fn append_to_string(self, string: &mut String) {
         string.push_str(self);
     }
+
+    #[inline]
+    fn write_to<W: fmt::Write + ?Sized>(self, writer: &mut W) -> fmt::Result {
+        writer.write_str(self)
+    }
 }
 
 /// A canvas for string rendering.
@@ -79,6 +105,8 @@ pub struct Canvas<P: Element> {
     dark_pixel: P,
     dark_cap_inc: isize,
     capacity: isize,
+    row_ends: Vec<usize>,
+    options: CanvasOptions,
 }
 
 impl<P: Element> Pixel for P {
@@ -114,6 +142,8 @@ impl<P: Element> RenderCanvas for Canvas<P> {
             dark_pixel,
             dark_cap_inc,
             capacity,
+            row_ends: vec![0; height.as_usize()],
+            options: CanvasOptions::default(),
         }
     }
 
@@ -122,24 +152,100 @@ impl<P: Element> RenderCanvas for Canvas<P> {
         let y = y.as_usize();
         self.capacity += self.dark_cap_inc;
         self.buffer[x + y * self.width] = self.dark_pixel;
+        self.row_ends[y] = cmp::max(self.row_ends[y], x + 1);
+    }
+
+    #[inline]
+    fn configure(&mut self, options: &CanvasOptions) {
+        self.options = options.clone();
     }
 
     fn into_image(self) -> Self::Image {
-        let mut result = String::with_capacity(self.capacity.as_usize());
-        for (i, pixel) in self.buffer.into_iter().enumerate() {
-            if i != 0 && i % self.width == 0 {
-                result.push('\n');
-            }
-            pixel.append_to_string(&mut result);
+        let line_ending = self.options.line_ending.as_str();
+        let lines_count = self.row_ends.len();
+        let mut capacity = self.capacity.as_usize() + (line_ending.len() - 1) * lines_count;
+        if self.options.trailing_newline {
+            capacity += line_ending.len();
         }
+        let mut result = String::with_capacity(capacity);
+        self.write_image(&mut result)
+            .expect("writing into a string should not fail");
         result
     }
 }
 
+impl<P: Element> WriteCanvas for Canvas<P> {
+    fn write_image<W: fmt::Write + ?Sized>(self, writer: &mut W) -> fmt::Result {
+        let line_ending = self.options.line_ending.as_str();
+        for (i, row) in self.rows().enumerate() {
+            if i != 0 {
+                writer.write_str(line_ending)?;
+            }
+            for pixel in row {
+                pixel.write_to(writer)?;
+            }
+        }
+        if self.options.trailing_newline {
+            writer.write_str(line_ending)?;
+        }
+        Ok(())
+    }
+}
+
+impl<P: Element> Canvas<P> {
+    /// Returns the rows of pixels, without the trailing light pixels if
+    /// trimming is enabled.
+    fn rows(&self) -> impl Iterator<Item = &[P]> {
+        let trim = self.options.trim_trailing_light;
+        self.buffer
+            .chunks_exact(cmp::max(self.width, 1))
+            .zip(&self.row_ends)
+            .map(move |(row, end)| if trim { &row[..*end] } else { row })
+    }
+
+    /// Finalizes the canvas to a list of lines, without line endings.
+    fn into_lines(self) -> Vec<String> {
+        self.rows()
+            .map(|row| {
+                let mut line = String::with_capacity(row.iter().map(|p| p.str_len()).sum());
+                for pixel in row {
+                    pixel.append_to_string(&mut line);
+                }
+                line
+            })
+            .collect()
+    }
+}
+
+impl<P: Element> Renderer<'_, P> {
+    /// Renders the QR code into a list of lines, one for each row of pixels.
+    ///
+    /// The lines do not contain line endings, so [`Renderer::line_ending`] and
+    /// [`Renderer::trailing_newline`] are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::QrCode;
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let lines = code.render::<char>().build_lines();
+    /// assert_eq!(lines.len(), 29);
+    /// for line in lines {
+    ///     println!("> {line}");
+    /// }
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn build_lines(&self) -> Vec<String> {
+        self.draw().into_lines()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::render::Renderer;
+    use crate::render::{LineEnding, Renderer};
 
     #[test]
     fn test_render_to_string() {
@@ -170,4 +276,49 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_build_lines() {
+        let colors = &[Color::Dark, Color::Light, Color::Light, Color::Dark];
+        let lines = Renderer::new(colors, 2, 2, 1)
+            .light_color("A")
+            .dark_color("!B!")
+            .build_lines();
+        assert_eq!(lines, ["AAAA", "A!B!AA", "AA!B!A", "AAAA"]);
+    }
+
+    #[test]
+    fn test_trim_trailing_light() {
+        let colors = &[Color::Dark, Color::Light, Color::Light, Color::Light];
+        let image = Renderer::<char>::new(colors, 2, 2, 1)
+            .dark_color('#')
+            .light_color('.')
+            .trim_trailing_light(true)
+            .build();
+        assert_eq!(image, "\n.#\n\n");
+
+        let lines = Renderer::<char>::new(colors, 2, 2, 1)
+            .trim_trailing_light(true)
+            .build_lines();
+        assert_eq!(lines, ["", " \u{2588}", "", ""]);
+    }
+
+    #[test]
+    fn test_line_ending() {
+        let colors = &[Color::Dark, Color::Light, Color::Light, Color::Dark];
+        let image = Renderer::<char>::new(colors, 2, 2, 0)
+            .dark_color('#')
+            .light_color('.')
+            .line_ending(LineEnding::CrLf)
+            .build();
+        assert_eq!(image, "#.\r\n.#");
+
+        let image = Renderer::<char>::new(colors, 2, 2, 0)
+            .dark_color('#')
+            .light_color('.')
+            .line_ending(LineEnding::CrLf)
+            .trailing_newline(true)
+            .build();
+        assert_eq!(image, "#.\r\n.#\r\n");
+    }
 }
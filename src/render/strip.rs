@@ -0,0 +1,233 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Rendering of multiple linked symbols into a single [SVG] image.
+//!
+//! A payload which is too long for one symbol can be split across several
+//! symbols linked by structured append. [`Strip`] lays such symbols out in a
+//! row or a column, each with its own quiet zone, the same module size, and
+//! an optional sequence caption such as `2/3`, so that they can be shipped as
+//! a single artifact.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, render::strip::Strip};
+//!
+//! let codes = [
+//!     QrCode::new(b"first part").unwrap(),
+//!     QrCode::new(b"second part").unwrap(),
+//! ];
+//! let svg_xml = Strip::new(&codes).captions(true).build();
+//! assert!(svg_xml.contains(">2/2</text>"));
+//! ```
+//!
+//! [SVG]: https://www.w3.org/Graphics/SVG/
+
+use alloc::{format, string::String};
+use core::{cmp, fmt::Write};
+
+use crate::{QrCode, cast::As, render::svg::Color, types::Color as ModuleColor};
+
+/// The height of the sequence captions in modules.
+const CAPTION_HEIGHT: u32 = 4;
+
+/// The direction in which the symbols are laid out.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Direction {
+    /// From left to right.
+    #[default]
+    Horizontal,
+
+    /// From top to bottom.
+    Vertical,
+}
+
+/// A builder which renders multiple symbols into a single SVG image.
+#[derive(Clone, Copy, Debug)]
+pub struct Strip<'a> {
+    codes: &'a [QrCode],
+    direction: Direction,
+    module_size: u32,
+    dark_color: Color<'a>,
+    light_color: Color<'a>,
+    has_captions: bool,
+}
+
+impl<'a> Strip<'a> {
+    /// Creates a new builder for the symbols, in sequence order.
+    ///
+    /// The defaults are a horizontal layout, 8 pixels per module, black on
+    /// white, and no captions.
+    #[must_use]
+    pub const fn new(codes: &'a [QrCode]) -> Self {
+        Self {
+            codes,
+            direction: Direction::Horizontal,
+            module_size: 8,
+            dark_color: Color("#000"),
+            light_color: Color("#fff"),
+            has_captions: false,
+        }
+    }
+
+    /// Sets the direction in which the symbols are laid out.
+    #[inline]
+    pub const fn direction(&mut self, direction: Direction) -> &mut Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Sets the size of each module in pixels. This is the same for all
+    /// symbols.
+    #[inline]
+    pub const fn module_size(&mut self, module_size: u32) -> &mut Self {
+        self.module_size = module_size;
+        self
+    }
+
+    /// Sets the color of dark modules.
+    #[inline]
+    pub const fn dark_color(&mut self, color: Color<'a>) -> &mut Self {
+        self.dark_color = color;
+        self
+    }
+
+    /// Sets the color of light modules and the background.
+    #[inline]
+    pub const fn light_color(&mut self, color: Color<'a>) -> &mut Self {
+        self.light_color = color;
+        self
+    }
+
+    /// Sets whether to draw a caption such as `1/3` below each symbol.
+    #[inline]
+    pub const fn captions(&mut self, has_captions: bool) -> &mut Self {
+        self.has_captions = has_captions;
+        self
+    }
+
+    /// Renders the symbols into an SVG image.
+    #[must_use]
+    pub fn build(&self) -> String {
+        let caption_height = if self.has_captions { CAPTION_HEIGHT } else { 0 };
+        // The size of each symbol in modules, including the quiet zone.
+        let cell = |code: &QrCode| {
+            let quiet_zone = if code.version().is_normal() { 4 } else { 2 };
+            (
+                code.width().as_u32() + 2 * quiet_zone,
+                code.height().as_u32() + 2 * quiet_zone + caption_height,
+            )
+        };
+        let (width, height) =
+            self.codes
+                .iter()
+                .map(cell)
+                .fold((0, 0), |(w, h), (cw, ch)| match self.direction {
+                    Direction::Horizontal => (w + cw, cmp::max(h, ch)),
+                    Direction::Vertical => (cmp::max(w, cw), h + ch),
+                });
+
+        let mut path = String::new();
+        let mut captions = String::new();
+        let (mut left, mut top) = (0, 0);
+        for (i, code) in self.codes.iter().enumerate() {
+            let quiet_zone = if code.version().is_normal() { 4 } else { 2 };
+            for (y, row) in code.to_colors().chunks(code.width()).enumerate() {
+                let y = top + quiet_zone + y.as_u32();
+                let mut x = 0;
+                for run in row.chunk_by(|a, b| a == b) {
+                    let len = run.len().as_u32();
+                    if run[0] == ModuleColor::Dark {
+                        let x = left + quiet_zone + x;
+                        write!(path, "M{x} {y}h{len}v1h-{len}z").unwrap();
+                    }
+                    x += len;
+                }
+            }
+            let (cell_width, cell_height) = cell(code);
+            if self.has_captions {
+                write!(
+                    captions,
+                    r#"<text x="{x}" y="{y}">{n}/{total}</text>"#,
+                    x = left + cell_width / 2,
+                    y = top + cell_height - 2,
+                    n = i + 1,
+                    total = self.codes.len()
+                )
+                .unwrap();
+            }
+            match self.direction {
+                Direction::Horizontal => left += cell_width,
+                Direction::Vertical => top += cell_height,
+            }
+        }
+
+        let mut svg = format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+                r#"<svg xmlns="http://www.w3.org/2000/svg" version="1.1""#,
+                r#" width="{pw}" height="{ph}" viewBox="0 0 {w} {h}">"#,
+                r#"<path d="M0 0h{w}v{h}H0z" fill="{bg}"/>"#,
+                r#"<path fill="{fg}" shape-rendering="crispEdges" d="{path}"/>"#
+            ),
+            pw = width * self.module_size,
+            ph = height * self.module_size,
+            w = width,
+            h = height,
+            fg = self.dark_color.0,
+            bg = self.light_color.0,
+            path = path
+        );
+        if self.has_captions {
+            write!(
+                svg,
+                concat!(
+                    r#"<g fill="{fg}" font-family="sans-serif" font-size="2""#,
+                    r#" text-anchor="middle">{captions}</g>"#
+                ),
+                fg = self.dark_color.0,
+                captions = captions
+            )
+            .unwrap();
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_horizontal() {
+        let codes = [
+            QrCode::new(b"01234567").unwrap(),
+            QrCode::new_micro(b"01234567").unwrap(),
+        ];
+        let svg = Strip::new(&codes).module_size(1).build();
+        // 21 + 8 modules, followed by 13 + 4 modules.
+        assert!(svg.contains(r#"width="46" height="29" viewBox="0 0 46 29""#));
+        // The finder patterns of both symbols.
+        assert!(svg.contains("M4 4h7v1h-7z"));
+        assert!(svg.contains("M31 2h7v1h-7z"));
+        assert!(!svg.contains("<text"));
+    }
+
+    #[test]
+    fn test_vertical_with_captions() {
+        let codes = [
+            QrCode::new(b"01234567").unwrap(),
+            QrCode::new(b"01234567").unwrap(),
+        ];
+        let svg = Strip::new(&codes)
+            .direction(Direction::Vertical)
+            .captions(true)
+            .build();
+        assert!(svg.contains(r#"width="232" height="528" viewBox="0 0 29 66""#));
+        assert!(svg.contains("M4 37h7v1h-7z"));
+        assert!(svg.contains(r#"<text x="14" y="31">1/2</text><text x="14" y="64">2/2</text>"#));
+    }
+}
@@ -0,0 +1,144 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Render settings which can be loaded from configuration files.
+//!
+//! A [`RenderStyle`] holds the settings of a [`Renderer`] as plain data. With
+//! the `serde` feature, it can be deserialized from TOML, JSON, or any other
+//! format supported by [`serde`](https://serde.rs/), and then applied with
+//! [`Renderer::style`]. Every field is optional, and only the fields which are
+//! set override the settings of the renderer.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, render::style::RenderStyle};
+//!
+//! let style = RenderStyle {
+//!     dark_color: Some('#'),
+//!     light_color: Some('.'),
+//!     has_quiet_zone: Some(false),
+//!     ..RenderStyle::default()
+//! };
+//! let code = QrCode::new(b"Hello").unwrap();
+//! let s = code.render().style(&style).build();
+//! assert!(s.starts_with("#######."));
+//! ```
+
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+
+use crate::render::{Background, Pixel, Preset, Renderer};
+
+/// Render settings as plain data. See the [module-level
+/// documentation](self) for details.
+///
+/// The settings are applied in the order of the fields, so the [`Preset`] is
+/// applied first and the other fields override it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct RenderStyle<P> {
+    /// The preset to start from. See [`Renderer::preset`].
+    pub preset: Option<Preset>,
+
+    /// The color of dark modules. See [`Renderer::dark_color`].
+    pub dark_color: Option<P>,
+
+    /// The color of light modules. See [`Renderer::light_color`].
+    pub light_color: Option<P>,
+
+    /// The size of the quiet zone in modules. See [`Renderer::quiet_zone`].
+    pub quiet_zone: Option<u32>,
+
+    /// Whether to include the quiet zone. See [`Renderer::has_quiet_zone`].
+    pub has_quiet_zone: Option<bool>,
+
+    /// The size of each module in pixels. See
+    /// [`Renderer::module_dimensions`].
+    pub module_dimensions: Option<(u32, u32)>,
+
+    /// The minimum total image size in pixels. See
+    /// [`Renderer::min_dimensions`].
+    pub min_dimensions: Option<(u32, u32)>,
+
+    /// The maximum total image size in pixels. See
+    /// [`Renderer::max_dimensions`].
+    pub max_dimensions: Option<(u32, u32)>,
+
+    /// How the background of vector outputs is painted.
+    pub background: Option<Background>,
+}
+
+impl<P: Pixel> Renderer<'_, P> {
+    /// Applies the settings of a [`RenderStyle`]. Fields which are [`None`]
+    /// leave the current settings unchanged.
+    pub fn style(&mut self, style: &RenderStyle<P>) -> &mut Self {
+        if let Some(preset) = style.preset {
+            self.preset(preset);
+        }
+        if let Some(color) = style.dark_color {
+            self.dark_color(color);
+        }
+        if let Some(color) = style.light_color {
+            self.light_color(color);
+        }
+        if let Some(quiet_zone) = style.quiet_zone {
+            self.quiet_zone(quiet_zone);
+        }
+        if let Some(has_quiet_zone) = style.has_quiet_zone {
+            self.has_quiet_zone(has_quiet_zone);
+        }
+        if let Some((width, height)) = style.module_dimensions {
+            self.module_dimensions(width, height);
+        }
+        if let Some((width, height)) = style.min_dimensions {
+            self.min_dimensions(width, height);
+        }
+        if let Some((width, height)) = style.max_dimensions {
+            self.max_dimensions(width, height);
+        }
+        if let Some(background) = style.background {
+            self.canvas_options.background = background;
+        }
+        self
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::QrCode;
+
+    #[test]
+    fn test_deserialize() {
+        let style: RenderStyle<char> = toml::from_str(
+            r##"
+            preset = "screen"
+            dark_color = "#"
+            light_color = "."
+            quiet_zone = 1
+            module_dimensions = [2, 1]
+            background = "none"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(style.preset, Some(Preset::Screen));
+        assert_eq!(style.module_dimensions, Some((2, 1)));
+        assert_eq!(style.background, Some(Background::None));
+        assert_eq!(style.has_quiet_zone, None);
+
+        let code = QrCode::new_micro(b"1").unwrap();
+        let s = code.render().style(&style).build();
+        // M2 is 13 modules wide, plus the quiet zone of 1 module on each side.
+        let first_row = ".".repeat(30);
+        assert_eq!(s.lines().next(), Some(first_row.as_str()));
+        assert!(s.lines().nth(1).unwrap().starts_with("..##############."));
+    }
+
+    #[test]
+    fn test_unknown_field() {
+        assert!(toml::from_str::<RenderStyle<char>>("colour = \"#\"").is_err());
+    }
+}
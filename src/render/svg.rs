@@ -20,11 +20,16 @@
 //!
 //! [SVG]: https://www.w3.org/Graphics/SVG/
 
-use alloc::{format, string::String};
-use core::{fmt::Write, marker::PhantomData};
+use alloc::{format, string::String, vec, vec::Vec};
+use core::{
+    cmp,
+    fmt::{self, Write},
+};
 
 use crate::{
-    render::{Canvas as RenderCanvas, Pixel},
+    render::{
+        self, Background, Canvas as RenderCanvas, CanvasOptions, Pixel, Renderer, Unit, WriteCanvas,
+    },
     types::Color as ModuleColor,
 };
 
@@ -38,6 +43,7 @@ use crate::{
 ///
 /// [CSS Color Module Level 4]: https://www.w3.org/TR/css-color-4/
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct Color<'a>(pub &'a str);
 
 impl<'a> Pixel for Color<'a> {
@@ -48,38 +54,97 @@ impl<'a> Pixel for Color<'a> {
     fn default_color(color: ModuleColor) -> Self {
         Color(color.select("#000", "#fff"))
     }
+
+    /// Obtains the luminance of the color if it is in the `#rgb` or `#rrggbb`
+    /// form.
+    #[inline]
+    fn luminance(self) -> Option<f64> {
+        render::css_luminance(self.0)
+    }
+}
+
+/// A gradient which fills the dark modules, set by [`Renderer::dark_fill`].
+///
+/// The gradient spans the bounding box of the dark modules. Each stop is an
+/// offset from `0.0` at the start to `1.0` at the end of the gradient, and its
+/// color. There should be at least two stops.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fill {
+    /// A linear gradient.
+    LinearGradient {
+        /// The offsets and colors of the gradient.
+        stops: Vec<(f64, String)>,
+
+        /// The direction of the gradient in degrees, clockwise from left to
+        /// right, e.g. `90.0` runs from top to bottom.
+        angle: f64,
+    },
+
+    /// A radial gradient from the center to the edges.
+    RadialGradient {
+        /// The offsets and colors of the gradient.
+        stops: Vec<(f64, String)>,
+    },
+}
+
+impl Fill {
+    /// Writes the gradient element with the given `id`.
+    fn write_gradient<W: Write + ?Sized>(&self, svg: &mut W, id: &str) -> fmt::Result {
+        let (element, stops) = match self {
+            Self::LinearGradient { stops, angle } => {
+                write!(
+                    svg,
+                    r#"<linearGradient id="{id}" gradientTransform="rotate({} 0.5 0.5)">"#,
+                    render::Length(*angle)
+                )?;
+                ("linearGradient", stops)
+            }
+            Self::RadialGradient { stops } => {
+                write!(svg, r#"<radialGradient id="{id}">"#)?;
+                ("radialGradient", stops)
+            }
+        };
+        for (offset, color) in stops {
+            write!(
+                svg,
+                r#"<stop offset="{}" stop-color="{}"/>"#,
+                render::Length(*offset),
+                escape_xml(color)
+            )?;
+        }
+        write!(svg, "</{element}>")
+    }
 }
 
 /// A canvas for SVG rendering.
 #[derive(Debug)]
 pub struct Canvas<'a> {
-    svg: String,
-    marker: PhantomData<Color<'a>>,
+    width: u32,
+    height: u32,
+    dark_pixel: Color<'a>,
+    light_pixel: Color<'a>,
+    rects: Vec<Rect>,
+    colored_rects: Vec<(Color<'a>, Vec<Rect>)>,
+    options: CanvasOptions,
 }
 
+/// A dark rectangle as `(left, top, width, height)`.
+type Rect = (u32, u32, u32, u32);
+
 impl<'a> RenderCanvas for Canvas<'a> {
     type Pixel = Color<'a>;
     type Image = String;
 
     #[inline]
     fn new(width: u32, height: u32, dark_pixel: Self::Pixel, light_pixel: Self::Pixel) -> Self {
-        let svg = format!(
-            concat!(
-                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
-                r#"<svg xmlns="http://www.w3.org/2000/svg""#,
-                r#" version="1.1" width="{w}" height="{h}""#,
-                r#" viewBox="0 0 {w} {h}" shape-rendering="crispEdges">"#,
-                r#"<path d="M0 0h{w}v{h}H0z" fill="{bg}"/>"#,
-                r#"<path fill="{fg}" d=""#
-            ),
-            w = width,
-            h = height,
-            fg = dark_pixel.0,
-            bg = light_pixel.0
-        );
         Self {
-            svg,
-            marker: PhantomData,
+            width,
+            height,
+            dark_pixel,
+            light_pixel,
+            rects: Vec::new(),
+            colored_rects: Vec::new(),
+            options: CanvasOptions::default(),
         }
     }
 
@@ -88,14 +153,359 @@ impl<'a> RenderCanvas for Canvas<'a> {
         self.draw_dark_rect(x, y, 1, 1);
     }
 
+    /// Draws a dark rectangle, extending the previous one if it ends where
+    /// this one starts on the same rows.
     #[inline]
     fn draw_dark_rect(&mut self, left: u32, top: u32, width: u32, height: u32) {
-        write!(self.svg, "M{left} {top}h{width}v{height}h-{width}z").unwrap();
+        push_rect(&mut self.rects, (left, top, width, height));
+    }
+
+    #[inline]
+    fn draw_pixel(&mut self, x: u32, y: u32, pixel: Self::Pixel) {
+        self.draw_rect(x, y, 1, 1, pixel);
+    }
+
+    /// Draws a rectangle in its own `<path>` element, which is shared by all
+    /// the rectangles of the same color.
+    #[inline]
+    fn draw_rect(&mut self, left: u32, top: u32, width: u32, height: u32, pixel: Self::Pixel) {
+        if pixel == self.dark_pixel {
+            self.draw_dark_rect(left, top, width, height);
+            return;
+        }
+        let rect = (left, top, width, height);
+        match self.colored_rects.iter_mut().find(|(c, _)| *c == pixel) {
+            Some((_, rects)) => push_rect(rects, rect),
+            None => self.colored_rects.push((pixel, vec![rect])),
+        }
+    }
+
+    #[inline]
+    fn configure(&mut self, options: &CanvasOptions) {
+        self.options = options.clone();
+    }
+
+    #[inline]
+    fn into_image(self) -> Self::Image {
+        render::write_image_to_string(self)
+    }
+}
+
+impl WriteCanvas for Canvas<'_> {
+    fn write_image<W: Write + ?Sized>(self, svg: &mut W) -> fmt::Result {
+        let (w, h) = (self.width, self.height);
+        let (width, height) = match self.options.physical_module_size {
+            Some((size, unit)) => {
+                let unit = unit.as_css();
+                let (module_width, module_height) = self.options.module_size;
+                let modules_x = f64::from(w) / f64::from(cmp::max(module_width, 1));
+                let modules_y = f64::from(h) / f64::from(cmp::max(module_height, 1));
+                (
+                    format!("{}{unit}", render::Length(modules_x * size)),
+                    format!("{}{unit}", render::Length(modules_y * size)),
+                )
+            }
+            None => (format!("{w}"), format!("{h}")),
+        };
+        write!(
+            svg,
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+                r#"<svg xmlns="http://www.w3.org/2000/svg""#,
+                r#" version="1.1" width="{width}" height="{height}""#,
+                r#" viewBox="0 0 {w} {h}" shape-rendering="crispEdges""#
+            ),
+            width = width,
+            height = height,
+            w = w,
+            h = h
+        )?;
+        let class = self.options.css_class.as_deref().map(escape_xml);
+        if let Some(class) = &class {
+            write!(svg, r#" class="{class}""#)?;
+        }
+        if self.options.title.is_some() {
+            svg.write_str(r#" role="img""#)?;
+        }
+        svg.write_char('>')?;
+        if let Some(title) = &self.options.title {
+            write!(svg, "<title>{}</title>", escape_xml(title))?;
+        }
+        if let Some(description) = &self.options.description {
+            write!(svg, "<desc>{}</desc>", escape_xml(description))?;
+        }
+        let class_attr = |suffix| {
+            class
+                .as_ref()
+                .map(|class| format!(r#" class="{class}-{suffix}""#))
+                .unwrap_or_default()
+        };
+        let bg = match self.options.background {
+            Background::Light => Some(self.light_pixel.0),
+            Background::None => None,
+            Background::White => Some("#fff"),
+        };
+        if let Some(bg) = bg {
+            write!(
+                svg,
+                r#"<path{class} d="M0 0h{w}v{h}H0z" fill="{bg}"/>"#,
+                class = class_attr("light")
+            )?;
+        }
+        let fill_id = dark_fill_id(self.options.css_class.as_deref());
+        if let Some(fill) = &self.options.svg_dark_fill {
+            svg.write_str("<defs>")?;
+            fill.write_gradient(svg, &fill_id)?;
+            svg.write_str("</defs>")?;
+        }
+        write!(svg, r#"<path{} fill=""#, class_attr("dark"))?;
+        if self.options.svg_dark_fill.is_some() {
+            write!(svg, "url(#{fill_id}) ")?;
+        }
+        write!(svg, r#"{}" d=""#, self.dark_pixel.0)?;
+        for (left, top, width, height) in merge_vertically(self.rects) {
+            write!(svg, "M{left} {top}h{width}v{height}h-{width}z")?;
+        }
+        svg.write_str(r#""/>"#)?;
+        for (color, rects) in self.colored_rects {
+            write!(svg, r#"<path fill="{}" d=""#, color.0)?;
+            for (left, top, width, height) in merge_vertically(rects) {
+                write!(svg, "M{left} {top}h{width}v{height}h-{width}z")?;
+            }
+            svg.write_str(r#""/>"#)?;
+        }
+        svg.write_str("</svg>")?;
+        if self.options.trailing_newline {
+            svg.write_str(self.options.line_ending.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the ID of the gradient of the dark modules, which is derived from
+/// the CSS class.
+///
+/// The class may contain spaces or other characters which are not allowed in
+/// an ID, which are replaced with `-`. The ID is prefixed with `qr` unless it
+/// starts with a letter or `_`.
+fn dark_fill_id(class: Option<&str>) -> String {
+    let mut id = class
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    if !id.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        id.insert_str(0, if id.is_empty() { "qr" } else { "qr-" });
+    }
+    id.push_str("-dark-fill");
+    id
+}
+
+/// Escapes the characters which are not allowed in XML text and attribute
+/// values.
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Appends a rectangle, extending the previous one if it ends where this one
+/// starts on the same rows.
+fn push_rect(rects: &mut Vec<Rect>, rect: Rect) {
+    let (left, top, width, height) = rect;
+    if let Some((l, t, w, h)) = rects.last_mut() {
+        if *t == top && *h == height && *l + *w == left {
+            *w += width;
+            return;
+        }
+    }
+    rects.push(rect);
+}
+
+/// Merges the rectangles of the same columns which are stacked on top of each
+/// other, and returns them in row major order.
+///
+/// The modules are drawn row by row, so runs in the same row are already
+/// merged by [`push_rect`].
+fn merge_vertically(mut rects: Vec<Rect>) -> Vec<Rect> {
+    rects.sort_unstable_by_key(|&(left, top, width, _)| (left, width, top));
+    let mut merged: Vec<Rect> = Vec::with_capacity(rects.len());
+    for (left, top, width, height) in rects {
+        if let Some((l, t, w, h)) = merged.last_mut() {
+            if *l == left && *w == width && *t + *h == top {
+                *h += height;
+                continue;
+            }
+        }
+        merged.push((left, top, width, height));
+    }
+    merged.sort_unstable_by_key(|&(left, top, ..)| (top, left));
+    merged
+}
+
+impl<'a> Renderer<'a, Color<'a>> {
+    /// Sets the physical size of each module, so that the `width` and `height`
+    /// attributes of the SVG are given in the `unit` instead of in pixels.
+    ///
+    /// The `viewBox` is unchanged, so the module size in pixels only affects
+    /// the coordinates used inside the SVG.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{
+    /// #     QrCode,
+    /// #     render::{Unit, svg::Color},
+    /// # };
+    /// #
+    /// let code = QrCode::new(b"01234567").unwrap();
+    /// let svg = code
+    ///     .render::<Color>()
+    ///     .physical_module_size(0.5, Unit::Mm)
+    ///     .build();
+    /// // 21 modules plus a quiet zone of 4 modules on each side.
+    /// assert!(svg.contains(r#"width="14.5mm" height="14.5mm" viewBox="0 0 232 232""#));
+    /// ```
+    #[inline]
+    pub const fn physical_module_size(&mut self, size: f64, unit: Unit) -> &mut Self {
+        self.canvas_options.physical_module_size = Some((size, unit));
+        self
+    }
+
+    /// Sets how the background is painted. Default is [`Background::Light`].
+    ///
+    /// With [`Background::None`], the background `<path>` is omitted, so the
+    /// SVG only contains the dark modules and can be layered over other
+    /// graphics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{
+    /// #     QrCode,
+    /// #     render::{Background, svg::Color},
+    /// # };
+    /// #
+    /// let code = QrCode::new(b"01234567").unwrap();
+    /// let svg = code.render::<Color>().background(Background::None).build();
+    /// assert!(!svg.contains(r##"fill="#fff""##));
+    /// ```
+    #[inline]
+    pub const fn background(&mut self, background: Background) -> &mut Self {
+        self.canvas_options.background = background;
+        self
+    }
+
+    /// Sets the CSS class of the `<svg>` element, so that the image can be
+    /// styled with an external style sheet.
+    ///
+    /// The background and the dark modules are also given the classes
+    /// `{class}-light` and `{class}-dark`. Their `fill` attributes are kept as
+    /// defaults, which any CSS rule overrides.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, render::svg::Color};
+    /// #
+    /// let code = QrCode::new(b"01234567").unwrap();
+    /// let svg = code.render::<Color>().css_class("qr").build();
+    /// assert!(svg.contains(r#" class="qr">"#));
+    /// assert!(svg.contains(r#"<path class="qr-light" d="M0 0h232v232H0z""#));
+    /// assert!(svg.contains(r##"<path class="qr-dark" fill="#000""##));
+    /// ```
+    #[inline]
+    pub fn css_class(&mut self, class: impl Into<String>) -> &mut Self {
+        self.canvas_options.css_class = Some(class.into());
+        self
+    }
+
+    /// Sets the title of the image, which screen readers announce.
+    ///
+    /// The title is written as a `<title>` element, and the `<svg>` element is
+    /// given `role="img"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, render::svg::Color};
+    /// #
+    /// let code = QrCode::new(b"https://example.com/").unwrap();
+    /// let svg = code
+    ///     .render::<Color>()
+    ///     .title("QR code for example.com")
+    ///     .build();
+    /// assert!(svg.contains(r#" role="img"><title>QR code for example.com</title>"#));
+    /// ```
+    #[inline]
+    pub fn title(&mut self, title: impl Into<String>) -> &mut Self {
+        self.canvas_options.title = Some(title.into());
+        self
+    }
+
+    /// Sets the description of the image, which is written as a `<desc>`
+    /// element.
+    #[inline]
+    pub fn desc(&mut self, description: impl Into<String>) -> &mut Self {
+        self.canvas_options.description = Some(description.into());
+        self
     }
 
+    /// Fills the dark modules with a gradient instead of the dark color.
+    ///
+    /// The gradient is written in a `<defs>` element with the ID
+    /// `{class}-dark-fill`, where `{class}` is the [CSS class] or `qr`.
+    /// Characters of the class which are not allowed in an ID, such as the
+    /// spaces between multiple classes, are replaced with `-`. Set different
+    /// classes to embed several images with different gradients in the same
+    /// document. The dark color remains the fallback for viewers
+    /// which do not support gradients, and is the color checked by
+    /// [`Renderer::audit`], so every stop should contrast with the light color
+    /// as much as the dark color does.
+    ///
+    /// [CSS class]: Renderer::css_class
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{
+    /// #     QrCode,
+    /// #     render::svg::{Color, Fill},
+    /// # };
+    /// #
+    /// let code = QrCode::new(b"01234567").unwrap();
+    /// let svg = code
+    ///     .render::<Color>()
+    ///     .dark_fill(Fill::LinearGradient {
+    ///         stops: vec![(0.0, "#000080".into()), (1.0, "#800080".into())],
+    ///         angle: 45.0,
+    ///     })
+    ///     .build();
+    /// assert!(svg.contains(concat!(
+    ///     r#"<defs><linearGradient id="qr-dark-fill" gradientTransform="rotate(45 0.5 0.5)">"#,
+    ///     r##"<stop offset="0" stop-color="#000080"/><stop offset="1" stop-color="#800080"/>"##,
+    ///     "</linearGradient></defs>"
+    /// )));
+    /// assert!(svg.contains(r##"<path fill="url(#qr-dark-fill) #000" d="##));
+    /// ```
     #[inline]
-    fn into_image(mut self) -> Self::Image {
-        self.svg.push_str(r#""/></svg>"#);
-        self.svg
+    pub fn dark_fill(&mut self, fill: Fill) -> &mut Self {
+        self.canvas_options.svg_dark_fill = Some(fill);
+        self
     }
 }
@@ -0,0 +1,294 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Terminal capability detection.
+//!
+//! [`Terminal::detect`] inspects the environment of the current process to
+//! find out whether the terminal can display Unicode block characters and
+//! colors, and how large it is. [`Terminal::recommend`] then picks the most
+//! suitable way to print a given QR code.
+//!
+//! The detection only relies on environment variables (`TERM`, `COLORTERM`,
+//! `NO_COLOR`, `WT_SESSION`, `COLUMNS`, `LINES` and the locale variables), so
+//! it never blocks or touches the terminal itself. On Windows, Windows
+//! Terminal is recognized by `WT_SESSION`.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{
+//!     QrCode,
+//!     render::{
+//!         terminal::{Glyphs, Terminal},
+//!         unicode::Dense1x2,
+//!     },
+//! };
+//!
+//! let code = QrCode::new(b"Hello").unwrap();
+//! let recommendation = Terminal::detect().recommend(&code);
+//! let s = match recommendation.glyphs {
+//!     Glyphs::Ascii => code
+//!         .render::<char>()
+//!         .module_dimensions(2, 1)
+//!         .dark_color('#')
+//!         .light_color(' ')
+//!         .build(),
+//!     _ => code.render::<Dense1x2>().build(),
+//! };
+//! println!("{s}");
+//! ```
+
+use std::{env, string::String};
+
+use crate::{QrCode, cast::As};
+
+/// The characters used to draw modules on a terminal.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Glyphs {
+    /// Braille patterns with 2×4 modules per character, as rendered by
+    /// [`Braille`](crate::render::unicode::Braille).
+    Braille,
+
+    /// Half blocks with 1×2 modules per character, as rendered by
+    /// [`Dense1x2`](crate::render::unicode::Dense1x2).
+    HalfBlock,
+
+    /// Plain ASCII with 2 characters per module, so that modules are roughly
+    /// square.
+    Ascii,
+}
+
+impl Glyphs {
+    /// Returns the number of columns and rows needed to print a symbol of the
+    /// given size in modules.
+    #[must_use]
+    pub const fn size(self, width: u32, height: u32) -> (u32, u32) {
+        match self {
+            Self::Braille => (width.div_ceil(2), height.div_ceil(4)),
+            Self::HalfBlock => (width, height.div_ceil(2)),
+            Self::Ascii => (width * 2, height),
+        }
+    }
+}
+
+/// The colors supported by a terminal.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ColorSupport {
+    /// No colors.
+    None,
+
+    /// The 16 standard ANSI colors.
+    Ansi16,
+
+    /// The 256-color palette.
+    Ansi256,
+
+    /// 24-bit colors.
+    TrueColor,
+}
+
+/// The recommended way to print a QR code, returned by
+/// [`Terminal::recommend`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Recommendation {
+    /// The characters to draw modules with.
+    pub glyphs: Glyphs,
+
+    /// The colors which can be used.
+    pub color: ColorSupport,
+
+    /// Whether the QR code, including the quiet zone, fits the terminal, or
+    /// [`None`] if the size of the terminal is unknown.
+    pub fits: Option<bool>,
+}
+
+/// The capabilities of a terminal.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Terminal {
+    is_utf8: bool,
+    color: ColorSupport,
+    size: Option<(u32, u32)>,
+}
+
+impl Terminal {
+    /// Detects the capabilities of the terminal from the environment variables
+    /// of the current process.
+    #[must_use]
+    #[inline]
+    pub fn detect() -> Self {
+        Self::from_env(|key| env::var(key).ok())
+    }
+
+    /// Detects the capabilities of the terminal from environment variables
+    /// provided by `var`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::render::terminal::{ColorSupport, Terminal};
+    /// #
+    /// let terminal = Terminal::from_env(|key| match key {
+    ///     "TERM" => Some("xterm-256color".into()),
+    ///     "LANG" => Some("en_US.UTF-8".into()),
+    ///     "COLUMNS" => Some("80".into()),
+    ///     "LINES" => Some("24".into()),
+    ///     _ => None,
+    /// });
+    /// assert!(terminal.is_utf8());
+    /// assert_eq!(terminal.color_support(), ColorSupport::Ansi256);
+    /// assert_eq!(terminal.size(), Some((80, 24)));
+    /// ```
+    pub fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        let term = var("TERM").unwrap_or_default();
+        let is_dumb = term == "dumb";
+        let is_windows_terminal = var("WT_SESSION").is_some();
+
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .into_iter()
+            .find_map(|key| var(key).filter(|v| !v.is_empty()))
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let is_utf8 = !is_dumb
+            && (locale.contains("utf-8") || locale.contains("utf8") || is_windows_terminal);
+
+        let color_term = var("COLORTERM").unwrap_or_default();
+        let color = if var("NO_COLOR").is_some_and(|v| !v.is_empty()) || is_dumb {
+            ColorSupport::None
+        } else if color_term == "truecolor" || color_term == "24bit" || is_windows_terminal {
+            ColorSupport::TrueColor
+        } else if term.contains("256color") {
+            ColorSupport::Ansi256
+        } else if term.is_empty() {
+            ColorSupport::None
+        } else {
+            ColorSupport::Ansi16
+        };
+
+        let dimension = |key| var(key).and_then(|v| v.trim().parse::<u32>().ok());
+        let size = dimension("COLUMNS").zip(dimension("LINES"));
+
+        Self {
+            is_utf8,
+            color,
+            size,
+        }
+    }
+
+    /// Returns [`true`] if the terminal can display Unicode characters.
+    #[must_use]
+    #[inline]
+    pub const fn is_utf8(&self) -> bool {
+        self.is_utf8
+    }
+
+    /// Returns the colors supported by the terminal.
+    #[must_use]
+    #[inline]
+    pub const fn color_support(&self) -> ColorSupport {
+        self.color
+    }
+
+    /// Returns the number of columns and rows of the terminal, if known.
+    #[must_use]
+    #[inline]
+    pub const fn size(&self) -> Option<(u32, u32)> {
+        self.size
+    }
+
+    /// Recommends how to print the QR code, including the default quiet zone
+    /// of [`QrCode::render`].
+    ///
+    /// Half blocks are preferred when Unicode is available, since braille
+    /// patterns are not supported by every font. Braille patterns are only
+    /// recommended if the QR code does not fit the terminal otherwise.
+    #[must_use]
+    pub fn recommend(&self, code: &QrCode) -> Recommendation {
+        let quiet_zone = if code.version().is_normal() { 4 } else { 2 };
+        let width = code.width().as_u32() + 2 * quiet_zone;
+        let height = code.height().as_u32() + 2 * quiet_zone;
+        let fits = |glyphs: Glyphs| {
+            self.size.map(|(columns, rows)| {
+                let (w, h) = glyphs.size(width, height);
+                w <= columns && h <= rows
+            })
+        };
+
+        let glyphs = if !self.is_utf8 {
+            Glyphs::Ascii
+        } else if fits(Glyphs::HalfBlock) == Some(false) && fits(Glyphs::Braille) == Some(true) {
+            Glyphs::Braille
+        } else {
+            Glyphs::HalfBlock
+        };
+        Recommendation {
+            glyphs,
+            color: self.color,
+            fits: fits(glyphs),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terminal(vars: &[(&str, &str)]) -> Terminal {
+        Terminal::from_env(|key| {
+            vars.iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| String::from(*v))
+        })
+    }
+
+    #[test]
+    fn test_color_support() {
+        let color = |vars| terminal(vars).color_support();
+        assert_eq!(color(&[]), ColorSupport::None);
+        assert_eq!(color(&[("TERM", "dumb")]), ColorSupport::None);
+        assert_eq!(color(&[("TERM", "xterm")]), ColorSupport::Ansi16);
+        assert_eq!(color(&[("TERM", "screen-256color")]), ColorSupport::Ansi256);
+        assert_eq!(
+            color(&[("TERM", "xterm"), ("COLORTERM", "truecolor")]),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(color(&[("WT_SESSION", "1")]), ColorSupport::TrueColor);
+        assert_eq!(
+            color(&[("TERM", "xterm-256color"), ("NO_COLOR", "1")]),
+            ColorSupport::None
+        );
+    }
+
+    #[test]
+    fn test_utf8() {
+        assert!(terminal(&[("LANG", "ja_JP.UTF-8")]).is_utf8());
+        assert!(terminal(&[("LC_ALL", "C.utf8"), ("LANG", "C")]).is_utf8());
+        assert!(!terminal(&[("LC_ALL", "C"), ("LANG", "en_US.UTF-8")]).is_utf8());
+        assert!(!terminal(&[("TERM", "dumb"), ("LANG", "en_US.UTF-8")]).is_utf8());
+    }
+
+    #[test]
+    fn test_recommend() {
+        // Version 3 is 29 modules wide, or 37 modules with the quiet zone.
+        let code = QrCode::new(b"https://example.com/recommend").unwrap();
+        assert_eq!(code.width(), 29);
+
+        let recommendation = terminal(&[("LANG", "C")]).recommend(&code);
+        assert_eq!(recommendation.glyphs, Glyphs::Ascii);
+        assert_eq!(recommendation.fits, None);
+
+        let utf8 = |columns: &'static str, lines: &'static str| {
+            terminal(&[("LANG", "C.UTF-8"), ("COLUMNS", columns), ("LINES", lines)])
+                .recommend(&code)
+        };
+        let recommendation = utf8("80", "24");
+        assert_eq!(recommendation.glyphs, Glyphs::HalfBlock);
+        assert_eq!(recommendation.fits, Some(true));
+        let recommendation = utf8("80", "12");
+        assert_eq!(recommendation.glyphs, Glyphs::Braille);
+        assert_eq!(recommendation.fits, Some(true));
+        let recommendation = utf8("10", "5");
+        assert_eq!(recommendation.glyphs, Glyphs::HalfBlock);
+        assert_eq!(recommendation.fits, Some(false));
+    }
+}
@@ -3,7 +3,8 @@
 //
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-//! UTF-8 rendering, with 2 pixels per symbol.
+//! UTF-8 rendering, with 2 pixels per symbol with [`Dense1x2`], or 8 pixels
+//! per symbol with [`Braille`].
 //!
 //! # Examples
 //!
@@ -17,12 +18,14 @@
 
 use alloc::{string::String, vec, vec::Vec};
 
-use crate::render::{Canvas as RenderCanvas, Color, Pixel};
+use crate::render::{Canvas as RenderCanvas, CanvasOptions, Color, Pixel, Renderer};
 
 const CODEPAGE: [&str; 4] = [" ", "\u{2584}", "\u{2580}", "\u{2588}"];
 
 /// An image pixel for UTF-8 rendering.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Dense1x2 {
     /// The pixel is dark colored.
     Dark,
@@ -65,6 +68,8 @@ pub struct Canvas1x2 {
     canvas: Vec<u8>,
     width: u32,
     dark_pixel: u8,
+    light_pixel: u8,
+    options: CanvasOptions,
 }
 
 impl RenderCanvas for Canvas1x2 {
@@ -74,10 +79,13 @@ impl RenderCanvas for Canvas1x2 {
     fn new(width: u32, height: u32, dark_pixel: Self::Pixel, light_pixel: Self::Pixel) -> Self {
         let canvas = vec![light_pixel.value(); (width * height) as usize];
         let dark_pixel = dark_pixel.value();
+        let light_pixel = light_pixel.value();
         Self {
             canvas,
             width,
             dark_pixel,
+            light_pixel,
+            options: CanvasOptions::default(),
         }
     }
 
@@ -86,7 +94,29 @@ impl RenderCanvas for Canvas1x2 {
         self.canvas[(x + y * self.width) as usize] = self.dark_pixel;
     }
 
+    #[inline]
+    fn configure(&mut self, options: &CanvasOptions) {
+        self.options = options.clone();
+    }
+
     fn into_image(self) -> Self::Image {
+        let (line_ending, trailing_newline) = (
+            self.options.line_ending.as_str(),
+            self.options.trailing_newline,
+        );
+        let mut image = self.into_lines().join(line_ending);
+        if trailing_newline {
+            image.push_str(line_ending);
+        }
+        image
+    }
+}
+
+impl Canvas1x2 {
+    /// Finalizes the canvas to a list of lines, without line endings.
+    fn into_lines(self) -> Vec<String> {
+        let trim = self.options.trim_trailing_light;
+        let light = self.light_pixel;
         self.canvas
             // Chopping array into 1-line sized fragments
             .chunks_exact(self.width as usize)
@@ -94,33 +124,216 @@ impl RenderCanvas for Canvas1x2 {
             // And then glueing every 2 lines.
             .chunks(2)
             .map(|rows| {
-                {
-                    // Then zipping those 2 lines together into a single 2-bit number list.
-                    if rows.len() == 2 {
-                        rows[0]
-                            .iter()
-                            .zip(rows[1])
-                            .map(|(top, bot)| top * 2 + bot)
-                            .collect::<Vec<u8>>()
-                    } else {
-                        rows[0].iter().map(|top| top * 2).collect::<Vec<u8>>()
+                // Then zipping those 2 lines together into a single 2-bit number list.
+                let (mut symbols, light_symbol) = if rows.len() == 2 {
+                    let symbols = rows[0]
+                        .iter()
+                        .zip(rows[1])
+                        .map(|(top, bot)| top * 2 + bot)
+                        .collect::<Vec<u8>>();
+                    (symbols, light * 3)
+                } else {
+                    let symbols = rows[0].iter().map(|top| top * 2).collect::<Vec<u8>>();
+                    (symbols, light * 2)
+                };
+                // Omitting the trailing light symbols if requested.
+                while trim && symbols.last() == Some(&light_symbol) {
+                    symbols.pop();
+                }
+                symbols
+                    .into_iter()
+                    // Mapping those 2-bit numbers to corresponding pixels.
+                    .map(Dense1x2::parse_2_bits)
+                    .collect::<Vec<&str>>()
+                    .concat()
+            })
+            .collect()
+    }
+}
+
+impl Renderer<'_, Dense1x2> {
+    /// Renders the QR code into a list of lines, one for each row of
+    /// characters.
+    ///
+    /// The lines do not contain line endings, so [`Renderer::line_ending`] and
+    /// [`Renderer::trailing_newline`] are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, render::unicode::Dense1x2};
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let lines = code.render::<Dense1x2>().build_lines();
+    /// assert_eq!(lines.len(), 15);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn build_lines(&self) -> Vec<String> {
+        self.draw().into_lines()
+    }
+}
+
+// Braille
+
+/// The bit of the braille pattern for each pixel of a 2×4 cell, indexed by
+/// row and then by column.
+const BRAILLE_DOTS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// An image pixel for UTF-8 rendering with braille patterns.
+///
+/// Dark pixels are drawn as raised dots. Each character holds 2×4 pixels, so
+/// the output is a quarter of the height of [`Dense1x2`] and half of its
+/// width. Terminals draw the dots with gaps between them, so the output is
+/// harder to scan than block characters and is mostly useful for previews.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Braille {
+    /// The pixel is dark colored.
+    Dark,
+
+    /// The pixel is light colored.
+    Light,
+}
+
+impl Pixel for Braille {
+    type Image = String;
+    type Canvas = Canvas2x4;
+
+    #[inline]
+    fn default_color(color: Color) -> Self {
+        color.select(Self::Dark, Self::Light)
+    }
+
+    #[inline]
+    fn default_unit_size() -> (u32, u32) {
+        (1, 1)
+    }
+}
+
+/// A canvas for UTF-8 rendering with a resolution of 2×4 modules per
+/// character.
+#[derive(Debug)]
+pub struct Canvas2x4 {
+    canvas: Vec<bool>,
+    width: u32,
+    dark_pixel: Braille,
+    light_pixel: Braille,
+    options: CanvasOptions,
+}
+
+impl RenderCanvas for Canvas2x4 {
+    type Pixel = Braille;
+    type Image = String;
+
+    fn new(width: u32, height: u32, dark_pixel: Self::Pixel, light_pixel: Self::Pixel) -> Self {
+        Self {
+            canvas: vec![false; (width * height) as usize],
+            width,
+            dark_pixel,
+            light_pixel,
+            options: CanvasOptions::default(),
+        }
+    }
+
+    #[inline]
+    fn draw_dark_pixel(&mut self, x: u32, y: u32) {
+        self.canvas[(x + y * self.width) as usize] = true;
+    }
+
+    #[inline]
+    fn configure(&mut self, options: &CanvasOptions) {
+        self.options = options.clone();
+    }
+
+    fn into_image(self) -> Self::Image {
+        let (line_ending, trailing_newline) = (
+            self.options.line_ending.as_str(),
+            self.options.trailing_newline,
+        );
+        let mut image = self.into_lines().join(line_ending);
+        if trailing_newline {
+            image.push_str(line_ending);
+        }
+        image
+    }
+}
+
+impl Canvas2x4 {
+    /// Finalizes the canvas to a list of lines, without line endings.
+    fn into_lines(self) -> Vec<String> {
+        let width = self.width as usize;
+        let is_raised = |is_dark| {
+            let pixel = if is_dark {
+                self.dark_pixel
+            } else {
+                self.light_pixel
+            };
+            pixel == Braille::Dark
+        };
+        // The pixels beyond the bottom and the right edges are light.
+        let blank = if is_raised(false) { 0xff } else { 0 };
+        self.canvas
+            .chunks_exact(width)
+            .collect::<Vec<_>>()
+            .chunks(4)
+            .map(|rows| {
+                let mut cells = vec![blank; width.div_ceil(2)];
+                for (y, row) in rows.iter().enumerate() {
+                    for (x, is_dark) in row.iter().enumerate() {
+                        let dot = BRAILLE_DOTS[y][x % 2];
+                        if is_raised(*is_dark) {
+                            cells[x / 2] |= dot;
+                        } else {
+                            cells[x / 2] &= !dot;
+                        }
                     }
                 }
-                .into_iter()
-                // Mapping those 2-bit numbers to corresponding pixels.
-                .map(Dense1x2::parse_2_bits)
-                .collect::<Vec<&str>>()
-                .concat()
+                while self.options.trim_trailing_light && cells.last() == Some(&blank) {
+                    cells.pop();
+                }
+                cells
+                    .into_iter()
+                    .map(|cell| char::from_u32(0x2800 + u32::from(cell)).unwrap_or(' '))
+                    .collect()
             })
-            .collect::<Vec<String>>()
-            .join("\n")
+            .collect()
+    }
+}
+
+impl Renderer<'_, Braille> {
+    /// Renders the QR code into a list of lines, one for each row of
+    /// characters.
+    ///
+    /// The lines do not contain line endings, so [`Renderer::line_ending`] and
+    /// [`Renderer::trailing_newline`] are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{QrCode, render::unicode::Braille};
+    /// #
+    /// let code = QrCode::new(b"Hello").unwrap();
+    /// let lines = code.render::<Braille>().build_lines();
+    /// // 29 modules including the quiet zone.
+    /// assert_eq!(lines.len(), 8);
+    /// assert_eq!(lines[0].chars().count(), 15);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn build_lines(&self) -> Vec<String> {
+        self.draw().into_lines()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{EcLevel, QrCode, Version, render::Renderer};
+    use crate::{
+        EcLevel, QrCode, Version,
+        render::{LineEnding, Renderer},
+    };
 
     #[test]
     fn test_render_to_utf8_string() {
@@ -139,6 +352,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_lines() {
+        let colors = &[Color::Dark, Color::Light, Color::Light, Color::Dark];
+        let lines = Renderer::<Dense1x2>::new(colors, 2, 2, 1).build_lines();
+        assert_eq!(lines, [" ▄  ", "  ▀ "]);
+    }
+
+    #[test]
+    fn test_trim_trailing_light() {
+        let colors = &[Color::Dark, Color::Light, Color::Light, Color::Dark];
+        let image = Renderer::<Dense1x2>::new(colors, 2, 2, 1)
+            .trim_trailing_light(true)
+            .build();
+        assert_eq!(&image, concat!(" ▄\n", "  ▀"));
+
+        let image = Renderer::<Dense1x2>::new(colors, 2, 2, 1)
+            .dark_color(Dense1x2::Light)
+            .light_color(Dense1x2::Dark)
+            .trim_trailing_light(true)
+            .build();
+        assert_eq!(&image, concat!("█▀\n", "██▄"));
+    }
+
+    #[test]
+    fn test_line_ending() {
+        let colors = &[Color::Dark, Color::Light, Color::Light, Color::Dark];
+        let image = Renderer::<Dense1x2>::new(colors, 2, 2, 1)
+            .line_ending(LineEnding::CrLf)
+            .trailing_newline(true)
+            .build();
+        assert_eq!(&image, concat!(" ▄  \r\n", "  ▀ \r\n"));
+    }
+
     #[test]
     fn integration_render_utf8_1x2() {
         let code = QrCode::with_version(b"09876542", Version::Micro(2), EcLevel::L).unwrap();
@@ -183,4 +429,45 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_render_braille() {
+        let colors = &[Color::Dark, Color::Light, Color::Light, Color::Dark];
+        let image = Renderer::<Braille>::new(colors, 2, 2, 0).build();
+        assert_eq!(image, "\u{2811}");
+
+        // 4×4 pixels with the quiet zone, and the dots of the symbol are in the
+        // middle.
+        let image = Renderer::<Braille>::new(colors, 2, 2, 1).build();
+        assert_eq!(image, "\u{2810}\u{2804}");
+
+        let image = Renderer::<Braille>::new(&[Color::Dark; 9], 3, 3, 0).build();
+        assert_eq!(image, "\u{283f}\u{2807}");
+    }
+
+    #[test]
+    fn test_braille_inverted_and_trimmed() {
+        let colors = &[Color::Light; 4];
+        let image = Renderer::<Braille>::new(colors, 2, 2, 1)
+            .trim_trailing_light(true)
+            .build();
+        assert_eq!(image, "");
+
+        // The pixels beyond the edges are light, so they are raised too.
+        let image = Renderer::<Braille>::new(&[Color::Dark], 1, 1, 0)
+            .dark_color(Braille::Light)
+            .light_color(Braille::Dark)
+            .build();
+        assert_eq!(image, "\u{28fe}");
+    }
+
+    #[test]
+    fn integration_render_braille() {
+        let code = QrCode::new(b"Hello").unwrap();
+        let image = code.render::<Braille>().quiet_zone(0).build();
+        let lines = image.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 6);
+        // The top left corner of the finder pattern.
+        assert!(lines[0].starts_with("\u{284f}\u{28ed}"));
+    }
 }
@@ -0,0 +1,267 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Rendering into the planes of YUV video frames.
+//!
+//! [`YuvOverlay`] stamps the symbol directly into the Y plane of a frame, and
+//! optionally sets the chroma of the covered area to neutral in the UV plane
+//! of an NV12 frame or in the U and V planes of an I420 frame. This avoids
+//! converting each frame to RGB and back just to overlay a QR code, e.g. a
+//! timecode or a session token.
+//!
+//! The chroma planes of NV12 and I420 are subsampled by 2 in both directions,
+//! so a chroma sample which is only partly covered by the symbol is also set
+//! to neutral.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, render::yuv::YuvOverlay};
+//!
+//! let (width, height) = (320, 240);
+//! let mut y_plane = vec![0; width * height];
+//! let mut uv_plane = vec![0; width * height / 2];
+//!
+//! let code = QrCode::new(b"00:01:02:03").unwrap();
+//! YuvOverlay::new(&code)
+//!     .module_size(3)
+//!     .position(16, 16)
+//!     .draw_nv12(&mut y_plane, width, &mut uv_plane, width);
+//! // The top left pixel of the quiet zone is white in limited range.
+//! assert_eq!(y_plane[16 * width + 16], 235);
+//! assert_eq!(uv_plane[8 * width + 16..8 * width + 18], [128, 128]);
+//! ```
+
+use alloc::vec;
+use core::{cmp, ops::Range};
+
+use crate::{QrCode, cast::As, render::scanline::Scanlines, types::Color};
+
+/// The value of a chroma sample without color.
+const NEUTRAL_CHROMA: u8 = 128;
+
+/// A renderer which writes the symbol into YUV planes.
+///
+/// Each plane is given as a byte slice and a stride, i.e. the number of bytes
+/// from the start of a row to the start of the next row. Pixels which do not
+/// fit in the plane are skipped.
+#[derive(Clone, Copy, Debug)]
+pub struct YuvOverlay<'a> {
+    scanlines: Scanlines<'a>,
+    position: (usize, usize),
+    dark_luma: u8,
+    light_luma: u8,
+}
+
+impl<'a> YuvOverlay<'a> {
+    /// Creates a new renderer for the QR code.
+    ///
+    /// The defaults are 1 pixel per module, the standard quiet zone, the top
+    /// left corner of the frame, and the limited range luma of 16 for dark
+    /// and 235 for light modules.
+    #[must_use]
+    pub const fn new(code: &'a QrCode) -> Self {
+        Self {
+            scanlines: Scanlines::new(code),
+            position: (0, 0),
+            dark_luma: 16,
+            light_luma: 235,
+        }
+    }
+
+    /// Sets the size of each module in pixels. The value is at least 1.
+    #[inline]
+    pub fn module_size(&mut self, module_size: u32) -> &mut Self {
+        self.scanlines.module_size(module_size);
+        self
+    }
+
+    /// Sets the width of the quiet zone in modules.
+    #[inline]
+    pub const fn quiet_zone(&mut self, quiet_zone: u32) -> &mut Self {
+        self.scanlines.quiet_zone(quiet_zone);
+        self
+    }
+
+    /// Sets the position of the top left corner of the quiet zone in the
+    /// frame, in pixels.
+    #[inline]
+    pub const fn position(&mut self, x: usize, y: usize) -> &mut Self {
+        self.position = (x, y);
+        self
+    }
+
+    /// Sets the luma of the dark and the light modules. Use 0 and 255 for
+    /// full range video.
+    #[inline]
+    pub const fn luma(&mut self, dark: u8, light: u8) -> &mut Self {
+        self.dark_luma = dark;
+        self.light_luma = light;
+        self
+    }
+
+    /// Returns the width of the stamped area in pixels.
+    #[must_use]
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.scanlines.width()
+    }
+
+    /// Returns the height of the stamped area in pixels.
+    #[must_use]
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.scanlines.height()
+    }
+
+    /// Writes the symbol into the Y plane only, leaving the chroma as it is.
+    pub fn draw_luma(&self, y_plane: &mut [u8], y_stride: usize) {
+        let (left, top) = self.position;
+        let mut buf = vec![Color::Light; self.width().as_usize()];
+        self.scanlines.for_each_row(&mut buf, |y, row| {
+            let Some(line) = plane_row(y_plane, y_stride, top + y.as_usize()) else {
+                return;
+            };
+            let line = line.get_mut(left..).unwrap_or_default();
+            for (pixel, color) in line.iter_mut().zip(row) {
+                *pixel = color.select(self.dark_luma, self.light_luma);
+            }
+        });
+    }
+
+    /// Writes the symbol into the planes of an NV12 frame, where the U and V
+    /// samples are interleaved in a single plane.
+    pub fn draw_nv12(
+        &self,
+        y_plane: &mut [u8],
+        y_stride: usize,
+        uv_plane: &mut [u8],
+        uv_stride: usize,
+    ) {
+        self.draw_luma(y_plane, y_stride);
+        let (columns, rows) = self.chroma_area();
+        for row in rows {
+            let Some(line) = plane_row(uv_plane, uv_stride, row) else {
+                break;
+            };
+            let start = cmp::min(2 * columns.start, line.len());
+            let end = cmp::min(2 * columns.end, line.len());
+            line[start..end].fill(NEUTRAL_CHROMA);
+        }
+    }
+
+    /// Writes the symbol into the planes of an I420 frame, where the U and V
+    /// samples are in separate planes.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_i420(
+        &self,
+        y_plane: &mut [u8],
+        y_stride: usize,
+        u_plane: &mut [u8],
+        u_stride: usize,
+        v_plane: &mut [u8],
+        v_stride: usize,
+    ) {
+        self.draw_luma(y_plane, y_stride);
+        let (columns, rows) = self.chroma_area();
+        for (plane, stride) in [(u_plane, u_stride), (v_plane, v_stride)] {
+            for row in rows.clone() {
+                let Some(line) = plane_row(plane, stride, row) else {
+                    break;
+                };
+                let start = cmp::min(columns.start, line.len());
+                let end = cmp::min(columns.end, line.len());
+                line[start..end].fill(NEUTRAL_CHROMA);
+            }
+        }
+    }
+
+    /// Returns the columns and the rows of the chroma samples which the
+    /// stamped area covers.
+    fn chroma_area(&self) -> (Range<usize>, Range<usize>) {
+        let (left, top) = self.position;
+        let right = left + self.width().as_usize();
+        let bottom = top + self.height().as_usize();
+        (left / 2..right.div_ceil(2), top / 2..bottom.div_ceil(2))
+    }
+}
+
+/// Returns the row of the plane, or [`None`] if the plane is too short.
+fn plane_row(plane: &mut [u8], stride: usize, row: usize) -> Option<&mut [u8]> {
+    let start = row.checked_mul(stride)?;
+    plane.get_mut(start..start.checked_add(stride)?.min(plane.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::{EcLevel, Version};
+
+    #[test]
+    fn test_luma_matches_colors() {
+        let code = QrCode::new_micro(b"01234567").unwrap();
+        let (width, height) = (40, 30);
+        let mut y_plane = vec![0; width * height];
+        YuvOverlay::new(&code)
+            .quiet_zone(0)
+            .position(5, 7)
+            .luma(0, 255)
+            .draw_luma(&mut y_plane, width);
+        let expected = code
+            .to_colors()
+            .iter()
+            .map(|c| c.select(0, 255))
+            .collect::<Vec<_>>();
+        for y in 0..13 {
+            let start = (7 + y) * width + 5;
+            assert_eq!(y_plane[start..start + 13], expected[y * 13..(y + 1) * 13]);
+            assert_eq!(y_plane[start - 1], 0);
+            assert_eq!(y_plane[start + 13], 0);
+        }
+    }
+
+    #[test]
+    fn test_clipped() {
+        let code = QrCode::new(b"01234567").unwrap();
+        // The symbol is 29×29 pixels, but the frame is only 20×20.
+        let mut y_plane = vec![0; 20 * 20];
+        let mut u_plane = vec![0; 10 * 10];
+        let mut v_plane = vec![0; 10 * 10];
+        YuvOverlay::new(&code).position(11, 11).draw_i420(
+            &mut y_plane,
+            20,
+            &mut u_plane,
+            10,
+            &mut v_plane,
+            10,
+        );
+        assert_eq!(y_plane[10 * 20 + 10], 0);
+        assert_eq!(y_plane[14 * 20 + 14], 235);
+        // The center of the top left finder pattern.
+        assert_eq!(y_plane[18 * 20 + 18], 16);
+        assert_eq!(u_plane[4 * 10 + 4], 0);
+        assert_eq!(u_plane[5 * 10 + 5], 128);
+        assert_eq!(v_plane[9 * 10 + 9], 128);
+    }
+
+    #[test]
+    fn test_odd_position_covers_chroma() {
+        let code = QrCode::with_version(b"1", Version::Micro(2), EcLevel::L).unwrap();
+        let overlay = *YuvOverlay::new(&code).quiet_zone(0).position(3, 1);
+        // The symbol covers the pixels from 3 to 15 and the rows from 1 to 13.
+        assert_eq!(overlay.chroma_area(), (1..8, 0..7));
+        let mut y_plane = vec![0; 16 * 16];
+        let mut uv_plane = vec![0; 16 * 8];
+        overlay.draw_nv12(&mut y_plane, 16, &mut uv_plane, 16);
+        assert_eq!(uv_plane[..2], [0, 0]);
+        assert!(
+            uv_plane[2..7 * 16]
+                .chunks(16)
+                .all(|row| row[..14] == [128; 14])
+        );
+        assert!(uv_plane[7 * 16..].iter().all(|uv| *uv == 0));
+    }
+}
@@ -0,0 +1,24 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! A small pseudo-random number generator for reproducible output.
+
+/// The `SplitMix64` pseudo-random number generator.
+pub struct SplitMix64(pub u64);
+
+impl SplitMix64 {
+    pub const fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in the range of 0.0 to 1.0.
+    pub fn next_f64(&mut self) -> f64 {
+        let bits = u32::try_from(self.next() >> 32).unwrap_or(u32::MAX);
+        f64::from(bits) / f64::from(u32::MAX)
+    }
+}
@@ -0,0 +1,436 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Simulating damage to a symbol.
+//!
+//! A [`Simulation`] marks modules as flipped, i.e. read with the wrong color,
+//! or erased, i.e. known to be unreadable, following a [`Pattern`] such as
+//! random noise, a scratched band or a torn corner. The [`Report`] then tells,
+//! block by block, whether the Reed–Solomon error correction can still recover
+//! the data. This gives an estimate of the robustness of each error correction
+//! level before printing.
+//!
+//! A codeword with at least one erased module counts as an erasure, and a
+//! codeword with only flipped modules counts as an error. A block can recover
+//! the data as long as twice the number of errors plus the number of erasures
+//! does not exceed its correction capacity. Damage to the functional patterns
+//! is counted, but not modeled, since whether a reader can still locate the
+//! symbol depends on the reader.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{
+//!     EcLevel, QrCode,
+//!     simulate::{Corner, Pattern, Simulation},
+//! };
+//!
+//! let code = QrCode::with_error_correction_level(b"LOT 42", EcLevel::H).unwrap();
+//! let report = Simulation::for_code(&code)
+//!     .unwrap()
+//!     .erase(Pattern::Corner {
+//!         corner: Corner::BottomRight,
+//!         size: 6,
+//!     })
+//!     .report();
+//! assert!(report.is_recoverable());
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    QrCode,
+    canvas::{Canvas, CodewordBit, ModuleKind},
+    cast::As,
+    ec,
+    rng::SplitMix64,
+    types::{EcLevel, QrResult, Region, Version},
+};
+
+/// A corner of the symbol.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Corner {
+    /// The top left corner.
+    TopLeft,
+
+    /// The top right corner.
+    TopRight,
+
+    /// The bottom left corner.
+    BottomLeft,
+
+    /// The bottom right corner.
+    BottomRight,
+}
+
+/// A set of damaged modules.
+///
+/// Modules outside the symbol are ignored.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pattern {
+    /// Each module is damaged with the probability `ratio`, which should be in
+    /// the range of 0.0 to 1.0. The same `seed` damages the same modules.
+    Random {
+        /// The probability that a module is damaged.
+        ratio: f64,
+
+        /// The seed of the pseudo-random number generator.
+        seed: u64,
+    },
+
+    /// A horizontal band of rows.
+    Rows {
+        /// The index of the first row.
+        start: usize,
+
+        /// The number of rows.
+        count: usize,
+    },
+
+    /// A vertical band of columns.
+    Columns {
+        /// The index of the first column.
+        start: usize,
+
+        /// The number of columns.
+        count: usize,
+    },
+
+    /// A square of modules in a corner.
+    Corner {
+        /// The corner.
+        corner: Corner,
+
+        /// The side length of the square in modules.
+        size: usize,
+    },
+
+    /// A rectangle of modules.
+    Region(Region),
+}
+
+/// How a module is damaged.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Effect {
+    /// The module is read with the wrong color.
+    Flip,
+
+    /// The module is known to be unreadable.
+    Erase,
+}
+
+/// A damage simulation of a symbol.
+#[derive(Clone, Debug)]
+pub struct Simulation {
+    version: Version,
+    ec_level: EcLevel,
+    placement: Vec<Option<CodewordBit>>,
+    kinds: Vec<ModuleKind>,
+    damage: Vec<Option<Effect>>,
+}
+
+impl Simulation {
+    /// Creates a new simulation of an undamaged symbol of the version and the
+    /// error correction level.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the version and the error correction level are
+    /// incompatible.
+    pub fn new(version: Version, ec_level: EcLevel) -> QrResult<Self> {
+        let placement = Canvas::codeword_placement(version, ec_level)?;
+        let kinds = Canvas::module_kinds(version, ec_level)?;
+        let damage = vec![None; placement.len()];
+        Ok(Self {
+            version,
+            ec_level,
+            placement,
+            kinds,
+            damage,
+        })
+    }
+
+    /// Creates a new simulation of an undamaged QR code.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the version and the error correction level of the QR
+    /// code are incompatible, e.g. if it is not constructed by encoding data.
+    #[inline]
+    pub fn for_code(code: &QrCode) -> QrResult<Self> {
+        Self::new(code.version(), code.error_correction_level())
+    }
+
+    /// Marks the modules of the pattern as read with the wrong color. A module
+    /// which is already erased stays erased.
+    #[inline]
+    pub fn flip(&mut self, pattern: Pattern) -> &mut Self {
+        self.apply(pattern, Effect::Flip)
+    }
+
+    /// Marks the modules of the pattern as unreadable.
+    #[inline]
+    pub fn erase(&mut self, pattern: Pattern) -> &mut Self {
+        self.apply(pattern, Effect::Erase)
+    }
+
+    /// Removes all damage.
+    #[inline]
+    pub fn repair(&mut self) -> &mut Self {
+        self.damage.fill(None);
+        self
+    }
+
+    /// Marks the modules of the pattern with the effect.
+    fn apply(&mut self, pattern: Pattern, effect: Effect) -> &mut Self {
+        let width = self.version.width().as_usize();
+        let height = self.version.height().as_usize();
+        let rect = match pattern {
+            Pattern::Random { ratio, seed } => {
+                let mut rng = SplitMix64(seed);
+                for damage in &mut self.damage {
+                    if rng.next_f64() < ratio {
+                        *damage = (*damage).max(Some(effect));
+                    }
+                }
+                return self;
+            }
+            Pattern::Rows { start, count } => (0, start, width, count),
+            Pattern::Columns { start, count } => (start, 0, count, height),
+            Pattern::Corner { corner, size } => {
+                let size = size.min(width).min(height);
+                match corner {
+                    Corner::TopLeft => (0, 0, size, size),
+                    Corner::TopRight => (width - size, 0, size, size),
+                    Corner::BottomLeft => (0, height - size, size, size),
+                    Corner::BottomRight => (width - size, height - size, size, size),
+                }
+            }
+            Pattern::Region(region) => (region.x, region.y, region.width, region.height),
+        };
+        let (x, y, w, h) = rect;
+        for y in y..y.saturating_add(h).min(height) {
+            for x in x..x.saturating_add(w).min(width) {
+                let damage = &mut self.damage[y * width + x];
+                *damage = (*damage).max(Some(effect));
+            }
+        }
+        self
+    }
+
+    /// Reports whether the data can be recovered from the damaged symbol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the version and the error correction level are incompatible,
+    /// which is checked when the simulation is created.
+    #[must_use]
+    pub fn report(&self) -> Report {
+        let (block_count, capacity) = ec::block_correction_capacity(self.version, self.ec_level)
+            .expect("checked when the simulation is created");
+        let mut codewords = Vec::<Vec<Option<Effect>>>::with_capacity(block_count);
+        let mut damaged_function_modules = 0;
+        for ((bit, kind), damage) in self.placement.iter().zip(&self.kinds).zip(&self.damage) {
+            let Some(damage) = damage else {
+                continue;
+            };
+            match bit {
+                Some(CodewordBit {
+                    block, codeword, ..
+                }) => {
+                    if codewords.len() <= *block {
+                        codewords.resize_with(*block + 1, Vec::new);
+                    }
+                    let block = &mut codewords[*block];
+                    if block.len() <= *codeword {
+                        block.resize(*codeword + 1, None);
+                    }
+                    block[*codeword] = block[*codeword].max(Some(*damage));
+                }
+                None if *kind != ModuleKind::Remainder => damaged_function_modules += 1,
+                None => {}
+            }
+        }
+        codewords.resize_with(block_count, Vec::new);
+        let blocks = codewords
+            .into_iter()
+            .map(|codewords| BlockDamage {
+                errors: codewords
+                    .iter()
+                    .filter(|c| **c == Some(Effect::Flip))
+                    .count(),
+                erasures: codewords
+                    .iter()
+                    .filter(|c| **c == Some(Effect::Erase))
+                    .count(),
+                capacity,
+            })
+            .collect();
+        Report {
+            blocks,
+            damaged_function_modules,
+        }
+    }
+}
+
+/// The damaged codewords of an error correction block.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BlockDamage {
+    /// The number of codewords with flipped modules only.
+    pub errors: usize,
+
+    /// The number of codewords with erased modules.
+    pub erasures: usize,
+
+    /// The number of erasures which the block can recover. Each error costs
+    /// two of them.
+    pub capacity: usize,
+}
+
+impl BlockDamage {
+    /// Returns the share of the correction capacity which the damage uses. The
+    /// block is recoverable if this is at most 1.0.
+    #[must_use]
+    pub fn load(&self) -> f64 {
+        let cost = 2 * self.errors + self.erasures;
+        if cost == 0 {
+            0.0
+        } else if self.capacity == 0 {
+            f64::INFINITY
+        } else {
+            f64::from(cost.as_u32()) / f64::from(self.capacity.as_u32())
+        }
+    }
+
+    /// Checks whether the error correction can recover the block.
+    #[must_use]
+    #[inline]
+    pub const fn is_recoverable(&self) -> bool {
+        2 * self.errors + self.erasures <= self.capacity
+    }
+}
+
+/// The result of a damage simulation.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Report {
+    /// The damage of each error correction block.
+    pub blocks: Vec<BlockDamage>,
+
+    /// The number of damaged modules of the functional patterns, the format
+    /// information and the version information.
+    pub damaged_function_modules: usize,
+}
+
+impl Report {
+    /// Checks whether the error correction can recover every block.
+    ///
+    /// Damage to the functional patterns is not taken into account.
+    #[must_use]
+    pub fn is_recoverable(&self) -> bool {
+        self.blocks.iter().all(BlockDamage::is_recoverable)
+    }
+
+    /// Returns the largest [`BlockDamage::load`] of the blocks. The data is
+    /// recoverable if this is at most 1.0.
+    #[must_use]
+    pub fn worst_load(&self) -> f64 {
+        self.blocks
+            .iter()
+            .map(BlockDamage::load)
+            .fold(0.0, f64::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undamaged() {
+        let report = Simulation::new(Version::Normal(5), EcLevel::Q)
+            .unwrap()
+            .report();
+        assert_eq!(report.blocks.len(), 4);
+        assert!(report.blocks.iter().all(|b| b.capacity == 18));
+        assert!(report.is_recoverable());
+        assert!(report.worst_load().abs() < 1e-9);
+        assert_eq!(report.damaged_function_modules, 0);
+    }
+
+    #[test]
+    fn test_single_codeword() {
+        // The first data codeword occupies the 2×4 modules in the bottom right
+        // corner.
+        let mut simulation = Simulation::new(Version::Normal(1), EcLevel::M).unwrap();
+        let corner = Region {
+            x: 19,
+            y: 17,
+            width: 2,
+            height: 4,
+        };
+        let report = simulation.flip(Pattern::Region(corner)).report();
+        assert_eq!(report.blocks[0].errors, 1);
+        assert_eq!(report.blocks[0].erasures, 0);
+        // 10 error correction codewords, of which 2 detect misdecodes.
+        assert_eq!(report.blocks[0].capacity, 8);
+        assert!((report.worst_load() - 0.25).abs() < 1e-9);
+
+        // An erasure takes precedence over a flip.
+        let report = simulation.erase(Pattern::Region(corner)).report();
+        assert_eq!(report.blocks[0].errors, 0);
+        assert_eq!(report.blocks[0].erasures, 1);
+    }
+
+    #[test]
+    fn test_ec_levels() {
+        // A band of 2 rows across the middle of the symbol.
+        let band = Pattern::Rows {
+            start: 14,
+            count: 2,
+        };
+        let recoverable = |ec_level| {
+            Simulation::new(Version::Normal(5), ec_level)
+                .unwrap()
+                .flip(band)
+                .report()
+                .is_recoverable()
+        };
+        assert!(!recoverable(EcLevel::L));
+        assert!(recoverable(EcLevel::H));
+    }
+
+    #[test]
+    fn test_functional_patterns() {
+        let report = Simulation::new(Version::Micro(2), EcLevel::L)
+            .unwrap()
+            .erase(Pattern::Corner {
+                corner: Corner::TopLeft,
+                size: 7,
+            })
+            .report();
+        assert_eq!(report.damaged_function_modules, 49);
+        assert!(report.is_recoverable());
+    }
+
+    #[test]
+    fn test_random_is_reproducible() {
+        let mut simulation = Simulation::new(Version::RectMicro(13, 77), EcLevel::H).unwrap();
+        let pattern = Pattern::Random {
+            ratio: 0.05,
+            seed: 42,
+        };
+        let first = simulation.flip(pattern).report();
+        let second = simulation.repair().flip(pattern).report();
+        assert_eq!(first, second);
+        assert!(first.blocks.iter().any(|b| b.errors > 0));
+        let none = simulation
+            .repair()
+            .flip(Pattern::Random {
+                ratio: 0.0,
+                seed: 42,
+            })
+            .report();
+        assert!(none.worst_load().abs() < 1e-9);
+    }
+}
@@ -9,20 +9,42 @@
 //! The `types` module contains types associated with the functional elements of
 //! a QR code.
 
-use core::{cmp::Ordering, error::Error, fmt, ops::Not};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::{cmp::Ordering, error::Error, fmt, ops::Not, str::FromStr};
 
+#[cfg(feature = "alloc")]
+use crate::bits::Bits;
 use crate::cast::As;
 
 // `QrResult`
 
 /// `QrError` encodes the error encountered when generating a QR code.
+///
+/// New kinds of errors may be added in the future, each with a new
+/// [`QrError::code`].
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum QrError {
     /// The data is too long to encode into a QR code for the given version.
-    DataTooLong,
+    ///
+    /// When the version is chosen automatically, the numbers are those of the
+    /// largest version which was tried.
+    DataTooLong {
+        /// The number of bits needed to encode the data.
+        bits_needed: usize,
+
+        /// The number of data bits which the version holds at the error
+        /// correction level.
+        bits_available: usize,
+    },
 
     /// The provided version / error correction level combination is invalid.
-    InvalidVersion,
+    InvalidVersion {
+        /// The invalid version. This is [`None`] if the input does not even
+        /// describe a version, e.g. a string which fails to parse.
+        version: Option<Version>,
+    },
 
     /// Some characters in the data cannot be supported by the provided QR code
     /// version.
@@ -33,24 +55,257 @@ pub enum QrError {
     InvalidEciDesignator,
 
     /// A character not belonging to the character set is found.
-    InvalidCharacter,
+    InvalidCharacter {
+        /// The byte offset of the invalid character in the data.
+        offset: usize,
+    },
+
+    /// The symbol does not conform to ISO/IEC 18004 or ISO/IEC 23941.
+    #[cfg_attr(
+        feature = "alloc",
+        doc = "See [`conformance`](crate::conformance) for details."
+    )]
+    NonConformant,
+
+    /// The mode is not supported by the provided Micro QR code version. M1
+    /// only supports [`Mode::Numeric`], and M2 only supports
+    /// [`Mode::Numeric`] and [`Mode::Alphanumeric`].
+    UnsupportedMode(Mode),
+
+    /// The data contains a byte sequence which is not a Shift JIS double-byte
+    /// character supported by [`Mode::Kanji`].
+    InvalidKanji {
+        /// The byte offset of the invalid character in the data.
+        offset: usize,
+
+        /// The invalid byte pair. The second byte is [`None`] if the data ends
+        /// in the middle of a character.
+        pair: (u8, Option<u8>),
+    },
+
+    #[cfg_attr(
+        feature = "alloc",
+        doc = "The data is not a valid symbol in the [`wire`](crate::wire) format."
+    )]
+    #[cfg_attr(
+        not(feature = "alloc"),
+        doc = "The data is not a valid symbol in the `wire` format."
+    )]
+    InvalidWireFormat,
+
+    /// The structured append header is invalid. A symbol sequence consists of
+    /// 1 to 16 symbols, and the index should be less than the number of
+    /// symbols.
+    InvalidStructuredAppend,
+
+    /// The reserved region is outside the symbol, covers a module which does
+    /// not hold data or error correction codewords, or damages more codewords
+    /// than the error correction can recover.
+    InvalidReservedRegion,
+
+    /// The mask pattern is not supported by the version.
+    InvalidMaskPattern,
+
+    /// The overlay covers a module which does not hold data or error
+    /// correction codewords, or damages more codewords than the error
+    /// correction can recover.
+    InvalidOverlay,
+
+    /// The symbol does not decode back to a faithful encoding of its data.
+    VerificationFailed,
+
+    /// The string is not the name of an error correction level.
+    InvalidEcLevel,
+
+    /// The buffer is too small to encode the QR code into. See the `heapless`
+    /// module for details.
+    BufferTooSmall {
+        /// The number of bytes needed.
+        bytes_needed: usize,
+
+        /// The number of bytes in the buffer.
+        bytes_available: usize,
+    },
 }
 
 impl fmt::Display for QrError {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::DataTooLong => write!(f, "data too long"),
-            Self::InvalidVersion => write!(f, "invalid version"),
+            Self::DataTooLong {
+                bits_needed,
+                bits_available,
+            } => write!(
+                f,
+                "data too long: {bits_needed} bits needed, but only {bits_available} bits available"
+            ),
+            Self::InvalidVersion { version: None } => write!(f, "invalid version"),
+            Self::InvalidVersion {
+                version: Some(version),
+            } => write!(f, "invalid version {version}"),
             Self::UnsupportedCharacterSet => write!(f, "unsupported character set"),
             Self::InvalidEciDesignator => write!(f, "invalid ECI designator"),
-            Self::InvalidCharacter => write!(f, "invalid character"),
+            Self::InvalidCharacter { offset } => {
+                write!(f, "invalid character at offset {offset}")
+            }
+            Self::NonConformant => write!(f, "non-conformant symbol"),
+            Self::InvalidWireFormat => write!(f, "invalid wire format"),
+            Self::InvalidStructuredAppend => write!(f, "invalid structured append header"),
+            Self::InvalidReservedRegion => write!(f, "invalid reserved region"),
+            Self::InvalidMaskPattern => write!(f, "invalid mask pattern"),
+            Self::InvalidOverlay => write!(f, "invalid overlay"),
+            Self::VerificationFailed => write!(f, "verification failed"),
+            Self::InvalidEcLevel => write!(f, "invalid error correction level"),
+            Self::BufferTooSmall {
+                bytes_needed,
+                bytes_available,
+            } => write!(
+                f,
+                "buffer too small: {bytes_needed} bytes needed, but only {bytes_available} bytes available"
+            ),
+            Self::UnsupportedMode(mode) => write!(f, "{mode:?} mode not supported by the version"),
+            Self::InvalidKanji {
+                offset,
+                pair: (first, Some(second)),
+            } => write!(
+                f,
+                "invalid Kanji character {first:02X}{second:02X} at offset {offset}"
+            ),
+            Self::InvalidKanji {
+                offset,
+                pair: (first, None),
+            } => write!(
+                f,
+                "incomplete Kanji character {first:02X} at offset {offset}"
+            ),
+        }
+    }
+}
+
+impl QrError {
+    /// Gets the stable numeric code of the error.
+    ///
+    /// The code identifies the kind of the error regardless of the data it
+    /// carries, e.g. to look up a localized message or to report the error
+    /// across an FFI boundary. The code of an existing kind never changes, and
+    /// a new kind gets a new code.
+    ///
+    /// | Code | Error                                   |
+    /// | ---- | --------------------------------------- |
+    /// | 1    | [`QrError::DataTooLong`]                |
+    /// | 2    | [`QrError::InvalidVersion`]             |
+    /// | 3    | [`QrError::UnsupportedCharacterSet`]    |
+    /// | 4    | [`QrError::InvalidEciDesignator`]       |
+    /// | 5    | [`QrError::InvalidCharacter`]           |
+    /// | 6    | [`QrError::NonConformant`]              |
+    /// | 7    | [`QrError::UnsupportedMode`]            |
+    /// | 8    | [`QrError::InvalidKanji`]               |
+    /// | 9    | [`QrError::InvalidWireFormat`]          |
+    /// | 10   | [`QrError::InvalidStructuredAppend`]    |
+    /// | 11   | [`QrError::InvalidReservedRegion`]      |
+    /// | 12   | [`QrError::InvalidMaskPattern`]         |
+    /// | 13   | [`QrError::InvalidOverlay`]             |
+    /// | 14   | [`QrError::VerificationFailed`]         |
+    /// | 15   | [`QrError::InvalidEcLevel`]             |
+    /// | 16   | [`QrError::BufferTooSmall`]             |
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::types::QrError;
+    /// #
+    /// let error = QrError::DataTooLong {
+    ///     bits_needed: 200,
+    ///     bits_available: 128,
+    /// };
+    /// assert_eq!(error.code(), 1);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn code(&self) -> u16 {
+        match self {
+            Self::DataTooLong { .. } => 1,
+            Self::InvalidVersion { .. } => 2,
+            Self::UnsupportedCharacterSet => 3,
+            Self::InvalidEciDesignator => 4,
+            Self::InvalidCharacter { .. } => 5,
+            Self::NonConformant => 6,
+            Self::UnsupportedMode(_) => 7,
+            Self::InvalidKanji { .. } => 8,
+            Self::InvalidWireFormat => 9,
+            Self::InvalidStructuredAppend => 10,
+            Self::InvalidReservedRegion => 11,
+            Self::InvalidMaskPattern => 12,
+            Self::InvalidOverlay => 13,
+            Self::VerificationFailed => 14,
+            Self::InvalidEcLevel => 15,
+            Self::BufferTooSmall { .. } => 16,
         }
     }
 }
 
+/// Every error originates in this crate and does not wrap another error, so
+/// [`Error::source`] is always [`None`].
 impl Error for QrError {}
 
+#[cfg(all(test, feature = "alloc"))]
+mod qr_error_tests {
+    use alloc::{string::ToString, vec::Vec};
+
+    use super::*;
+
+    #[test]
+    fn test_codes_are_unique() {
+        let errors = [
+            QrError::DataTooLong {
+                bits_needed: 1,
+                bits_available: 0,
+            },
+            QrError::InvalidVersion { version: None },
+            QrError::UnsupportedCharacterSet,
+            QrError::InvalidEciDesignator,
+            QrError::InvalidCharacter { offset: 0 },
+            QrError::NonConformant,
+            QrError::UnsupportedMode(Mode::Byte),
+            QrError::InvalidKanji {
+                offset: 0,
+                pair: (0x81, None),
+            },
+            QrError::InvalidWireFormat,
+            QrError::InvalidStructuredAppend,
+            QrError::InvalidReservedRegion,
+            QrError::InvalidMaskPattern,
+            QrError::InvalidOverlay,
+            QrError::VerificationFailed,
+            QrError::InvalidEcLevel,
+            QrError::BufferTooSmall {
+                bytes_needed: 1,
+                bytes_available: 0,
+            },
+        ];
+        let codes = errors.iter().map(QrError::code).collect::<Vec<_>>();
+        assert_eq!(codes, (1..=16).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_display_context() {
+        let error = QrError::DataTooLong {
+            bits_needed: 200,
+            bits_available: 128,
+        };
+        assert_eq!(
+            error.to_string(),
+            "data too long: 200 bits needed, but only 128 bits available"
+        );
+        let error = QrError::InvalidVersion {
+            version: Some(Version::Micro(5)),
+        };
+        assert_eq!(error.to_string(), "invalid version M5");
+        let error = QrError::InvalidCharacter { offset: 3 };
+        assert_eq!(error.to_string(), "invalid character at offset 3");
+    }
+}
+
 /// `QrResult` is a convenient alias for a QR code generation result.
 pub type QrResult<T> = Result<T, QrError>;
 
@@ -58,6 +313,7 @@ pub type QrResult<T> = Result<T, QrError>;
 
 /// The color of a module.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Color {
     /// The module is light colored.
     Light,
@@ -85,6 +341,27 @@ impl Color {
             Self::Dark => dark,
         }
     }
+
+    /// Converts a grayscale pixel to the color of a module. The pixel is dark
+    /// if its luma is less than `threshold`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::Color;
+    /// #
+    /// assert_eq!(Color::from_luma(30, 128), Color::Dark);
+    /// assert_eq!(Color::from_luma(200, 128), Color::Light);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn from_luma(luma: u8, threshold: u8) -> Self {
+        if luma < threshold {
+            Self::Dark
+        } else {
+            Self::Light
+        }
+    }
 }
 
 impl Not for Color {
@@ -99,11 +376,49 @@ impl Not for Color {
     }
 }
 
+impl From<bool> for Color {
+    /// Converts `true` to [`Color::Dark`] and `false` to [`Color::Light`].
+    #[inline]
+    fn from(is_dark: bool) -> Self {
+        if is_dark { Self::Dark } else { Self::Light }
+    }
+}
+
+impl From<Color> for bool {
+    /// Converts [`Color::Dark`] to `true` and [`Color::Light`] to `false`.
+    #[inline]
+    fn from(color: Color) -> Self {
+        color == Color::Dark
+    }
+}
+
+#[cfg(test)]
+mod color_tests {
+    use super::*;
+
+    #[test]
+    fn test_bool_round_trip() {
+        for color in [Color::Light, Color::Dark] {
+            assert_eq!(Color::from(bool::from(color)), color);
+        }
+        assert_eq!(Color::from(true), Color::Dark);
+        assert!(!bool::from(Color::Light));
+    }
+
+    #[test]
+    fn test_from_luma() {
+        assert_eq!(Color::from_luma(127, 128), Color::Dark);
+        assert_eq!(Color::from_luma(128, 128), Color::Light);
+        assert_eq!(Color::from_luma(0, 0), Color::Light);
+    }
+}
+
 // Error correction level
 
 /// The error correction level. It allows the original information be recovered
 /// even if parts of the code is damaged.
-#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum EcLevel {
     /// Low error correction. Allows up to 7% of wrong blocks.
     L = 0,
@@ -119,14 +434,64 @@ pub enum EcLevel {
     H = 3,
 }
 
-#[cfg(test)]
+/// Formats the error correction level as its letter, e.g. `M`.
+impl fmt::Display for EcLevel {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let letter = match self {
+            Self::L => "L",
+            Self::M => "M",
+            Self::Q => "Q",
+            Self::H => "H",
+        };
+        f.pad(letter)
+    }
+}
+
+/// Parses the letter of an error correction level, ignoring case.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{EcLevel, types::QrError};
+/// #
+/// assert_eq!("Q".parse(), Ok(EcLevel::Q));
+/// assert_eq!("h".parse(), Ok(EcLevel::H));
+/// assert_eq!("X".parse::<EcLevel>(), Err(QrError::InvalidEcLevel));
+/// ```
+impl FromStr for EcLevel {
+    type Err = QrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "L" | "l" => Ok(Self::L),
+            "M" | "m" => Ok(Self::M),
+            "Q" | "q" => Ok(Self::Q),
+            "H" | "h" => Ok(Self::H),
+            _ => Err(QrError::InvalidEcLevel),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
 mod ec_level_tests {
+    use alloc::string::ToString;
+
     use super::*;
 
     #[test]
     fn test_default() {
         assert_eq!(EcLevel::default(), EcLevel::M);
     }
+
+    #[test]
+    fn test_display_from_str() {
+        for ec_level in [EcLevel::L, EcLevel::M, EcLevel::Q, EcLevel::H] {
+            assert_eq!(ec_level.to_string().parse(), Ok(ec_level));
+        }
+        assert_eq!("".parse::<EcLevel>(), Err(QrError::InvalidEcLevel));
+        assert_eq!("LM".parse::<EcLevel>(), Err(QrError::InvalidEcLevel));
+    }
 }
 
 // Version
@@ -134,7 +499,11 @@ mod ec_level_tests {
 /// In QR code terminology, `Version` means the size of the generated image.
 /// Larger version means the size of code is larger, and therefore can carry
 /// more information.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+///
+/// Normal QR code is always encoded as Model 2. Model 1, which was dropped from
+/// ISO/IEC 18004 in its 2006 edition, is not supported.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Version {
     /// A normal QR code version. The parameter should be between 1 and 40. The
     /// smallest version is `Version::Normal(1)` of size 21×21, and the largest
@@ -157,6 +526,132 @@ pub enum Version {
 }
 
 impl Version {
+    /// Creates a normal QR code version.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the version is not between 1 and 40.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{Version, types::QrError};
+    /// #
+    /// assert_eq!(Version::new_normal(40), Ok(Version::Normal(40)));
+    /// assert_eq!(
+    ///     Version::new_normal(41),
+    ///     Err(QrError::InvalidVersion {
+    ///         version: Some(Version::Normal(41))
+    ///     })
+    /// );
+    /// ```
+    pub fn new_normal(version: u8) -> QrResult<Self> {
+        Self::Normal(i16::from(version)).validate()
+    }
+
+    /// Creates a Micro QR code version.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the version is not between 1 and 4.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{Version, types::QrError};
+    /// #
+    /// assert_eq!(Version::new_micro(4), Ok(Version::Micro(4)));
+    /// assert_eq!(
+    ///     Version::new_micro(0),
+    ///     Err(QrError::InvalidVersion {
+    ///         version: Some(Version::Micro(0))
+    ///     })
+    /// );
+    /// ```
+    pub fn new_micro(version: u8) -> QrResult<Self> {
+        Self::Micro(i16::from(version)).validate()
+    }
+
+    /// Creates a rMQR code version from the height and the width in modules.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the size is not one of the 32 sizes defined by
+    /// ISO/IEC 23941.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{Version, types::QrError};
+    /// #
+    /// assert_eq!(
+    ///     Version::new_rect_micro(7, 43),
+    ///     Ok(Version::RectMicro(7, 43))
+    /// );
+    /// assert_eq!(
+    ///     Version::new_rect_micro(7, 45),
+    ///     Err(QrError::InvalidVersion {
+    ///         version: Some(Version::RectMicro(7, 45))
+    ///     })
+    /// );
+    /// ```
+    pub fn new_rect_micro(height: u32, width: u32) -> QrResult<Self> {
+        if height > 17 || width > 139 {
+            return Err(QrError::InvalidVersion { version: None });
+        }
+        Self::RectMicro(height.as_i16(), width.as_i16()).validate()
+    }
+
+    /// Checks that the version refers to a valid QR code, Micro QR code or rMQR
+    /// code, and returns it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the version is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{Version, types::QrError};
+    /// #
+    /// assert_eq!(Version::Micro(2).validate(), Ok(Version::Micro(2)));
+    /// assert_eq!(
+    ///     Version::Normal(0).validate(),
+    ///     Err(QrError::InvalidVersion {
+    ///         version: Some(Version::Normal(0))
+    ///     })
+    /// );
+    /// ```
+    pub const fn validate(self) -> QrResult<Self> {
+        if self.is_normal() || self.is_micro() || self.is_rect_micro() {
+            Ok(self)
+        } else {
+            Err(QrError::InvalidVersion {
+                version: Some(self),
+            })
+        }
+    }
+
+    /// Gets the width and the height of the code in modules as unsigned
+    /// integers.
+    ///
+    /// Unlike [`Version::width`] and [`Version::height`], this does not need a
+    /// cast before computing pixel sizes or indexing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::Version;
+    /// #
+    /// assert_eq!(Version::Normal(1).size(), (21, 21));
+    /// assert_eq!(Version::RectMicro(17, 139).size(), (139, 17));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn size(self) -> (u32, u32) {
+        (self.width().as_u32(), self.height().as_u32())
+    }
+
     /// Gets the number of horizontally-arranged "modules" on each size of the
     /// QR code, i.e. the width of the code.
     ///
@@ -239,7 +734,9 @@ impl Version {
             }
             _ => {}
         }
-        Err(QrError::InvalidVersion)
+        Err(QrError::InvalidVersion {
+            version: Some(self),
+        })
     }
 
     /// Returns the number of bits needed to encode the mode indicator.
@@ -326,6 +823,94 @@ impl Version {
         self.rect_micro_index().is_ok()
     }
 
+    /// Returns the kind of symbol which the version refers to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{Version, types::VersionKind};
+    /// #
+    /// assert_eq!(Version::Normal(7).kind(), VersionKind::Normal);
+    /// assert_eq!(Version::Micro(2).kind(), VersionKind::Micro);
+    /// assert_eq!(Version::RectMicro(7, 43).kind(), VersionKind::RectMicro);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn kind(self) -> VersionKind {
+        match self {
+            Self::Normal(_) => VersionKind::Normal,
+            Self::Micro(_) => VersionKind::Micro,
+            Self::RectMicro(..) => VersionKind::RectMicro,
+        }
+    }
+
+    /// Checks whether the version supports the given mode.
+    ///
+    /// Micro QR code M1 only supports [`Mode::Numeric`], and M2 only supports
+    /// [`Mode::Numeric`] and [`Mode::Alphanumeric`]. Every other version
+    /// supports all modes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{Version, types::Mode};
+    /// #
+    /// assert_eq!(Version::Micro(1).supports_mode(Mode::Alphanumeric), false);
+    /// assert_eq!(Version::Micro(2).supports_mode(Mode::Alphanumeric), true);
+    /// assert_eq!(Version::Micro(2).supports_mode(Mode::Byte), false);
+    /// assert_eq!(Version::Micro(3).supports_mode(Mode::Kanji), true);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn supports_mode(self, mode: Mode) -> bool {
+        match self {
+            Self::Micro(1) => matches!(mode, Mode::Numeric),
+            Self::Micro(2) => matches!(mode, Mode::Numeric | Mode::Alphanumeric),
+            _ => true,
+        }
+    }
+
+    /// Gets the maximum number of characters which a single segment in the
+    /// mode can hold in the version at the error correction level.
+    ///
+    /// A character is a digit in [`Mode::Numeric`], a character of the
+    /// alphanumeric set in [`Mode::Alphanumeric`], a byte in [`Mode::Byte`],
+    /// and a double-byte character in [`Mode::Kanji`]. This does not encode
+    /// anything, so it is cheap enough to call on every keystroke.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the version is invalid, if it is not valid to use
+    /// the `ec_level` for the version, or if the version does not support the
+    /// mode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{EcLevel, Version, types::{Mode, QrError}};
+    /// #
+    /// assert_eq!(
+    ///     Version::Normal(1).capacity(Mode::Numeric, EcLevel::L),
+    ///     Ok(41)
+    /// );
+    /// assert_eq!(
+    ///     Version::Normal(40).capacity(Mode::Byte, EcLevel::H),
+    ///     Ok(1273)
+    /// );
+    /// assert_eq!(
+    ///     Version::Micro(1).capacity(Mode::Byte, EcLevel::L),
+    ///     Err(QrError::UnsupportedMode(Mode::Byte))
+    /// );
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn capacity(self, mode: Mode, ec_level: EcLevel) -> QrResult<usize> {
+        let max_len = Bits::new(self.validate()?).max_len(ec_level)?;
+        if !self.supports_mode(mode) {
+            return Err(QrError::UnsupportedMode(mode));
+        }
+        Ok(mode.chars_fitting(self, max_len))
+    }
+
     /// Gets the index of the version of the rMQR code.
     pub(crate) const fn rect_micro_index(self) -> QrResult<usize> {
         match self {
@@ -361,7 +946,9 @@ impl Version {
             Self::RectMicro(17, 77) => Ok(29),
             Self::RectMicro(17, 99) => Ok(30),
             Self::RectMicro(17, 139) => Ok(31),
-            _ => Err(QrError::InvalidVersion),
+            _ => Err(QrError::InvalidVersion {
+                version: Some(self),
+            }),
         }
     }
 
@@ -374,7 +961,9 @@ impl Version {
             Self::RectMicro(_, 77) => Ok(3),
             Self::RectMicro(_, 99) => Ok(4),
             Self::RectMicro(_, 139) => Ok(5),
-            _ => Err(QrError::InvalidVersion),
+            _ => Err(QrError::InvalidVersion {
+                version: Some(self),
+            }),
         }
     }
 
@@ -385,10 +974,167 @@ impl Version {
     pub(crate) const RMQR_ALL_HEIGHT: [i16; 6] = [7, 9, 11, 13, 15, 17];
 }
 
-#[cfg(test)]
+/// Formats the version with the names used by the standards: the number for
+/// QR code (e.g. `7`), `M` and the number for Micro QR code (e.g. `M3`), and
+/// `R`, the height, `x` and the width for rMQR code (e.g. `R11x77`).
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Normal(v) => write!(f, "{v}"),
+            Self::Micro(v) => write!(f, "M{v}"),
+            Self::RectMicro(height, width) => write!(f, "R{height}x{width}"),
+        }
+    }
+}
+
+/// Parses a version in the format of its [`Display`](fmt::Display)
+/// implementation, ignoring the case of the letters.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{Version, types::QrError};
+/// #
+/// assert_eq!("7".parse(), Ok(Version::Normal(7)));
+/// assert_eq!("M3".parse(), Ok(Version::Micro(3)));
+/// assert_eq!("R11x77".parse(), Ok(Version::RectMicro(11, 77)));
+/// assert_eq!(
+///     "41".parse::<Version>(),
+///     Err(QrError::InvalidVersion {
+///         version: Some(Version::Normal(41))
+///     })
+/// );
+/// assert_eq!(
+///     "V1".parse::<Version>(),
+///     Err(QrError::InvalidVersion { version: None })
+/// );
+/// ```
+impl FromStr for Version {
+    type Err = QrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Unlike `str::parse`, this does not accept a sign.
+        let error = QrError::InvalidVersion { version: None };
+        let parse = |s: &str| {
+            if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(error);
+            }
+            s.parse().map_err(|_| error)
+        };
+        let version = if let Some(number) = s.strip_prefix(['M', 'm']) {
+            Self::Micro(parse(number)?)
+        } else if let Some(size) = s.strip_prefix(['R', 'r']) {
+            let (height, width) = size.split_once(['x', 'X']).ok_or(error)?;
+            Self::RectMicro(parse(height)?, parse(width)?)
+        } else {
+            Self::Normal(parse(s)?)
+        };
+        version.validate()
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
 mod version_tests {
+    use alloc::{string::ToString, vec};
+
     use super::*;
 
+    #[test]
+    fn test_display_from_str() {
+        let versions = (1..=40)
+            .map(Version::Normal)
+            .chain((1..=4).map(Version::Micro))
+            .chain(Version::RMQR_ALL_HEIGHT.into_iter().flat_map(|height| {
+                Version::RMQR_ALL_WIDTH
+                    .into_iter()
+                    .map(move |width| Version::RectMicro(height, width))
+            }))
+            .filter(|version| version.validate().is_ok());
+        for version in versions {
+            assert_eq!(version.to_string().parse(), Ok(version));
+        }
+        assert_eq!(Version::RectMicro(13, 99).to_string(), "R13x99");
+        assert_eq!("m4".parse(), Ok(Version::Micro(4)));
+        assert_eq!("r7X43".parse(), Ok(Version::RectMicro(7, 43)));
+        for s in ["", "+1", "M", "R", "R7", "R7x", "V1", " 1"] {
+            assert_eq!(
+                s.parse::<Version>(),
+                Err(QrError::InvalidVersion { version: None }),
+                "{s}"
+            );
+        }
+        for (s, version) in [
+            ("0", Version::Normal(0)),
+            ("M5", Version::Micro(5)),
+            ("R7x44", Version::RectMicro(7, 44)),
+        ] {
+            assert_eq!(
+                s.parse::<Version>(),
+                Err(QrError::InvalidVersion {
+                    version: Some(version)
+                }),
+                "{s}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_capacity_matches_encoding() {
+        let versions = (1..=40)
+            .map(Version::Normal)
+            .chain((1..=4).map(Version::Micro))
+            .chain(Version::RMQR_ALL_HEIGHT.into_iter().flat_map(|height| {
+                Version::RMQR_ALL_WIDTH
+                    .into_iter()
+                    .map(move |width| Version::RectMicro(height, width))
+            }))
+            .filter(|version| version.validate().is_ok());
+        let fits = |version, mode, ec_level, len| {
+            let mut bits = Bits::new(version);
+            let pushed = match mode {
+                Mode::Numeric => bits.push_numeric_data(&vec![b'1'; len]),
+                Mode::Alphanumeric => bits.push_alphanumeric_data(&vec![b'A'; len]),
+                Mode::Byte => bits.push_byte_data(&vec![b'a'; len]),
+                Mode::Kanji => bits.push_kanji_data(&b"\x93\x5f".repeat(len)),
+            };
+            pushed.and_then(|()| bits.push_terminator(ec_level)).is_ok()
+        };
+        for version in versions {
+            for mode in [Mode::Numeric, Mode::Alphanumeric, Mode::Byte, Mode::Kanji] {
+                for ec_level in [EcLevel::L, EcLevel::M, EcLevel::Q, EcLevel::H] {
+                    let Ok(len) = version.capacity(mode, ec_level) else {
+                        continue;
+                    };
+                    assert!(fits(version, mode, ec_level, len), "{version} {mode:?}");
+                    assert!(
+                        !fits(version, mode, ec_level, len + 1),
+                        "{version} {mode:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_capacity_errors() {
+        assert_eq!(
+            Version::Micro(2).capacity(Mode::Kanji, EcLevel::L),
+            Err(QrError::UnsupportedMode(Mode::Kanji))
+        );
+        assert_eq!(
+            Version::Micro(1).capacity(Mode::Numeric, EcLevel::M),
+            Err(QrError::InvalidVersion {
+                version: Some(Version::Micro(1))
+            })
+        );
+        assert_eq!(
+            Version::Normal(41).capacity(Mode::Numeric, EcLevel::L),
+            Err(QrError::InvalidVersion {
+                version: Some(Version::Normal(41))
+            })
+        );
+    }
+
     #[test]
     fn test_width() {
         assert_eq!(Version::Normal(1).width(), 21);
@@ -400,6 +1146,42 @@ mod version_tests {
         assert_eq!(Version::RectMicro(17, 139).width(), 139);
     }
 
+    #[test]
+    fn test_constructors() {
+        for version in 1..=40 {
+            assert_eq!(
+                Version::new_normal(version),
+                Ok(Version::Normal(version.into()))
+            );
+        }
+        assert_eq!(
+            Version::new_normal(0),
+            Err(QrError::InvalidVersion {
+                version: Some(Version::Normal(0))
+            })
+        );
+        assert_eq!(
+            Version::new_micro(5),
+            Err(QrError::InvalidVersion {
+                version: Some(Version::Micro(5))
+            })
+        );
+        assert_eq!(
+            Version::new_rect_micro(17, 139),
+            Ok(Version::RectMicro(17, 139))
+        );
+        assert_eq!(
+            Version::new_rect_micro(u32::MAX, 43),
+            Err(QrError::InvalidVersion { version: None })
+        );
+        assert_eq!(
+            Version::RectMicro(9, 9).validate(),
+            Err(QrError::InvalidVersion {
+                version: Some(Version::RectMicro(9, 9))
+            })
+        );
+    }
+
     #[test]
     fn test_height() {
         assert_eq!(Version::Normal(1).height(), 21);
@@ -462,6 +1244,393 @@ mod version_tests {
         assert!(!Version::Normal(1).is_rect_micro());
         assert!(!Version::Micro(1).is_rect_micro());
     }
+
+    #[test]
+    fn test_supports_mode() {
+        let modes = [Mode::Numeric, Mode::Alphanumeric, Mode::Byte, Mode::Kanji];
+        let supported =
+            |version: Version| modes.iter().filter(|m| version.supports_mode(**m)).count();
+        assert_eq!(supported(Version::Micro(1)), 1);
+        assert_eq!(supported(Version::Micro(2)), 2);
+        assert_eq!(supported(Version::Micro(3)), 4);
+        assert_eq!(supported(Version::Micro(4)), 4);
+        assert_eq!(supported(Version::Normal(1)), 4);
+        assert_eq!(supported(Version::RectMicro(7, 43)), 4);
+    }
+}
+
+// rMQR code size
+
+/// The kind of symbol, i.e. the family of [`Version`]s.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum VersionKind {
+    /// A normal QR code, see [`Version::Normal`].
+    Normal,
+
+    /// A Micro QR code, see [`Version::Micro`].
+    Micro,
+
+    /// A rMQR code, see [`Version::RectMicro`].
+    RectMicro,
+}
+
+impl VersionKind {
+    /// Returns the largest version of the kind, which has the longest
+    /// character count indicators.
+    pub(crate) const fn largest(self) -> Version {
+        match self {
+            Self::Normal => Version::Normal(40),
+            Self::Micro => Version::Micro(4),
+            Self::RectMicro => Version::RectMicro(17, 139),
+        }
+    }
+
+    /// Returns the versions of the kind, from the smallest area to the
+    /// largest. Versions with the same area are ordered by width.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn versions(self) -> Vec<Version> {
+        match self {
+            Self::Normal => (1..=40).map(Version::Normal).collect(),
+            Self::Micro => (1..=4).map(Version::Micro).collect(),
+            Self::RectMicro => {
+                let mut versions = RmqrSize::ALL.map(Version::from).to_vec();
+                versions.sort_by_key(|v| (v.width() * v.height(), v.width()));
+                versions
+            }
+        }
+    }
+}
+
+/// The size of a rMQR code.
+///
+/// Unlike [`Version::RectMicro`], only the 32 sizes defined by ISO/IEC 23941
+/// are representable, so a `match` over the sizes is exhaustive.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{Version, types::RmqrSize};
+/// #
+/// let version = Version::from(RmqrSize::R13x77);
+/// assert_eq!(version, Version::RectMicro(13, 77));
+/// assert_eq!(RmqrSize::try_from(version), Ok(RmqrSize::R13x77));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RmqrSize {
+    /// R7x43, i.e. 7 modules high and 43 modules wide.
+    R7x43,
+
+    /// R7x59, i.e. 7 modules high and 59 modules wide.
+    R7x59,
+
+    /// R7x77, i.e. 7 modules high and 77 modules wide.
+    R7x77,
+
+    /// R7x99, i.e. 7 modules high and 99 modules wide.
+    R7x99,
+
+    /// R7x139, i.e. 7 modules high and 139 modules wide.
+    R7x139,
+
+    /// R9x43, i.e. 9 modules high and 43 modules wide.
+    R9x43,
+
+    /// R9x59, i.e. 9 modules high and 59 modules wide.
+    R9x59,
+
+    /// R9x77, i.e. 9 modules high and 77 modules wide.
+    R9x77,
+
+    /// R9x99, i.e. 9 modules high and 99 modules wide.
+    R9x99,
+
+    /// R9x139, i.e. 9 modules high and 139 modules wide.
+    R9x139,
+
+    /// R11x27, i.e. 11 modules high and 27 modules wide.
+    R11x27,
+
+    /// R11x43, i.e. 11 modules high and 43 modules wide.
+    R11x43,
+
+    /// R11x59, i.e. 11 modules high and 59 modules wide.
+    R11x59,
+
+    /// R11x77, i.e. 11 modules high and 77 modules wide.
+    R11x77,
+
+    /// R11x99, i.e. 11 modules high and 99 modules wide.
+    R11x99,
+
+    /// R11x139, i.e. 11 modules high and 139 modules wide.
+    R11x139,
+
+    /// R13x27, i.e. 13 modules high and 27 modules wide.
+    R13x27,
+
+    /// R13x43, i.e. 13 modules high and 43 modules wide.
+    R13x43,
+
+    /// R13x59, i.e. 13 modules high and 59 modules wide.
+    R13x59,
+
+    /// R13x77, i.e. 13 modules high and 77 modules wide.
+    R13x77,
+
+    /// R13x99, i.e. 13 modules high and 99 modules wide.
+    R13x99,
+
+    /// R13x139, i.e. 13 modules high and 139 modules wide.
+    R13x139,
+
+    /// R15x43, i.e. 15 modules high and 43 modules wide.
+    R15x43,
+
+    /// R15x59, i.e. 15 modules high and 59 modules wide.
+    R15x59,
+
+    /// R15x77, i.e. 15 modules high and 77 modules wide.
+    R15x77,
+
+    /// R15x99, i.e. 15 modules high and 99 modules wide.
+    R15x99,
+
+    /// R15x139, i.e. 15 modules high and 139 modules wide.
+    R15x139,
+
+    /// R17x43, i.e. 17 modules high and 43 modules wide.
+    R17x43,
+
+    /// R17x59, i.e. 17 modules high and 59 modules wide.
+    R17x59,
+
+    /// R17x77, i.e. 17 modules high and 77 modules wide.
+    R17x77,
+
+    /// R17x99, i.e. 17 modules high and 99 modules wide.
+    R17x99,
+
+    /// R17x139, i.e. 17 modules high and 139 modules wide.
+    R17x139,
+}
+
+impl RmqrSize {
+    /// All sizes, in the order of ISO/IEC 23941, i.e. by height and then by
+    /// width.
+    pub const ALL: [Self; 32] = [
+        Self::R7x43,
+        Self::R7x59,
+        Self::R7x77,
+        Self::R7x99,
+        Self::R7x139,
+        Self::R9x43,
+        Self::R9x59,
+        Self::R9x77,
+        Self::R9x99,
+        Self::R9x139,
+        Self::R11x27,
+        Self::R11x43,
+        Self::R11x59,
+        Self::R11x77,
+        Self::R11x99,
+        Self::R11x139,
+        Self::R13x27,
+        Self::R13x43,
+        Self::R13x59,
+        Self::R13x77,
+        Self::R13x99,
+        Self::R13x139,
+        Self::R15x43,
+        Self::R15x59,
+        Self::R15x77,
+        Self::R15x99,
+        Self::R15x139,
+        Self::R17x43,
+        Self::R17x59,
+        Self::R17x77,
+        Self::R17x99,
+        Self::R17x139,
+    ];
+
+    /// Gets the height and the width in modules.
+    const fn dimensions(self) -> (i16, i16) {
+        match self {
+            Self::R7x43 => (7, 43),
+            Self::R7x59 => (7, 59),
+            Self::R7x77 => (7, 77),
+            Self::R7x99 => (7, 99),
+            Self::R7x139 => (7, 139),
+            Self::R9x43 => (9, 43),
+            Self::R9x59 => (9, 59),
+            Self::R9x77 => (9, 77),
+            Self::R9x99 => (9, 99),
+            Self::R9x139 => (9, 139),
+            Self::R11x27 => (11, 27),
+            Self::R11x43 => (11, 43),
+            Self::R11x59 => (11, 59),
+            Self::R11x77 => (11, 77),
+            Self::R11x99 => (11, 99),
+            Self::R11x139 => (11, 139),
+            Self::R13x27 => (13, 27),
+            Self::R13x43 => (13, 43),
+            Self::R13x59 => (13, 59),
+            Self::R13x77 => (13, 77),
+            Self::R13x99 => (13, 99),
+            Self::R13x139 => (13, 139),
+            Self::R15x43 => (15, 43),
+            Self::R15x59 => (15, 59),
+            Self::R15x77 => (15, 77),
+            Self::R15x99 => (15, 99),
+            Self::R15x139 => (15, 139),
+            Self::R17x43 => (17, 43),
+            Self::R17x59 => (17, 59),
+            Self::R17x77 => (17, 77),
+            Self::R17x99 => (17, 99),
+            Self::R17x139 => (17, 139),
+        }
+    }
+
+    /// Gets the number of vertically-arranged modules.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::types::RmqrSize;
+    /// #
+    /// assert_eq!(RmqrSize::R11x27.height(), 11);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn height(self) -> i16 {
+        self.dimensions().0
+    }
+
+    /// Gets the number of horizontally-arranged modules.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::types::RmqrSize;
+    /// #
+    /// assert_eq!(RmqrSize::R11x27.width(), 27);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn width(self) -> i16 {
+        self.dimensions().1
+    }
+}
+
+impl From<RmqrSize> for Version {
+    #[inline]
+    fn from(size: RmqrSize) -> Self {
+        Self::RectMicro(size.height(), size.width())
+    }
+}
+
+impl TryFrom<Version> for RmqrSize {
+    type Error = QrError;
+
+    /// Converts a [`Version::RectMicro`] to the size.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the version is not a valid rMQR code version.
+    #[inline]
+    fn try_from(version: Version) -> QrResult<Self> {
+        version.rect_micro_index().map(|index| Self::ALL[index])
+    }
+}
+
+#[cfg(test)]
+mod rmqr_size_tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for (index, size) in RmqrSize::ALL.into_iter().enumerate() {
+            let version = Version::from(size);
+            assert_eq!(version.rect_micro_index(), Ok(index));
+            assert_eq!(RmqrSize::try_from(version), Ok(size));
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(
+            RmqrSize::try_from(Version::RectMicro(7, 27)),
+            Err(QrError::InvalidVersion {
+                version: Some(Version::RectMicro(7, 27))
+            })
+        );
+        assert_eq!(
+            RmqrSize::try_from(Version::Normal(1)),
+            Err(QrError::InvalidVersion {
+                version: Some(Version::Normal(1))
+            })
+        );
+    }
+}
+
+// Region
+
+/// A rectangular region of modules.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Region {
+    /// The x-coordinate of the left edge.
+    pub x: usize,
+
+    /// The y-coordinate of the top edge.
+    pub y: usize,
+
+    /// The number of modules per row.
+    pub width: usize,
+
+    /// The number of modules per column.
+    pub height: usize,
+}
+
+impl Region {
+    /// Creates a region of the given size in the center of the symbol of the
+    /// version.
+    ///
+    /// If the symbol and the region differ in the parity of their size, the
+    /// region is moved towards the top-left corner.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::{Version, types::Region};
+    /// #
+    /// let region = Region::centered(Version::Normal(1), 5, 4);
+    /// assert_eq!((region.x, region.y), (8, 8));
+    /// ```
+    #[must_use]
+    pub fn centered(version: Version, width: usize, height: usize) -> Self {
+        let (symbol_width, symbol_height) =
+            (version.width().as_usize(), version.height().as_usize());
+        Self {
+            x: symbol_width.saturating_sub(width) / 2,
+            y: symbol_height.saturating_sub(height) / 2,
+            width,
+            height,
+        }
+    }
+
+    /// Returns whether the region contains the module at the given
+    /// coordinates.
+    #[must_use]
+    #[inline]
+    pub const fn contains(&self, x: usize, y: usize) -> bool {
+        self.x <= x && x < self.x + self.width && self.y <= y && y < self.y + self.height
+    }
+
+    /// Returns the number of modules in the region.
+    #[must_use]
+    #[inline]
+    pub const fn area(&self) -> usize {
+        self.width * self.height
+    }
 }
 
 // Mode indicator
@@ -537,6 +1706,20 @@ impl Mode {
         }
     }
 
+    /// Computes the number of characters which fit in a segment of `len` bits,
+    /// including its header, in the version.
+    pub(crate) fn chars_fitting(self, version: Version, len: usize) -> usize {
+        let length_bits = self.length_bits_count(version);
+        let data_bits = len.saturating_sub(version.mode_bits_count() + length_bits);
+        let chars = match self {
+            Self::Numeric => data_bits * 3 / 10,
+            Self::Alphanumeric => data_bits * 2 / 11,
+            Self::Byte => data_bits / 8,
+            Self::Kanji => data_bits / 13,
+        };
+        chars.min((1 << length_bits) - 1)
+    }
+
     /// Computes the number of bits needed to some data of a given raw length.
     ///
     /// <div class="warning">
@@ -629,6 +1812,176 @@ mod mode_tests {
     }
 }
 
+// Character set
+
+/// A character set which text can be transcoded to, together with its ECI
+/// designator.
+#[cfg_attr(
+    feature = "alloc",
+    doc = "",
+    doc = "See [`bits::encode_auto_with_charset`](crate::bits::encode_auto_with_charset)."
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Charset {
+    /// ISO/IEC 8859-1 (Latin-1, Western European), ECI 3.
+    Iso8859_1,
+
+    /// ISO/IEC 8859-5 (Latin/Cyrillic), ECI 7.
+    Iso8859_5,
+
+    /// ISO/IEC 8859-15 (Latin-9, Western European with the euro sign), ECI 17.
+    Iso8859_15,
+
+    /// UTF-16 in big-endian byte order, ECI 25.
+    Utf16Be,
+
+    /// UTF-8, ECI 26.
+    Utf8,
+}
+
+impl Charset {
+    /// Returns the ECI designator of the character set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::types::Charset;
+    /// #
+    /// assert_eq!(Charset::Utf8.eci_designator(), 26);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn eci_designator(self) -> u32 {
+        match self {
+            Self::Iso8859_1 => 3,
+            Self::Iso8859_5 => 7,
+            Self::Iso8859_15 => 17,
+            Self::Utf16Be => 25,
+            Self::Utf8 => 26,
+        }
+    }
+
+    /// Returns `true` if ASCII characters are encoded as single ASCII bytes in
+    /// the character set, so that they may be encoded in [`Mode::Numeric`] or
+    /// [`Mode::Alphanumeric`].
+    #[must_use]
+    #[inline]
+    pub const fn is_ascii_compatible(self) -> bool {
+        !matches!(self, Self::Utf16Be)
+    }
+
+    /// Transcodes the text into the character set.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] with [`QrError::InvalidCharacter`] if the text contains
+    /// a character which the character set cannot represent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use qrcode2::types::{Charset, QrError};
+    /// #
+    /// assert_eq!(Charset::Iso8859_15.encode("5 €"), Ok(b"5 \xa4".to_vec()));
+    /// assert_eq!(
+    ///     Charset::Iso8859_1.encode("5 €"),
+    ///     Err(QrError::InvalidCharacter { offset: 2 })
+    /// );
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn encode(self, text: &str) -> QrResult<Vec<u8>> {
+        match self {
+            Self::Utf8 => Ok(text.as_bytes().to_vec()),
+            Self::Utf16Be => Ok(text.encode_utf16().flat_map(u16::to_be_bytes).collect()),
+            Self::Iso8859_1 | Self::Iso8859_5 | Self::Iso8859_15 => text
+                .char_indices()
+                .map(|(offset, c)| {
+                    self.encode_char(c)
+                        .ok_or(QrError::InvalidCharacter { offset })
+                })
+                .collect(),
+        }
+    }
+
+    /// Encodes a character in one of the single-byte character sets.
+    fn encode_char(self, c: char) -> Option<u8> {
+        let c = u32::from(c);
+        let byte = match (self, c) {
+            (Self::Iso8859_5, 0xa7) => 0xfd,
+            (Self::Iso8859_5, 0xad) => 0xad,
+            (Self::Iso8859_5, 0xa1..=0xff)
+            | (Self::Iso8859_15, 0xa4 | 0xa6 | 0xa8 | 0xb4 | 0xb8 | 0xbc..=0xbe) => return None,
+            (Self::Iso8859_5, 0x0401..=0x040c) => c - 0x0401 + 0xa1,
+            (Self::Iso8859_5, 0x040e..=0x044f) => c - 0x040e + 0xae,
+            (Self::Iso8859_5, 0x0451..=0x045c) => c - 0x0451 + 0xf1,
+            (Self::Iso8859_5, 0x045e..=0x045f) => c - 0x045e + 0xfe,
+            (Self::Iso8859_5, 0x2116) => 0xf0,
+            (Self::Iso8859_15, 0x20ac) => 0xa4,
+            (Self::Iso8859_15, 0x0160) => 0xa6,
+            (Self::Iso8859_15, 0x0161) => 0xa8,
+            (Self::Iso8859_15, 0x017d) => 0xb4,
+            (Self::Iso8859_15, 0x017e) => 0xb8,
+            (Self::Iso8859_15, 0x0152) => 0xbc,
+            (Self::Iso8859_15, 0x0153) => 0xbd,
+            (Self::Iso8859_15, 0x0178) => 0xbe,
+            (_, 0..=0xff) => c,
+            _ => return None,
+        };
+        u8::try_from(byte).ok()
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod charset_tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_ascii() {
+        for charset in [
+            Charset::Iso8859_1,
+            Charset::Iso8859_5,
+            Charset::Iso8859_15,
+            Charset::Utf8,
+        ] {
+            assert_eq!(charset.encode("Hello, 123"), Ok(b"Hello, 123".to_vec()));
+        }
+        assert_eq!(Charset::Utf16Be.encode("Hi"), Ok(b"\0H\0i".to_vec()));
+    }
+
+    #[test]
+    fn test_encode_iso_8859_5() {
+        assert_eq!(
+            Charset::Iso8859_5.encode("Ёж№§я"),
+            Ok(b"\xa1\xd6\xf0\xfd\xef".to_vec())
+        );
+        assert_eq!(
+            Charset::Iso8859_5.encode("Ёé"),
+            Err(QrError::InvalidCharacter { offset: 2 })
+        );
+    }
+
+    #[test]
+    fn test_encode_iso_8859_15() {
+        assert_eq!(
+            Charset::Iso8859_15.encode("ŠœŸé"),
+            Ok(b"\xa6\xbd\xbe\xe9".to_vec())
+        );
+        assert_eq!(
+            Charset::Iso8859_15.encode("¤"),
+            Err(QrError::InvalidCharacter { offset: 0 })
+        );
+        assert_eq!(Charset::Iso8859_1.encode("¤é"), Ok(b"\xa4\xe9".to_vec()));
+    }
+
+    #[test]
+    fn test_encode_utf_16_be() {
+        assert_eq!(
+            Charset::Utf16Be.encode("日😀"),
+            Ok(b"\x65\xe5\xd8\x3d\xde\x00".to_vec())
+        );
+    }
+}
+
 /// The number of bits needed to encode the length of the data.
 ///
 /// [Numeric, Alphanumeric, Byte, Kanji]
@@ -0,0 +1,271 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! JavaScript bindings with [`wasm-bindgen`].
+//!
+//! [`WasmQrCode`] is exported to JavaScript as `QrCode`, so that web apps can
+//! generate QR codes, Micro QR codes and rMQR codes in the browser and render
+//! them as SVG or as RGBA pixels for a `<canvas>`.
+//!
+//! ```ts
+//! import { QrCode } from "qrcode2";
+//!
+//! const code = QrCode.rectMicro(new TextEncoder().encode("Hello"), "M");
+//! document.body.innerHTML = code.toSvg(8, "#000", "#fff");
+//!
+//! const image = code.toRgba(4, 0x000000ff, 0xffffffff);
+//! const context = canvas.getContext("2d");
+//! context.putImageData(new ImageData(image.data, image.width), 0, 0);
+//! ```
+//!
+//! The error correction level is given as `"L"`, `"M"`, `"Q"` or `"H"`, and
+//! the errors are thrown as JavaScript `Error`s.
+//!
+//! [`wasm-bindgen`]: https://rustwasm.github.io/docs/wasm-bindgen/
+
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use wasm_bindgen::{Clamped, JsError, prelude::wasm_bindgen};
+
+use crate::{
+    QrCode,
+    cast::As,
+    render::{scanline::Scanlines, svg},
+    types::{Color, EcLevel},
+};
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_EC_LEVEL: &str = r#"
+/** The error correction level. */
+export type EcLevel = "L" | "M" | "Q" | "H";
+"#;
+
+/// A QR code, exported to JavaScript as `QrCode`.
+#[wasm_bindgen(js_name = QrCode)]
+#[derive(Clone, Debug)]
+pub struct WasmQrCode(QrCode);
+
+#[wasm_bindgen(js_class = QrCode)]
+impl WasmQrCode {
+    /// Constructs a new QR code which automatically encodes the given data at
+    /// the given error correction level.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the error correction level is invalid or if the
+    /// data is too long.
+    pub fn new(
+        data: &[u8],
+        #[wasm_bindgen(js_name = ecLevel, unchecked_param_type = "EcLevel")] ec_level: &str,
+    ) -> Result<Self, JsError> {
+        let ec_level = ec_level.parse::<EcLevel>()?;
+        Ok(Self(QrCode::with_error_correction_level(data, ec_level)?))
+    }
+
+    /// Constructs a new Micro QR code which automatically encodes the given
+    /// data at the given error correction level.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the error correction level is invalid or if the
+    /// data is too long.
+    pub fn micro(
+        data: &[u8],
+        #[wasm_bindgen(js_name = ecLevel, unchecked_param_type = "EcLevel")] ec_level: &str,
+    ) -> Result<Self, JsError> {
+        let ec_level = ec_level.parse::<EcLevel>()?;
+        Ok(Self(QrCode::micro_with_error_correction_level(
+            data, ec_level,
+        )?))
+    }
+
+    /// Constructs a new rMQR code which automatically encodes the given data
+    /// at the given error correction level.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the error correction level is invalid or if the
+    /// data is too long.
+    #[wasm_bindgen(js_name = rectMicro)]
+    pub fn rect_micro(
+        data: &[u8],
+        #[wasm_bindgen(js_name = ecLevel, unchecked_param_type = "EcLevel")] ec_level: &str,
+    ) -> Result<Self, JsError> {
+        let ec_level = ec_level.parse::<EcLevel>()?;
+        Ok(Self(QrCode::rect_micro_with_error_correction_level(
+            data, ec_level,
+        )?))
+    }
+
+    /// Gets the version of this QR code, e.g. `"7"`, `"M3"` or `"R11x77"`.
+    #[must_use]
+    #[wasm_bindgen(getter)]
+    pub fn version(&self) -> String {
+        self.0.version().to_string()
+    }
+
+    /// Gets the error correction level of this QR code.
+    #[must_use]
+    #[wasm_bindgen(getter, js_name = ecLevel, unchecked_return_type = "EcLevel")]
+    pub fn ec_level(&self) -> String {
+        self.0.error_correction_level().to_string()
+    }
+
+    /// Gets the number of modules per side.
+    #[must_use]
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> u32 {
+        self.0.width().as_u32()
+    }
+
+    /// Gets the number of modules vertically. This is different from the width
+    /// only for rMQR code.
+    #[must_use]
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> u32 {
+        self.0.height().as_u32()
+    }
+
+    /// Renders this QR code as an SVG image with `module_size` pixels per
+    /// module.
+    ///
+    /// The colors are CSS colors. `quiet_zone` is the width of the quiet zone
+    /// in modules, which defaults to the standard width.
+    #[must_use]
+    #[wasm_bindgen(js_name = toSvg)]
+    pub fn to_svg(
+        &self,
+        #[wasm_bindgen(js_name = moduleSize)] module_size: u32,
+        #[wasm_bindgen(js_name = darkColor)] dark_color: &str,
+        #[wasm_bindgen(js_name = lightColor)] light_color: &str,
+        #[wasm_bindgen(js_name = quietZone)] quiet_zone: Option<u32>,
+    ) -> String {
+        let mut renderer = self.0.render();
+        renderer
+            .module_dimensions(module_size, module_size)
+            .dark_color(svg::Color(dark_color))
+            .light_color(svg::Color(light_color));
+        if let Some(quiet_zone) = quiet_zone {
+            renderer.quiet_zone(quiet_zone);
+        }
+        renderer.build()
+    }
+
+    /// Renders this QR code as RGBA pixels with `module_size` pixels per
+    /// module.
+    ///
+    /// The colors are given as `0xRRGGBBAA`. `quiet_zone` is the width of the
+    /// quiet zone in modules, which defaults to the standard width.
+    #[must_use]
+    #[wasm_bindgen(js_name = toRgba)]
+    pub fn to_rgba(
+        &self,
+        #[wasm_bindgen(js_name = moduleSize)] module_size: u32,
+        #[wasm_bindgen(js_name = darkColor)] dark_color: u32,
+        #[wasm_bindgen(js_name = lightColor)] light_color: u32,
+        #[wasm_bindgen(js_name = quietZone)] quiet_zone: Option<u32>,
+    ) -> RgbaImage {
+        let mut scanlines = Scanlines::new(&self.0);
+        scanlines.module_size(module_size);
+        if let Some(quiet_zone) = quiet_zone {
+            scanlines.quiet_zone(quiet_zone);
+        }
+        let (width, height) = (scanlines.width(), scanlines.height());
+        let (dark, light) = (dark_color.to_be_bytes(), light_color.to_be_bytes());
+        let mut data = Vec::with_capacity(width.as_usize() * height.as_usize() * 4);
+        let mut row = vec![Color::Light; width.as_usize()];
+        scanlines.for_each_row(&mut row, |_, row| {
+            for color in row {
+                data.extend_from_slice(color.select(&dark, &light));
+            }
+        });
+        RgbaImage {
+            width,
+            height,
+            data,
+        }
+    }
+}
+
+/// An RGBA image, which can be passed to the `ImageData` constructor.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RgbaImage {
+    /// The width of the image in pixels.
+    #[wasm_bindgen(readonly)]
+    pub width: u32,
+
+    /// The height of the image in pixels.
+    #[wasm_bindgen(readonly)]
+    pub height: u32,
+
+    data: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl RgbaImage {
+    /// Gets the pixels, 4 bytes per pixel in row-major order.
+    #[must_use]
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> Clamped<Vec<u8>> {
+        Clamped(self.data.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constructors() {
+        let code = WasmQrCode::new(b"Hello", "M").unwrap();
+        assert_eq!(code.version(), "1");
+        assert_eq!(code.ec_level(), "M");
+        assert_eq!((code.width(), code.height()), (21, 21));
+
+        let code = WasmQrCode::micro(b"Hello", "l").unwrap();
+        assert_eq!(code.version(), "M3");
+        assert_eq!(code.ec_level(), "L");
+        assert_eq!((code.width(), code.height()), (15, 15));
+
+        let code = WasmQrCode::rect_micro(b"Hello", "M").unwrap();
+        assert_eq!(code.version(), "R11x27");
+        assert_eq!((code.width(), code.height()), (27, 11));
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let code = WasmQrCode::micro(b"Hello", "L").unwrap();
+        let svg = code.to_svg(2, "red", "#ffffff", Some(0));
+        assert!(svg.contains(r#"width="30" height="30""#));
+        assert!(svg.contains("red"));
+        assert_eq!(
+            svg,
+            code.0
+                .render()
+                .module_dimensions(2, 2)
+                .dark_color(svg::Color("red"))
+                .light_color(svg::Color("#ffffff"))
+                .quiet_zone(0)
+                .build()
+        );
+    }
+
+    #[test]
+    fn test_to_rgba() {
+        let code = WasmQrCode::rect_micro(b"Hello", "M").unwrap();
+        let image = code.to_rgba(2, 0x1122_33ff, 0xffff_ff00, None);
+        assert_eq!((image.width, image.height), (62, 30));
+        assert_eq!(image.data.len(), 62 * 30 * 4);
+        // The quiet zone is light, and the corner of the finder pattern is
+        // dark.
+        assert_eq!(image.data[..4], [0xff, 0xff, 0xff, 0x00]);
+        let offset = (4 * 62 + 4) * 4;
+        assert_eq!(image.data[offset..offset + 4], [0x11, 0x22, 0x33, 0xff]);
+    }
+}
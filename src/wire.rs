@@ -0,0 +1,371 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! A compact binary wire format for encoded symbols.
+//!
+//! Encoding a QR code is too expensive for some constrained devices, such as
+//! microcontroller display nodes connected over BLE or LoRa. This format lets
+//! a host send the module matrix of an already encoded symbol, which the
+//! device can draw directly.
+//!
+//! # Format
+//!
+//! The first byte is the header:
+//!
+//! | Bits | Meaning                                                   |
+//! | ---- | --------------------------------------------------------- |
+//! | 7–6  | `0` for QR code, `1` for Micro QR code, `2` for rMQR code |
+//! | 5–4  | The error correction level, `0` (L) to `3` (H)            |
+//! | 3–1  | Reserved, must be `0`                                     |
+//! | 0    | `0` for [`Encoding::Packed`], `1` for [`Encoding::RunLength`] |
+//!
+//! It is followed by the version number as a single byte, or the height and
+//! the width as two bytes for rMQR code. The rest is the module matrix in row
+//! major order, as described in [`Encoding`].
+//!
+//! With the `serde` feature, [`QrCode`] also implements `Serialize` and
+//! `Deserialize` as a struct of the version, the error correction level and
+//! the module matrix in the [`Encoding::Packed`] representation.
+//!
+//! # Examples
+//!
+//! ```
+//! use qrcode2::{QrCode, wire};
+//!
+//! let code = QrCode::new(b"01234567").unwrap();
+//! let bytes = wire::encode(&code);
+//! assert!(bytes.len() < 64);
+//!
+//! let decoded = wire::decode(&bytes).unwrap();
+//! assert_eq!(decoded.to_colors(), code.to_colors());
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::{
+    QrCode,
+    cast::As,
+    ec,
+    types::{Color, EcLevel, QrError, QrResult, Version},
+};
+
+/// The representation of the module matrix.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Encoding {
+    /// One bit per module, most significant bit first, where `1` is dark.
+    /// The last byte is padded with `0`.
+    Packed,
+
+    /// The lengths of alternating runs of light and dark modules, starting
+    /// with light, each written as an unsigned LEB128 number. The first run is
+    /// empty if the first module is dark.
+    RunLength,
+}
+
+impl Encoding {
+    const fn flag(self) -> u8 {
+        match self {
+            Self::Packed => 0,
+            Self::RunLength => 1,
+        }
+    }
+}
+
+/// Serializes the QR code, using the encoding which gives the shorter output.
+#[must_use]
+pub fn encode(code: &QrCode) -> Vec<u8> {
+    let packed = encode_with(code, Encoding::Packed);
+    let run_length = encode_with(code, Encoding::RunLength);
+    if run_length.len() < packed.len() {
+        run_length
+    } else {
+        packed
+    }
+}
+
+/// Serializes the QR code with the given encoding.
+///
+/// # Examples
+///
+/// ```
+/// # use qrcode2::{QrCode, wire::{self, Encoding}};
+/// #
+/// let code = QrCode::new(b"01234567").unwrap();
+/// let bytes = wire::encode_with(&code, Encoding::Packed);
+/// // The header, the version, and 21×21 bits.
+/// assert_eq!(bytes.len(), 2 + 56);
+/// ```
+#[must_use]
+pub fn encode_with(code: &QrCode, encoding: Encoding) -> Vec<u8> {
+    let (kind, version) = match code.version() {
+        Version::Normal(v) => (0, [v, 0]),
+        Version::Micro(v) => (1, [v, 0]),
+        Version::RectMicro(h, w) => (2, [h, w]),
+    };
+    let ec_level = code.error_correction_level() as u8;
+    let mut bytes = Vec::new();
+    bytes.push(kind << 6 | ec_level << 4 | encoding.flag());
+    bytes.push(version[0].to_le_bytes()[0]);
+    if kind == 2 {
+        bytes.push(version[1].to_le_bytes()[0]);
+    }
+
+    let colors = code.to_colors();
+    match encoding {
+        Encoding::Packed => bytes.extend(pack(&colors)),
+        Encoding::RunLength => {
+            let mut current = Color::Light;
+            let mut run = 0_usize;
+            for color in colors {
+                if color != current {
+                    push_leb128(&mut bytes, run);
+                    current = color;
+                    run = 0;
+                }
+                run += 1;
+            }
+            push_leb128(&mut bytes, run);
+        }
+    }
+    bytes
+}
+
+/// Deserializes a QR code serialized by [`encode`] or [`encode_with`].
+///
+/// # Errors
+///
+/// Returns [`Err`] with [`QrError::InvalidVersion`] if the version or the
+/// error correction level is invalid, or [`QrError::InvalidWireFormat`] if the
+/// data is malformed otherwise.
+pub fn decode(data: &[u8]) -> QrResult<QrCode> {
+    let (&header, mut rest) = data.split_first().ok_or(QrError::InvalidWireFormat)?;
+    if header & 0b1110 != 0 {
+        return Err(QrError::InvalidWireFormat);
+    }
+    let mut next = || {
+        let (&byte, tail) = rest.split_first().ok_or(QrError::InvalidWireFormat)?;
+        rest = tail;
+        Ok(i16::from(byte))
+    };
+    let version = match header >> 6 {
+        0 => Version::Normal(next()?),
+        1 => Version::Micro(next()?),
+        2 => Version::RectMicro(next()?, next()?),
+        _ => return Err(QrError::InvalidWireFormat),
+    };
+    let ec_level = match header >> 4 & 0b11 {
+        0 => EcLevel::L,
+        1 => EcLevel::M,
+        2 => EcLevel::Q,
+        _ => EcLevel::H,
+    };
+    ec::max_allowed_errors(version, ec_level)?;
+
+    let len = version.width().as_usize() * version.height().as_usize();
+    let colors = if header & 1 == 0 {
+        unpack(rest, len)?
+    } else {
+        let mut colors = Vec::with_capacity(len);
+        let mut current = Color::Light;
+        while !rest.is_empty() {
+            let run = read_leb128(&mut rest)?;
+            if run > len - colors.len() {
+                return Err(QrError::InvalidWireFormat);
+            }
+            colors.resize(colors.len() + run, current);
+            current = !current;
+        }
+        if colors.len() != len {
+            return Err(QrError::InvalidWireFormat);
+        }
+        colors
+    };
+    Ok(QrCode::from_colors(colors, version, ec_level))
+}
+
+/// Packs the colors into bytes, most significant bit first, where `1` is dark.
+fn pack(colors: &[Color]) -> impl Iterator<Item = u8> + '_ {
+    colors.chunks(8).map(|chunk| {
+        chunk
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c == Color::Dark)
+            .fold(0, |byte, (i, _)| byte | 0x80 >> i)
+    })
+}
+
+/// Unpacks `len` colors from the bytes produced by [`pack`].
+fn unpack(bytes: &[u8], len: usize) -> QrResult<Vec<Color>> {
+    if bytes.len() != len.div_ceil(8) {
+        return Err(QrError::InvalidWireFormat);
+    }
+    Ok((0..len)
+        .map(|i| Color::from(bytes[i / 8] & 0x80 >> (i % 8) != 0))
+        .collect())
+}
+
+fn push_leb128(bytes: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        bytes.push((value & 0x7f).to_le_bytes()[0] | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value.to_le_bytes()[0]);
+}
+
+fn read_leb128(bytes: &mut &[u8]) -> QrResult<usize> {
+    let mut value = 0_usize;
+    for shift in (0..usize::BITS).step_by(7) {
+        let (&byte, tail) = bytes.split_first().ok_or(QrError::InvalidWireFormat)?;
+        *bytes = tail;
+        value |= usize::from(byte & 0x7f)
+            .checked_shl(shift)
+            .ok_or(QrError::InvalidWireFormat)?;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(QrError::InvalidWireFormat)
+}
+
+/// The form of a QR code in [`serde`] formats.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize, serde::Serialize)]
+struct SerdeQrCode {
+    version: Version,
+    ec_level: EcLevel,
+    modules: Vec<u8>,
+}
+
+/// Serializes the version, the error correction level and the module matrix
+/// packed as in [`Encoding::Packed`].
+#[cfg(feature = "serde")]
+impl serde::Serialize for QrCode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerdeQrCode {
+            version: self.version(),
+            ec_level: self.error_correction_level(),
+            modules: pack(&self.to_colors()).collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for QrCode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = SerdeQrCode::deserialize(deserializer)?;
+        let (version, ec_level) = (code.version, code.ec_level);
+        ec::max_allowed_errors(version, ec_level).map_err(serde::de::Error::custom)?;
+        let len = version.width().as_usize() * version.height().as_usize();
+        let colors = unpack(&code.modules, len).map_err(serde::de::Error::custom)?;
+        Ok(Self::from_colors(colors, version, ec_level))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let codes = [
+            QrCode::new(b"01234567").unwrap(),
+            QrCode::new_micro(b"01234567").unwrap(),
+            QrCode::new_rect_micro(b"01234567").unwrap(),
+            QrCode::with_error_correction_level([b'a'; 300], EcLevel::H).unwrap(),
+        ];
+        for code in codes {
+            for encoding in [Encoding::Packed, Encoding::RunLength] {
+                let decoded = decode(&encode_with(&code, encoding)).unwrap();
+                assert_eq!(decoded.version(), code.version());
+                assert_eq!(
+                    decoded.error_correction_level(),
+                    code.error_correction_level()
+                );
+                assert_eq!(decoded.to_colors(), code.to_colors());
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_length() {
+        let code = QrCode::new_micro(b"01234567").unwrap();
+        let bytes = encode_with(&code, Encoding::RunLength);
+        // M2-M, then the first row starts with 7 dark modules.
+        assert_eq!(bytes[..5], [0x51, 2, 0, 7, 1]);
+    }
+
+    #[test]
+    fn test_leb128() {
+        let mut bytes = Vec::new();
+        push_leb128(&mut bytes, 300);
+        assert_eq!(bytes, [0xac, 0x02]);
+        assert_eq!(read_leb128(&mut bytes.as_slice()), Ok(300));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let bytes = encode_with(&QrCode::new(b"01234567").unwrap(), Encoding::Packed);
+        assert_eq!(decode(&[]).err(), Some(QrError::InvalidWireFormat));
+        assert_eq!(
+            decode(&bytes[..bytes.len() - 1]).err(),
+            Some(QrError::InvalidWireFormat)
+        );
+        assert_eq!(
+            decode(&[0x00, 41]).err(),
+            Some(QrError::InvalidVersion {
+                version: Some(Version::Normal(41))
+            })
+        );
+        assert_eq!(decode(&[0xc0, 1]).err(), Some(QrError::InvalidWireFormat));
+        assert_eq!(
+            decode(&[0x01, 1, 0x80]).err(),
+            Some(QrError::InvalidWireFormat)
+        );
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let codes = [
+            QrCode::new(b"01234567").unwrap(),
+            QrCode::new_micro(b"01234567").unwrap(),
+            QrCode::new_rect_micro(b"01234567").unwrap(),
+        ];
+        for code in codes {
+            let serialized = toml::to_string(&code).unwrap();
+            let deserialized: QrCode = toml::from_str(&serialized).unwrap();
+            assert_eq!(deserialized.version(), code.version());
+            assert_eq!(
+                deserialized.error_correction_level(),
+                code.error_correction_level()
+            );
+            assert_eq!(deserialized.to_colors(), code.to_colors());
+        }
+    }
+
+    #[test]
+    fn test_serialize() {
+        let code = QrCode::new_rect_micro(b"01234567").unwrap();
+        let serialized = toml::to_string(&code).unwrap();
+        assert!(serialized.starts_with("ec_level = \"M\"\nmodules = [254, "));
+        assert!(serialized.ends_with("\n[version]\nRectMicro = [11, 27]\n"));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let invalid = [
+            "version = { Normal = 41 }\nec_level = \"L\"\nmodules = []",
+            "version = { Micro = 1 }\nec_level = \"H\"\nmodules = []",
+            "version = { Micro = 1 }\nec_level = \"L\"\nmodules = [0]",
+        ];
+        for s in invalid {
+            assert!(toml::from_str::<QrCode>(s).is_err(), "{s}");
+        }
+    }
+}
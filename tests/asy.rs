@@ -0,0 +1,55 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![cfg(feature = "asy")]
+
+use qrcode2::{
+    QrCode,
+    render::{Unit, asy::Color},
+};
+
+#[test]
+fn test_annex_i_qr_as_asy() {
+    let code = QrCode::new(b"01234567").unwrap();
+    let image = code.render::<Color>().build();
+    let expected = include_str!("data/test_annex_i_qr_as_asy.asy");
+    assert_eq!(&image, expected);
+}
+
+#[test]
+fn test_annex_i_micro_qr_as_asy() {
+    let code = QrCode::new_micro(b"01234567").unwrap();
+    let image = code
+        .render()
+        .min_dimensions(200, 200)
+        .dark_color(Color([0.5, 0.0, 0.0]))
+        .light_color(Color([1.0, 1.0, 0.5]))
+        .physical_module_size(0.5, Unit::Mm)
+        .build();
+    let expected = include_str!("data/test_annex_i_micro_qr_as_asy.asy");
+    assert_eq!(&image, expected);
+}
+
+#[test]
+fn test_annex_i_rmqr_as_asy() {
+    let code = QrCode::new_rect_micro(b"01234567").unwrap();
+    let image = code.render::<Color>().build();
+    let expected = include_str!("data/test_annex_i_rmqr_as_asy.asy");
+    assert_eq!(&image, expected);
+}
+
+#[test]
+fn test_physical_module_size_units() {
+    let code = QrCode::new(b"01234567").unwrap();
+    for (size, unit, expected) in [
+        (0.1, Unit::In, "unitsize(0.1inch/8);\n"),
+        (4.0, Unit::Px, "unitsize(3bp/8);\n"),
+    ] {
+        let image = code
+            .render::<Color>()
+            .physical_module_size(size, unit)
+            .build();
+        assert!(image.starts_with(expected), "{unit:?}");
+    }
+}
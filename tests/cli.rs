@@ -0,0 +1,99 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![cfg(feature = "cli")]
+
+use std::{
+    fs,
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+use qrcode2::{
+    EcLevel, QrCode, Version,
+    image::Luma,
+    render::{svg::Color, unicode::Dense1x2},
+};
+
+fn run(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_qrcode2"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_svg_from_stdin() {
+    let output = run(&["-t", "svg", "-l", "q"], b"Hello, world!");
+    assert!(output.status.success());
+    let expected = QrCode::with_error_correction_level(b"Hello, world!", EcLevel::Q)
+        .unwrap()
+        .render::<Color<'_>>()
+        .dark_color(Color("#000000"))
+        .light_color(Color("#ffffff"))
+        .build();
+    assert_eq!(output.stdout, expected.as_bytes());
+}
+
+#[test]
+fn test_utf8_to_stdout() {
+    let output = run(&["--variant", "micro", "01234567"], b"");
+    assert!(output.status.success());
+    let expected = QrCode::new_micro(b"01234567")
+        .unwrap()
+        .render::<Dense1x2>()
+        .build();
+    assert_eq!(output.stdout, format!("{expected}\n").as_bytes());
+}
+
+#[test]
+fn test_png_from_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let input = temp_dir.path().join("input.txt");
+    let output = temp_dir.path().join("qrcode.png");
+    fs::write(&input, "Hello").unwrap();
+    let status = run(
+        &[
+            "--variant",
+            "rmqr",
+            "-v",
+            "7",
+            "43",
+            "-s",
+            "3",
+            "-r",
+            input.to_str().unwrap(),
+            "-o",
+            output.to_str().unwrap(),
+        ],
+        b"",
+    )
+    .status;
+    assert!(status.success());
+
+    let image = image::open(&output).unwrap().into_luma8();
+    assert_eq!((image.width(), image.height()), (3 * (43 + 4), 3 * (7 + 4)));
+    let expected = QrCode::with_version(b"Hello", Version::RectMicro(7, 43), EcLevel::M)
+        .unwrap()
+        .render::<Luma<u8>>()
+        .module_dimensions(3, 3)
+        .build();
+    assert_eq!(image, expected);
+}
+
+#[test]
+fn test_invalid_arguments() {
+    let output = run(&["--variant", "rmqr", "-v", "7", "--", "Hello"], b"");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("height and the width"));
+
+    let output = run(&["-o", "qrcode.txt", "Hello"], b"");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("output format"));
+}
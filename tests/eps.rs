@@ -4,7 +4,10 @@
 
 #![cfg(feature = "eps")]
 
-use qrcode2::{QrCode, render::eps::Color};
+use qrcode2::{
+    QrCode,
+    render::{Background, eps::Color},
+};
 
 #[test]
 fn test_annex_i_qr_as_eps() {
@@ -34,3 +37,39 @@ fn test_annex_i_rmqr_as_eps() {
     let expected = include_str!("data/test_annex_i_rmqr_as_eps.eps");
     assert_eq!(&image, expected);
 }
+
+#[test]
+fn test_annex_i_micro_qr_as_eps_without_background() {
+    let code = QrCode::new_micro(b"01234567").unwrap();
+    let image = code
+        .render()
+        .min_dimensions(200, 200)
+        .dark_color(Color([0.5, 0.0, 0.0]))
+        .light_color(Color([1.0, 1.0, 0.5]))
+        .background(Background::None)
+        .build();
+    let expected = include_str!("data/test_annex_i_micro_qr_as_eps.eps").replacen(
+        "gsave\n1 1 0.5 setrgbcolor\n0 0 204 204 rectfill\ngrestore\n",
+        "",
+        1,
+    );
+    assert_eq!(image, expected);
+}
+
+#[test]
+fn test_annex_i_micro_qr_as_eps_with_white_background() {
+    let code = QrCode::new_micro(b"01234567").unwrap();
+    let image = code
+        .render()
+        .min_dimensions(200, 200)
+        .dark_color(Color([0.5, 0.0, 0.0]))
+        .light_color(Color([1.0, 1.0, 0.5]))
+        .background(Background::White)
+        .build();
+    let expected = include_str!("data/test_annex_i_micro_qr_as_eps.eps").replacen(
+        "1 1 0.5 setrgbcolor",
+        "1 1 1 setrgbcolor",
+        1,
+    );
+    assert_eq!(image, expected);
+}
@@ -30,3 +30,30 @@ fn test_annex_i_rmqr_as_pic() {
     let expected = include_str!("data/test_annex_i_rmqr_as_pic.pic");
     assert_eq!(&image, expected);
 }
+
+#[test]
+fn test_annex_i_micro_qr_as_pic_with_options() {
+    let code = QrCode::new_micro(b"01234567").unwrap();
+    let image = code
+        .render::<Color>()
+        .min_dimensions(1, 1)
+        .inches_per_module(0.1)
+        .origin_offset(0.5, 0.25)
+        .outline_modules(true)
+        .line_thickness(0.5)
+        .build();
+    let expected = include_str!("data/test_annex_i_micro_qr_as_pic.pic").replacen(
+        concat!(
+            "maxpswid=17;maxpsht=17;movewid=0;moveht=1;boxwid=1;boxht=1\n",
+            "define p { box wid $3 ht $4 fill 1 thickness 0.1 with .nw at $1,-$2 }\n",
+            "box wid maxpswid ht maxpsht with .nw at 0,0\n"
+        ),
+        concat!(
+            "maxpswid=1.7;maxpsht=1.7;scale=10;movewid=0;moveht=1;boxwid=1;boxht=1\n",
+            "define p { box wid $3 ht $4 thickness 0.5 with .nw at 5+$1,-2.5-$2 }\n",
+            "box wid 17 ht 17 with .nw at 5,-2.5\n"
+        ),
+        1,
+    );
+    assert_eq!(image, expected);
+}
@@ -5,7 +5,14 @@
 
 #![cfg(feature = "svg")]
 
-use qrcode2::{QrCode, render::svg::Color};
+use qrcode2::{
+    EcLevel, QrCode, Version,
+    canvas::ModuleKind,
+    render::{
+        Background, LineEnding, Unit,
+        svg::{Color, Fill},
+    },
+};
 
 #[test]
 fn test_annex_i_qr_as_svg() {
@@ -35,3 +42,220 @@ fn test_annex_i_rmqr_as_svg() {
     let expected = include_str!("data/test_annex_i_rmqr_as_svg.svg");
     assert_eq!(&image, expected);
 }
+
+#[test]
+fn test_annex_i_qr_as_svg_with_trailing_newline() {
+    let code = QrCode::new(b"01234567").unwrap();
+    let image = code
+        .render::<Color<'_>>()
+        .line_ending(LineEnding::CrLf)
+        .trailing_newline(true)
+        .build();
+    let expected = include_str!("data/test_annex_i_qr_as_svg.svg");
+    assert_eq!(image.strip_suffix("\r\n"), Some(expected));
+}
+
+#[test]
+fn test_annex_i_rmqr_as_svg_with_physical_module_size() {
+    let code = QrCode::new_rect_micro(b"01234567").unwrap();
+    let image = code
+        .render::<Color<'_>>()
+        .physical_module_size(0.5, Unit::Mm)
+        .build();
+    let expected = include_str!("data/test_annex_i_rmqr_as_svg.svg").replacen(
+        r#"width="248" height="120""#,
+        r#"width="15.5mm" height="7.5mm""#,
+        1,
+    );
+    assert_eq!(image, expected);
+}
+
+#[test]
+fn test_annex_i_qr_as_svg_without_background() {
+    let code = QrCode::new(b"01234567").unwrap();
+    let image = code
+        .render::<Color<'_>>()
+        .background(Background::None)
+        .build();
+    let expected = include_str!("data/test_annex_i_qr_as_svg.svg").replacen(
+        r##"<path d="M0 0h232v232H0z" fill="#fff"/>"##,
+        "",
+        1,
+    );
+    assert_eq!(image, expected);
+}
+
+/// Counts how many rectangles of the `d` attribute cover each module.
+fn path_coverage(path: &str, width: usize, height: usize) -> Vec<i32> {
+    let mut coverage = vec![0; width * height];
+    for rect in path.split_terminator('z') {
+        let (position, size) = rect[1..].split_once('h').unwrap();
+        let (left, top) = position.split_once(' ').unwrap();
+        let (rect_width, rect_height) = size.split_once('v').unwrap();
+        let rect_height = rect_height.split_once('h').unwrap().0;
+        let [left, top, rect_width, rect_height] =
+            [left, top, rect_width, rect_height].map(|n| n.parse::<usize>().unwrap());
+        for y in top..top + rect_height {
+            for x in left..left + rect_width {
+                coverage[y * width + x] += 1;
+            }
+        }
+    }
+    coverage
+}
+
+/// Returns the `d` attribute of the first `<path>` element after `marker`.
+fn path_after<'a>(image: &'a str, marker: &str) -> &'a str {
+    image
+        .split_once(marker)
+        .and_then(|(_, rest)| rest.split_once(r#" d=""#))
+        .and_then(|(_, path)| path.split_once('"'))
+        .unwrap()
+        .0
+}
+
+#[test]
+fn test_merged_rects_cover_dark_modules() {
+    for code in [
+        QrCode::new(b"01234567").unwrap(),
+        QrCode::with_version(b"01234567", Version::Normal(40), EcLevel::L).unwrap(),
+        QrCode::new_rect_micro(b"01234567").unwrap(),
+    ] {
+        let image = code
+            .render::<Color<'_>>()
+            .module_dimensions(1, 1)
+            .quiet_zone(0)
+            .background(Background::None)
+            .build();
+        let path = path_after(&image, "<path");
+
+        // Each module is covered by exactly one rectangle.
+        let coverage = path_coverage(path, code.width(), code.height());
+        let expected = code
+            .to_colors()
+            .iter()
+            .map(|c| c.select(1, 0))
+            .collect::<Vec<_>>();
+        assert_eq!(coverage, expected);
+    }
+}
+
+#[test]
+fn test_finder_and_alignment_colors() {
+    let code = QrCode::with_version(b"01234567", Version::Normal(7), EcLevel::L).unwrap();
+    let image = code
+        .render::<Color<'_>>()
+        .module_dimensions(1, 1)
+        .quiet_zone(0)
+        .background(Background::None)
+        .finder_dark_color(Color("#f00"))
+        .alignment_dark_color(Color("#00f"))
+        .build();
+    let (width, height) = (code.width(), code.height());
+    let colors = code.to_colors();
+    let kinds = code.module_kinds();
+    for (marker, kind) in [
+        (r##"fill="#000""##, None),
+        (r##"fill="#f00""##, Some(ModuleKind::Finder)),
+        (r##"fill="#00f""##, Some(ModuleKind::Alignment)),
+    ] {
+        let coverage = path_coverage(path_after(&image, marker), width, height);
+        let expected = colors
+            .iter()
+            .zip(&kinds)
+            .map(|(c, k)| {
+                let in_path = kind.map_or_else(
+                    || !matches!(k, ModuleKind::Finder | ModuleKind::Alignment),
+                    |kind| *k == kind,
+                );
+                i32::from(in_path && c.select(true, false))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(coverage, expected, "{marker}");
+    }
+}
+
+#[test]
+fn test_annex_i_qr_as_svg_with_class_and_title() {
+    let data = "01234567";
+    let code = QrCode::new(data).unwrap();
+    // The strings can be built at runtime.
+    let title = format!("Numbers <{data}>");
+    let image = code
+        .render::<Color<'_>>()
+        .css_class("qr")
+        .title(&title)
+        .desc(String::from("Annex I & \"test\""))
+        .build();
+    let expected = include_str!("data/test_annex_i_qr_as_svg.svg")
+        .replacen(
+            r#"shape-rendering="crispEdges">"#,
+            concat!(
+                r#"shape-rendering="crispEdges" class="qr" role="img">"#,
+                "<title>Numbers &lt;01234567&gt;</title>",
+                "<desc>Annex I &amp; &quot;test&quot;</desc>"
+            ),
+            1,
+        )
+        .replacen("<path d=", r#"<path class="qr-light" d="#, 1)
+        .replacen("<path fill=", r#"<path class="qr-dark" fill="#, 1);
+    assert_eq!(image, expected);
+}
+
+#[test]
+fn test_annex_i_qr_as_svg_with_radial_gradient() {
+    let code = QrCode::new(b"01234567").unwrap();
+    let image = code
+        .render::<Color<'_>>()
+        .css_class("brand")
+        .dark_fill(Fill::RadialGradient {
+            stops: vec![
+                (0.0, format!("#{:06x}", 0x80_0000)),
+                (0.5, "#000080".into()),
+            ],
+        })
+        .build();
+    let expected = include_str!("data/test_annex_i_qr_as_svg.svg")
+        .replacen(
+            r#"shape-rendering="crispEdges">"#,
+            r#"shape-rendering="crispEdges" class="brand">"#,
+            1,
+        )
+        .replacen("<path d=", r#"<path class="brand-light" d="#, 1)
+        .replacen(
+            r##"<path fill="#000""##,
+            concat!(
+                r#"<defs><radialGradient id="brand-dark-fill">"#,
+                r##"<stop offset="0" stop-color="#800000"/>"##,
+                r##"<stop offset="0.5" stop-color="#000080"/>"##,
+                "</radialGradient></defs>",
+                r##"<path class="brand-dark" fill="url(#brand-dark-fill) #000""##
+            ),
+            1,
+        );
+    assert_eq!(image, expected);
+}
+
+#[test]
+fn test_annex_i_qr_as_svg_with_gradient_and_multiple_classes() {
+    let code = QrCode::new(b"01234567").unwrap();
+    let image = code
+        .render::<Color<'_>>()
+        .css_class("qr 2nd")
+        .dark_fill(Fill::LinearGradient {
+            stops: vec![(0.0, "#800000".into()), (1.0, "#000080".into())],
+            angle: 90.0,
+        })
+        .build();
+    assert!(image.contains(r#"<linearGradient id="qr-2nd-dark-fill" "#));
+    assert!(image.contains(r#"fill="url(#qr-2nd-dark-fill) #000""#));
+
+    let image = code
+        .render::<Color<'_>>()
+        .css_class("2nd")
+        .dark_fill(Fill::RadialGradient {
+            stops: vec![(0.0, "#800000".into()), (1.0, "#000080".into())],
+        })
+        .build();
+    assert!(image.contains(r#"fill="url(#qr-2nd-dark-fill) #000""#));
+}